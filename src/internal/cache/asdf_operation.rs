@@ -17,6 +17,7 @@ use crate::internal::cache::utils::Empty;
 use crate::internal::cache::CacheObject;
 use crate::internal::config::global_config;
 use crate::internal::config::up::utils::VersionMatcher;
+use crate::internal::config::up::utils::VersionParser;
 use crate::internal::env::now as omni_now;
 
 const ASDF_OPERATION_CACHE_NAME: &str = "asdf_operation";
@@ -295,6 +296,17 @@ impl AsdfOperationUpdateCachePluginVersions {
             .find(|v| matcher.matches(v))
             .cloned()
     }
+
+    pub fn get_with_prefix(&self, prefix: &str, matcher: &VersionMatcher) -> Option<String> {
+        self.versions
+            .iter()
+            .filter(|v| {
+                v.strip_prefix(prefix)
+                    .is_some_and(|version| matcher.matches(version))
+            })
+            .max_by(|a, b| VersionParser::compare(a, b))
+            .cloned()
+    }
 }
 
 impl Empty for AsdfOperationUpdateCachePluginVersions {
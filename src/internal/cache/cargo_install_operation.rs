@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::io;
+
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::internal::cache::handler::exclusive;
+use crate::internal::cache::handler::shared;
+use crate::internal::cache::loaders::get_cargo_install_operation_cache;
+use crate::internal::cache::loaders::set_cargo_install_operation_cache;
+use crate::internal::cache::utils;
+use crate::internal::cache::utils::Empty;
+use crate::internal::cache::CacheObject;
+use crate::internal::config::global_config;
+use crate::internal::env::now as omni_now;
+
+const CARGO_INSTALL_CACHE_NAME: &str = "cargo_install_operation";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CargoInstallOperationCache {
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub installed: Vec<CargoInstallInstalled>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub versions: BTreeMap<String, CargoInstallVersions>,
+    #[serde(
+        default = "utils::origin_of_time",
+        with = "time::serde::rfc3339",
+        skip_serializing_if = "utils::is_origin_of_time"
+    )]
+    pub updated_at: OffsetDateTime,
+}
+
+impl CargoInstallOperationCache {
+    pub fn updated(&mut self) {
+        self.updated_at = OffsetDateTime::now_utc();
+    }
+
+    pub fn add_versions(&mut self, crate_name: &str, versions: &CargoInstallVersions) -> bool {
+        self.versions
+            .insert(crate_name.to_string(), versions.clone());
+        self.updated();
+        true
+    }
+
+    pub fn get_versions(&self, crate_name: &str) -> Option<&CargoInstallVersions> {
+        self.versions.get(crate_name)
+    }
+
+    pub fn add_installed(&mut self, workdir_id: &str, crate_name: &str, version: &str) -> bool {
+        let inserted = if let Some(install) = self
+            .installed
+            .iter_mut()
+            .find(|i| i.crate_name == crate_name && i.version == version)
+        {
+            if install.required_by.insert(workdir_id.to_string())
+                || install.last_required_at < omni_now()
+            {
+                install.last_required_at = omni_now();
+                true
+            } else {
+                false
+            }
+        } else {
+            let install = CargoInstallInstalled {
+                crate_name: crate_name.to_string(),
+                version: version.to_string(),
+                required_by: [workdir_id.to_string()].iter().cloned().collect(),
+                last_required_at: omni_now(),
+            };
+            self.installed.push(install);
+            true
+        };
+
+        if inserted {
+            self.updated();
+        }
+
+        inserted
+    }
+}
+
+impl Empty for CargoInstallOperationCache {
+    fn is_empty(&self) -> bool {
+        self.installed.is_empty() && self.versions.is_empty()
+    }
+}
+
+impl CacheObject for CargoInstallOperationCache {
+    fn new_empty() -> Self {
+        Self {
+            installed: Vec::new(),
+            versions: BTreeMap::new(),
+            updated_at: utils::origin_of_time(),
+        }
+    }
+
+    fn get() -> Self {
+        get_cargo_install_operation_cache()
+    }
+
+    fn shared() -> io::Result<Self> {
+        shared::<Self>(CARGO_INSTALL_CACHE_NAME)
+    }
+
+    fn exclusive<F>(processing_fn: F) -> io::Result<Self>
+    where
+        F: FnOnce(&mut Self) -> bool,
+    {
+        exclusive::<Self, F, fn(Self)>(
+            CARGO_INSTALL_CACHE_NAME,
+            processing_fn,
+            set_cargo_install_operation_cache,
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CargoInstallInstalled {
+    #[serde(default, rename = "crate", skip_serializing_if = "String::is_empty")]
+    pub crate_name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub version: String,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub required_by: BTreeSet<String>,
+    #[serde(default = "utils::origin_of_time", with = "time::serde::rfc3339")]
+    pub last_required_at: OffsetDateTime,
+}
+
+impl CargoInstallInstalled {
+    pub fn stale(&self) -> bool {
+        self.last_required_at < omni_now()
+    }
+
+    pub fn removable(&self) -> bool {
+        if !self.required_by.is_empty() {
+            return false;
+        }
+
+        let config = global_config();
+        let grace_period = config.cache.cargo_install.cleanup_after;
+        let grace_period = time::Duration::seconds(grace_period as i64);
+
+        (self.last_required_at + grace_period) < omni_now()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CargoInstallVersions {
+    pub versions: Vec<String>,
+    #[serde(default = "OffsetDateTime::now_utc", with = "time::serde::rfc3339")]
+    pub fetched_at: OffsetDateTime,
+}
+
+impl CargoInstallVersions {
+    /// Parses the entry of a crate in a sparse registry index, which
+    /// contains one JSON object per line for each published version.
+    pub fn from_index(contents: &str) -> Result<Self, String> {
+        #[derive(Deserialize)]
+        struct IndexEntry {
+            vers: String,
+            #[serde(default)]
+            yanked: bool,
+        }
+
+        let mut versions = Vec::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let entry: IndexEntry = match serde_json::from_str(line) {
+                Ok(entry) => entry,
+                Err(err) => return Err(format!("failed to parse index entry: {}", err)),
+            };
+
+            // Yanked versions cannot be installed with --locked and
+            // should not be picked when resolving a version
+            if !entry.yanked {
+                versions.push(entry.vers);
+            }
+        }
+
+        Ok(Self {
+            versions,
+            fetched_at: OffsetDateTime::now_utc(),
+        })
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fetched_at >= omni_now()
+    }
+
+    pub fn is_stale(&self, ttl: u64) -> bool {
+        let duration = time::Duration::seconds(ttl as i64);
+        self.fetched_at + duration < OffsetDateTime::now_utc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_from_index() {
+        let contents = r#"
+{"name":"mdbook","vers":"0.4.36","deps":[],"cksum":"abc","features":{},"yanked":false}
+{"name":"mdbook","vers":"0.4.37","deps":[],"cksum":"def","features":{},"yanked":true}
+{"name":"mdbook","vers":"0.5.0-alpha.1","deps":[],"cksum":"ghi","features":{},"yanked":false}
+"#;
+
+        let versions = CargoInstallVersions::from_index(contents).expect("failed to parse index");
+        assert_eq!(versions.versions, vec!["0.4.36", "0.5.0-alpha.1"]);
+    }
+}
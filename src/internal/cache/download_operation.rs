@@ -0,0 +1,132 @@
+use std::collections::BTreeSet;
+use std::io;
+
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::internal::cache::handler::exclusive;
+use crate::internal::cache::handler::shared;
+use crate::internal::cache::loaders::get_download_operation_cache;
+use crate::internal::cache::loaders::set_download_operation_cache;
+use crate::internal::cache::utils;
+use crate::internal::cache::utils::Empty;
+use crate::internal::cache::CacheObject;
+use crate::internal::config::global_config;
+use crate::internal::env::now as omni_now;
+
+const DOWNLOAD_CACHE_NAME: &str = "download_operation";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DownloadOperationCache {
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub installed: Vec<DownloadInstalled>,
+    #[serde(
+        default = "utils::origin_of_time",
+        with = "time::serde::rfc3339",
+        skip_serializing_if = "utils::is_origin_of_time"
+    )]
+    pub updated_at: OffsetDateTime,
+}
+
+impl DownloadOperationCache {
+    pub fn updated(&mut self) {
+        self.updated_at = OffsetDateTime::now_utc();
+    }
+
+    pub fn add_installed(&mut self, workdir_id: &str, name: &str, version: &str) -> bool {
+        let inserted = if let Some(install) = self
+            .installed
+            .iter_mut()
+            .find(|i| i.name == name && i.version == version)
+        {
+            if install.required_by.insert(workdir_id.to_string())
+                || install.last_required_at < omni_now()
+            {
+                install.last_required_at = omni_now();
+                true
+            } else {
+                false
+            }
+        } else {
+            let install = DownloadInstalled {
+                name: name.to_string(),
+                version: version.to_string(),
+                required_by: [workdir_id.to_string()].iter().cloned().collect(),
+                last_required_at: omni_now(),
+            };
+            self.installed.push(install);
+            true
+        };
+
+        if inserted {
+            self.updated();
+        }
+
+        inserted
+    }
+}
+
+impl Empty for DownloadOperationCache {
+    fn is_empty(&self) -> bool {
+        self.installed.is_empty()
+    }
+}
+
+impl CacheObject for DownloadOperationCache {
+    fn new_empty() -> Self {
+        Self {
+            installed: Vec::new(),
+            updated_at: utils::origin_of_time(),
+        }
+    }
+
+    fn get() -> Self {
+        get_download_operation_cache()
+    }
+
+    fn shared() -> io::Result<Self> {
+        shared::<Self>(DOWNLOAD_CACHE_NAME)
+    }
+
+    fn exclusive<F>(processing_fn: F) -> io::Result<Self>
+    where
+        F: FnOnce(&mut Self) -> bool,
+    {
+        exclusive::<Self, F, fn(Self)>(
+            DOWNLOAD_CACHE_NAME,
+            processing_fn,
+            set_download_operation_cache,
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DownloadInstalled {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub version: String,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub required_by: BTreeSet<String>,
+    #[serde(default = "utils::origin_of_time", with = "time::serde::rfc3339")]
+    pub last_required_at: OffsetDateTime,
+}
+
+impl DownloadInstalled {
+    pub fn stale(&self) -> bool {
+        self.last_required_at < omni_now()
+    }
+
+    pub fn removable(&self) -> bool {
+        if !self.required_by.is_empty() {
+            return false;
+        }
+
+        let config = global_config();
+        let grace_period = config.cache.download.cleanup_after;
+        let grace_period = time::Duration::seconds(grace_period as i64);
+
+        (self.last_required_at + grace_period) < omni_now()
+    }
+}
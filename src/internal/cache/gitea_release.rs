@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+use std::io;
+
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::internal::cache::github_release::GithubReleaseInstalled;
+use crate::internal::cache::handler::exclusive;
+use crate::internal::cache::handler::shared;
+use crate::internal::cache::loaders::get_gitea_release_operation_cache;
+use crate::internal::cache::loaders::set_gitea_release_operation_cache;
+use crate::internal::cache::utils;
+use crate::internal::cache::utils::Empty;
+use crate::internal::cache::CacheObject;
+use crate::internal::cache::GithubReleases;
+use crate::internal::env::now as omni_now;
+
+const GITEA_RELEASE_CACHE_NAME: &str = "gitea_release_operation";
+
+/// Cache for the `gitea-release` operation; the releases are stored
+/// in the same format as GitHub releases once fetched from the API,
+/// which allows to reuse the asset selection logic.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GiteaReleaseOperationCache {
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub installed: Vec<GithubReleaseInstalled>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub releases: BTreeMap<String, GithubReleases>,
+    #[serde(
+        default = "utils::origin_of_time",
+        with = "time::serde::rfc3339",
+        skip_serializing_if = "utils::is_origin_of_time"
+    )]
+    pub updated_at: OffsetDateTime,
+}
+
+impl GiteaReleaseOperationCache {
+    pub fn updated(&mut self) {
+        self.updated_at = OffsetDateTime::now_utc();
+    }
+
+    pub fn add_releases(&mut self, repository: &str, releases: &GithubReleases) -> bool {
+        self.releases
+            .insert(repository.to_string(), releases.clone());
+        self.updated();
+        true
+    }
+
+    pub fn get_releases(&self, repository: &str) -> Option<&GithubReleases> {
+        self.releases.get(repository)
+    }
+
+    pub fn add_installed(&mut self, workdir_id: &str, repository: &str, version: &str) -> bool {
+        let inserted = if let Some(install) = self
+            .installed
+            .iter_mut()
+            .find(|i| i.repository == repository && i.version == version)
+        {
+            if install.required_by.insert(workdir_id.to_string())
+                || install.last_required_at < omni_now()
+            {
+                install.last_required_at = omni_now();
+                true
+            } else {
+                false
+            }
+        } else {
+            let install = GithubReleaseInstalled {
+                repository: repository.to_string(),
+                version: version.to_string(),
+                required_by: [workdir_id.to_string()].iter().cloned().collect(),
+                last_required_at: omni_now(),
+            };
+            self.installed.push(install);
+            true
+        };
+
+        if inserted {
+            self.updated();
+        }
+
+        inserted
+    }
+}
+
+impl Empty for GiteaReleaseOperationCache {
+    fn is_empty(&self) -> bool {
+        self.installed.is_empty() && self.releases.is_empty()
+    }
+}
+
+impl CacheObject for GiteaReleaseOperationCache {
+    fn new_empty() -> Self {
+        Self {
+            installed: Vec::new(),
+            releases: BTreeMap::new(),
+            updated_at: utils::origin_of_time(),
+        }
+    }
+
+    fn get() -> Self {
+        get_gitea_release_operation_cache()
+    }
+
+    fn shared() -> io::Result<Self> {
+        shared::<Self>(GITEA_RELEASE_CACHE_NAME)
+    }
+
+    fn exclusive<F>(processing_fn: F) -> io::Result<Self>
+    where
+        F: FnOnce(&mut Self) -> bool,
+    {
+        exclusive::<Self, F, fn(Self)>(
+            GITEA_RELEASE_CACHE_NAME,
+            processing_fn,
+            set_gitea_release_operation_cache,
+        )
+    }
+}
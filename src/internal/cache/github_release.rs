@@ -18,7 +18,6 @@ use crate::internal::cache::loaders::set_github_release_operation_cache;
 use crate::internal::cache::utils;
 use crate::internal::cache::utils::Empty;
 use crate::internal::cache::CacheObject;
-use crate::internal::config::global_config;
 use crate::internal::config::up::utils::VersionMatcher;
 use crate::internal::config::up::utils::VersionParser;
 use crate::internal::env::now as omni_now;
@@ -179,13 +178,14 @@ impl GithubReleaseInstalled {
         self.last_required_at < omni_now()
     }
 
-    pub fn removable(&self) -> bool {
+    /// Returns whether the installed release can be removed, considering
+    /// the provided grace period in seconds; the grace period depends on
+    /// the forge the release was installed from.
+    pub fn removable_after(&self, grace_period: u64) -> bool {
         if !self.required_by.is_empty() {
             return false;
         }
 
-        let config = global_config();
-        let grace_period = config.cache.github_release.cleanup_after;
         let grace_period = time::Duration::seconds(grace_period as i64);
 
         (self.last_required_at + grace_period) < omni_now()
@@ -199,11 +199,14 @@ pub struct GithubReleasesSelector {
     pub build: bool,
     pub binary: bool,
     pub asset_name: Option<String>,
+    pub tag_prefix: Option<String>,
+    pub tag_regex: Option<String>,
     pub skip_arch_matching: bool,
     pub skip_os_matching: bool,
     pub checksum_lookup: bool,
     pub checksum_algorithm: Option<String>,
     pub checksum_asset_name: Option<String>,
+    pub signature_extensions: Vec<String>,
 }
 
 impl GithubReleasesSelector {
@@ -234,6 +237,16 @@ impl GithubReleasesSelector {
         self
     }
 
+    pub fn tag_prefix(mut self, tag_prefix: Option<String>) -> Self {
+        self.tag_prefix = tag_prefix;
+        self
+    }
+
+    pub fn tag_regex(mut self, tag_regex: Option<String>) -> Self {
+        self.tag_regex = tag_regex;
+        self
+    }
+
     pub fn skip_arch_matching(mut self, skip_arch_matching: bool) -> Self {
         self.skip_arch_matching = skip_arch_matching;
         self
@@ -259,6 +272,33 @@ impl GithubReleasesSelector {
         self
     }
 
+    pub fn signature_extensions(mut self, signature_extensions: Vec<String>) -> Self {
+        self.signature_extensions = signature_extensions;
+        self
+    }
+
+    /// Returns the version of the release with the given tag, or
+    /// `None` if the tag does not match the tag prefix or regex
+    fn tag_version(&self, tag_name: &str, tag_regex: Option<&Regex>) -> Option<String> {
+        let tag_name = match self.tag_prefix {
+            Some(ref tag_prefix) => tag_name.strip_prefix(tag_prefix.as_str())?,
+            None => tag_name,
+        };
+
+        let tag_regex = match tag_regex {
+            Some(tag_regex) => tag_regex,
+            None => return Some(tag_name.to_string()),
+        };
+
+        let captures = tag_regex.captures(tag_name)?;
+        let version = captures
+            .name("version")
+            .or_else(|| captures.iter().skip(1).flatten().next())
+            .map_or(tag_name, |version| version.as_str());
+
+        Some(version.to_string())
+    }
+
     // Use a tiny unsigned int for the matching, 0 means no matching,
     // 1 means matching for extended arch, and 2 means matching for regular arch
     fn asset_matches(&self, asset: &GithubReleaseAsset) -> usize {
@@ -380,6 +420,7 @@ impl GithubReleasesSelector {
                     || asset.content_type == "text/plain"
                     || asset.content_type.starts_with("text/plain;"))
                     && !matching_assets.iter().any(|a| a.name == asset.name)
+                    && !self.is_signature(&asset.name)
             })
             .cloned()
             .collect::<Vec<GithubReleaseAsset>>();
@@ -500,6 +541,38 @@ impl GithubReleasesSelector {
         // Return the assets
         matching_assets
     }
+
+    fn is_signature(&self, asset_name: &str) -> bool {
+        self.signature_extensions
+            .iter()
+            .any(|ext| asset_name.ends_with(ext.as_str()))
+    }
+
+    /// Finds the signature of the given assets and of their checksum
+    /// files, published next to them with one of the signature extensions
+    fn assets_with_signatures(
+        &self,
+        mut matching_assets: Vec<GithubReleaseAsset>,
+        assets: &[GithubReleaseAsset],
+    ) -> Vec<GithubReleaseAsset> {
+        let signature_of = |name: &str| {
+            self.signature_extensions.iter().find_map(|ext| {
+                assets
+                    .iter()
+                    .find(|asset| asset.name == format!("{}{}", name, ext))
+                    .map(|asset| Box::new(asset.clone()))
+            })
+        };
+
+        for asset in &mut matching_assets {
+            asset.signature_asset = signature_of(&asset.name);
+            if let Some(checksum_asset) = asset.checksum_asset.as_mut() {
+                checksum_asset.signature_asset = signature_of(&checksum_asset.name);
+            }
+        }
+
+        matching_assets
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -507,6 +580,14 @@ pub struct GithubReleases {
     pub releases: Vec<GithubReleaseVersion>,
     #[serde(default = "OffsetDateTime::now_utc", with = "time::serde::rfc3339")]
     pub fetched_at: OffsetDateTime,
+    /// The ETag of the release list, to check whether it changed
+    /// with a conditional request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// The Last-Modified date of the release list, to check whether
+    /// it changed with a conditional request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
 }
 
 impl GithubReleases {
@@ -519,9 +600,86 @@ impl GithubReleases {
         Ok(Self {
             releases,
             fetched_at: OffsetDateTime::now_utc(),
+            etag: None,
+            last_modified: None,
+        })
+    }
+
+    /// Parses the releases as returned by the GitLab API; GitLab does not
+    /// provide a pre-release flag, but upcoming releases are considered
+    /// as drafts since they are not yet released. Release links do not
+    /// provide a content type, so we consider them as binary streams,
+    /// which allows them to be considered when looking for checksums.
+    pub fn from_gitlab_json(json: &str) -> Result<Self, String> {
+        let releases: Vec<GitlabReleaseVersion> = match serde_json::from_str(json) {
+            Ok(releases) => releases,
+            Err(err) => return Err(format!("failed to parse releases: {}", err)),
+        };
+
+        let releases = releases
+            .into_iter()
+            .map(|release| GithubReleaseVersion {
+                tag_name: release.tag_name,
+                name: release.name,
+                draft: release.upcoming_release,
+                prerelease: false,
+                tag_version: None,
+                assets: release
+                    .assets
+                    .links
+                    .into_iter()
+                    .map(|link| GithubReleaseAsset {
+                        name: link.name,
+                        browser_download_url: link.direct_asset_url.unwrap_or(link.url),
+                        state: "uploaded".to_string(),
+                        content_type: "application/octet-stream".to_string(),
+                        size: 0,
+                        checksum_asset: None,
+                        signature_asset: None,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(Self {
+            releases,
+            fetched_at: OffsetDateTime::now_utc(),
+            etag: None,
+            last_modified: None,
         })
     }
 
+    /// Parses the releases as returned by the Gitea API, which follows
+    /// the same format as the GitHub API except for the content type
+    /// of the assets that is not provided.
+    pub fn from_gitea_json(json: &str) -> Result<Self, String> {
+        let mut releases = Self::from_json(json)?;
+
+        for asset in releases
+            .releases
+            .iter_mut()
+            .flat_map(|release| release.assets.iter_mut())
+        {
+            if asset.content_type.is_empty() {
+                asset.content_type = "application/octet-stream".to_string();
+            }
+        }
+
+        Ok(releases)
+    }
+
+    /// Returns whether the release list can be checked for changes
+    /// with a conditional request
+    pub fn is_conditional(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+
+    /// Marks the release list as fetched now, e.g. when a conditional
+    /// request confirmed that it did not change
+    pub fn refresh(&mut self) {
+        self.fetched_at = OffsetDateTime::now_utc();
+    }
+
     pub fn is_fresh(&self) -> bool {
         self.fetched_at >= omni_now()
     }
@@ -539,6 +697,11 @@ impl GithubReleases {
         // the prefix is going to be, `v` or anything else
         matcher.prefix(true);
 
+        let tag_regex = match selector.tag_regex {
+            Some(ref tag_regex) => Some(Regex::new(tag_regex).ok()?),
+            None => None,
+        };
+
         self.releases
             .iter()
             .filter_map(|release| {
@@ -552,8 +715,10 @@ impl GithubReleases {
                     return None;
                 }
 
-                // Parse the version
-                let release_version = release.version();
+                // Parse the version, only considering the releases
+                // whose tag matches the expected prefix and regex
+                let release_version =
+                    selector.tag_version(&release.tag_name, tag_regex.as_ref())?;
 
                 // Make sure the version fits the requested version
                 if !matcher.matches(&release_version) {
@@ -570,6 +735,7 @@ impl GithubReleases {
                 if assets.is_empty() {
                     return None;
                 }
+                let assets = selector.assets_with_signatures(assets, &release.assets);
 
                 let release = GithubReleaseVersion {
                     tag_name: release.tag_name.clone(),
@@ -577,6 +743,7 @@ impl GithubReleases {
                     draft: release.draft,
                     prerelease: release.prerelease,
                     assets,
+                    tag_version: Some(release_version.clone()).filter(|v| *v != release.tag_name),
                 };
 
                 Some((release_version, release))
@@ -597,14 +764,48 @@ pub struct GithubReleaseVersion {
     pub prerelease: bool,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub assets: Vec<GithubReleaseAsset>,
+    /// The version parsed from the tag name, when different from
+    /// the tag name, e.g. when the tag name has a prefix
+    #[serde(default, skip)]
+    pub tag_version: Option<String>,
 }
 
 impl GithubReleaseVersion {
     pub fn version(&self) -> String {
-        self.tag_name.clone()
+        self.tag_version
+            .clone()
+            .unwrap_or_else(|| self.tag_name.clone())
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct GitlabReleaseVersion {
+    #[serde(default)]
+    tag_name: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    upcoming_release: bool,
+    #[serde(default)]
+    assets: GitlabReleaseAssets,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct GitlabReleaseAssets {
+    #[serde(default)]
+    links: Vec<GitlabReleaseLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitlabReleaseLink {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    direct_asset_url: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GithubReleaseAsset {
     #[serde(default, skip_serializing_if = "String::is_empty")]
@@ -619,6 +820,8 @@ pub struct GithubReleaseAsset {
     pub size: u64,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub checksum_asset: Option<Box<GithubReleaseAsset>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_asset: Option<Box<GithubReleaseAsset>>,
 }
 
 impl GithubReleaseAsset {
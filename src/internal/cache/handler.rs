@@ -3,8 +3,9 @@ use std::fs::File;
 use std::fs::OpenOptions;
 use std::io;
 use std::io::Read;
-use std::io::Seek;
 use std::io::Write;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
 use std::path::PathBuf;
 
 use fs4::fs_std::FileExt;
@@ -133,6 +134,16 @@ where
     Ok(cache)
 }
 
+/// Whether the opened file is still the one at the given path
+fn is_same_file(file: &File, path: &Path) -> bool {
+    match (file.metadata(), std::fs::metadata(path)) {
+        (Ok(file_metadata), Ok(path_metadata)) => {
+            file_metadata.dev() == path_metadata.dev() && file_metadata.ino() == path_metadata.ino()
+        }
+        _ => false,
+    }
+}
+
 pub fn exclusive<C, F1, F2>(cache_name: &str, processing_fn: F1, set_cache_fn: F2) -> io::Result<C>
 where
     C: CacheObject + Clone + Serialize + for<'a> Deserialize<'a>,
@@ -148,16 +159,23 @@ where
     }
     let cache_path = cache_dir_path.join(format!("{}.json", cache_name));
 
-    // Open the cache file
-    let mut file = OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .truncate(false)
-        .open(cache_path)?;
-
-    // Take the exclusive lock on the file, it will be release when `_file_lock` goes out of scope
-    let _file_lock = file.lock_exclusive();
+    // Open the cache file and take the exclusive lock on it, it will be released
+    // when `file` goes out of scope; since the file is replaced when written, it
+    // might have been replaced by another writer while we were waiting for the
+    // lock, in which case we need to lock the new file instead
+    let mut file = loop {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&cache_path)?;
+        file.lock_exclusive()?;
+
+        if is_same_file(&file, &cache_path) {
+            break file;
+        }
+    };
 
     // Read the content of the file, and parse it as JSON
     let mut content = String::new();
@@ -174,13 +192,25 @@ where
     if processing_fn(&mut cache) {
         let serialized = serde_json::to_string(&cache).unwrap();
 
-        // Replace entirely the content of the file with the new JSON
-        file.set_len(0)?;
-        file.seek(io::SeekFrom::Start(0))?;
-        file.write_all(serialized.as_bytes())?;
+        // Write the new JSON to a temporary file that then replaces the cache file,
+        // so that readers, which do not take the lock, never see a partial write
+        let tmp_path =
+            cache_dir_path.join(format!(".{}.json.{}", cache_name, uuid::Uuid::new_v4()));
+        if let Err(err) = File::create(&tmp_path)
+            .and_then(|mut tmp_file| tmp_file.write_all(serialized.as_bytes()))
+        {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(err);
+        }
 
-        // Update the global cache variable with the new data
+        // Update the global cache variable with the new data; we do it before
+        // replacing the file so that this happens while we still hold the lock
         set_cache_fn(cache.clone());
+
+        if let Err(err) = std::fs::rename(&tmp_path, &cache_path) {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(err);
+        }
     }
 
     // Return the cache as modified by the closure, no matter if the file was updated or not
@@ -4,24 +4,39 @@ use lazy_static::lazy_static;
 
 use crate::internal::cache::AsdfOperationCache;
 use crate::internal::cache::CacheObject;
+use crate::internal::cache::CargoInstallOperationCache;
+use crate::internal::cache::DownloadOperationCache;
+use crate::internal::cache::GiteaReleaseOperationCache;
 use crate::internal::cache::GithubReleaseOperationCache;
+use crate::internal::cache::GitlabReleaseOperationCache;
 use crate::internal::cache::HomebrewOperationCache;
 use crate::internal::cache::OmniPathCache;
 use crate::internal::cache::PromptsCache;
 use crate::internal::cache::RepositoriesCache;
+use crate::internal::cache::SystemPackageOperationCache;
 use crate::internal::cache::UpEnvironmentsCache;
 
 lazy_static! {
     static ref ASDF_OPERATION_CACHE: Mutex<AsdfOperationCache> =
         Mutex::new(AsdfOperationCache::new_load());
+    static ref CARGO_INSTALL_OPERATION_CACHE: Mutex<CargoInstallOperationCache> =
+        Mutex::new(CargoInstallOperationCache::new_load());
+    static ref DOWNLOAD_OPERATION_CACHE: Mutex<DownloadOperationCache> =
+        Mutex::new(DownloadOperationCache::new_load());
     static ref GITHUB_RELEASES_OPERATION_CACHE: Mutex<GithubReleaseOperationCache> =
         Mutex::new(GithubReleaseOperationCache::new_load());
+    static ref GITEA_RELEASES_OPERATION_CACHE: Mutex<GiteaReleaseOperationCache> =
+        Mutex::new(GiteaReleaseOperationCache::new_load());
+    static ref GITLAB_RELEASES_OPERATION_CACHE: Mutex<GitlabReleaseOperationCache> =
+        Mutex::new(GitlabReleaseOperationCache::new_load());
     static ref HOMEBREW_OPERATION_CACHE: Mutex<HomebrewOperationCache> =
         Mutex::new(HomebrewOperationCache::new_load());
     static ref OMNIPATH_CACHE: Mutex<OmniPathCache> = Mutex::new(OmniPathCache::new_load());
     static ref PROMPTS_CACHE: Mutex<PromptsCache> = Mutex::new(PromptsCache::new_load());
     static ref REPOSITORIES_CACHE: Mutex<RepositoriesCache> =
         Mutex::new(RepositoriesCache::new_load());
+    static ref SYSTEM_PACKAGE_OPERATION_CACHE: Mutex<SystemPackageOperationCache> =
+        Mutex::new(SystemPackageOperationCache::new_load());
     static ref UP_ENVIRONMENTS_CACHE: Mutex<UpEnvironmentsCache> =
         Mutex::new(UpEnvironmentsCache::new_load());
 }
@@ -38,10 +53,26 @@ pub fn get_asdf_operation_cache() -> AsdfOperationCache {
     generic_get_cache(&ASDF_OPERATION_CACHE)
 }
 
+pub fn get_cargo_install_operation_cache() -> CargoInstallOperationCache {
+    generic_get_cache(&CARGO_INSTALL_OPERATION_CACHE)
+}
+
+pub fn get_download_operation_cache() -> DownloadOperationCache {
+    generic_get_cache(&DOWNLOAD_OPERATION_CACHE)
+}
+
 pub fn get_github_release_operation_cache() -> GithubReleaseOperationCache {
     generic_get_cache(&GITHUB_RELEASES_OPERATION_CACHE)
 }
 
+pub fn get_gitea_release_operation_cache() -> GiteaReleaseOperationCache {
+    generic_get_cache(&GITEA_RELEASES_OPERATION_CACHE)
+}
+
+pub fn get_gitlab_release_operation_cache() -> GitlabReleaseOperationCache {
+    generic_get_cache(&GITLAB_RELEASES_OPERATION_CACHE)
+}
+
 pub fn get_homebrew_operation_cache() -> HomebrewOperationCache {
     generic_get_cache(&HOMEBREW_OPERATION_CACHE)
 }
@@ -58,6 +89,10 @@ pub fn get_repositories_cache() -> RepositoriesCache {
     generic_get_cache(&REPOSITORIES_CACHE)
 }
 
+pub fn get_system_package_operation_cache() -> SystemPackageOperationCache {
+    generic_get_cache(&SYSTEM_PACKAGE_OPERATION_CACHE)
+}
+
 pub fn get_up_environments_cache() -> UpEnvironmentsCache {
     generic_get_cache(&UP_ENVIRONMENTS_CACHE)
 }
@@ -74,10 +109,26 @@ pub fn set_asdf_operation_cache(cache_set: AsdfOperationCache) {
     generic_set_cache(&ASDF_OPERATION_CACHE, cache_set);
 }
 
+pub fn set_cargo_install_operation_cache(cache_set: CargoInstallOperationCache) {
+    generic_set_cache(&CARGO_INSTALL_OPERATION_CACHE, cache_set);
+}
+
+pub fn set_download_operation_cache(cache_set: DownloadOperationCache) {
+    generic_set_cache(&DOWNLOAD_OPERATION_CACHE, cache_set);
+}
+
 pub fn set_github_release_operation_cache(cache_set: GithubReleaseOperationCache) {
     generic_set_cache(&GITHUB_RELEASES_OPERATION_CACHE, cache_set);
 }
 
+pub fn set_gitea_release_operation_cache(cache_set: GiteaReleaseOperationCache) {
+    generic_set_cache(&GITEA_RELEASES_OPERATION_CACHE, cache_set);
+}
+
+pub fn set_gitlab_release_operation_cache(cache_set: GitlabReleaseOperationCache) {
+    generic_set_cache(&GITLAB_RELEASES_OPERATION_CACHE, cache_set);
+}
+
 pub fn set_homebrew_operation_cache(cache_set: HomebrewOperationCache) {
     generic_set_cache(&HOMEBREW_OPERATION_CACHE, cache_set);
 }
@@ -94,6 +145,10 @@ pub fn set_repositories_cache(cache_set: RepositoriesCache) {
     generic_set_cache(&REPOSITORIES_CACHE, cache_set);
 }
 
+pub fn set_system_package_operation_cache(cache_set: SystemPackageOperationCache) {
+    generic_set_cache(&SYSTEM_PACKAGE_OPERATION_CACHE, cache_set);
+}
+
 pub fn set_up_environments_cache(cache_set: UpEnvironmentsCache) {
     generic_set_cache(&UP_ENVIRONMENTS_CACHE, cache_set);
 }
@@ -3,11 +3,23 @@ pub(crate) mod loaders;
 pub(crate) mod asdf_operation;
 pub(crate) use asdf_operation::AsdfOperationCache;
 
+pub(crate) mod cargo_install_operation;
+pub(crate) use cargo_install_operation::CargoInstallOperationCache;
+
+pub(crate) mod download_operation;
+pub(crate) use download_operation::DownloadOperationCache;
+
 pub(crate) mod github_release;
 pub(crate) use github_release::GithubReleaseOperationCache;
 pub(crate) use github_release::GithubReleaseVersion;
 pub(crate) use github_release::GithubReleases;
 
+pub(crate) mod gitea_release;
+pub(crate) use gitea_release::GiteaReleaseOperationCache;
+
+pub(crate) mod gitlab_release;
+pub(crate) use gitlab_release::GitlabReleaseOperationCache;
+
 pub(crate) mod handler;
 
 pub(crate) mod homebrew_operation;
@@ -25,6 +37,9 @@ pub(crate) use prompts::PromptsCache;
 pub(crate) mod repositories;
 pub(crate) use repositories::RepositoriesCache;
 
+pub(crate) mod system_package_operation;
+pub(crate) use system_package_operation::SystemPackageOperationCache;
+
 pub(crate) mod up_environments;
 pub(crate) use up_environments::UpEnvironmentsCache;
 
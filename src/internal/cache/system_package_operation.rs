@@ -0,0 +1,209 @@
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::internal::cache::handler::exclusive;
+use crate::internal::cache::handler::shared;
+use crate::internal::cache::loaders::get_system_package_operation_cache;
+use crate::internal::cache::loaders::set_system_package_operation_cache;
+use crate::internal::cache::offsetdatetime_hashmap;
+use crate::internal::cache::utils;
+use crate::internal::cache::utils::Empty;
+use crate::internal::cache::CacheObject;
+use crate::internal::config::global_config;
+use crate::internal::env::now as omni_now;
+
+const SYSTEM_PACKAGE_OPERATION_CACHE_NAME: &str = "system_package_operation";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SystemPackageOperationCache {
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub installed: Vec<SystemPackageInstalled>,
+    #[serde(
+        default = "HashMap::new",
+        skip_serializing_if = "HashMap::is_empty",
+        with = "offsetdatetime_hashmap"
+    )]
+    pub managers_updated_at: HashMap<String, OffsetDateTime>,
+    #[serde(
+        default = "HashMap::new",
+        skip_serializing_if = "HashMap::is_empty",
+        with = "offsetdatetime_hashmap"
+    )]
+    pub packages_checked_at: HashMap<String, OffsetDateTime>,
+    #[serde(
+        default = "utils::origin_of_time",
+        with = "time::serde::rfc3339",
+        skip_serializing_if = "utils::is_origin_of_time"
+    )]
+    pub updated_at: OffsetDateTime,
+}
+
+impl SystemPackageOperationCache {
+    pub fn updated(&mut self) {
+        self.updated_at = OffsetDateTime::now_utc();
+    }
+
+    fn package_key(manager: &str, name: &str, version: Option<String>) -> String {
+        format!(
+            "{}:{}{}",
+            manager,
+            name,
+            match version {
+                Some(version) => format!("@{}", version),
+                None => "".to_string(),
+            }
+        )
+    }
+
+    pub fn add_install(
+        &mut self,
+        workdir_id: &str,
+        manager: &str,
+        name: &str,
+        version: Option<String>,
+        installed: bool,
+    ) -> bool {
+        let inserted = if let Some(install) = self
+            .installed
+            .iter_mut()
+            .find(|i| i.manager == manager && i.name == name && i.version == version)
+        {
+            install.installed = install.installed || installed;
+            if install.required_by.insert(workdir_id.to_string())
+                || install.last_required_at < omni_now()
+            {
+                install.last_required_at = omni_now();
+                true
+            } else {
+                false
+            }
+        } else {
+            let install = SystemPackageInstalled {
+                manager: manager.to_string(),
+                name: name.to_string(),
+                version,
+                installed,
+                required_by: [workdir_id.to_string()].iter().cloned().collect(),
+                last_required_at: omni_now(),
+            };
+            self.installed.push(install);
+            true
+        };
+
+        if inserted {
+            self.updated();
+        }
+
+        inserted
+    }
+
+    pub fn updated_manager(&mut self, manager: &str) {
+        self.managers_updated_at
+            .insert(manager.to_string(), OffsetDateTime::now_utc());
+        self.updated();
+    }
+
+    pub fn should_update_manager(&self, manager: &str) -> bool {
+        let expire_after = Duration::from_secs(global_config().cache.system_package.update_expire);
+        match self.managers_updated_at.get(manager) {
+            Some(updated_at) => (*updated_at + expire_after) < OffsetDateTime::now_utc(),
+            None => true,
+        }
+    }
+
+    pub fn checked_install(&mut self, manager: &str, name: &str, version: Option<String>) {
+        self.packages_checked_at.insert(
+            Self::package_key(manager, name, version),
+            OffsetDateTime::now_utc(),
+        );
+        self.updated();
+    }
+
+    pub fn should_check_install(&self, manager: &str, name: &str, version: Option<String>) -> bool {
+        let expire_after =
+            Duration::from_secs(global_config().cache.system_package.install_check_expire);
+        match self
+            .packages_checked_at
+            .get(&Self::package_key(manager, name, version))
+        {
+            Some(checked_at) => (*checked_at + expire_after) < OffsetDateTime::now_utc(),
+            None => true,
+        }
+    }
+
+    pub fn removed_install(&mut self, manager: &str, name: &str, version: Option<String>) {
+        self.packages_checked_at
+            .remove(&Self::package_key(manager, name, version));
+    }
+}
+
+impl Empty for SystemPackageOperationCache {
+    fn is_empty(&self) -> bool {
+        self.installed.is_empty()
+            && self.managers_updated_at.is_empty()
+            && self.packages_checked_at.is_empty()
+    }
+}
+
+impl CacheObject for SystemPackageOperationCache {
+    fn new_empty() -> Self {
+        Self {
+            installed: Vec::new(),
+            managers_updated_at: HashMap::new(),
+            packages_checked_at: HashMap::new(),
+            updated_at: utils::origin_of_time(),
+        }
+    }
+
+    fn get() -> Self {
+        get_system_package_operation_cache()
+    }
+
+    fn shared() -> io::Result<Self> {
+        shared::<Self>(SYSTEM_PACKAGE_OPERATION_CACHE_NAME)
+    }
+
+    fn exclusive<F>(processing_fn: F) -> io::Result<Self>
+    where
+        F: FnOnce(&mut Self) -> bool,
+    {
+        exclusive::<Self, F, fn(Self)>(
+            SYSTEM_PACKAGE_OPERATION_CACHE_NAME,
+            processing_fn,
+            set_system_package_operation_cache,
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SystemPackageInstalled {
+    pub manager: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default = "utils::set_false", skip_serializing_if = "utils::is_false")]
+    pub installed: bool,
+    #[serde(default = "BTreeSet::new", skip_serializing_if = "BTreeSet::is_empty")]
+    pub required_by: BTreeSet<String>,
+    #[serde(default = "utils::origin_of_time", with = "time::serde::rfc3339")]
+    pub last_required_at: OffsetDateTime,
+}
+
+impl SystemPackageInstalled {
+    pub fn stale(&self) -> bool {
+        self.last_required_at < omni_now()
+    }
+
+    pub fn removable(&self) -> bool {
+        // System packages are never uninstalled by omni, as they might
+        // have become a dependency of anything else on the system; we
+        // only stop tracking them once no workdir requires them anymore
+        self.required_by.is_empty()
+    }
+}
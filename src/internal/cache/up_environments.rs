@@ -74,6 +74,20 @@ impl UpEnvironmentsCache {
         true
     }
 
+    pub fn add_env_vars(&mut self, workdir_id: &str, env_vars: Vec<EnvOperationConfig>) -> bool {
+        if env_vars.is_empty() {
+            return false;
+        }
+
+        let env = self
+            .env
+            .entry(workdir_id.to_string())
+            .or_insert_with(UpEnvironment::new);
+        env.env_vars.extend(env_vars.into_iter().map(|e| e.into()));
+        self.updated();
+        true
+    }
+
     pub fn add_env_var(&mut self, workdir_id: &str, key: &str, value: &str) -> bool {
         self.add_env_var_operation(workdir_id, key, value, EnvOperationEnum::Set)
     }
@@ -192,6 +206,53 @@ impl UpEnvironmentsCache {
         false
     }
 
+    /// Records the changes made to the environment by the given step
+    pub fn set_step_changes(
+        &mut self,
+        workdir_id: &str,
+        step: &str,
+        changes: UpStepEnvironment,
+    ) -> bool {
+        let env = self
+            .env
+            .entry(workdir_id.to_string())
+            .or_insert_with(UpEnvironment::new);
+        env.steps.insert(step.to_string(), changes);
+        self.updated();
+        true
+    }
+
+    /// Removes the changes previously recorded for the given step from
+    /// the environment, so that the step can be run again on its own
+    pub fn revert_step(&mut self, workdir_id: &str, step: &str) -> bool {
+        let env = match self.env.get_mut(workdir_id) {
+            Some(env) => env,
+            None => return false,
+        };
+
+        let changes = match env.steps.remove(step) {
+            Some(changes) => changes,
+            None => return false,
+        };
+
+        env.revert(&changes);
+        self.updated();
+        true
+    }
+
+    /// Removes the provided changes from the environment, without them
+    /// having been recorded for a step
+    pub fn revert_changes(&mut self, workdir_id: &str, changes: &UpStepEnvironment) -> bool {
+        let env = match self.env.get_mut(workdir_id) {
+            Some(env) => env,
+            None => return false,
+        };
+
+        env.revert(changes);
+        self.updated();
+        true
+    }
+
     pub fn contains(&self, workdir_id: &str) -> bool {
         self.env.contains_key(workdir_id)
     }
@@ -258,6 +319,10 @@ pub struct UpEnvironment {
     pub config_modtimes: HashMap<String, u64>,
     #[serde(default, skip_serializing_if = "String::is_empty")]
     pub config_hash: String,
+    /// The changes made to the environment by each of the steps, so
+    /// that the environment can be updated for only some of the steps
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub steps: BTreeMap<String, UpStepEnvironment>,
 }
 
 impl UpEnvironment {
@@ -268,6 +333,56 @@ impl UpEnvironment {
             env_vars: Vec::new(),
             config_modtimes: HashMap::new(),
             config_hash: String::new(),
+            steps: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the changes made to the environment since it was in
+    /// the provided state
+    pub fn changes_since(&self, before: &UpEnvironment) -> UpStepEnvironment {
+        UpStepEnvironment {
+            versions: self
+                .versions
+                .iter()
+                .filter(|version| !before.versions.iter().any(|b| b.same_as(version)))
+                .cloned()
+                .collect(),
+            paths: self
+                .paths
+                .iter()
+                .filter(|path| !before.paths.contains(path))
+                .cloned()
+                .collect(),
+            env_vars: self
+                .env_vars
+                .iter()
+                .skip(before.env_vars.len())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Returns the environment made only of the changes recorded for
+    /// the given steps
+    pub fn for_steps(&self, steps: &[String]) -> UpEnvironment {
+        let mut env = UpEnvironment::new();
+        for changes in steps.iter().filter_map(|step| self.steps.get(step)) {
+            env.versions.extend(changes.versions.iter().cloned());
+            env.paths.extend(changes.paths.iter().cloned());
+            env.env_vars.extend(changes.env_vars.iter().cloned());
+        }
+        env
+    }
+
+    /// Removes the provided changes from the environment
+    fn revert(&mut self, changes: &UpStepEnvironment) {
+        self.versions
+            .retain(|version| !changes.versions.iter().any(|c| c.same_as(version)));
+        self.paths.retain(|path| !changes.paths.contains(path));
+        for env_var in changes.env_vars.iter() {
+            if let Some(idx) = self.env_vars.iter().rposition(|e| e == env_var) {
+                self.env_vars.remove(idx);
+            }
         }
     }
 
@@ -310,6 +425,10 @@ pub struct UpVersion {
 }
 
 impl UpVersion {
+    fn same_as(&self, other: &UpVersion) -> bool {
+        self.tool == other.tool && self.version == other.version && self.dir == other.dir
+    }
+
     pub fn new(tool: &str, tool_real_name: Option<&str>, version: &str, dir: &str) -> Self {
         Self {
             tool: tool.to_string(),
@@ -321,7 +440,18 @@ impl UpVersion {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// The changes made to the environment of a work directory by a step
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UpStepEnvironment {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub versions: Vec<UpVersion>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<PathBuf>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_vars: Vec<UpEnvVar>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct UpEnvVar {
     #[serde(
         rename = "n",
@@ -355,3 +485,70 @@ impl From<EnvOperationConfig> for UpEnvVar {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_changes_can_be_reverted() {
+        let mut cache = UpEnvironmentsCache::new_empty();
+        cache.add_path("wd", PathBuf::from("/static/bin"));
+        cache.add_env_var("wd", "BEFORE", "1");
+
+        let before = cache.get_env("wd").unwrap().clone();
+        cache.add_path("wd", PathBuf::from("/step/bin"));
+        cache.add_path("wd", PathBuf::from("/static/bin"));
+        cache.add_env_var("wd", "STEP", "1");
+        cache.add_version(
+            "wd",
+            "python",
+            None,
+            "3.12.1",
+            BTreeSet::from(["".to_string()]),
+        );
+
+        let changes = cache.get_env("wd").unwrap().changes_since(&before);
+        assert_eq!(changes.paths, vec![PathBuf::from("/step/bin")]);
+        assert_eq!(changes.env_vars.len(), 1);
+        assert_eq!(changes.env_vars[0].name, "STEP");
+        assert_eq!(changes.versions.len(), 1);
+
+        cache.set_step_changes("wd", "1:step", changes);
+        assert_eq!(
+            cache
+                .get_env("wd")
+                .unwrap()
+                .for_steps(&["1:step".to_string()])
+                .paths,
+            vec![PathBuf::from("/step/bin")]
+        );
+
+        assert!(cache.revert_step("wd", "1:step"));
+        assert!(!cache.revert_step("wd", "1:step"));
+
+        let env = cache.get_env("wd").unwrap();
+        assert_eq!(env.paths, vec![PathBuf::from("/static/bin")]);
+        assert_eq!(env.env_vars, before.env_vars);
+        assert!(env.versions.is_empty());
+        assert!(env.steps.is_empty());
+    }
+    #[test]
+    fn unrecorded_changes_can_be_reverted() {
+        let mut cache = UpEnvironmentsCache::new_empty();
+        assert!(!cache.revert_changes("wd", &UpStepEnvironment::default()));
+
+        cache.add_path("wd", PathBuf::from("/static/bin"));
+        let before = cache.get_env("wd").unwrap().clone();
+        cache.add_path("wd", PathBuf::from("/verified/bin"));
+        cache.add_env_var("wd", "VERIFIED", "1");
+
+        let changes = cache.get_env("wd").unwrap().changes_since(&before);
+        assert!(cache.revert_changes("wd", &changes));
+
+        let env = cache.get_env("wd").unwrap();
+        assert_eq!(env.paths, vec![PathBuf::from("/static/bin")]);
+        assert!(env.env_vars.is_empty());
+        assert!(env.steps.is_empty());
+    }
+}
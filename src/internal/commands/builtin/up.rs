@@ -15,6 +15,7 @@ use once_cell::sync::OnceCell;
 use serde::Serialize;
 use tokio::process::Command as TokioCommand;
 
+use crate::internal::cache::up_environments::UpEnvVar;
 use crate::internal::cache::utils::Empty;
 use crate::internal::cache::CacheObject;
 use crate::internal::cache::PromptsCache;
@@ -25,9 +26,13 @@ use crate::internal::commands::builtin::HelpCommand;
 use crate::internal::config::config;
 use crate::internal::config::flush_config;
 use crate::internal::config::global_config;
+use crate::internal::config::parser::EnvOperationConfig;
+use crate::internal::config::up::plan::UpPlanAction;
+use crate::internal::config::up::plan::UpPlanItem;
 use crate::internal::config::up::utils::run_progress;
 use crate::internal::config::up::utils::PrintProgressHandler;
 use crate::internal::config::up::utils::ProgressHandler;
+use crate::internal::config::up::utils::RetryPolicy;
 use crate::internal::config::up::utils::RunConfig;
 use crate::internal::config::up::utils::SpinnerProgressHandler;
 use crate::internal::config::up::utils::SyncUpdateInit;
@@ -35,13 +40,18 @@ use crate::internal::config::up::utils::SyncUpdateInitOption;
 use crate::internal::config::up::utils::SyncUpdateListener;
 use crate::internal::config::up::utils::SyncUpdateOperation;
 use crate::internal::config::up::UpConfig;
+use crate::internal::config::up::UpError;
+use crate::internal::config::up::UpLock;
 use crate::internal::config::up::UpOptions;
+use crate::internal::config::up::UpPlan;
+use crate::internal::config::up::UpStepSelection;
 use crate::internal::config::CommandSyntax;
 use crate::internal::config::ConfigExtendOptions;
 use crate::internal::config::ConfigLoader;
 use crate::internal::config::ConfigValue;
 use crate::internal::config::SyntaxOptArg;
 use crate::internal::config::SyntaxOptArgType;
+use crate::internal::env::is_offline;
 use crate::internal::env::shell_is_interactive;
 use crate::internal::errors::SyncUpdateError;
 use crate::internal::git::format_path_with_template;
@@ -64,10 +74,15 @@ use crate::omni_warning;
 struct UpCommandArgs {
     cache_enabled: bool,
     clone_suggested: UpCommandArgsCloneSuggestedOptions,
+    dry_run: bool,
     fail_on_upgrade: bool,
+    jobs: Option<usize>,
+    offline: bool,
+    only: Vec<String>,
     prompt: bool,
     prompt_all: bool,
     prompt_ids: HashSet<String>,
+    skip: Vec<String>,
     trust: UpCommandArgsTrustOptions,
     update_repository: bool,
     update_user_config: UpCommandArgsUpdateUserConfigOptions,
@@ -87,6 +102,11 @@ impl UpCommandArgs {
                     .long("no-cache")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                clap::Arg::new("dry-run")
+                    .long("dry-run")
+                    .action(clap::ArgAction::SetTrue),
+            )
             .arg(
                 clap::Arg::new("fail-on-upgrade")
                     .long("fail-on-upgrade")
@@ -97,6 +117,28 @@ impl UpCommandArgs {
                     .long("bootstrap")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                clap::Arg::new("jobs")
+                    .long("jobs")
+                    .short('j')
+                    .action(clap::ArgAction::Set)
+                    .value_parser(clap::value_parser!(usize)),
+            )
+            .arg(
+                clap::Arg::new("offline")
+                    .long("offline")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("only")
+                    .long("only")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                clap::Arg::new("skip")
+                    .long("skip")
+                    .action(clap::ArgAction::Append),
+            )
             .arg(
                 clap::Arg::new("clone-suggested")
                     .long("clone-suggested")
@@ -235,10 +277,21 @@ impl UpCommandArgs {
         Self {
             cache_enabled: !*matches.get_one::<bool>("no-cache").unwrap_or(&false),
             clone_suggested,
+            dry_run: *matches.get_one::<bool>("dry-run").unwrap_or(&false),
             fail_on_upgrade: *matches.get_one::<bool>("fail-on-upgrade").unwrap_or(&false),
+            jobs: matches.get_one::<usize>("jobs").copied(),
+            offline: *matches.get_one::<bool>("offline").unwrap_or(&false) || is_offline(),
+            only: matches
+                .get_many::<String>("only")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
             prompt,
             prompt_all,
             prompt_ids,
+            skip: matches
+                .get_many::<String>("skip")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
             trust,
             update_repository: *matches
                 .get_one::<bool>("update-repository")
@@ -391,6 +444,139 @@ impl UpCommand {
         })
     }
 
+    /// Returns the selection of the steps to run from the `--only` and
+    /// `--skip` options, exiting with an error if any of the selectors
+    /// does not match a step of the configuration
+    fn selection(&self, up_config: Option<&UpConfig>) -> UpStepSelection {
+        let selection = UpStepSelection::new(&self.cli_args().only, &self.cli_args().skip);
+        if selection.is_empty() {
+            return selection;
+        }
+
+        if self.is_down() {
+            omni_error!(format!(
+                "the {} and {} options are only supported by {}",
+                "--only".light_yellow(),
+                "--skip".light_yellow(),
+                "omni up".bold(),
+            ));
+            exit(1);
+        }
+
+        let steps = up_config
+            .map(|up_config| up_config.steps.as_slice())
+            .unwrap_or_default();
+        let unmatched = selection.unmatched(steps);
+        if !unmatched.is_empty() {
+            omni_error!(format!(
+                "no step matches {}",
+                unmatched
+                    .iter()
+                    .map(|selector| selector.to_string().light_yellow())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+            exit(1);
+        }
+
+        selection
+    }
+
+    fn version_lock(&self) -> Result<UpLock, UpError> {
+        let wd_root = match workdir(".").root() {
+            Some(wd_root) => wd_root.to_string(),
+            None => std::env::current_dir()
+                .map(|dir| dir.to_string_lossy().to_string())
+                .unwrap_or_else(|_| ".".to_string()),
+        };
+
+        UpLock::for_workdir(&wd_root)
+    }
+
+    /// Prints the plan of what the command would do, without touching
+    /// the filesystem, and exits with a non-zero status if the
+    /// environment of the work directory is not up to date.
+    fn exec_dry_run(&self) -> ! {
+        let wd = workdir(".");
+        let wd_id = wd.id();
+
+        if self.is_down() && (!wd.in_workdir() || wd_id.is_none()) {
+            omni_info!(format!("Outside of a work directory, nothing to do."));
+            exit(0);
+        }
+
+        let current = wd_id
+            .as_ref()
+            .and_then(|wd_id| UpEnvironmentsCache::get().get_env(wd_id).cloned());
+
+        let plan = if self.is_up() {
+            let cfg = config(".");
+            let version_lock = self.version_lock().unwrap_or_else(|err| {
+                omni_warning!(format!("{}", err));
+                UpLock::empty()
+            });
+            let selection = self.selection(cfg.up.as_ref());
+            let options = UpOptions::new()
+                .cache(self.cli_args().cache_enabled)
+                .upgrade(self.cli_args().upgrade)
+                .offline(self.cli_args().offline)
+                .selection(selection.clone())
+                .version_lock(&version_lock);
+
+            // When only some of the steps are selected, we only compare
+            // with what those steps previously added to the environment
+            let current = match (&cfg.up, current) {
+                (Some(up_config), Some(current)) if !selection.is_empty() => {
+                    Some(current.for_steps(&up_config.selected_step_keys(&options)))
+                }
+                (_, current) => current,
+            };
+
+            let items = match &cfg.up {
+                Some(up_config) => up_config.plan(&options, current.as_ref()),
+                None => vec![],
+            };
+
+            let env_vars: Vec<EnvOperationConfig> = if selection.is_empty() {
+                cfg.env.clone().into()
+            } else {
+                vec![]
+            };
+            let env_vars = env_vars.into_iter().map(UpEnvVar::from).collect::<Vec<_>>();
+
+            let config_changed = match &current {
+                _ if !selection.is_empty() => false,
+                Some(current) => current.config_hash != cfg.up_hash(),
+                None => !items.is_empty() || !env_vars.is_empty(),
+            };
+
+            UpPlan::new(items, env_vars, current, config_changed)
+        } else {
+            let items = current
+                .as_ref()
+                .map(|current| {
+                    current
+                        .versions
+                        .iter()
+                        .map(|version| {
+                            UpPlanItem::new(
+                                version.tool_real_name.as_deref().unwrap_or(&version.tool),
+                                UpPlanAction::Remove,
+                            )
+                            .with_version(&version.version)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            UpPlan::new(items, vec![], current, false)
+        };
+
+        plan.print();
+
+        exit(if plan.is_up_to_date() { 0 } else { 1 });
+    }
+
     fn handle_prompts(&self) -> bool {
         // Skip prompts if the user has disabled them
         if !self.cli_args().prompt {
@@ -407,11 +593,19 @@ impl UpCommand {
         for prompt in prompts.iter() {
             let prompt = match prompt.in_context() {
                 Ok(prompt) => prompt,
-                Err(_err) => continue,
+                Err(err) => {
+                    omni_warning!(format!("prompt {}: {}", prompt.id, err));
+                    continue;
+                }
             };
 
-            if !prompt.should_prompt() {
-                continue;
+            match prompt.should_prompt() {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(err) => {
+                    omni_warning!(err);
+                    continue;
+                }
             }
 
             // TODO: How do we detect if the answer is obsolete?
@@ -1291,6 +1485,19 @@ impl BuiltinCommand for UpCommand {
                     arg_type: SyntaxOptArgType::Flag,
                     ..Default::default()
                 },
+                SyntaxOptArg {
+                    name: "--dry-run".to_string(),
+                    desc: Some(
+                        concat!(
+                            "Print what would be installed, upgraded or removed, and the changes ",
+                            "to the environment, without changing anything; exits with a non-zero ",
+                            "status if the environment is out of date \x1B[90m(default: no)\x1B[0m",
+                        )
+                        .to_string(),
+                    ),
+                    arg_type: SyntaxOptArgType::Flag,
+                    ..Default::default()
+                },
                 SyntaxOptArg {
                     name: "--fail-on-upgrade".to_string(),
                     desc: Some(
@@ -1304,6 +1511,67 @@ impl BuiltinCommand for UpCommand {
                     arg_type: SyntaxOptArgType::Flag,
                     ..Default::default()
                 },
+                SyntaxOptArg {
+                    name: "--jobs".to_string(),
+                    aliases: vec!["-j".to_string()],
+                    desc: Some(
+                        concat!(
+                            "The maximum number of steps to run at the same time; only steps that ",
+                            "do not depend on each other, like downloads of releases, are run ",
+                            "concurrently; overrides the \x1B[1mup_command.concurrency\x1B[0m ",
+                            "configuration \x1B[90m(default: 4)\x1B[0m",
+                        )
+                        .to_string(),
+                    ),
+                    placeholder: Some("N".to_string()),
+                    arg_type: SyntaxOptArgType::Integer,
+                    ..Default::default()
+                },
+                SyntaxOptArg {
+                    name: "--offline".to_string(),
+                    desc: Some(
+                        concat!(
+                            "Only use what is cached or already installed, without accessing ",
+                            "the network; steps that need to download something fail with an ",
+                            "error; can also be enabled by setting \x1B[1mOMNI_OFFLINE=1\x1B[0m ",
+                            "\x1B[90m(default: no)\x1B[0m",
+                        )
+                        .to_string(),
+                    ),
+                    arg_type: SyntaxOptArgType::Flag,
+                    ..Default::default()
+                },
+                SyntaxOptArg {
+                    name: "--only".to_string(),
+                    desc: Some(
+                        concat!(
+                            "Only run the steps matching the selector, which can be the type of ",
+                            "the step (e.g. \x1B[1mgithub-release\x1B[0m), the name of a resource ",
+                            "handled by the step (e.g. a repository or package name), or the ",
+                            "index of the step in the configuration, starting at 1; the ",
+                            "environment is updated for those steps only; can be repeated or ",
+                            "take a comma-separated list of selectors",
+                        )
+                        .to_string(),
+                    ),
+                    placeholder: Some("SELECTOR".to_string()),
+                    arg_type: SyntaxOptArgType::Array(Box::new(SyntaxOptArgType::String)),
+                    ..Default::default()
+                },
+                SyntaxOptArg {
+                    name: "--skip".to_string(),
+                    desc: Some(
+                        concat!(
+                            "Do not run the steps matching the selector, using the same selectors ",
+                            "as \x1B[1m--only\x1B[0m; the environment is updated for the other ",
+                            "steps only",
+                        )
+                        .to_string(),
+                    ),
+                    placeholder: Some("SELECTOR".to_string()),
+                    arg_type: SyntaxOptArgType::Array(Box::new(SyntaxOptArgType::String)),
+                    ..Default::default()
+                },
                 SyntaxOptArg {
                     name: "--bootstrap".to_string(),
                     desc: Some(
@@ -1433,6 +1701,11 @@ impl BuiltinCommand for UpCommand {
             unreachable!();
         }
 
+        // Let the commands run by the steps know that we are offline
+        if self.cli_args().offline {
+            std::env::set_var("OMNI_OFFLINE", "1");
+        }
+
         let wd = workdir(".");
         if let Some(wd_root) = wd.root() {
             // Switch directory to the work directory root so it can
@@ -1448,6 +1721,10 @@ impl BuiltinCommand for UpCommand {
             }
         }
 
+        if self.cli_args().dry_run {
+            self.exec_dry_run();
+        }
+
         if !self.update_repository() {
             if let (Some(wd_id), Some(git_commit)) = (wd.id(), git_env_fresh(".").commit()) {
                 if RepositoriesCache::get().check_fingerprint(
@@ -1554,6 +1831,8 @@ impl BuiltinCommand for UpCommand {
             exit(0);
         }
 
+        let selection = self.selection(up_config.as_ref());
+
         let trust = self.trust();
         if !trust {
             omni_info!(format!(
@@ -1570,6 +1849,16 @@ impl BuiltinCommand for UpCommand {
             exit(1);
         }
 
+        // Load the versions locked for the work directory, so that
+        // we install exactly the same versions as previous runs
+        let version_lock = match self.version_lock() {
+            Ok(version_lock) => version_lock,
+            Err(err) => {
+                omni_error!(format!("{}", err));
+                exit(1);
+            }
+        };
+
         // Read the head commit of the repository
         let head_commit = git_env_fresh(".").commit().map(|commit| commit.to_string());
 
@@ -1629,12 +1918,16 @@ impl BuiltinCommand for UpCommand {
         // Prepare the options for the up command
         let options = UpOptions::new().lock_file(&lock_file);
 
-        // No matter what's happening after, we want a clean cache for that
-        // repository, as we're rebuilding the up environment from scratch
-        UpConfig::clear_cache();
+        // Unless only some of the steps are selected, in which case the
+        // environment is updated for those steps only, we want a clean
+        // cache for that repository, as we're rebuilding the up
+        // environment from scratch
+        if selection.is_empty() {
+            UpConfig::clear_cache();
+        }
 
         // If there are environment variables to set, do it
-        if has_up_config && self.is_up() {
+        if has_up_config && self.is_up() && selection.is_empty() {
             if let Err(err) = UpEnvironmentsCache::exclusive(|up_env| {
                 let wd = workdir(".");
                 if let Some(workdir_id) = wd.id() {
@@ -1672,7 +1965,19 @@ impl BuiltinCommand for UpCommand {
                     .clone()
                     .cache(self.cli_args().cache_enabled)
                     .fail_on_upgrade(self.cli_args().fail_on_upgrade)
-                    .upgrade(self.cli_args().upgrade);
+                    .upgrade(self.cli_args().upgrade)
+                    .offline(self.cli_args().offline)
+                    .concurrency(
+                        self.cli_args()
+                            .jobs
+                            .unwrap_or(global_config().up_command.concurrency),
+                    )
+                    .retry(RetryPolicy::new(
+                        config(".").up_command.retries,
+                        config(".").up_command.timeout,
+                    ))
+                    .selection(selection.clone())
+                    .version_lock(&version_lock);
                 if let Err(err) = up_config.up(&options) {
                     self.handle_sync_operation(
                         SyncUpdateOperation::OmniError(format!(
@@ -1684,6 +1989,24 @@ impl BuiltinCommand for UpCommand {
                     self.handle_sync_operation(SyncUpdateOperation::Exit(1), &options);
                 }
 
+                match version_lock.save(self.cli_args().upgrade) {
+                    Ok(true) => self.handle_sync_operation(
+                        SyncUpdateOperation::OmniInfo(format!(
+                            "Locked versions written to {}",
+                            ".omni/up.lock".light_yellow()
+                        )),
+                        &options,
+                    ),
+                    Ok(false) => {}
+                    Err(err) => self.handle_sync_operation(
+                        SyncUpdateOperation::OmniWarning(format!(
+                            "failed to write lock file: {}",
+                            err
+                        )),
+                        &options,
+                    ),
+                }
+
                 if let (Some(wd_id), Some(git_commit)) = (wd.id(), head_commit) {
                     if let Err(err) = RepositoriesCache::exclusive(|repos| {
                         repos.update_fingerprint(&wd_id, "head_commit", fingerprint(&git_commit))
@@ -1725,10 +2048,15 @@ impl BuiltinCommand for UpCommand {
     fn autocomplete(&self, _comp_cword: usize, _argv: Vec<String>) -> Result<(), ()> {
         println!("--bootstrap");
         println!("--clone-suggested");
+        println!("--dry-run");
         println!("--fail-on-upgrade");
+        println!("--jobs");
         println!("--no-cache");
+        println!("--offline");
+        println!("--only");
         println!("--prompt");
         println!("--prompt-all");
+        println!("--skip");
         println!("--trust");
         println!("--update-repository");
         println!("--update-user-config");
@@ -0,0 +1,47 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::internal::config::utils::parse_duration_or_default;
+use crate::internal::config::ConfigValue;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CargoInstallCacheConfig {
+    pub versions_expire: u64,
+    pub cleanup_after: u64,
+}
+
+impl Default for CargoInstallCacheConfig {
+    fn default() -> Self {
+        Self {
+            versions_expire: Self::DEFAULT_VERSIONS_EXPIRE,
+            cleanup_after: Self::DEFAULT_CLEANUP_AFTER,
+        }
+    }
+}
+
+impl CargoInstallCacheConfig {
+    const DEFAULT_VERSIONS_EXPIRE: u64 = 86400; // 1 day
+    const DEFAULT_CLEANUP_AFTER: u64 = 604800; // 1 week
+
+    pub fn from_config_value(config_value: Option<ConfigValue>) -> Self {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return Self::default(),
+        };
+
+        let versions_expire = parse_duration_or_default(
+            config_value.get("versions_expire").as_ref(),
+            Self::DEFAULT_VERSIONS_EXPIRE,
+        );
+
+        let cleanup_after = parse_duration_or_default(
+            config_value.get("cleanup_after").as_ref(),
+            Self::DEFAULT_CLEANUP_AFTER,
+        );
+
+        Self {
+            versions_expire,
+            cleanup_after,
+        }
+    }
+}
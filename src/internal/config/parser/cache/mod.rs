@@ -4,8 +4,17 @@ pub(crate) use root::CacheConfig;
 mod asdf;
 pub(crate) use asdf::AsdfCacheConfig;
 
+mod cargo_install;
+pub(crate) use cargo_install::CargoInstallCacheConfig;
+
+mod download;
+pub(crate) use download::DownloadCacheConfig;
+
 mod github_release;
 pub(crate) use github_release::GithubReleaseCacheConfig;
 
 mod homebrew;
 pub(crate) use homebrew::HomebrewCacheConfig;
+
+mod system_package;
+pub(crate) use system_package::SystemPackageCacheConfig;
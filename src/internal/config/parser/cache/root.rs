@@ -2,8 +2,11 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::internal::config::parser::cache::AsdfCacheConfig;
+use crate::internal::config::parser::cache::CargoInstallCacheConfig;
+use crate::internal::config::parser::cache::DownloadCacheConfig;
 use crate::internal::config::parser::cache::GithubReleaseCacheConfig;
 use crate::internal::config::parser::cache::HomebrewCacheConfig;
+use crate::internal::config::parser::cache::SystemPackageCacheConfig;
 use crate::internal::config::ConfigValue;
 use crate::internal::env::cache_home;
 
@@ -11,8 +14,13 @@ use crate::internal::env::cache_home;
 pub struct CacheConfig {
     pub path: String,
     pub asdf: AsdfCacheConfig,
+    pub cargo_install: CargoInstallCacheConfig,
+    pub download: DownloadCacheConfig,
     pub github_release: GithubReleaseCacheConfig,
+    pub gitea_release: GithubReleaseCacheConfig,
+    pub gitlab_release: GithubReleaseCacheConfig,
     pub homebrew: HomebrewCacheConfig,
+    pub system_package: SystemPackageCacheConfig,
 }
 
 impl Default for CacheConfig {
@@ -20,8 +28,13 @@ impl Default for CacheConfig {
         Self {
             path: cache_home(),
             asdf: AsdfCacheConfig::default(),
+            cargo_install: CargoInstallCacheConfig::default(),
+            download: DownloadCacheConfig::default(),
             github_release: GithubReleaseCacheConfig::default(),
+            gitea_release: GithubReleaseCacheConfig::default(),
+            gitlab_release: GithubReleaseCacheConfig::default(),
             homebrew: HomebrewCacheConfig::default(),
+            system_package: SystemPackageCacheConfig::default(),
         }
     }
 }
@@ -39,15 +52,29 @@ impl CacheConfig {
         };
 
         let asdf = AsdfCacheConfig::from_config_value(config_value.get("asdf"));
+        let cargo_install =
+            CargoInstallCacheConfig::from_config_value(config_value.get("cargo_install"));
+        let download = DownloadCacheConfig::from_config_value(config_value.get("download"));
         let github_release =
             GithubReleaseCacheConfig::from_config_value(config_value.get("github_release"));
+        let gitea_release =
+            GithubReleaseCacheConfig::from_config_value(config_value.get("gitea_release"));
+        let gitlab_release =
+            GithubReleaseCacheConfig::from_config_value(config_value.get("gitlab_release"));
         let homebrew = HomebrewCacheConfig::from_config_value(config_value.get("homebrew"));
+        let system_package =
+            SystemPackageCacheConfig::from_config_value(config_value.get("system_package"));
 
         Self {
             path,
             asdf,
+            cargo_install,
+            download,
             github_release,
+            gitea_release,
+            gitlab_release,
             homebrew,
+            system_package,
         }
     }
 }
@@ -52,7 +52,7 @@ impl Empty for EnvConfig {
 }
 
 impl EnvConfig {
-    pub(super) fn from_config_value(config_value: Option<ConfigValue>) -> Self {
+    pub(crate) fn from_config_value(config_value: Option<ConfigValue>) -> Self {
         let operations = if let Some(config_value) = config_value {
             let operations_array = if let Some(array) = config_value.as_array() {
                 array
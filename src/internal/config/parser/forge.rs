@@ -0,0 +1,208 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::internal::cache::utils::Empty;
+use crate::internal::config::parser::github::StringFilter;
+use crate::internal::config::ConfigValue;
+
+/// Configuration for a software forge other than GitHub (e.g. GitLab,
+/// Gitea) from which releases can be downloaded.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ForgeConfig {
+    #[serde(default, rename = "auth", skip_serializing_if = "Vec::is_empty")]
+    auth_list: Vec<ForgeAuthConfigWithFilters>,
+}
+
+impl Empty for ForgeConfig {
+    fn is_empty(&self) -> bool {
+        self.auth_list.is_empty()
+    }
+}
+
+impl ForgeConfig {
+    pub(super) fn from_config_value(config_value: Option<ConfigValue>) -> Self {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return Self::default(),
+        };
+
+        Self {
+            auth_list: ForgeAuthConfigWithFilters::from_config_value_multi(
+                config_value.get("auth"),
+            ),
+        }
+    }
+
+    pub fn auth_for(&self, repo: &str, api_hostname: &str) -> ForgeAuthConfig {
+        self.auth_list
+            .iter()
+            .find(|auth| auth.matches(repo, api_hostname))
+            .map(|auth| auth.auth.clone())
+            .unwrap_or(ForgeAuthConfig::default())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct ForgeAuthConfigWithFilters {
+    #[serde(
+        default,
+        with = "serde_yaml::with::singleton_map",
+        skip_serializing_if = "StringFilter::is_default"
+    )]
+    pub repo: StringFilter,
+    #[serde(
+        default,
+        with = "serde_yaml::with::singleton_map",
+        skip_serializing_if = "StringFilter::is_default"
+    )]
+    pub hostname: StringFilter,
+    #[serde(flatten)]
+    pub auth: ForgeAuthConfig,
+}
+
+impl ForgeAuthConfigWithFilters {
+    pub fn matches(&self, repo: &str, api_hostname: &str) -> bool {
+        self.repo.matches(repo) && self.hostname.matches(api_hostname)
+    }
+
+    pub(super) fn from_config_value_multi(config_value: Option<ConfigValue>) -> Vec<Self> {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return vec![],
+        };
+
+        if let Some(array) = config_value.as_array() {
+            array
+                .iter()
+                .map(ForgeAuthConfigWithFilters::from_config_value)
+                .collect()
+        } else {
+            vec![ForgeAuthConfigWithFilters::from_config_value(&config_value)]
+        }
+    }
+
+    fn from_config_value(config_value: &ConfigValue) -> Self {
+        Self {
+            repo: StringFilter::from_config_value(config_value.get("repo")),
+            hostname: StringFilter::from_config_value(config_value.get("hostname")),
+            auth: ForgeAuthConfig::from_config_value(Some(config_value.clone())),
+        }
+    }
+}
+
+/// The authentication to use for the API requests of a forge; by
+/// default, the token is read from the usual environment variable
+/// of the forge (e.g. `GITLAB_TOKEN`) if it is set.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ForgeAuthConfig {
+    Token(String),
+    TokenEnvVar(String),
+    Skip(bool),
+    #[default]
+    Auto,
+}
+
+impl ForgeAuthConfig {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub(in crate::internal::config) fn from_config_value(
+        config_value: Option<ConfigValue>,
+    ) -> Self {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return Self::default(),
+        };
+
+        if let Some(string) = config_value.as_str() {
+            return match string.as_str() {
+                "skip" => Self::Skip(true),
+                "auto" => Self::Auto,
+                _ => {
+                    // If all caps and underscores, consider it's an environment variable
+                    if string.chars().all(|c| c.is_uppercase() || c == '_') {
+                        Self::TokenEnvVar(string.to_string())
+                    } else {
+                        Self::Token(string.to_string())
+                    }
+                }
+            };
+        } else if let Some(table) = config_value.as_table() {
+            if let Some(skip) = table.get("skip") {
+                if skip.as_bool().unwrap_or(false) {
+                    return Self::Skip(true);
+                }
+            }
+
+            if let Some(token_env_var) = table.get("token_env_var") {
+                if let Some(token_env_var) = token_env_var.as_str_forced() {
+                    return Self::TokenEnvVar(token_env_var.to_string());
+                }
+            }
+
+            if let Some(token) = table.get("token") {
+                if let Some(token) = token.as_str_forced() {
+                    return Self::Token(token.to_string());
+                }
+            }
+        }
+
+        Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_from_config_value() {
+        let config_value = ConfigValue::from_str("GITLAB_PRIVATE_TOKEN").unwrap();
+        assert_eq!(
+            ForgeAuthConfig::from_config_value(Some(config_value)),
+            ForgeAuthConfig::TokenEnvVar("GITLAB_PRIVATE_TOKEN".to_string())
+        );
+
+        let config_value = ConfigValue::from_str("glpat-abc123").unwrap();
+        assert_eq!(
+            ForgeAuthConfig::from_config_value(Some(config_value)),
+            ForgeAuthConfig::Token("glpat-abc123".to_string())
+        );
+
+        let config_value = ConfigValue::from_str("{\"skip\": true}").unwrap();
+        assert_eq!(
+            ForgeAuthConfig::from_config_value(Some(config_value)),
+            ForgeAuthConfig::Skip(true)
+        );
+
+        assert_eq!(
+            ForgeAuthConfig::from_config_value(None),
+            ForgeAuthConfig::Auto
+        );
+    }
+
+    #[test]
+    fn test_auth_for() {
+        let config_value = ConfigValue::from_str(
+            r#"{"auth": [{"hostname": "gitlab.example.com", "token_env_var": "EXAMPLE_TOKEN"}, {"repo": "group/*", "token": "abc"}]}"#,
+        )
+        .unwrap();
+        let config = ForgeConfig::from_config_value(Some(config_value));
+
+        assert_eq!(
+            config.auth_for("owner/repo", "gitlab.example.com"),
+            ForgeAuthConfig::TokenEnvVar("EXAMPLE_TOKEN".to_string())
+        );
+        assert_eq!(
+            config.auth_for("group/repo", "gitlab.com"),
+            ForgeAuthConfig::Token("abc".to_string())
+        );
+        assert_eq!(
+            config.auth_for("owner/repo", "gitlab.com"),
+            ForgeAuthConfig::Auto
+        );
+    }
+}
@@ -34,6 +34,10 @@ pub(crate) use env::EnvOperationEnum;
 mod errors;
 pub(crate) use errors::ParseArgsErrorKind;
 
+mod forge;
+pub(crate) use forge::ForgeAuthConfig;
+pub(crate) use forge::ForgeConfig;
+
 mod github;
 pub(crate) use github::GithubAuthConfig;
 pub(crate) use github::GithubConfig;
@@ -44,6 +48,9 @@ pub(crate) use makefile_commands::MakefileCommandsConfig;
 mod match_skip_prompt_if_config;
 pub(crate) use match_skip_prompt_if_config::MatchSkipPromptIfConfig;
 
+mod network;
+pub(crate) use network::NetworkConfig;
+
 mod omniconfig;
 pub(crate) use omniconfig::OmniConfig;
 
@@ -70,5 +77,8 @@ pub(crate) use suggest_clone::SuggestCloneConfig;
 mod suggest_config;
 pub(crate) use suggest_config::SuggestConfig;
 
+mod tool_registry;
+pub(crate) use tool_registry::ToolRegistryConfig;
+
 mod up_command;
 pub(crate) use up_command::UpCommandConfig;
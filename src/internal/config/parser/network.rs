@@ -0,0 +1,332 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::internal::cache::utils::Empty;
+use crate::internal::config::utils::config_relative_path;
+use crate::internal::config::ConfigScope;
+use crate::internal::config::ConfigValue;
+
+/// Configuration of the network accesses of omni, applied to all the
+/// HTTP requests it makes, e.g. to list and download releases or to
+/// update itself.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct NetworkConfig {
+    #[serde(default, skip_serializing_if = "NetworkProxyConfig::is_empty")]
+    pub proxy: NetworkProxyConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ca_certificates: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<NetworkMirrorConfig>,
+}
+
+impl Empty for NetworkConfig {
+    fn is_empty(&self) -> bool {
+        self.proxy.is_empty() && self.ca_certificates.is_empty() && self.mirrors.is_empty()
+    }
+}
+
+impl NetworkConfig {
+    pub(super) fn from_config_value(config_value: Option<ConfigValue>) -> Self {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return Self::default(),
+        };
+
+        // Redirecting the requests or trusting other certificates should
+        // never be decided by a work directory, so we only consider the
+        // values of the user configuration
+        let config_value = match config_value.reject_scope(&ConfigScope::Workdir) {
+            Some(config_value) => config_value,
+            None => return Self::default(),
+        };
+
+        let ca_certificates = match config_value.get("ca_certificates") {
+            Some(value) => match value.as_array() {
+                Some(array) => array.iter().filter_map(config_relative_path).collect(),
+                None => config_relative_path(&value).into_iter().collect(),
+            },
+            None => vec![],
+        };
+
+        let mut mirrors = config_value
+            .get_as_table("mirrors")
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(prefix, mirror)| {
+                mirror.as_str_forced().map(|mirror| NetworkMirrorConfig {
+                    prefix: prefix.to_string(),
+                    mirror,
+                })
+            })
+            .collect::<Vec<_>>();
+        // The most specific prefix takes precedence
+        mirrors.sort_by(|a, b| {
+            b.prefix
+                .len()
+                .cmp(&a.prefix.len())
+                .then_with(|| a.prefix.cmp(&b.prefix))
+        });
+
+        Self {
+            proxy: NetworkProxyConfig::from_config_value(config_value.get("proxy")),
+            ca_certificates,
+            mirrors,
+        }
+    }
+
+    /// Returns the URL to use to access the given URL, which is the
+    /// given URL with its prefix replaced by the matching mirror, if any
+    pub fn rewrite_url(&self, url: &str) -> String {
+        match self
+            .mirrors
+            .iter()
+            .find(|mirror| url.starts_with(&mirror.prefix))
+        {
+            Some(mirror) => format!("{}{}", mirror.mirror, &url[mirror.prefix.len()..]),
+            None => url.to_string(),
+        }
+    }
+
+    /// Returns a client builder configured with the proxies and the
+    /// additional certificates to trust
+    pub fn client_builder(&self) -> Result<reqwest::blocking::ClientBuilder, String> {
+        let mut client_builder = reqwest::blocking::Client::builder();
+
+        for proxy in self.proxy.proxies()? {
+            client_builder = client_builder.proxy(proxy);
+        }
+
+        for path in &self.ca_certificates {
+            let contents = std::fs::read(path)
+                .map_err(|err| format!("failed to read CA certificates {}: {}", path, err))?;
+            let certificates = reqwest::Certificate::from_pem_bundle(&contents)
+                .map_err(|err| format!("invalid CA certificates {}: {}", path, err))?;
+            for certificate in certificates {
+                client_builder = client_builder.add_root_certificate(certificate);
+            }
+        }
+
+        Ok(client_builder)
+    }
+
+    /// Returns a client configured with the proxies and the additional
+    /// certificates to trust
+    pub fn client(&self) -> Result<reqwest::blocking::Client, String> {
+        self.client_builder()?
+            .build()
+            .map_err(|err| format!("failed to create client: {}", err))
+    }
+}
+
+/// The proxies to use for the HTTP requests; when none is configured,
+/// the `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` environment variables
+/// are used if set.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct NetworkProxyConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub https: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub no_proxy: Vec<String>,
+}
+
+impl NetworkProxyConfig {
+    pub fn is_empty(&self) -> bool {
+        self.http.is_none() && self.https.is_none() && self.no_proxy.is_empty()
+    }
+
+    fn from_config_value(config_value: Option<ConfigValue>) -> Self {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return Self::default(),
+        };
+
+        // A single proxy can be provided for all the requests
+        if let Some(proxy) = config_value.as_str_forced() {
+            return Self {
+                http: Some(proxy.clone()),
+                https: Some(proxy),
+                no_proxy: vec![],
+            };
+        }
+
+        let no_proxy = match config_value.get("no_proxy") {
+            Some(value) => match value.as_array() {
+                Some(array) => array.iter().filter_map(|value| value.as_str()).collect(),
+                None => value
+                    .as_str_forced()
+                    .map(|value| {
+                        value
+                            .split(',')
+                            .map(|host| host.trim().to_string())
+                            .filter(|host| !host.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
+            None => vec![],
+        };
+
+        Self {
+            http: config_value.get_as_str_forced("http"),
+            https: config_value.get_as_str_forced("https"),
+            no_proxy,
+        }
+    }
+
+    fn proxies(&self) -> Result<Vec<reqwest::Proxy>, String> {
+        let no_proxy = reqwest::NoProxy::from_string(&self.no_proxy.join(","));
+
+        let mut proxies = vec![];
+        if let Some(http) = &self.http {
+            let proxy = reqwest::Proxy::http(http)
+                .map_err(|err| format!("invalid http proxy {}: {}", http, err))?;
+            proxies.push(proxy.no_proxy(no_proxy.clone()));
+        }
+        if let Some(https) = &self.https {
+            let proxy = reqwest::Proxy::https(https)
+                .map_err(|err| format!("invalid https proxy {}: {}", https, err))?;
+            proxies.push(proxy.no_proxy(no_proxy.clone()));
+        }
+
+        Ok(proxies)
+    }
+}
+
+/// A mirror to use instead of the URLs starting with a given prefix
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NetworkMirrorConfig {
+    pub prefix: String,
+    pub mirror: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> NetworkConfig {
+        let config_value = ConfigValue::from_str(yaml).expect("invalid yaml");
+        NetworkConfig::from_config_value(Some(config_value))
+    }
+
+    #[test]
+    fn from_config_value() {
+        let config = parse(
+            r#"{"proxy": "http://proxy.example.com:3128", "ca_certificates": "/etc/ssl/corp.pem", "mirrors": {"https://github.com/": "https://mirror.example.com/github/"}}"#,
+        );
+        assert_eq!(
+            config.proxy,
+            NetworkProxyConfig {
+                http: Some("http://proxy.example.com:3128".to_string()),
+                https: Some("http://proxy.example.com:3128".to_string()),
+                no_proxy: vec![],
+            }
+        );
+        assert_eq!(
+            config.ca_certificates,
+            vec!["/etc/ssl/corp.pem".to_string()]
+        );
+        assert_eq!(
+            config.mirrors,
+            vec![NetworkMirrorConfig {
+                prefix: "https://github.com/".to_string(),
+                mirror: "https://mirror.example.com/github/".to_string(),
+            }]
+        );
+
+        let config = parse(
+            r#"{"proxy": {"https": "http://proxy.example.com:3128", "no_proxy": "localhost, .corp"}}"#,
+        );
+        assert_eq!(
+            config.proxy,
+            NetworkProxyConfig {
+                http: None,
+                https: Some("http://proxy.example.com:3128".to_string()),
+                no_proxy: vec!["localhost".to_string(), ".corp".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn rewrite_url() {
+        let config = parse(
+            r#"{"mirrors": {"https://github.com/": "https://mirror.example.com/github/", "https://github.com/omnicli/": "https://mirror.example.com/omnicli/"}}"#,
+        );
+
+        assert_eq!(
+            config.rewrite_url("https://github.com/cli/cli/releases/download/v1/gh.tar.gz"),
+            "https://mirror.example.com/github/cli/cli/releases/download/v1/gh.tar.gz"
+        );
+        assert_eq!(
+            config.rewrite_url("https://github.com/omnicli/omni/releases"),
+            "https://mirror.example.com/omnicli/omni/releases"
+        );
+        assert_eq!(
+            config.rewrite_url("https://api.github.com/repos/cli/cli/releases"),
+            "https://api.github.com/repos/cli/cli/releases"
+        );
+    }
+
+    #[test]
+    fn client_with_mirror() {
+        let mut mock_server = mockito::Server::new();
+        let mock = mock_server
+            .mock("GET", "/mirror/tool.tar.gz")
+            .with_status(200)
+            .with_body("mirrored")
+            .create();
+
+        let config = NetworkConfig {
+            mirrors: vec![NetworkMirrorConfig {
+                prefix: "https://downloads.example.com/".to_string(),
+                mirror: format!("{}/mirror/", mock_server.url()),
+            }],
+            ..NetworkConfig::default()
+        };
+
+        let client = config.client().expect("failed to create client");
+        let response = client
+            .get(config.rewrite_url("https://downloads.example.com/tool.tar.gz"))
+            .send()
+            .expect("request failed");
+        assert_eq!(response.text().expect("failed to read body"), "mirrored");
+        mock.assert();
+    }
+
+    #[test]
+    fn client_with_proxy() {
+        let mut mock_server = mockito::Server::new();
+        let mock = mock_server
+            .mock("GET", "/tool.tar.gz")
+            .match_header("host", "downloads.example.com")
+            .with_status(200)
+            .with_body("proxied")
+            .create();
+
+        let config = NetworkConfig {
+            proxy: NetworkProxyConfig {
+                http: Some(mock_server.url()),
+                ..NetworkProxyConfig::default()
+            },
+            ..NetworkConfig::default()
+        };
+
+        let client = config.client().expect("failed to create client");
+        let response = client
+            .get("http://downloads.example.com/tool.tar.gz")
+            .send()
+            .expect("request failed");
+        assert_eq!(response.text().expect("failed to read body"), "proxied");
+        mock.assert();
+    }
+
+    #[test]
+    fn client_with_invalid_ca_certificates() {
+        let config = NetworkConfig {
+            ca_certificates: vec!["/nonexistent/ca.pem".to_string()],
+            ..NetworkConfig::default()
+        };
+        assert!(config.client().is_err());
+    }
+}
@@ -12,15 +12,18 @@ use crate::internal::config::parser::CloneConfig;
 use crate::internal::config::parser::CommandDefinition;
 use crate::internal::config::parser::ConfigCommandsConfig;
 use crate::internal::config::parser::EnvConfig;
+use crate::internal::config::parser::ForgeConfig;
 use crate::internal::config::parser::GithubConfig;
 use crate::internal::config::parser::MakefileCommandsConfig;
 use crate::internal::config::parser::MatchSkipPromptIfConfig;
+use crate::internal::config::parser::NetworkConfig;
 use crate::internal::config::parser::PathConfig;
 use crate::internal::config::parser::PathRepoUpdatesConfig;
 use crate::internal::config::parser::PromptsConfig;
 use crate::internal::config::parser::ShellAliasesConfig;
 use crate::internal::config::parser::SuggestCloneConfig;
 use crate::internal::config::parser::SuggestConfig;
+use crate::internal::config::parser::ToolRegistryConfig;
 use crate::internal::config::parser::UpCommandConfig;
 use crate::internal::config::up::UpConfig;
 use crate::internal::config::ConfigScope;
@@ -61,9 +64,15 @@ pub struct OmniConfig {
     pub config_commands: ConfigCommandsConfig,
     #[serde(skip_serializing_if = "EnvConfig::is_empty")]
     pub env: EnvConfig,
+    #[serde(skip_serializing_if = "ForgeConfig::is_empty")]
+    pub gitea: ForgeConfig,
     #[serde(skip_serializing_if = "GithubConfig::is_empty")]
     pub github: GithubConfig,
+    #[serde(skip_serializing_if = "ForgeConfig::is_empty")]
+    pub gitlab: ForgeConfig,
     pub makefile_commands: MakefileCommandsConfig,
+    #[serde(skip_serializing_if = "NetworkConfig::is_empty")]
+    pub network: NetworkConfig,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub org: Vec<OrgConfig>,
     pub path: PathConfig,
@@ -78,6 +87,8 @@ pub struct OmniConfig {
     pub suggest_clone: SuggestCloneConfig,
     #[serde(skip_serializing_if = "SuggestConfig::is_empty")]
     pub suggest_config: SuggestConfig,
+    #[serde(skip_serializing_if = "ToolRegistryConfig::is_empty")]
+    pub tool_registry: ToolRegistryConfig,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub up: Option<UpConfig>,
     pub up_command: UpCommandConfig,
@@ -130,10 +141,13 @@ impl OmniConfig {
                 config_value.get("config_commands"),
             ),
             env: EnvConfig::from_config_value(config_value.get("env")),
+            gitea: ForgeConfig::from_config_value(config_value.get("gitea")),
             github: GithubConfig::from_config_value(config_value.get("github")),
+            gitlab: ForgeConfig::from_config_value(config_value.get("gitlab")),
             makefile_commands: MakefileCommandsConfig::from_config_value(
                 config_value.get("makefile_commands"),
             ),
+            network: NetworkConfig::from_config_value(config_value.get("network")),
             org: org_config,
             path: PathConfig::from_config_value(config_value.get("path")),
             path_repo_updates: PathRepoUpdatesConfig::from_config_value(
@@ -147,6 +161,7 @@ impl OmniConfig {
             shell_aliases: ShellAliasesConfig::from_config_value(config_value.get("shell_aliases")),
             suggest_clone: SuggestCloneConfig::from_config_value(config_value.get("suggest_clone")),
             suggest_config: SuggestConfig::from_config_value(config_value.get("suggest_config")),
+            tool_registry: ToolRegistryConfig::from_config_value(config_value.get("tool_registry")),
             up: UpConfig::from_config_value(config_value.get("up")),
             up_command: UpCommandConfig::from_config_value(config_value.get("up_command")),
             worktree: config_value
@@ -20,7 +20,7 @@ pub struct PathConfig {
 }
 
 impl PathConfig {
-    pub(super) fn from_config_value(config_value: Option<ConfigValue>) -> Self {
+    pub(crate) fn from_config_value(config_value: Option<ConfigValue>) -> Self {
         let config_value = match config_value {
             Some(config_value) => config_value,
             None => return Self::default(),
@@ -3,7 +3,9 @@ use std::collections::HashMap;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::internal::config::up::utils::SignatureConfig;
 use crate::internal::config::utils::parse_duration_or_default;
+use crate::internal::config::ConfigScope;
 use crate::internal::config::ConfigValue;
 use crate::internal::env::shell_is_interactive;
 use crate::internal::git::update_git_repo;
@@ -12,6 +14,13 @@ use crate::internal::git::update_git_repo;
 pub struct PathRepoUpdatesConfig {
     pub enabled: bool,
     pub self_update: PathRepoUpdatesSelfUpdateEnum,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "serde_yaml::with::singleton_map"
+    )]
+    pub self_update_signature: Option<SignatureConfig>,
+    #[serde(skip)]
+    pub self_update_signature_error: Option<String>,
     pub on_command_not_found: PathRepoUpdatesOnCommandNotFoundEnum,
     pub pre_auth: bool,
     pub pre_auth_timeout: u64,
@@ -30,6 +39,8 @@ impl Default for PathRepoUpdatesConfig {
         Self {
             enabled: Self::DEFAULT_ENABLED,
             self_update: PathRepoUpdatesSelfUpdateEnum::default(),
+            self_update_signature: None,
+            self_update_signature_error: None,
             on_command_not_found: PathRepoUpdatesOnCommandNotFoundEnum::default(),
             pre_auth: Self::DEFAULT_PRE_AUTH,
             pre_auth_timeout: Self::DEFAULT_PRE_AUTH_TIMEOUT,
@@ -91,6 +102,20 @@ impl PathRepoUpdatesConfig {
             PathRepoUpdatesSelfUpdateEnum::default()
         };
 
+        // Which signatures to trust for the updates of omni should never
+        // be decided by a work directory, so we only consider the values
+        // of the user configuration
+        let (self_update_signature, self_update_signature_error) = match config_value
+            .get("self_update_signature")
+            .and_then(|value| value.reject_scope(&ConfigScope::Workdir))
+        {
+            Some(value) => match SignatureConfig::from_config_value(Some(&value)) {
+                Ok(signature) => (signature, None),
+                Err(err) => (None, Some(err)),
+            },
+            None => (None, None),
+        };
+
         let on_command_not_found =
             if let Some(value) = config_value.get_as_bool("on_command_not_found") {
                 PathRepoUpdatesOnCommandNotFoundEnum::from_bool(value)
@@ -107,6 +132,8 @@ impl PathRepoUpdatesConfig {
                 .get_as_bool_forced("enabled")
                 .unwrap_or(Self::DEFAULT_ENABLED),
             self_update,
+            self_update_signature,
+            self_update_signature_error,
             on_command_not_found,
             pre_auth: config_value
                 .get_as_bool_forced("pre_auth")
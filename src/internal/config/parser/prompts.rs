@@ -7,6 +7,7 @@ use crate::internal::cache::utils::CacheObject;
 use crate::internal::cache::utils::Empty;
 use crate::internal::cache::PromptsCache;
 use crate::internal::config::template::config_template_context;
+use crate::internal::config::template::evaluate_template_condition;
 use crate::internal::config::template::render_config_template;
 use crate::internal::config::template::tera_render_error_message;
 use crate::internal::config::ConfigValue;
@@ -146,15 +147,31 @@ impl PromptConfig {
         })
     }
 
-    pub fn should_prompt(&self) -> bool {
-        match &self.if_condition {
-            Some(if_condition) => {
-                let if_condition = if_condition.trim().to_lowercase();
+    /// Returns whether the prompt should be asked, by evaluating its
+    /// `if` condition, if any, in the template context (which gives
+    /// access to earlier answers through `prompts`), the same way as
+    /// the `if` condition of the steps of `up`.
+    pub fn should_prompt(&self) -> Result<bool, String> {
+        let if_condition = match &self.if_condition {
+            Some(if_condition) => if_condition.trim(),
+            None => return Ok(true),
+        };
 
-                matches!(if_condition.as_str(), "true" | "yes" | "on" | "1")
-            }
-            None => true,
+        // Keep supporting conditions that were fully resolved by
+        // the template rendering
+        match if_condition.to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => return Ok(true),
+            "" | "false" | "no" | "off" | "0" => return Ok(false),
+            _ => {}
         }
+
+        let context = config_template_context(".");
+        evaluate_template_condition(if_condition, &context).map_err(|err| {
+            format!(
+                "prompt {}: invalid condition '{}': {}",
+                self.id, if_condition, err
+            )
+        })
     }
 
     pub fn in_context(&self) -> Result<Self, String> {
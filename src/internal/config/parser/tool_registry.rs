@@ -0,0 +1,79 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::internal::cache::utils::Empty;
+use crate::internal::config::utils::config_relative_path;
+use crate::internal::config::ConfigScope;
+use crate::internal::config::ConfigValue;
+
+/// The registry files defining the tools that can be installed with
+/// the `tool` operation of `omni up`, in addition to or overriding the
+/// tools of the registry shipped with omni.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+pub struct ToolRegistryConfig {
+    pub files: Vec<String>,
+}
+
+impl Empty for ToolRegistryConfig {
+    fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl Serialize for ToolRegistryConfig {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.files.serialize(serializer)
+    }
+}
+
+impl ToolRegistryConfig {
+    pub(crate) fn from_config_value(config_value: Option<ConfigValue>) -> Self {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return Self::default(),
+        };
+
+        // The registry is shared by all the work directories, so it
+        // can only be defined in the user configuration
+        let config_value = match config_value.reject_scope(&ConfigScope::Workdir) {
+            Some(config_value) => config_value,
+            None => return Self::default(),
+        };
+
+        let files = match config_value.as_array() {
+            Some(array) => array.iter().filter_map(config_relative_path).collect(),
+            None => config_relative_path(&config_value).into_iter().collect(),
+        };
+
+        Self { files }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_config_value() {
+        let config_value =
+            ConfigValue::from_str(r#"["/etc/omni/tools.yaml", "/opt/tools.yaml"]"#).unwrap();
+        let config = ToolRegistryConfig::from_config_value(Some(config_value));
+        assert_eq!(
+            config.files,
+            vec![
+                "/etc/omni/tools.yaml".to_string(),
+                "/opt/tools.yaml".to_string()
+            ]
+        );
+
+        let config_value = ConfigValue::from_str(r#""/etc/omni/tools.yaml""#).unwrap();
+        let config = ToolRegistryConfig::from_config_value(Some(config_value));
+        assert_eq!(config.files, vec!["/etc/omni/tools.yaml".to_string()]);
+
+        let config = ToolRegistryConfig::from_config_value(None);
+        assert!(config.is_empty());
+    }
+}
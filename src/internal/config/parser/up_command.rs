@@ -7,9 +7,12 @@ use crate::internal::config::ConfigValue;
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpCommandConfig {
     pub auto_bootstrap: bool,
+    pub concurrency: usize,
     pub notify_workdir_config_updated: bool,
     pub notify_workdir_config_available: bool,
     pub preferred_tools: Vec<String>,
+    pub retries: u32,
+    pub timeout: Option<u64>,
     pub upgrade: bool,
 }
 
@@ -17,9 +20,12 @@ impl Default for UpCommandConfig {
     fn default() -> Self {
         Self {
             auto_bootstrap: Self::DEFAULT_AUTO_BOOTSTRAP,
+            concurrency: Self::DEFAULT_CONCURRENCY,
             notify_workdir_config_updated: Self::DEFAULT_NOTIFY_WORKDIR_CONFIG_UPDATED,
             notify_workdir_config_available: Self::DEFAULT_NOTIFY_WORKDIR_CONFIG_AVAILABLE,
             preferred_tools: Vec::new(),
+            retries: Self::DEFAULT_RETRIES,
+            timeout: None,
             upgrade: Self::DEFAULT_UPGRADE,
         }
     }
@@ -27,8 +33,10 @@ impl Default for UpCommandConfig {
 
 impl UpCommandConfig {
     const DEFAULT_AUTO_BOOTSTRAP: bool = true;
+    const DEFAULT_CONCURRENCY: usize = 4;
     const DEFAULT_NOTIFY_WORKDIR_CONFIG_UPDATED: bool = true;
     const DEFAULT_NOTIFY_WORKDIR_CONFIG_AVAILABLE: bool = true;
+    const DEFAULT_RETRIES: u32 = 0;
     const DEFAULT_UPGRADE: bool = false;
 
     pub(super) fn from_config_value(config_value: Option<ConfigValue>) -> Self {
@@ -60,6 +68,10 @@ impl UpCommandConfig {
             auto_bootstrap: config_value_global
                 .get_as_bool_forced("auto_bootstrap")
                 .unwrap_or(Self::DEFAULT_AUTO_BOOTSTRAP),
+            concurrency: config_value_global
+                .get_as_unsigned_integer("concurrency")
+                .map(|concurrency| concurrency.max(1) as usize)
+                .unwrap_or(Self::DEFAULT_CONCURRENCY),
             notify_workdir_config_updated: config_value_global
                 .get_as_bool_forced("notify_workdir_config_updated")
                 .unwrap_or(Self::DEFAULT_NOTIFY_WORKDIR_CONFIG_UPDATED),
@@ -67,6 +79,15 @@ impl UpCommandConfig {
                 .get_as_bool_forced("notify_workdir_config_available")
                 .unwrap_or(Self::DEFAULT_NOTIFY_WORKDIR_CONFIG_AVAILABLE),
             preferred_tools,
+            // The retries and timeout depend on the network and mirrors
+            // that a work directory uses, so they can be overridden there
+            retries: config_value
+                .get_as_unsigned_integer("retries")
+                .map(|retries| retries.min(u32::MAX as u64) as u32)
+                .unwrap_or(Self::DEFAULT_RETRIES),
+            timeout: config_value
+                .get_as_unsigned_integer("timeout")
+                .filter(|timeout| *timeout > 0),
             // The upgrade option is fine to handle as a workdir option too
             upgrade: config_value
                 .get_as_bool_forced("upgrade")
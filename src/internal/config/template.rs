@@ -53,6 +53,10 @@ pub fn config_template_context<T: AsRef<str>>(path: T) -> tera::Context {
         context.insert("repo", &repo);
     }
 
+    // Load context for the current system
+    context.insert("os", std::env::consts::OS);
+    context.insert("arch", std::env::consts::ARCH);
+
     // Load context for the environment
     let env = std::env::vars().collect::<HashMap<String, String>>();
     context.insert("env", &env);
@@ -84,6 +88,33 @@ pub fn tera_render_error_message(err: tera::Error) -> String {
     errmsg
 }
 
+/// Evaluates a condition in the given context; the condition can either be
+/// a tera expression (e.g. `os == "macos"`), or a tera template (e.g.
+/// `{{ env.CI | default(value="") }}`) rendering to a truthy value, that is
+/// `true`, `yes`, `on` or `1`.
+pub fn evaluate_template_condition(
+    condition: &str,
+    context: &tera::Context,
+) -> Result<bool, String> {
+    let template_str = if condition.contains("{{") || condition.contains("{%") {
+        condition.to_string()
+    } else {
+        format!("{{% if {} %}}true{{% else %}}false{{% endif %}}", condition)
+    };
+
+    let mut template = Tera::default();
+    template
+        .add_raw_template("condition", &template_str)
+        .map_err(tera_render_error_message)?;
+
+    let rendered = render_config_template(&template, context).map_err(tera_render_error_message)?;
+
+    Ok(matches!(
+        rendered.trim().to_lowercase().as_str(),
+        "true" | "yes" | "on" | "1"
+    ))
+}
+
 pub fn render_askpass_template(context: &tera::Context) -> Result<String, tera::Error> {
     let template_str = include_str!("../../../templates/askpass.sh.tmpl");
 
@@ -191,3 +222,95 @@ pub fn filter_escape_multiline_command(
     }
     Ok(tera::Value::String(escaped))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> tera::Context {
+        let mut context = tera::Context::new();
+        context.insert("os", "linux");
+        context.insert(
+            "env",
+            &HashMap::from([("CI".to_string(), "true".to_string())]),
+        );
+        context
+    }
+
+    #[test]
+    fn evaluate_condition_expression() {
+        let context = context();
+        assert_eq!(
+            evaluate_template_condition("os == \"linux\"", &context),
+            Ok(true)
+        );
+        assert_eq!(
+            evaluate_template_condition("os == \"macos\"", &context),
+            Ok(false)
+        );
+        assert_eq!(
+            evaluate_template_condition("os == \"linux\" and not env.CI", &context),
+            Ok(false)
+        );
+        assert_eq!(
+            evaluate_template_condition("env.CI == \"false\"", &context),
+            Ok(false)
+        );
+        assert_eq!(
+            evaluate_template_condition(
+                "env.MISSING | default(value=\"false\") == \"false\"",
+                &context
+            ),
+            Ok(true)
+        );
+        assert_eq!(evaluate_template_condition("true", &context), Ok(true));
+        assert_eq!(
+            evaluate_template_condition("not env.CI is defined", &context),
+            Ok(false)
+        );
+        assert_eq!(
+            evaluate_template_condition("prompts.unanswered", &context),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn evaluate_condition_with_prompts() {
+        let mut context = context();
+        context.insert(
+            "prompts",
+            &serde_json::json!({"team": "team1", "subteam": ["subteam 1"]}),
+        );
+        assert_eq!(
+            evaluate_template_condition(
+                "prompts.team in [\"team1\", \"team 2\"] and \"subteam 1\" in prompts.subteam",
+                &context
+            ),
+            Ok(true)
+        );
+        assert_eq!(
+            evaluate_template_condition("\"subteam 2\" not in prompts.subteam", &context),
+            Ok(true)
+        );
+        assert!(evaluate_template_condition("prompts.unanswered == \"yes\"", &context).is_err());
+    }
+
+    #[test]
+    fn evaluate_condition_template() {
+        let context = context();
+        assert_eq!(
+            evaluate_template_condition("{{ env.CI }}", &context),
+            Ok(true)
+        );
+        assert_eq!(
+            evaluate_template_condition("{% if os == \"macos\" %}yes{% endif %}", &context),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn evaluate_condition_error() {
+        let context = context();
+        assert!(evaluate_template_condition("os ==", &context).is_err());
+    }
+}
@@ -15,6 +15,7 @@ use tokio::process::Command as TokioCommand;
 use walkdir::WalkDir;
 
 use crate::internal::cache::asdf_operation::AsdfOperationUpdateCachePluginVersions;
+use crate::internal::cache::up_environments::UpEnvironment;
 use crate::internal::cache::utils as cache_utils;
 use crate::internal::cache::AsdfOperationCache;
 use crate::internal::cache::CacheObject;
@@ -22,6 +23,9 @@ use crate::internal::cache::UpEnvironmentsCache;
 use crate::internal::config;
 use crate::internal::config::global_config;
 use crate::internal::config::up::homebrew::HomebrewInstall;
+use crate::internal::config::up::lock::UpLockEntry;
+use crate::internal::config::up::plan::UpPlanAction;
+use crate::internal::config::up::plan::UpPlanItem;
 use crate::internal::config::up::utils::data_path_dir_hash;
 use crate::internal::config::up::utils::run_progress;
 use crate::internal::config::up::utils::ProgressHandler;
@@ -29,6 +33,7 @@ use crate::internal::config::up::utils::RunConfig;
 use crate::internal::config::up::utils::UpProgressHandler;
 use crate::internal::config::up::utils::VersionMatcher;
 use crate::internal::config::up::utils::VersionParser;
+use crate::internal::config::up::utils::VoidProgressHandler;
 use crate::internal::config::up::UpConfigHomebrew;
 use crate::internal::config::up::UpConfigNix;
 use crate::internal::config::up::UpConfigTool;
@@ -49,6 +54,7 @@ lazy_static! {
 type DetectVersionFunc = fn(tool_real_name: String, path: PathBuf) -> Option<String>;
 type PostInstallFunc = fn(
     progress_handler: &dyn ProgressHandler,
+    options: &UpOptions,
     config_value: Option<ConfigValue>,
     tool: String,
     tool_real_name: String,
@@ -95,7 +101,10 @@ fn is_asdf_installed() -> bool {
     bin_path.is_file() && bin_path.metadata().unwrap().permissions().mode() & 0o111 != 0
 }
 
-fn install_asdf(progress_handler: &dyn ProgressHandler) -> Result<(), UpError> {
+fn install_asdf(
+    options: &UpOptions,
+    progress_handler: &dyn ProgressHandler,
+) -> Result<(), UpError> {
     // Add asdf to PATH if not there yet, as some of the asdf plugins depend on it being
     // in the PATH. We will want it to be at the beginning of the PATH, so that it takes
     // precedence over any other asdf installation.
@@ -108,6 +117,12 @@ fn install_asdf(progress_handler: &dyn ProgressHandler) -> Result<(), UpError> {
     std::env::set_var("PATH", new_path_env);
 
     if !is_asdf_installed() {
+        if options.offline {
+            let err = UpError::Offline("asdf".to_string());
+            progress_handler.progress(err.message());
+            return Err(err);
+        }
+
         progress_handler.progress("installing asdf".to_string());
 
         let mut git_clone = TokioCommand::new("git");
@@ -122,14 +137,18 @@ fn install_asdf(progress_handler: &dyn ProgressHandler) -> Result<(), UpError> {
         git_clone.stdout(std::process::Stdio::piped());
         git_clone.stderr(std::process::Stdio::piped());
 
-        run_progress(&mut git_clone, Some(progress_handler), RunConfig::default())?;
+        run_progress(
+            &mut git_clone,
+            Some(progress_handler),
+            RunConfig::default().with_retry(&options.retry),
+        )?;
     }
 
-    update_asdf(progress_handler)
+    update_asdf(options, progress_handler)
 }
 
-fn update_asdf(progress_handler: &dyn ProgressHandler) -> Result<(), UpError> {
-    if !AsdfOperationCache::get().should_update_asdf() {
+fn update_asdf(options: &UpOptions, progress_handler: &dyn ProgressHandler) -> Result<(), UpError> {
+    if options.offline || !AsdfOperationCache::get().should_update_asdf() {
         return Ok(());
     }
 
@@ -141,7 +160,7 @@ fn update_asdf(progress_handler: &dyn ProgressHandler) -> Result<(), UpError> {
     run_progress(
         &mut asdf_update,
         Some(progress_handler),
-        RunConfig::default(),
+        RunConfig::default().with_retry(&options.retry),
     )?;
 
     if let Err(err) = AsdfOperationCache::exclusive(|asdf_cache| {
@@ -178,6 +197,7 @@ fn is_asdf_tool_version_installed(tool: &str, version: &str) -> bool {
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct UpConfigAsdfBaseParams {
     pub tool_url: Option<String>,
+    pub version_prefix: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -194,6 +214,13 @@ pub struct UpConfigAsdfBase {
     #[serde(skip)]
     pub tool_url: Option<String>,
 
+    /// The prefix that the versions of the tool are listed with by
+    /// the plugin, when the plugin lists versions prefixed by a vendor
+    /// name (e.g. `temurin-21.0.2+13.0.LTS` for java); this prefix is
+    /// used when the requested version does not specify one.
+    #[serde(skip)]
+    pub version_prefix: Option<String>,
+
     /// The URL passed as parameter to override the location
     /// of the tool; this is stored as a separate parameter
     /// to make sure it can be dumped when looking at the
@@ -231,6 +258,7 @@ pub struct UpConfigAsdfBase {
     /// a version, such as installing plugins or running post-install scripts.
     /// The functions will be called with the following parameters:
     /// - progress_handler: a progress handler to use to report progress
+    /// - options: the options of the up operation, e.g. whether it is offline
     /// - tool: the name of the tool
     /// - versions: AsdfToolUpVersion objects describing the versions that were
     ///             up-ed, with the following fields:
@@ -249,6 +277,11 @@ pub struct UpConfigAsdfBase {
     #[serde(skip)]
     actual_versions: OnceCell<BTreeMap<String, BTreeSet<String>>>,
 
+    /// The versions of the tool that were installed during this run,
+    /// as opposed to the ones that were already installed.
+    #[serde(skip)]
+    installed_versions: OnceCell<BTreeSet<String>>,
+
     /// The configuration value that was used to create this object.
     #[serde(skip)]
     config_value: Option<ConfigValue>,
@@ -282,10 +315,15 @@ impl UpConfigAsdfBase {
         self.post_install_funcs.push(func);
     }
 
+    pub fn set_config_value(&mut self, config_value: Option<ConfigValue>) {
+        self.config_value = config_value;
+    }
+
     fn new_from_auto(&self, version: &str, dirs: BTreeSet<String>) -> Self {
         UpConfigAsdfBase {
             tool: self.tool.clone(),
             tool_url: self.tool_url.clone(),
+            version_prefix: self.version_prefix.clone(),
             version: version.to_string(),
             dirs: dirs.clone(),
             ..UpConfigAsdfBase::default()
@@ -376,6 +414,7 @@ impl UpConfigAsdfBase {
             tool,
             tool_real_name,
             tool_url,
+            version_prefix: params.version_prefix.clone(),
             override_tool_url,
             version,
             upgrade,
@@ -466,12 +505,12 @@ impl UpConfigAsdfBase {
         self.deps().up(options, &subhandler)?;
         update_dynamic_env_for_command(".");
 
-        if let Err(err) = install_asdf(progress_handler) {
+        if let Err(err) = install_asdf(options, progress_handler) {
             progress_handler.error();
             return Err(err);
         }
 
-        if let Err(err) = self.install_plugin(progress_handler) {
+        if let Err(err) = self.install_plugin(options, progress_handler) {
             progress_handler.error();
             return Err(err);
         }
@@ -484,7 +523,14 @@ impl UpConfigAsdfBase {
             Ok(installed) => {
                 let version = self.version()?;
 
+                let installed_versions = match installed {
+                    true => BTreeSet::from([version.clone()]),
+                    false => BTreeSet::new(),
+                };
+                let _ = self.installed_versions.set(installed_versions);
+
                 self.update_cache(progress_handler);
+                self.update_lock(options);
 
                 if !self.post_install_funcs.is_empty() {
                     let post_install_versions = vec![AsdfToolUpVersion {
@@ -500,6 +546,7 @@ impl UpConfigAsdfBase {
                     for func in self.post_install_funcs.iter() {
                         if let Err(err) = func(
                             progress_handler,
+                            options,
                             self.config_value.clone(),
                             self.tool.clone(),
                             self.name(),
@@ -652,11 +699,15 @@ impl UpConfigAsdfBase {
             }
 
             asdf_base.update_cache(progress_handler);
+            asdf_base.update_lock(options);
         }
 
         self.actual_versions
             .set(all_versions.clone())
             .expect("failed to set installed versions");
+        let _ = self
+            .installed_versions
+            .set(installed_versions.iter().cloned().collect());
 
         if !self.post_install_funcs.is_empty() {
             let post_install_versions = all_versions
@@ -671,6 +722,7 @@ impl UpConfigAsdfBase {
             for func in self.post_install_funcs.iter() {
                 if let Err(err) = func(
                     progress_handler,
+                    options,
                     self.config_value.clone(),
                     self.tool.clone(),
                     self.name(),
@@ -724,11 +776,210 @@ impl UpConfigAsdfBase {
         self.deps().down(progress_handler)
     }
 
+    /// The resources, as `(step, name)` pairs, for which the versions
+    /// of the tool are locked
+    pub fn lock_keys(&self) -> Vec<(String, String)> {
+        vec![(self.tool.clone(), self.name())]
+    }
+
+    /// Removes the work directory from the ones requiring the versions
+    /// of the tool resolved during this run, and uninstalls the versions
+    /// that were installed during this run if no other work directory
+    /// requires them
+    pub fn rollback(&self, progress_handler: &dyn ProgressHandler) {
+        let wd_id = match workdir(".").id() {
+            Some(wd_id) => wd_id,
+            None => return,
+        };
+
+        let mut versions = BTreeSet::new();
+        versions.extend(self.actual_version.get().cloned());
+        if let Some(actual_versions) = self.actual_versions.get() {
+            versions.extend(actual_versions.keys().cloned());
+        }
+        if versions.is_empty() {
+            return;
+        }
+
+        progress_handler.progress(format!(
+            "rolling back {} {}",
+            self.name(),
+            versions.iter().cloned().collect::<Vec<_>>().join(", ")
+        ));
+
+        let installed_versions = self.installed_versions.get().cloned().unwrap_or_default();
+        let mut to_uninstall = vec![];
+        if let Err(err) = AsdfOperationCache::exclusive(|asdf_cache| {
+            let mut updated = false;
+            asdf_cache.installed.retain_mut(|install| {
+                if install.tool != self.tool || !versions.contains(&install.version) {
+                    return true;
+                }
+
+                updated |= install.required_by.remove(&wd_id);
+                if install.required_by.is_empty() && installed_versions.contains(&install.version) {
+                    to_uninstall.push(install.version.clone());
+                    updated = true;
+                    return false;
+                }
+
+                true
+            });
+            updated
+        }) {
+            progress_handler.progress(format!("failed to update cache: {}", err));
+            return;
+        }
+
+        for version in to_uninstall {
+            if !is_asdf_tool_version_installed(&self.tool, &version) {
+                continue;
+            }
+
+            let mut asdf_uninstall = asdf_async_command();
+            asdf_uninstall.arg("uninstall");
+            asdf_uninstall.arg(self.tool.clone());
+            asdf_uninstall.arg(version.clone());
+
+            if let Err(err) = run_progress(
+                &mut asdf_uninstall,
+                Some(progress_handler),
+                RunConfig::default(),
+            ) {
+                progress_handler.progress(format!(
+                    "failed to uninstall {} {}: {}",
+                    self.name(),
+                    version,
+                    err
+                ));
+            }
+        }
+    }
+
+    /// Resolves the version that would be used by `up` without
+    /// installing or updating asdf, the plugin or the caches.
+    pub fn plan(&self, options: &UpOptions, current: Option<&UpEnvironment>) -> UpPlanItem {
+        let name = self.name();
+        if self.version == "auto" {
+            return UpPlanItem::new(&name, UpPlanAction::Run);
+        }
+
+        let current_version = current.and_then(|current| {
+            current
+                .versions
+                .iter()
+                .find(|version| version.tool == self.tool)
+                .map(|version| version.version.clone())
+        });
+
+        let item = match self.plan_version(options, &VoidProgressHandler {}) {
+            Ok((version, installed)) => UpPlanItem::new(
+                &name,
+                UpPlanAction::for_version(installed, current_version, &version),
+            )
+            .with_version(&version),
+            Err(err) => UpPlanItem::new(&name, UpPlanAction::Unknown(err.message())),
+        };
+
+        if self.post_install_funcs.is_empty() {
+            item
+        } else {
+            item.with_unknown_env()
+        }
+    }
+
+    fn plan_version(
+        &self,
+        options: &UpOptions,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<(String, bool), UpError> {
+        if let Some(locked) = self.locked(options) {
+            let installed = self.is_version_installed(&locked.version);
+            return Ok((locked.version.clone(), installed));
+        }
+
+        let mut versions = None;
+
+        if !self.upgrade_version(options) {
+            if let Ok(installed_versions) =
+                self.list_installed_versions_from_plugin(progress_handler)
+            {
+                let resolve_str = match self.version.as_str() {
+                    "latest" => {
+                        let list_versions = self.plan_list_versions(options, progress_handler)?;
+                        versions = Some(list_versions.clone());
+                        self.latest_version(&list_versions)?
+                    }
+                    _ => self.version.clone(),
+                };
+
+                if let Ok(version) =
+                    self.resolve_version_from_str(&resolve_str, &installed_versions)
+                {
+                    return Ok((version, true));
+                }
+            }
+        }
+
+        let versions = match versions {
+            Some(versions) => versions,
+            None => self.plan_list_versions(options, progress_handler)?,
+        };
+        let version = self.resolve_version(&versions)?;
+        let installed = self.is_version_installed(&version);
+
+        Ok((version, installed))
+    }
+
+    /// Lists the available versions like `list_versions`, but without
+    /// updating the plugin nor writing to the cache.
+    fn plan_list_versions(
+        &self,
+        options: &UpOptions,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<AsdfOperationUpdateCachePluginVersions, UpError> {
+        let cache = AsdfOperationCache::get();
+        let cached_versions = cache.get_asdf_plugin_versions(&self.tool);
+        if let Some(versions) = &cached_versions {
+            let expire = global_config().cache.asdf.plugin_versions_expire;
+            if options.read_cache && !versions.is_stale(expire) {
+                return Ok(versions.clone());
+            }
+        }
+
+        if self.is_plugin_installed() {
+            if let Ok(versions) = self.list_all_versions_from_plugin(progress_handler) {
+                return Ok(versions);
+            }
+        }
+
+        match cached_versions {
+            Some(versions) if options.read_cache => Ok(versions.clone()),
+            _ => Err(UpError::Exec(format!(
+                "unable to list available versions for {}",
+                self.name()
+            ))),
+        }
+    }
+
     fn list_versions(
         &self,
         options: &UpOptions,
-        progress_handler: &UpProgressHandler,
+        progress_handler: &dyn ProgressHandler,
     ) -> Result<AsdfOperationUpdateCachePluginVersions, UpError> {
+        // When offline, the cached version list is all we have, no
+        // matter how old it is, as listing the versions from the
+        // plugin usually requires network access
+        if options.offline {
+            return match AsdfOperationCache::get().get_asdf_plugin_versions(&self.tool) {
+                Some(versions) => {
+                    progress_handler.progress("using cached version list (offline)".light_black());
+                    Ok(versions.clone())
+                }
+                None => Err(UpError::Offline(format!("{} version list", self.name()))),
+            };
+        }
+
         let cached_versions = if options.read_cache {
             let cache = AsdfOperationCache::get();
             if let Some(versions) = cache.get_asdf_plugin_versions(&self.tool) {
@@ -748,7 +999,7 @@ impl UpConfigAsdfBase {
         };
 
         progress_handler.progress("refreshing versions list".to_string());
-        match self.list_versions_from_plugin(progress_handler) {
+        match self.list_versions_from_plugin(options, progress_handler) {
             Ok(versions) => {
                 if options.write_cache {
                     progress_handler.progress("updating cache with version list".to_string());
@@ -779,10 +1030,17 @@ impl UpConfigAsdfBase {
 
     fn list_versions_from_plugin(
         &self,
+        options: &UpOptions,
         progress_handler: &dyn ProgressHandler,
     ) -> Result<AsdfOperationUpdateCachePluginVersions, UpError> {
-        self.update_plugin(progress_handler)?;
+        self.update_plugin(options, progress_handler)?;
+        self.list_all_versions_from_plugin(progress_handler)
+    }
 
+    fn list_all_versions_from_plugin(
+        &self,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<AsdfOperationUpdateCachePluginVersions, UpError> {
         progress_handler.progress("listing available versions for plugin".to_string());
 
         let mut asdf_list_all = asdf_sync_command();
@@ -880,9 +1138,16 @@ impl UpConfigAsdfBase {
         match_version: &str,
         versions: &AsdfOperationUpdateCachePluginVersions,
     ) -> Result<String, UpError> {
-        let matcher = VersionMatcher::new(match_version);
+        let version = match self.split_version_prefix(match_version) {
+            Some((prefix, match_version)) => {
+                let mut matcher = VersionMatcher::new(&match_version);
+                matcher.build(true);
+                versions.get_with_prefix(&prefix, &matcher)
+            }
+            None => versions.get(&VersionMatcher::new(match_version)),
+        };
 
-        let version = versions.get(&matcher).ok_or_else(|| {
+        let version = version.ok_or_else(|| {
             UpError::Exec(format!(
                 "no {} version found matching {}",
                 self.name(),
@@ -893,6 +1158,32 @@ impl UpConfigAsdfBase {
         Ok(version)
     }
 
+    /// Splits the requested version into the vendor prefix and the
+    /// version to match, for tools that list versions with a vendor
+    /// prefix; returns None if the tool does not use prefixed versions.
+    fn split_version_prefix(&self, match_version: &str) -> Option<(String, String)> {
+        let default_prefix = self.version_prefix.as_ref()?;
+
+        // If the version starts with a letter and is not a keyword, it
+        // specifies its own vendor prefix, which ends with the first dash
+        // that is followed by a digit (e.g. `graalvm-community-21`)
+        if match_version.starts_with(|c: char| c.is_ascii_alphabetic()) && match_version != "latest"
+        {
+            if let Some(pos) = match_version
+                .as_bytes()
+                .windows(2)
+                .position(|w| w[0] == b'-' && w[1].is_ascii_digit())
+            {
+                return Some((
+                    match_version[..=pos].to_string(),
+                    match_version[pos + 1..].to_string(),
+                ));
+            }
+        }
+
+        Some((default_prefix.clone(), match_version.to_string()))
+    }
+
     fn version(&self) -> Result<String, UpError> {
         self.actual_version
             .get()
@@ -916,11 +1207,21 @@ impl UpConfigAsdfBase {
         false
     }
 
-    fn install_plugin(&self, progress_handler: &dyn ProgressHandler) -> Result<(), UpError> {
+    fn install_plugin(
+        &self,
+        options: &UpOptions,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<(), UpError> {
         if self.is_plugin_installed() {
             return Ok(());
         }
 
+        if options.offline {
+            let err = UpError::Offline(format!("{} plugin", self.tool));
+            progress_handler.progress(err.message());
+            return Err(err);
+        }
+
         progress_handler.progress(format!("installing {} plugin", self.tool));
 
         let mut asdf_plugin_add = asdf_async_command();
@@ -934,12 +1235,16 @@ impl UpConfigAsdfBase {
         run_progress(
             &mut asdf_plugin_add,
             Some(progress_handler),
-            RunConfig::default(),
+            RunConfig::default().with_retry(&options.retry),
         )
     }
 
-    fn update_plugin(&self, progress_handler: &dyn ProgressHandler) -> Result<(), UpError> {
-        if !AsdfOperationCache::get().should_update_asdf_plugin(&self.tool) {
+    fn update_plugin(
+        &self,
+        options: &UpOptions,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<(), UpError> {
+        if options.offline || !AsdfOperationCache::get().should_update_asdf_plugin(&self.tool) {
             return Ok(());
         }
 
@@ -953,7 +1258,7 @@ impl UpConfigAsdfBase {
         run_progress(
             &mut asdf_plugin_update,
             Some(progress_handler),
-            RunConfig::default(),
+            RunConfig::default().with_retry(&options.retry),
         )?;
 
         // Update the cache
@@ -975,11 +1280,53 @@ impl UpConfigAsdfBase {
         self.upgrade || options.upgrade || config(".").up_command.upgrade
     }
 
+    /// Returns the locked entry for this tool in the directories it is
+    /// used for, if any, and if the locked version still matches the
+    /// requested version
+    fn locked<'a>(&self, options: &UpOptions<'a>) -> Option<&'a UpLockEntry> {
+        let name = self.name();
+        let locked = if self.dirs.is_empty() {
+            options.locked(&self.tool, &name, "")
+        } else {
+            self.dirs
+                .iter()
+                .find_map(|dir| options.locked(&self.tool, &name, dir))
+        }?;
+
+        let versions = AsdfOperationUpdateCachePluginVersions::new(vec![locked.version.clone()]);
+        match self.resolve_version(&versions) {
+            Ok(_) => Some(locked),
+            Err(_) => None,
+        }
+    }
+
+    fn update_lock(&self, options: &UpOptions) {
+        let version = match self.version() {
+            Ok(version) => version,
+            Err(_err) => return,
+        };
+
+        let mut dirs = self.dirs.clone();
+        if dirs.is_empty() {
+            dirs.insert("".to_string());
+        }
+
+        for dir in dirs.iter() {
+            options.lock(UpLockEntry::new(&self.tool, &self.name(), dir, &version));
+        }
+    }
+
     fn resolve_and_install_version(
         &self,
         options: &UpOptions,
         progress_handler: &UpProgressHandler,
     ) -> Result<bool, UpError> {
+        // If the version is locked, we install exactly that version
+        if let Some(locked) = self.locked(options) {
+            progress_handler.progress(format!("using locked version {}", locked.version));
+            return self.install_version(&locked.version, options, progress_handler);
+        }
+
         let mut versions = None;
 
         // If the options do not include upgrade, then we can try using
@@ -989,16 +1336,21 @@ impl UpConfigAsdfBase {
                 self.list_installed_versions_from_plugin(progress_handler)
             {
                 let resolve_str = match self.version.as_str() {
-                    "latest" => {
-                        let list_versions = self.list_versions(options, progress_handler)?;
-                        versions = Some(list_versions.clone());
-                        let latest = self.latest_version(&list_versions)?;
-                        progress_handler.progress(
-                            format!("considering installed versions matching {}", latest)
-                                .light_black(),
-                        );
-                        latest
-                    }
+                    "latest" => match self.list_versions(options, progress_handler) {
+                        Ok(list_versions) => {
+                            versions = Some(list_versions.clone());
+                            let latest = self.latest_version(&list_versions)?;
+                            progress_handler.progress(
+                                format!("considering installed versions matching {}", latest)
+                                    .light_black(),
+                            );
+                            latest
+                        }
+                        // Without a version list while offline, any installed
+                        // version is better than none
+                        Err(UpError::Offline(_)) => "latest".to_string(),
+                        Err(err) => return Err(err),
+                    },
                     _ => self.version.clone(),
                 };
 
@@ -1016,7 +1368,14 @@ impl UpConfigAsdfBase {
 
         let versions = match versions {
             Some(versions) => versions,
-            None => self.list_versions(options, progress_handler)?,
+            None => self
+                .list_versions(options, progress_handler)
+                .map_err(|err| match err {
+                    UpError::Offline(_) => {
+                        UpError::Offline(format!("{} {}", self.name(), self.version))
+                    }
+                    err => err,
+                })?,
         };
         let version = match self.resolve_version(&versions) {
             Ok(available_version) => available_version,
@@ -1071,13 +1430,15 @@ impl UpConfigAsdfBase {
     fn install_version(
         &self,
         version: &str,
-        _options: &UpOptions,
+        options: &UpOptions,
         progress_handler: &dyn ProgressHandler,
     ) -> Result<bool, UpError> {
         let installed = if self.is_version_installed(version) {
             progress_handler.progress(format!("using {} {}", self.name(), version.light_yellow()));
 
             false
+        } else if options.offline {
+            return Err(UpError::Offline(format!("{} {}", self.name(), version)));
         } else {
             progress_handler.progress(format!(
                 "installing {} {}",
@@ -1093,7 +1454,7 @@ impl UpConfigAsdfBase {
             run_progress(
                 &mut asdf_install,
                 Some(progress_handler),
-                RunConfig::default(),
+                RunConfig::default().with_retry(&options.retry),
             )?;
 
             true
@@ -1,18 +1,33 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+use indicatif::MultiProgress;
 use itertools::Itertools;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::internal::cache::up_environments::UpEnvironment;
+use crate::internal::cache::up_environments::UpStepEnvironment;
 use crate::internal::cache::utils::Empty;
 use crate::internal::cache::CacheObject;
 use crate::internal::cache::UpEnvironmentsCache;
+use crate::internal::config::up::plan::UpPlanItem;
 use crate::internal::config::up::utils::cleanup_path;
 use crate::internal::config::up::utils::reshim;
 use crate::internal::config::up::utils::ProgressHandler;
 use crate::internal::config::up::utils::UpProgressHandler;
+use crate::internal::config::up::ToolRegistry;
 use crate::internal::config::up::UpConfigAsdfBase;
+use crate::internal::config::up::UpConfigCargoInstalls;
+use crate::internal::config::up::UpConfigDownloads;
 use crate::internal::config::up::UpConfigGithubReleases;
 use crate::internal::config::up::UpConfigHomebrew;
+use crate::internal::config::up::UpConfigRetry;
+use crate::internal::config::up::UpConfigSystemPackages;
 use crate::internal::config::up::UpConfigTool;
+use crate::internal::config::up::UpConfigVerify;
 use crate::internal::config::up::UpError;
 use crate::internal::config::up::UpOptions;
 use crate::internal::config::ConfigValue;
@@ -63,7 +78,15 @@ impl UpConfig {
             if value.is_str() {
                 let up_name = value.as_str().unwrap();
                 if let Some(up_config) = UpConfigTool::from_config_value(&up_name, None) {
-                    steps.push(up_config);
+                    match up_config.validate() {
+                        Ok(()) => steps.push(up_config),
+                        Err(err) => errors.push(UpError::Config(format!(
+                            "invalid config for step {} ({}): {}",
+                            index + 1,
+                            up_name,
+                            err
+                        ))),
+                    }
                 } else {
                     errors.push(UpError::Config(format!(
                         "invalid config for step {} ({})",
@@ -72,7 +95,59 @@ impl UpConfig {
                     )));
                 }
             } else if value.is_table() {
-                let table = value.as_table().unwrap();
+                let mut table = value.as_table().unwrap();
+
+                // A step can be made conditional with an `if` key
+                // next to the configuration of the step
+                let condition = match table.remove("if") {
+                    Some(condition) => match condition.as_str_forced() {
+                        Some(condition) => Some(condition),
+                        None => {
+                            errors.push(UpError::Config(format!(
+                                "invalid condition for step {}: {}",
+                                index + 1,
+                                condition
+                            )));
+                            continue;
+                        }
+                    },
+                    None => None,
+                };
+
+                // A step can be verified once upped with a `verify` key
+                // next to the configuration of the step
+                let verify = match table.remove("verify") {
+                    Some(verify) => match UpConfigVerify::from_config_value(&verify) {
+                        Ok(verify) => Some(verify),
+                        Err(err) => {
+                            errors.push(UpError::Config(format!(
+                                "invalid verify for step {}: {}",
+                                index + 1,
+                                err
+                            )));
+                            continue;
+                        }
+                    },
+                    None => None,
+                };
+
+                // The timeout and retries of the step can be overridden
+                // with `timeout` and `retries` keys next to its configuration
+                let retry = match UpConfigRetry::from_config_values(
+                    table.remove("retries").as_ref(),
+                    table.remove("timeout").as_ref(),
+                ) {
+                    Ok(retry) => retry,
+                    Err(err) => {
+                        errors.push(UpError::Config(format!(
+                            "invalid config for step {}: {}",
+                            index + 1,
+                            err
+                        )));
+                        continue;
+                    }
+                };
+
                 if table.len() != 1 {
                     errors.push(UpError::Config(format!(
                         "invalid config for step {}: {}",
@@ -83,17 +158,49 @@ impl UpConfig {
                 }
 
                 let (up_name, config_value) = table.iter().next().unwrap();
-                if let Some(up_config) =
-                    UpConfigTool::from_config_value(up_name, Some(config_value))
-                {
-                    steps.push(up_config);
-                } else {
-                    errors.push(UpError::Config(format!(
-                        "invalid config for step {} ({}): {}",
-                        index + 1,
-                        up_name,
-                        config_value
-                    )));
+
+                // The tools of the registry report why they cannot be
+                // expanded, e.g. when the tool is unknown
+                let up_config = match up_name.as_str() {
+                    "tool" | "tools" => ToolRegistry::load().expand(config_value),
+                    _ => UpConfigTool::from_config_value(up_name, Some(config_value))
+                        .ok_or_else(|| config_value.to_string()),
+                };
+
+                match up_config {
+                    Ok(up_config) => {
+                        let up_config = match retry {
+                            Some(retry) => UpConfigTool::Retry(retry, Box::new(up_config)),
+                            None => up_config,
+                        };
+                        let up_config = match verify {
+                            Some(verify) => UpConfigTool::Verify(verify, Box::new(up_config)),
+                            None => up_config,
+                        };
+
+                        if let Err(err) = up_config.validate() {
+                            errors.push(UpError::Config(format!(
+                                "invalid config for step {} ({}): {}",
+                                index + 1,
+                                up_name,
+                                err
+                            )));
+                            continue;
+                        }
+
+                        steps.push(match condition {
+                            Some(condition) => UpConfigTool::If(condition, Box::new(up_config)),
+                            None => up_config,
+                        });
+                    }
+                    Err(err) => {
+                        errors.push(UpError::Config(format!(
+                            "invalid config for step {} ({}): {}",
+                            index + 1,
+                            up_name,
+                            err
+                        )));
+                    }
                 }
             } else {
                 errors.push(UpError::Config(format!(
@@ -136,22 +243,65 @@ impl UpConfig {
         }
     }
 
+    /// Returns the key identifying the step at the given index of the
+    /// configuration in the environment cache
+    fn step_key(index: usize, step: &UpConfigTool) -> String {
+        format!("{}:{}", index + 1, step.to_name())
+    }
+
+    /// Returns the keys of the steps selected by the given options
+    pub fn selected_step_keys(&self, options: &UpOptions) -> Vec<String> {
+        self.steps
+            .iter()
+            .enumerate()
+            .filter(|(idx, step)| options.selection.selects(*idx, step))
+            .map(|(idx, step)| Self::step_key(idx, step))
+            .collect()
+    }
+
     pub fn up(&self, options: &UpOptions) -> Result<(), UpError> {
         // Get current directory
         let current_dir = std::env::current_dir().expect("Failed to get current directory");
 
-        // Filter the steps to only the available ones
-        let steps = self
+        // When only some of the steps are selected, the environment is
+        // updated for those steps only instead of being rebuilt
+        let incremental = !options.selection.is_empty();
+        let wd_id = workdir(".").id();
+
+        // When all the steps are selected, the lock only keeps the
+        // entries of the resources they declare, whether they are run
+        // or not
+        if !incremental {
+            options.declare_locked(
+                self.steps
+                    .iter()
+                    .flat_map(|step| step.lock_keys())
+                    .collect(),
+            );
+        }
+
+        // Filter the steps to only the selected and available ones
+        let (keys, steps): (Vec<String>, Vec<&UpConfigTool>) = self
             .steps
             .iter()
-            .filter(|step| step.is_available())
-            .collect::<Vec<&UpConfigTool>>();
-
-        // Go through the steps
-        let num_steps = steps.len() + 1;
-        for (idx, step) in steps.iter().enumerate() {
+            .enumerate()
+            .filter(|(idx, step)| options.selection.selects(*idx, step) && step.is_available())
+            .map(|(idx, step)| (Self::step_key(idx, step), step))
+            .unzip();
+
+        // Go through the steps, running at the same time the
+        // ones that can run concurrently
+        let num_steps = if incremental {
+            steps.len()
+        } else {
+            steps.len() + 1
+        };
+        for batch in Self::batches(&steps, options.concurrency) {
             // Make sure that we're in the right directory
-            let step_dir = current_dir.join(step.dir().unwrap_or("".to_string()));
+            let step_dir = match batch.as_slice() {
+                [idx] => current_dir.join(steps[*idx].dir().unwrap_or("".to_string())),
+                _ => current_dir.clone(),
+            };
             if let Err(error) = std::env::set_current_dir(&step_dir) {
                 return Err(UpError::Exec(format!(
                     "failed to change directory to {}: {}",
@@ -160,16 +310,192 @@ impl UpConfig {
                 )));
             }
 
-            let mut progress_handler = UpProgressHandler::new(Some((idx + 1, num_steps)));
-            if let Some(sync_file) = &options.lock_file {
-                progress_handler.set_sync_file(sync_file);
+            // Remove what the steps previously added to the environment,
+            // since they are going to add it again
+            if let (true, Some(wd_id)) = (incremental, &wd_id) {
+                if let Err(err) = UpEnvironmentsCache::exclusive(|up_env| {
+                    let mut updated = false;
+                    for idx in batch.iter() {
+                        updated |= up_env.revert_step(wd_id, &keys[*idx]);
+                    }
+                    updated
+                }) {
+                    omni_warning!(format!("failed to update cache: {}", err));
+                }
+            }
+
+            if let [idx] = batch.as_slice() {
+                let mut progress_handler = UpProgressHandler::new(Some((idx + 1, num_steps)));
+                if let Some(sync_file) = &options.lock_file {
+                    progress_handler.set_sync_file(sync_file);
+                }
+
+                let before = Self::current_env(&wd_id);
+                steps[*idx].up(options, &progress_handler)?;
+
+                if let (Some(before), Some(after)) = (before, Self::current_env(&wd_id)) {
+                    Self::record_step_changes(&wd_id, &keys[*idx], after.changes_since(&before));
+                }
+            } else {
+                let batch_steps = batch
+                    .iter()
+                    .map(|idx| (*idx, steps[*idx]))
+                    .collect::<Vec<_>>();
+                Self::up_concurrently(&batch_steps, num_steps, options)?;
+
+                // The steps that can run concurrently only add paths
+                for idx in batch.iter() {
+                    let changes = UpStepEnvironment {
+                        paths: steps[*idx].env_paths(),
+                        ..Default::default()
+                    };
+                    Self::record_step_changes(&wd_id, &keys[*idx], changes);
+                }
             }
+        }
 
-            step.up(options, &progress_handler)?
+        // Cleanup anything that's not needed; this is skipped when only
+        // some steps were run, since the other steps did not report
+        // the resources they are using
+        if !incremental {
+            self.cleanup(Some((num_steps, num_steps)), options)?;
+        } else if let Some(wd_id) = &wd_id {
+            self.reorder_paths(wd_id);
         }
 
-        // Cleanup anything that's not needed
-        self.cleanup(Some((num_steps, num_steps)), options)?;
+        Ok(())
+    }
+
+    /// Adds the paths of all the steps again, in the order of the
+    /// configuration, so that the PATH is the same as if all the
+    /// steps had been run
+    fn reorder_paths(&self, wd_id: &str) {
+        let keys = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(idx, step)| Self::step_key(idx, step))
+            .collect::<Vec<_>>();
+
+        let paths = match UpEnvironmentsCache::get().get_env(wd_id) {
+            Some(env) => env.for_steps(&keys).paths,
+            None => return,
+        };
+
+        if let Err(err) = UpEnvironmentsCache::exclusive(|up_env| up_env.add_paths(wd_id, paths)) {
+            omni_warning!(format!("failed to update cache: {}", err));
+        }
+    }
+
+    fn current_env(wd_id: &Option<String>) -> Option<UpEnvironment> {
+        let wd_id = wd_id.as_ref()?;
+        Some(
+            UpEnvironmentsCache::get()
+                .get_env(wd_id)
+                .cloned()
+                .unwrap_or_else(UpEnvironment::new),
+        )
+    }
+
+    fn record_step_changes(wd_id: &Option<String>, key: &str, changes: UpStepEnvironment) {
+        let wd_id = match wd_id {
+            Some(wd_id) => wd_id,
+            None => return,
+        };
+
+        if let Err(err) =
+            UpEnvironmentsCache::exclusive(|up_env| up_env.set_step_changes(wd_id, key, changes))
+        {
+            omni_warning!(format!("failed to update cache: {}", err));
+        }
+    }
+
+    /// Groups the steps, by index, in batches that can be run one after
+    /// the other; consecutive steps that can run concurrently are part of
+    /// the same batch, while any other step is in a batch of its own.
+    fn batches(steps: &[&UpConfigTool], concurrency: usize) -> Vec<Vec<usize>> {
+        let mut batches: Vec<Vec<usize>> = vec![];
+        let mut previous_concurrent = false;
+
+        for (idx, step) in steps.iter().enumerate() {
+            let concurrent = concurrency > 1 && step.can_run_concurrently();
+            match batches.last_mut() {
+                Some(batch) if concurrent && previous_concurrent => batch.push(idx),
+                _ => batches.push(vec![idx]),
+            }
+            previous_concurrent = concurrent;
+        }
+
+        batches
+    }
+
+    /// Runs the provided steps using up to `options.concurrency` threads;
+    /// no new step is started once a step failed, and the error of the
+    /// first failed step, in the order of the configuration, is returned.
+    fn up_concurrently(
+        steps: &[(usize, &UpConfigTool)],
+        num_steps: usize,
+        options: &UpOptions,
+    ) -> Result<(), UpError> {
+        let multiprogress = MultiProgress::new();
+        let next_step = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let errors = Mutex::new(Vec::new());
+        let options = &options.clone().concurrent();
+
+        std::thread::scope(|scope| {
+            for _ in 0..options.concurrency.min(steps.len()) {
+                scope.spawn(|| {
+                    while !failed.load(Ordering::SeqCst) {
+                        let (idx, step) = match steps.get(next_step.fetch_add(1, Ordering::SeqCst))
+                        {
+                            Some(step) => step,
+                            None => break,
+                        };
+
+                        let mut progress_handler =
+                            UpProgressHandler::new(Some((idx + 1, num_steps)));
+                        progress_handler.set_multiprogress(&multiprogress);
+                        if let Some(sync_file) = &options.lock_file {
+                            progress_handler.set_sync_file(sync_file);
+                        }
+
+                        if let Err(err) = step.up(options, &progress_handler) {
+                            failed.store(true, Ordering::SeqCst);
+                            errors
+                                .lock()
+                                .expect("failed to lock step errors")
+                                .push((*idx, err));
+                        }
+                    }
+                });
+            }
+        });
+
+        let errors = errors.into_inner().expect("failed to lock step errors");
+        if let Some((_, err)) = errors.into_iter().min_by_key(|(idx, _)| *idx) {
+            return Err(err);
+        }
+
+        // The steps added their paths to the environment in the order in which
+        // they finished, so we add them again in the order of the configuration
+        // to get the same PATH as if they had run one after the other
+        let paths = steps
+            .iter()
+            .flat_map(|(_, step)| step.env_paths())
+            .collect::<Vec<_>>();
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let wd_id = match workdir(".").id() {
+            Some(wd_id) => wd_id,
+            None => return Ok(()),
+        };
+
+        if let Err(err) = UpEnvironmentsCache::exclusive(|up_env| up_env.add_paths(&wd_id, paths)) {
+            omni_warning!(format!("failed to update cache: {}", err));
+        }
 
         Ok(())
     }
@@ -203,6 +529,17 @@ impl UpConfig {
         Ok(())
     }
 
+    /// Computes what `up` would do for each of the steps, without
+    /// installing anything nor updating the caches.
+    pub fn plan(&self, options: &UpOptions, current: Option<&UpEnvironment>) -> Vec<UpPlanItem> {
+        self.steps
+            .iter()
+            .enumerate()
+            .filter(|(idx, step)| options.selection.selects(*idx, step))
+            .flat_map(|(_, step)| step.plan(options, current))
+            .collect()
+    }
+
     /// Cleanup anything that's not needed anymore; this will call the cleanup
     /// method of every existing tool, so that it can cleanup dependencies from
     /// steps that do not exist anymore on top of previous versions of recently
@@ -230,6 +567,15 @@ impl UpConfig {
         if let Some(cleanup) = UpConfigGithubReleases::cleanup(&progress_handler)? {
             cleanups.push(cleanup);
         }
+        if let Some(cleanup) = UpConfigCargoInstalls::cleanup(&progress_handler)? {
+            cleanups.push(cleanup);
+        }
+        if let Some(cleanup) = UpConfigDownloads::cleanup(&progress_handler)? {
+            cleanups.push(cleanup);
+        }
+        if let Some(cleanup) = UpConfigSystemPackages::cleanup(&progress_handler)? {
+            cleanups.push(cleanup);
+        }
 
         // Then cleanup the data path
         if let Some(cleanup) = self.cleanup_data_path(&progress_handler)? {
@@ -296,3 +642,278 @@ impl UpConfig {
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn up_config(yaml: &str) -> UpConfig {
+        let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+        UpConfig::from_config_value(Some(config_value)).expect("failed to parse up config")
+    }
+
+    #[test]
+    fn batches_group_consecutive_concurrent_steps() {
+        let config = up_config(concat!(
+            "- github-release: owner/first\n",
+            "- download:\n",
+            "    url: https://example.com/tool\n",
+            "    name: tool\n",
+            "- custom:\n",
+            "    meet: echo meet\n",
+            "- gitlab-release: owner/second\n",
+            "- and:\n",
+            "  - gitea-release: owner/third\n",
+            "  - github-release: owner/fourth\n",
+        ));
+        let steps = config.steps.iter().collect::<Vec<_>>();
+
+        assert_eq!(
+            UpConfig::batches(&steps, 4),
+            vec![vec![0, 1], vec![2], vec![3, 4]]
+        );
+        assert_eq!(
+            UpConfig::batches(&steps, 1),
+            vec![vec![0], vec![1], vec![2], vec![3], vec![4]]
+        );
+    }
+
+    #[test]
+    fn conditional_steps() {
+        let config = up_config(concat!(
+            "- if: os == \"macos\"\n",
+            "  homebrew:\n",
+            "    install: [jq]\n",
+            "- if: false\n",
+            "  github-release: owner/repo\n",
+            "- python\n",
+        ));
+
+        assert!(config.errors.is_empty());
+        assert_eq!(config.steps.len(), 3);
+        assert!(
+            matches!(&config.steps[0], UpConfigTool::If(condition, tool) if condition == "os == \"macos\"" && tool.to_name() == "homebrew")
+        );
+        assert!(
+            matches!(&config.steps[1], UpConfigTool::If(condition, tool) if condition == "false" && tool.to_name() == "github-release")
+        );
+        assert!(matches!(&config.steps[2], UpConfigTool::Python(_)));
+
+        // The condition does not change the name of the step, nor whether it
+        // can be run concurrently with other steps
+        assert_eq!(config.steps[1].to_name(), "github-release");
+        assert!(config.steps[1].can_run_concurrently());
+    }
+
+    #[test]
+    fn lock_keys_of_all_steps() {
+        let config = up_config(concat!(
+            "- if: false\n",
+            "  github-release: owner/repo\n",
+            "- cargo-install: [ripgrep, fd-find]\n",
+            "- homebrew:\n",
+            "    install: [jq]\n",
+            "- python\n",
+            "- go\n",
+        ));
+
+        assert!(config.errors.is_empty());
+        let keys = config
+            .steps
+            .iter()
+            .flat_map(|step| step.lock_keys())
+            .map(|(step, name)| format!("{}:{}", step, name))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                "github-release:owner/repo",
+                "cargo-install:ripgrep",
+                "cargo-install:fd-find",
+                "python:python",
+                "golang:golang",
+            ]
+        );
+    }
+
+    #[test]
+    fn conditional_steps_require_a_single_tool() {
+        let config = up_config(concat!(
+            "- if: \"true\"\n",
+            "  python: 3.12\n",
+            "  nodejs: 20\n",
+        ));
+
+        assert!(config.steps.is_empty());
+        assert_eq!(config.errors.len(), 1);
+    }
+
+    #[test]
+    fn invalid_steps_are_reported() {
+        let config = up_config(concat!(
+            "- pacman:\n",
+            "    - package: curl\n",
+            "      version: 7.88.1\n",
+            "- pacman: [curl]\n",
+            "- github-release:\n",
+            "    repository: owner/repo\n",
+            "    tag_regex: \"^v(\"\n",
+        ));
+
+        assert_eq!(config.steps.len(), 1);
+        assert_eq!(config.errors.len(), 2);
+        assert_eq!(
+            config.errors[0].message(),
+            "invalid config for step 1 (pacman): pacman does not support installing a specific version of a package (curl)"
+        );
+        assert!(config.errors[1].message().starts_with(
+            "invalid config for step 3 (github-release): invalid tag_regex pattern '^v(': "
+        ));
+    }
+
+    #[test]
+    fn tool_steps() {
+        let config = up_config(concat!(
+            "- tool: rg@14\n",
+            "- if: os == \"linux\"\n",
+            "  tool: [jq, gh]\n",
+            "- tool: unknown-tool\n",
+        ));
+
+        assert_eq!(config.steps.len(), 2);
+        assert!(matches!(&config.steps[0], UpConfigTool::GithubRelease(_)));
+        assert!(matches!(
+            &config.steps[1],
+            UpConfigTool::If(_, tool) if matches!(tool.as_ref(), UpConfigTool::And(tools) if tools.len() == 2)
+        ));
+        assert_eq!(config.errors.len(), 1);
+        assert_eq!(
+            config.errors[0].message(),
+            "invalid config for step 3 (tool): unknown tool 'unknown-tool' in registry"
+        );
+    }
+
+    #[test]
+    fn verified_steps() {
+        let config = up_config(concat!(
+            "- github-release: owner/repo\n",
+            "  verify: repo --version\n",
+            "- if: \"true\"\n",
+            "  download:\n",
+            "    name: tool\n",
+            "    url: https://example.com/tool\n",
+            "  verify:\n",
+            "    command: tool version\n",
+            "    exit_code: 1\n",
+            "    output: '^tool '\n",
+            "- python:\n",
+            "    verify: not-a-step-key\n",
+        ));
+
+        assert!(config.errors.is_empty());
+        assert_eq!(config.steps.len(), 3);
+        assert!(
+            matches!(&config.steps[0], UpConfigTool::Verify(verify, tool) if verify.command == "repo --version" && tool.to_name() == "github-release")
+        );
+        assert!(matches!(
+            &config.steps[1],
+            UpConfigTool::If(_, tool) if matches!(
+                tool.as_ref(),
+                UpConfigTool::Verify(verify, _) if verify.exit_code == 1 && verify.output.as_deref() == Some("^tool ")
+            )
+        ));
+        assert!(matches!(&config.steps[2], UpConfigTool::Python(_)));
+
+        // Verified steps keep their name, but are never run concurrently
+        // since the verification uses the dynamic environment
+        assert_eq!(config.steps[0].to_name(), "github-release");
+        assert!(!config.steps[0].can_run_concurrently());
+        assert!(config.steps[0].matches_name("owner/repo"));
+    }
+
+    #[test]
+    fn verified_steps_require_a_command() {
+        let config = up_config(concat!(
+            "- github-release: owner/repo\n",
+            "  verify:\n",
+            "    exit_code: 1\n",
+        ));
+
+        assert!(config.steps.is_empty());
+        assert_eq!(config.errors.len(), 1);
+    }
+
+    #[test]
+    fn verified_steps_without_rollback() {
+        let config = up_config(concat!(
+            "- homebrew:\n",
+            "    install: [jq]\n",
+            "  verify: jq --version\n",
+            "- if: os == \"linux\"\n",
+            "  apt: [shellcheck]\n",
+            "  verify: shellcheck --version\n",
+            "- python: 3.12\n",
+            "  verify: python --version\n",
+        ));
+
+        assert!(config.errors.is_empty());
+        assert_eq!(config.steps.len(), 3);
+        assert!(
+            matches!(&config.steps[0], UpConfigTool::Verify(_, tool) if tool.to_name() == "homebrew")
+        );
+        assert!(!config.steps[0].can_rollback());
+        assert!(!config.steps[1].can_rollback());
+        assert!(config.steps[2].can_rollback());
+    }
+
+    #[test]
+    fn steps_with_retry_policy() {
+        let config = up_config(concat!(
+            "- github-release: owner/repo\n",
+            "  retries: 3\n",
+            "  timeout: 60\n",
+            "- nodejs: 20\n",
+            "  verify: node --version\n",
+            "  retries: 1\n",
+            "- python:\n",
+            "  timeout: nope\n",
+        ));
+
+        assert_eq!(config.errors.len(), 1);
+        assert_eq!(config.steps.len(), 2);
+        assert!(matches!(
+            &config.steps[0],
+            UpConfigTool::Retry(retry, _) if retry.retries == Some(3) && retry.timeout == Some(60)
+        ));
+        assert!(matches!(
+            &config.steps[1],
+            UpConfigTool::Verify(_, tool) if matches!(
+                tool.as_ref(),
+                UpConfigTool::Retry(retry, _) if retry.retries == Some(1) && retry.timeout.is_none()
+            )
+        ));
+
+        // The retry policy does not change how the step is handled
+        assert_eq!(config.steps[0].to_name(), "github-release");
+        assert!(config.steps[0].can_run_concurrently());
+        assert!(config.steps[0].matches_name("owner/repo"));
+    }
+
+    #[test]
+    fn batches_keep_dependent_steps_alone() {
+        let config = up_config(concat!(
+            "- rust\n",
+            "- cargo-install: ripgrep\n",
+            "- and:\n",
+            "  - github-release: owner/repo\n",
+            "  - custom:\n",
+            "      meet: echo meet\n",
+        ));
+        let steps = config.steps.iter().collect::<Vec<_>>();
+
+        assert_eq!(
+            UpConfig::batches(&steps, 4),
+            vec![vec![0], vec![1], vec![2]]
+        );
+    }
+}
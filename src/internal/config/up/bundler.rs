@@ -12,6 +12,7 @@ use crate::internal::config::up::utils::ProgressHandler;
 use crate::internal::config::up::utils::RunConfig;
 use crate::internal::config::up::utils::UpProgressHandler;
 use crate::internal::config::up::UpError;
+use crate::internal::config::up::UpOptions;
 use crate::internal::config::ConfigValue;
 use crate::internal::user_interface::StringColor;
 use crate::internal::workdir;
@@ -64,7 +65,11 @@ impl UpConfigBundler {
         }
     }
 
-    pub fn up(&self, progress_handler: &UpProgressHandler) -> Result<(), UpError> {
+    pub fn up(
+        &self,
+        options: &UpOptions,
+        progress_handler: &UpProgressHandler,
+    ) -> Result<(), UpError> {
         progress_handler.init("bundler".light_blue());
         progress_handler.progress("install Gemfile dependencies".to_string());
 
@@ -94,6 +99,11 @@ impl UpConfigBundler {
             bundle_install.arg("--gemfile");
             bundle_install.arg(gemfile);
         }
+        // When offline, the gems can only be installed from the ones
+        // already available on the system
+        if options.offline {
+            bundle_install.arg("--local");
+        }
         bundle_install.stdout(std::process::Stdio::piped());
         bundle_install.stderr(std::process::Stdio::piped());
 
@@ -104,6 +114,15 @@ impl UpConfigBundler {
         );
 
         if let Err(err) = &result {
+            if options.offline {
+                let err = UpError::Offline(format!(
+                    "gem bundle of {}",
+                    self.gemfile.as_deref().unwrap_or("Gemfile")
+                ));
+                progress_handler.error_with_message(err.message());
+                return Err(err);
+            }
+
             progress_handler.error_with_message(format!("bundle install failed: {}", err));
             return result;
         }
@@ -161,3 +180,55 @@ impl UpConfigBundler {
         abs_path(gemfile).to_str().unwrap().to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::os::unix::fs::PermissionsExt;
+
+    use crate::internal::config::up::utils::run_with_env;
+
+    #[test]
+    fn offline_installs_local_gems() {
+        run_with_env(&[], |tempdir| {
+            // Fake bundle that records its arguments, and for which the
+            // gems are not available locally
+            let bin_path = tempdir.join("bin");
+            std::fs::create_dir_all(&bin_path).expect("failed to create bin dir");
+            let bundle_path = bin_path.join("bundle");
+            std::fs::write(
+                &bundle_path,
+                format!(
+                    "#!/usr/bin/env bash\n\
+                     echo \"$@\" >> {args}\n\
+                     [[ \" $* \" == *\" --local \"* ]] && exit 1\n\
+                     exit 0\n",
+                    args = tempdir.join("bundle-args").display(),
+                ),
+            )
+            .expect("failed to write fake bundle");
+            let mut perms = std::fs::metadata(&bundle_path)
+                .expect("failed to get metadata")
+                .permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&bundle_path, perms).expect("failed to set permissions");
+
+            let config = UpConfigBundler {
+                gemfile: Some("Gemfile.dev".to_string()),
+                path: None,
+            };
+            let progress_handler = UpProgressHandler::new(None);
+
+            let options = UpOptions::default().offline(true);
+            assert_eq!(
+                config.up(&options, &progress_handler),
+                Err(UpError::Offline("gem bundle of Gemfile.dev".to_string()))
+            );
+
+            let args = std::fs::read_to_string(tempdir.join("bundle-args"))
+                .expect("failed to read bundle args");
+            assert_eq!(args, "install --gemfile Gemfile.dev --local\n");
+        });
+    }
+}
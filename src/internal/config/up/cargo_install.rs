@@ -0,0 +1,1612 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use itertools::Itertools;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::process::Command as TokioCommand;
+
+#[cfg(not(test))]
+use once_cell::sync::Lazy;
+
+use crate::internal::cache::cargo_install_operation::CargoInstallInstalled;
+use crate::internal::cache::cargo_install_operation::CargoInstallVersions;
+use crate::internal::cache::up_environments::UpEnvironment;
+use crate::internal::cache::utils as cache_utils;
+use crate::internal::cache::CacheObject;
+use crate::internal::cache::CargoInstallOperationCache;
+use crate::internal::cache::UpEnvironmentsCache;
+use crate::internal::config;
+use crate::internal::config::global_config;
+use crate::internal::config::up::lock::UpLockEntry;
+use crate::internal::config::up::plan::current_version_under;
+use crate::internal::config::up::plan::UpPlanAction;
+use crate::internal::config::up::plan::UpPlanItem;
+use crate::internal::config::up::utils::cleanup_path;
+use crate::internal::config::up::utils::force_remove_dir_all;
+use crate::internal::config::up::utils::run_progress;
+use crate::internal::config::up::utils::ProgressHandler;
+use crate::internal::config::up::utils::RunConfig;
+use crate::internal::config::up::utils::UpProgressHandler;
+use crate::internal::config::up::utils::VersionMatcher;
+use crate::internal::config::up::utils::VersionParser;
+use crate::internal::config::up::utils::VoidProgressHandler;
+use crate::internal::config::up::UpError;
+use crate::internal::config::up::UpOptions;
+use crate::internal::config::ConfigValue;
+use crate::internal::env::data_home;
+use crate::internal::user_interface::StringColor;
+use crate::internal::utils::base62_encode;
+use crate::internal::workdir;
+
+const CRATES_IO_INDEX_URL: &str = "https://index.crates.io";
+
+cfg_if::cfg_if! {
+    if #[cfg(test)] {
+        fn cargo_install_bin_path() -> PathBuf {
+            PathBuf::from(data_home()).join("cargo-install")
+        }
+    } else {
+        static CARGO_INSTALL_BIN_PATH: Lazy<PathBuf> = Lazy::new(|| PathBuf::from(data_home()).join("cargo-install"));
+
+        fn cargo_install_bin_path() -> PathBuf {
+            CARGO_INSTALL_BIN_PATH.clone()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct UpConfigCargoInstalls {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    crates: Vec<UpConfigCargoInstall>,
+}
+
+impl Serialize for UpConfigCargoInstalls {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.crates.len() {
+            0 => serializer.serialize_none(),
+            1 => serializer.serialize_newtype_struct("UpConfigCargoInstalls", &self.crates[0]),
+            _ => serializer.collect_seq(self.crates.iter()),
+        }
+    }
+}
+
+impl UpConfigCargoInstalls {
+    pub fn from_config_value(config_value: Option<&ConfigValue>) -> Self {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return UpConfigCargoInstalls::default(),
+        };
+
+        if let Some(_crate_name) = config_value.as_str_forced() {
+            return Self {
+                crates: vec![UpConfigCargoInstall::from_config_value(Some(config_value))],
+            };
+        }
+
+        if let Some(array) = config_value.as_array() {
+            return Self {
+                crates: array
+                    .iter()
+                    .map(|config_value| UpConfigCargoInstall::from_config_value(Some(config_value)))
+                    .collect(),
+            };
+        }
+
+        if let Some(table) = config_value.as_table() {
+            // Check if there is a 'crate' key, in which case it's a single
+            // crate and we can just parse it and return it
+            if table.contains_key("crate") {
+                return Self {
+                    crates: vec![UpConfigCargoInstall::from_config_value(Some(config_value))],
+                };
+            }
+
+            // Otherwise, we have a table of crates, where crate names are
+            // the keys and the values are the configuration for the crate;
+            // we want to go over them in lexico-graphical order to ensure that
+            // the order is consistent
+            let mut crates = Vec::new();
+            for crate_name in table.keys().sorted() {
+                let value = table.get(crate_name).expect("crate config not found");
+                let crate_name_value = match ConfigValue::from_str(crate_name) {
+                    Ok(value) => value,
+                    Err(_) => continue,
+                };
+
+                let mut crate_config = if let Some(table) = value.as_table() {
+                    table.clone()
+                } else if let Some(version) = value.as_str_forced() {
+                    let mut crate_config = HashMap::new();
+                    let value = match ConfigValue::from_str(&version) {
+                        Ok(value) => value,
+                        Err(_) => continue,
+                    };
+                    crate_config.insert("version".to_string(), value);
+                    crate_config
+                } else {
+                    HashMap::new()
+                };
+
+                crate_config.insert("crate".to_string(), crate_name_value);
+                crates.push(UpConfigCargoInstall::from_table(&crate_config));
+            }
+
+            return Self { crates };
+        }
+
+        UpConfigCargoInstalls::default()
+    }
+
+    /// Removes what the crates installed during the current run, and
+    /// the record of the work directory requiring them
+    pub fn rollback(&self, progress_handler: &dyn ProgressHandler) {
+        for crate_config in self.crates.iter() {
+            crate_config.rollback(progress_handler);
+        }
+    }
+
+    /// The names of the crates installed by the step
+    pub fn names(&self) -> Vec<String> {
+        self.crates
+            .iter()
+            .map(|crate_config| crate_config.crate_name.clone())
+            .collect()
+    }
+
+    /// The resources, as `(step, name)` pairs, for which the crates
+    /// installed by the step are locked
+    pub fn lock_keys(&self) -> Vec<(String, String)> {
+        self.crates
+            .iter()
+            .map(|crate_config| ("cargo-install".to_string(), crate_config.lock_name()))
+            .collect()
+    }
+
+    pub fn up(
+        &self,
+        options: &UpOptions,
+        progress_handler: &UpProgressHandler,
+    ) -> Result<(), UpError> {
+        if self.crates.len() == 1 {
+            return self.crates[0].up(options, progress_handler);
+        }
+
+        progress_handler.init("cargo install:".light_blue());
+        if self.crates.is_empty() {
+            progress_handler.error_with_message("no crate information".to_string());
+            return Err(UpError::Config("at least one crate required".to_string()));
+        }
+
+        progress_handler.progress("install dependencies".to_string());
+
+        let num = self.crates.len();
+        for (idx, cargo_crate) in self.crates.iter().enumerate() {
+            let subhandler = progress_handler.subhandler(
+                &format!(
+                    "[{current:padding$}/{total:padding$}] {cargo_crate} ",
+                    current = idx + 1,
+                    total = num,
+                    padding = format!("{}", num).len(),
+                    cargo_crate = cargo_crate.desc(),
+                )
+                .light_yellow(),
+            );
+            cargo_crate.up(options, &subhandler).inspect_err(|_err| {
+                progress_handler.error();
+            })?;
+        }
+
+        progress_handler.success_with_message(self.get_up_message());
+
+        Ok(())
+    }
+
+    fn get_up_message(&self) -> String {
+        let count: HashMap<CargoInstallHandled, usize> = self
+            .crates
+            .iter()
+            .map(|cargo_crate| cargo_crate.handling())
+            .fold(HashMap::new(), |mut map, item| {
+                *map.entry(item).or_insert(0) += 1;
+                map
+            });
+        let handled: Vec<String> = self
+            .crates
+            .iter()
+            .filter_map(|cargo_crate| match cargo_crate.handling() {
+                CargoInstallHandled::Handled | CargoInstallHandled::Noop => Some(format!(
+                    "{} {}",
+                    cargo_crate.crate_name,
+                    cargo_crate
+                        .actual_version
+                        .get()
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|| "?".to_string())
+                )),
+                _ => None,
+            })
+            .sorted()
+            .collect();
+
+        if handled.is_empty() {
+            return "nothing done".to_string();
+        }
+
+        let mut numbers = vec![];
+
+        if let Some(count) = count.get(&CargoInstallHandled::Handled) {
+            numbers.push(format!("{} installed", count).green());
+        }
+
+        if let Some(count) = count.get(&CargoInstallHandled::Noop) {
+            numbers.push(format!("{} already installed", count).light_black());
+        }
+
+        if numbers.is_empty() {
+            return "nothing done".to_string();
+        }
+
+        format!(
+            "{} {}",
+            numbers.join(", "),
+            format!("({})", handled.join(", ")).light_black().italic(),
+        )
+    }
+
+    pub fn down(&self, progress_handler: &UpProgressHandler) -> Result<(), UpError> {
+        if self.crates.len() == 1 {
+            return self.crates[0].down(progress_handler);
+        }
+
+        progress_handler.init("cargo install:".light_blue());
+        progress_handler.progress("updating dependencies".to_string());
+
+        let num = self.crates.len();
+        for (idx, cargo_crate) in self.crates.iter().enumerate() {
+            let subhandler = progress_handler.subhandler(
+                &format!(
+                    "[{current:padding$}/{total:padding$}] ",
+                    current = idx + 1,
+                    total = num,
+                    padding = format!("{}", num).len(),
+                )
+                .light_yellow(),
+            );
+            cargo_crate.down(&subhandler)?;
+        }
+
+        progress_handler.success_with_message("dependencies cleaned".light_green());
+
+        Ok(())
+    }
+
+    pub fn plan(&self, options: &UpOptions, current: Option<&UpEnvironment>) -> Vec<UpPlanItem> {
+        self.crates
+            .iter()
+            .map(|cargo_crate| cargo_crate.plan(options, current))
+            .collect()
+    }
+
+    pub fn cleanup(progress_handler: &UpProgressHandler) -> Result<Option<String>, UpError> {
+        let wd = workdir(".");
+        let wd_id = match wd.id() {
+            Some(wd_id) => wd_id,
+            None => return Err(UpError::Exec("failed to get workdir id".to_string())),
+        };
+
+        let mut return_value: Result<(bool, usize, Vec<PathBuf>), UpError> =
+            Err(UpError::Exec("cleanup_path not run".to_string()));
+
+        if let Err(err) = CargoInstallOperationCache::exclusive(|cargo_install| {
+            progress_handler.init("cargo install:".light_blue());
+            progress_handler.progress("checking for unused crates".to_string());
+
+            let mut updated = false;
+
+            let expected_paths = cargo_install
+                .installed
+                .iter_mut()
+                .filter_map(|install| {
+                    // Cleanup the references to this repository for
+                    // any installed crate that is not currently
+                    // listed in the up configuration
+                    if install.required_by.contains(&wd_id) && install.stale() {
+                        install.required_by.retain(|id| id != &wd_id);
+                        updated = true;
+                    }
+
+                    // Only return the path if the crate is expected,
+                    // as we will clear the bin path from all
+                    // unexpected crates
+                    if install.removable() {
+                        None
+                    } else {
+                        Some(
+                            cargo_install_bin_path()
+                                .join(&install.crate_name)
+                                .join(&install.version),
+                        )
+                    }
+                })
+                .collect::<Vec<PathBuf>>();
+
+            return_value = cleanup_path(
+                cargo_install_bin_path(),
+                expected_paths,
+                progress_handler,
+                true,
+            );
+
+            return_value.is_ok() && updated
+        }) {
+            progress_handler.progress(format!("failed to update cache: {}", err).light_yellow());
+        }
+
+        let (root_removed, num_removed, removed_paths) = return_value?;
+
+        if root_removed {
+            return Ok(Some("removed all installed crates".to_string()));
+        }
+
+        if num_removed == 0 {
+            return Ok(None);
+        }
+
+        // We want to go over the paths that were removed to
+        // return a proper message about the crates that
+        // were removed
+        let removed_crates = removed_paths
+            .iter()
+            .filter_map(|path| {
+                // Path should starts with the bin path if it is a crate
+                let rest_of_path = match path.strip_prefix(cargo_install_bin_path()) {
+                    Ok(rest_of_path) => rest_of_path,
+                    Err(_) => return None,
+                };
+
+                // Path should have two components left after stripping
+                // the bin path: the crate name (1) and the version (1)
+                let parts = rest_of_path
+                    .components()
+                    .map(|part| part.as_os_str().to_string_lossy().to_string())
+                    .collect::<Vec<String>>();
+                match parts.len() {
+                    1 => Some(format!("{} (all versions)", parts[0].light_yellow())),
+                    2 => Some(format!(
+                        "{} {}",
+                        parts[0].light_yellow(),
+                        parts[1].light_yellow()
+                    )),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if removed_crates.is_empty() {
+            return Ok(Some(format!(
+                "removed {} crate{}",
+                num_removed.light_yellow(),
+                if num_removed > 1 { "s" } else { "" }
+            )));
+        }
+
+        Ok(Some(format!("removed {}", removed_crates.join(", "))))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+pub enum CargoInstallHandled {
+    Handled,
+    Noop,
+    Unhandled,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct UpConfigCargoInstall {
+    /// The name of the crate to install
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+
+    /// The version of the crate to install
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Whether to always upgrade the crate or use the latest matching
+    /// already installed version.
+    #[serde(default, skip_serializing_if = "cache_utils::is_false")]
+    pub upgrade: bool,
+
+    /// Whether to install the pre-release version of the crate
+    /// if it is the most recent matching version
+    #[serde(default, skip_serializing_if = "cache_utils::is_false")]
+    pub prerelease: bool,
+
+    /// Whether to allow versions containing build details
+    /// (e.g. 1.2.3+build)
+    #[serde(default, skip_serializing_if = "cache_utils::is_false")]
+    pub build: bool,
+
+    /// Whether to install the crate using the `Cargo.lock` file
+    /// packaged with it (`cargo install --locked`)
+    #[serde(default, skip_serializing_if = "cache_utils::is_false")]
+    pub locked: bool,
+
+    /// The features to activate when building the crate
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+
+    /// Whether to activate all the available features of the crate
+    #[serde(default, skip_serializing_if = "cache_utils::is_false")]
+    pub all_features: bool,
+
+    /// Whether to disable the default features of the crate
+    #[serde(default, skip_serializing_if = "cache_utils::is_false")]
+    pub no_default_features: bool,
+
+    /// The URL of the sparse registry index to resolve the versions
+    /// of the crate from; this is only required if installing from
+    /// an alternative registry. By default, this is set to the
+    /// crates.io index (https://index.crates.io).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index_url: Option<String>,
+
+    #[serde(default, skip)]
+    pub actual_version: OnceCell<String>,
+
+    #[serde(default, skip)]
+    was_handled: OnceCell<CargoInstallHandled>,
+}
+
+impl UpConfigCargoInstall {
+    pub fn from_config_value(config_value: Option<&ConfigValue>) -> Self {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return UpConfigCargoInstall::default(),
+        };
+
+        if let Some(table) = config_value.as_table() {
+            Self::from_table(&table)
+        } else if let Some(crate_name) = config_value.as_str_forced() {
+            UpConfigCargoInstall {
+                crate_name,
+                ..UpConfigCargoInstall::default()
+            }
+        } else {
+            UpConfigCargoInstall::default()
+        }
+    }
+
+    fn from_table(table: &HashMap<String, ConfigValue>) -> Self {
+        let crate_name = match table.get("crate") {
+            Some(crate_name) => crate_name.as_str_forced().unwrap_or_default(),
+            None => {
+                if table.len() == 1 {
+                    let (key, value) = table.iter().next().unwrap();
+                    if let Some(version) = value.as_str_forced() {
+                        return UpConfigCargoInstall {
+                            crate_name: key.clone(),
+                            version: Some(version.to_string()),
+                            ..UpConfigCargoInstall::default()
+                        };
+                    } else if let (Some(table), Ok(crate_config_value)) =
+                        (value.as_table(), ConfigValue::from_str(key))
+                    {
+                        let mut crate_config = table.clone();
+                        crate_config.insert("crate".to_string(), crate_config_value);
+                        return UpConfigCargoInstall::from_table(&crate_config);
+                    }
+                }
+                return UpConfigCargoInstall::default();
+            }
+        };
+
+        let version = table
+            .get("version")
+            .map(|v| v.as_str_forced())
+            .unwrap_or(None);
+        let upgrade = table
+            .get("upgrade")
+            .map(|v| v.as_bool_forced())
+            .unwrap_or(None)
+            .unwrap_or(false);
+        let prerelease = table
+            .get("prerelease")
+            .map(|v| v.as_bool())
+            .unwrap_or(None)
+            .unwrap_or(false);
+        let build = table
+            .get("build")
+            .map(|v| v.as_bool())
+            .unwrap_or(None)
+            .unwrap_or(false);
+        let locked = table
+            .get("locked")
+            .map(|v| v.as_bool_forced())
+            .unwrap_or(None)
+            .unwrap_or(false);
+        let features = match table.get("features") {
+            Some(features) => match features.as_array() {
+                Some(array) => array.iter().filter_map(|v| v.as_str_forced()).collect(),
+                None => features
+                    .as_str_forced()
+                    .map(|v| {
+                        v.split([',', ' '])
+                            .filter(|feature| !feature.is_empty())
+                            .map(|feature| feature.to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
+            None => vec![],
+        };
+        let all_features = table
+            .get("all_features")
+            .map(|v| v.as_bool_forced())
+            .unwrap_or(None)
+            .unwrap_or(false);
+        let no_default_features = table
+            .get("no_default_features")
+            .map(|v| v.as_bool_forced())
+            .unwrap_or(None)
+            .unwrap_or(false);
+        let index_url = table
+            .get("index_url")
+            .map(|v| v.as_str_forced())
+            .unwrap_or(None);
+
+        UpConfigCargoInstall {
+            crate_name,
+            version,
+            upgrade,
+            prerelease,
+            build,
+            locked,
+            features,
+            all_features,
+            no_default_features,
+            index_url,
+            ..UpConfigCargoInstall::default()
+        }
+    }
+
+    fn update_cache(&self, progress_handler: &dyn ProgressHandler) {
+        let wd = workdir(".");
+        let wd_id = match wd.id() {
+            Some(wd_id) => wd_id,
+            None => return,
+        };
+
+        let version = match self.actual_version.get() {
+            Some(version) => version,
+            None => {
+                progress_handler.error_with_message("version not set".to_string());
+                return;
+            }
+        };
+
+        progress_handler.progress("updating cache".to_string());
+
+        if let Err(err) = CargoInstallOperationCache::exclusive(|cargo_install| {
+            cargo_install.add_installed(&wd_id, &self.crate_name, &self.version_dir(version))
+        }) {
+            progress_handler.progress(format!("failed to update cargo install cache: {}", err));
+            return;
+        }
+
+        let crate_bin_path = self.crate_version_path(version).join("bin");
+
+        if let Err(err) =
+            UpEnvironmentsCache::exclusive(|up_env| up_env.add_path(&wd_id, crate_bin_path))
+        {
+            progress_handler.progress(format!("failed to update up environment cache: {}", err));
+            return;
+        }
+
+        progress_handler.progress("updated cache".to_string());
+    }
+
+    fn desc(&self) -> String {
+        if self.crate_name.is_empty() {
+            "cargo install:".to_string()
+        } else {
+            format!(
+                "{} ({}):",
+                self.crate_name,
+                match self.version {
+                    None => "latest".to_string(),
+                    Some(ref version) if version.is_empty() => "latest".to_string(),
+                    Some(ref version) => version.clone(),
+                }
+            )
+        }
+    }
+
+    pub fn up(
+        &self,
+        options: &UpOptions,
+        progress_handler: &UpProgressHandler,
+    ) -> Result<(), UpError> {
+        progress_handler.init(self.desc().light_blue());
+        progress_handler.progress("install crate".to_string());
+
+        if self.crate_name.is_empty() {
+            progress_handler.error_with_message("crate is required".to_string());
+            return Err(UpError::Config("crate is required".to_string()));
+        }
+
+        let installed = self.resolve_and_install_version(options, progress_handler)?;
+
+        self.update_cache(progress_handler);
+        self.update_lock(options);
+
+        let version = match self.actual_version.get() {
+            Some(version) => version.to_string(),
+            None => "unknown".to_string(),
+        };
+        let msg = match installed {
+            true => format!("{} installed", version.light_yellow()),
+            false => format!("{} already installed", version).light_black(),
+        };
+        progress_handler.success_with_message(msg);
+
+        Ok(())
+    }
+
+    pub fn down(&self, progress_handler: &UpProgressHandler) -> Result<(), UpError> {
+        let wd = workdir(".");
+        let wd_id = match wd.id() {
+            Some(wd_id) => wd_id,
+            None => return Ok(()),
+        };
+
+        if let Err(err) = CargoInstallOperationCache::exclusive(|cargo_install| {
+            progress_handler.init(self.desc().light_blue());
+            progress_handler.progress("updating cargo install dependencies".to_string());
+
+            let mut updated = false;
+
+            for install in cargo_install.installed.iter_mut().filter(|install| {
+                install.crate_name == self.crate_name && install.required_by.contains(&wd_id)
+            }) {
+                install.required_by.retain(|id| id != &wd_id);
+                updated = true;
+            }
+
+            updated
+        }) {
+            progress_handler.progress(format!("failed to update cache: {}", err).light_yellow());
+        }
+
+        progress_handler.success_with_message("cargo install dependencies cleaned".light_green());
+
+        Ok(())
+    }
+
+    /// Removes the work directory from the ones requiring the crate,
+    /// and removes the crate if it was installed during this run and
+    /// no other work directory requires it
+    fn rollback(&self, progress_handler: &dyn ProgressHandler) {
+        let handling = self.handling();
+        if handling == CargoInstallHandled::Unhandled {
+            return;
+        }
+
+        let wd = workdir(".");
+        let (wd_id, version) = match (wd.id(), self.actual_version.get()) {
+            (Some(wd_id), Some(version)) => (wd_id, version),
+            _ => return,
+        };
+
+        progress_handler.progress(format!("rolling back {} {}", self.crate_name, version));
+
+        // The crate is only removed if it was installed during this
+        // run and no other work directory requires it
+        let mut remove = handling == CargoInstallHandled::Handled;
+        if let Err(err) = CargoInstallOperationCache::exclusive(|cargo_install| {
+            let version_dir = self.version_dir(version);
+            let matches = |install: &CargoInstallInstalled| {
+                install.crate_name == self.crate_name && install.version == version_dir
+            };
+
+            let mut updated = false;
+            for install in cargo_install.installed.iter_mut().filter(|i| matches(i)) {
+                updated |= install.required_by.remove(&wd_id);
+                remove &= install.required_by.is_empty();
+            }
+
+            if remove {
+                cargo_install.installed.retain(|install| !matches(install));
+            }
+
+            updated
+        }) {
+            progress_handler.progress(format!("failed to update cache: {}", err).light_yellow());
+            return;
+        }
+
+        if remove {
+            if let Err(err) = force_remove_dir_all(self.crate_version_path(version)) {
+                progress_handler.progress(format!("failed to remove crate: {}", err));
+            }
+        }
+    }
+
+    /// Resolves the version that would be used by `up` without
+    /// installing anything nor updating the caches.
+    pub fn plan(&self, options: &UpOptions, current: Option<&UpEnvironment>) -> UpPlanItem {
+        if self.crate_name.is_empty() {
+            return UpPlanItem::new(
+                "cargo-install",
+                UpPlanAction::Unknown("crate is required".to_string()),
+            );
+        }
+
+        let options = UpOptions {
+            write_cache: false,
+            ..options.clone()
+        };
+
+        let version = match self.plan_version(&options, &VoidProgressHandler {}) {
+            Ok(version) => version,
+            Err(err) => {
+                return UpPlanItem::new(&self.crate_name, UpPlanAction::Unknown(err.message()))
+            }
+        };
+
+        let crate_version_path = self.crate_version_path(&version);
+        let current_version =
+            current_version_under(current, &cargo_install_bin_path().join(&self.crate_name))
+                .map(|current| self.installed_version(&current).unwrap_or(current));
+
+        UpPlanItem::new(
+            &self.crate_name,
+            UpPlanAction::for_version(crate_version_path.exists(), current_version, &version),
+        )
+        .with_version(&version)
+        .with_path(crate_version_path.join("bin"))
+    }
+
+    fn plan_version(
+        &self,
+        options: &UpOptions,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<String, UpError> {
+        if let Some(locked) = self.locked(options) {
+            return Ok(locked.version.clone());
+        }
+
+        if !self.upgrade_version(options) {
+            let resolve_str = match self.version.as_ref() {
+                Some(version) if version != "latest" => version.to_string(),
+                _ => self.latest_version(&self.list_versions(options, progress_handler)?)?,
+            };
+
+            let installed_versions = self.list_installed_versions()?;
+            if let Ok(version) = self.resolve_version_from_str(&resolve_str, &installed_versions) {
+                return Ok(version);
+            }
+        }
+
+        let versions = self.list_versions(options, progress_handler)?;
+        self.resolve_version(&versions.versions)
+    }
+
+    fn upgrade_version(&self, options: &UpOptions) -> bool {
+        self.upgrade || options.upgrade || config(".").up_command.upgrade
+    }
+
+    fn resolve_and_install_version(
+        &self,
+        options: &UpOptions,
+        progress_handler: &UpProgressHandler,
+    ) -> Result<bool, UpError> {
+        let mut version = "".to_string();
+        let mut install_version = Err(UpError::Exec("did not even try".to_string()));
+        let mut versions = None;
+
+        // If the version is locked, we install exactly that version
+        if let Some(locked) = self.locked(options) {
+            progress_handler.progress(format!("using locked version {}", locked.version));
+
+            version = locked.version.clone();
+            install_version = self.install_version(options, &version, progress_handler);
+        }
+
+        // If the options do not include upgrade, then we can try using
+        // an already-installed version if any matches the requirements
+        if version.is_empty() && !self.upgrade_version(options) {
+            let resolve_str = match self.version.as_ref() {
+                Some(version) if version != "latest" => version.to_string(),
+                _ => match self.list_versions(options, progress_handler) {
+                    Ok(list_versions) => {
+                        versions = Some(list_versions.clone());
+                        let latest = self.latest_version(&list_versions)?;
+                        progress_handler.progress(
+                            format!("considering installed versions matching {}", latest)
+                                .light_black(),
+                        );
+                        latest
+                    }
+                    // Without a version list while offline, any installed
+                    // version is better than none
+                    Err(UpError::Offline(_)) => "latest".to_string(),
+                    Err(err) => return Err(err),
+                },
+            };
+
+            let installed_versions = self.list_installed_versions()?;
+            match self.resolve_version_from_str(&resolve_str, &installed_versions) {
+                Ok(installed_version) => {
+                    progress_handler.progress(format!(
+                        "found matching installed version {}",
+                        installed_version.light_yellow(),
+                    ));
+
+                    version = installed_version;
+                    install_version = Ok(false);
+                }
+                Err(_err) => {
+                    progress_handler.progress("no matching version installed".to_string());
+                }
+            }
+        }
+
+        if version.is_empty() {
+            let versions = match versions {
+                Some(versions) => versions,
+                None => match self.list_versions(options, progress_handler) {
+                    Ok(versions) => versions,
+                    Err(UpError::Offline(_)) => {
+                        let err = UpError::Offline(format!(
+                            "{} {}",
+                            self.crate_name,
+                            self.version.as_deref().unwrap_or("latest")
+                        ));
+                        progress_handler.error_with_message(err.message());
+                        return Err(err);
+                    }
+                    Err(err) => return Err(err),
+                },
+            };
+            let resolved = match self.resolve_version(&versions.versions) {
+                Ok(resolved) => resolved,
+                Err(err) => {
+                    // If the versions are not fresh of now, and we failed to
+                    // resolve the version, we should try to refresh the
+                    // versions list and try again
+                    if options.read_cache && !versions.is_fresh() {
+                        progress_handler.progress("no matching version found in cache".to_string());
+
+                        let versions = self.list_versions(
+                            &UpOptions {
+                                read_cache: false,
+                                ..options.clone()
+                            },
+                            progress_handler,
+                        )?;
+
+                        self.resolve_version(&versions.versions)
+                            .inspect_err(|err| {
+                                progress_handler.error_with_message(err.message());
+                            })?
+                    } else {
+                        progress_handler.error_with_message(err.message());
+                        return Err(err);
+                    }
+                }
+            };
+
+            version = resolved;
+
+            // Try installing the version found
+            install_version = self.install_version(options, &version, progress_handler);
+            if install_version.is_err() && !options.fail_on_upgrade {
+                // If we get here and there is an issue installing the version,
+                // list all installed versions and check if one of those could
+                // fit the requirement, in which case we can fallback to it
+                let installed_versions = self.list_installed_versions()?;
+                if let Ok(installed_version) = self.resolve_version(&installed_versions) {
+                    progress_handler.progress(format!(
+                        "falling back to {} {}",
+                        self.crate_name,
+                        installed_version.light_yellow(),
+                    ));
+
+                    version = installed_version;
+                    install_version = Ok(false);
+                }
+            }
+        }
+
+        if let Ok(installed) = &install_version {
+            self.actual_version.set(version.to_string()).map_err(|_| {
+                let errmsg = "failed to set actual version".to_string();
+                UpError::Exec(errmsg)
+            })?;
+
+            if self
+                .was_handled
+                .set(if *installed {
+                    CargoInstallHandled::Handled
+                } else {
+                    CargoInstallHandled::Noop
+                })
+                .is_err()
+            {
+                unreachable!("failed to set was_handled");
+            }
+        }
+
+        install_version
+    }
+
+    /// Returns the locked entry for this crate, if any, and if the
+    /// locked version still matches the requested version
+    fn locked<'a>(&self, options: &UpOptions<'a>) -> Option<&'a UpLockEntry> {
+        let locked = options.locked("cargo-install", &self.lock_name(), "")?;
+
+        let mut matcher = VersionMatcher::new(self.version.as_deref().unwrap_or("latest"));
+        matcher.prerelease(self.prerelease);
+        matcher.build(self.build);
+
+        if matcher.matches(&locked.version) {
+            Some(locked)
+        } else {
+            None
+        }
+    }
+
+    fn update_lock(&self, options: &UpOptions) {
+        if let Some(version) = self.actual_version.get() {
+            options.lock(UpLockEntry::new(
+                "cargo-install",
+                &self.lock_name(),
+                "",
+                version,
+            ));
+        }
+    }
+
+    fn list_versions(
+        &self,
+        options: &UpOptions,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<CargoInstallVersions, UpError> {
+        // When offline, the cached version list is all we have, no
+        // matter how old it is
+        if options.offline {
+            return match CargoInstallOperationCache::get().get_versions(&self.crate_name) {
+                Some(versions) => {
+                    progress_handler.progress("using cached version list (offline)".light_black());
+                    Ok(versions.clone())
+                }
+                None => Err(UpError::Offline(format!(
+                    "{} version list",
+                    self.crate_name
+                ))),
+            };
+        }
+
+        let cached_versions = if options.read_cache {
+            let cache = CargoInstallOperationCache::get();
+            if let Some(versions) = cache.get_versions(&self.crate_name) {
+                let versions = versions.clone();
+                let config = global_config();
+                let expire = config.cache.cargo_install.versions_expire;
+                if !versions.is_stale(expire) {
+                    progress_handler.progress("using cached version list".light_black());
+                    return Ok(versions);
+                }
+                Some(versions)
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        progress_handler.progress("refreshing versions list from the registry".to_string());
+        match self.list_versions_from_index(progress_handler) {
+            Ok(versions) => {
+                if options.write_cache {
+                    progress_handler.progress("updating cache with version list".to_string());
+                    if let Err(err) = CargoInstallOperationCache::exclusive(|cargo_install| {
+                        cargo_install.add_versions(&self.crate_name, &versions)
+                    }) {
+                        progress_handler.progress(format!("failed to update cache: {}", err));
+                    }
+                }
+
+                Ok(versions)
+            }
+            Err(err) => {
+                if let Some(cached_versions) = cached_versions {
+                    progress_handler.progress(format!(
+                        "{}; {}",
+                        format!("error refreshing version list: {}", err).red(),
+                        "using cached data".light_black()
+                    ));
+                    Ok(cached_versions)
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    fn list_versions_from_index(
+        &self,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<CargoInstallVersions, UpError> {
+        let index_url = self
+            .index_url
+            .clone()
+            .unwrap_or(CRATES_IO_INDEX_URL.to_string());
+        let crate_url = format!(
+            "{}/{}",
+            index_url.trim_end_matches('/'),
+            sparse_index_path(&self.crate_name)
+        );
+
+        let network = global_config().network;
+        let client = match network.client_builder().and_then(|client_builder| {
+            client_builder
+                .user_agent(format!("omni {}", env!("CARGO_PKG_VERSION")))
+                .build()
+                .map_err(|err| format!("failed to create client: {}", err))
+        }) {
+            Ok(client) => client,
+            Err(errmsg) => {
+                progress_handler.error_with_message(errmsg.clone());
+                return Err(UpError::Exec(errmsg));
+            }
+        };
+
+        let response = client
+            .get(network.rewrite_url(&crate_url))
+            .send()
+            .map_err(|err| {
+                let errmsg = format!("failed to get versions: {}", err);
+                progress_handler.error_with_message(errmsg.clone());
+                UpError::Exec(errmsg)
+            })?;
+
+        let status = response.status();
+        let contents = response.text().map_err(|err| {
+            let errmsg = format!("failed to read response: {}", err);
+            progress_handler.error_with_message(errmsg.clone());
+            UpError::Exec(errmsg)
+        })?;
+
+        if status == reqwest::StatusCode::NOT_FOUND {
+            let errmsg = format!("crate {} not found in {}", self.crate_name, index_url);
+            progress_handler.error_with_message(errmsg.clone());
+            return Err(UpError::Exec(errmsg));
+        }
+
+        if !status.is_success() {
+            let errmsg = format!("{}: {} ({})", crate_url, contents.trim(), status);
+            progress_handler.error_with_message(errmsg.clone());
+            return Err(UpError::Exec(errmsg));
+        }
+
+        let versions = CargoInstallVersions::from_index(&contents).map_err(|err| {
+            let errmsg = format!("failed to parse versions: {}", err);
+            progress_handler.error_with_message(errmsg.clone());
+            UpError::Exec(errmsg)
+        })?;
+
+        Ok(versions)
+    }
+
+    fn latest_version(&self, versions: &CargoInstallVersions) -> Result<String, UpError> {
+        let latest = self.resolve_version_from_str("latest", &versions.versions)?;
+        Ok(VersionParser::parse(&latest)
+            .expect("failed to parse version string")
+            .major()
+            .to_string())
+    }
+
+    fn list_installed_versions(&self) -> Result<Vec<String>, UpError> {
+        let crate_path = cargo_install_bin_path().join(&self.crate_name);
+
+        if !crate_path.exists() {
+            return Ok(vec![]);
+        }
+
+        let installed_versions = std::fs::read_dir(&crate_path)
+            .map_err(|err| {
+                let errmsg = format!("failed to read directory: {}", err);
+                UpError::Exec(errmsg)
+            })?
+            .filter_map(|entry| {
+                entry.ok().and_then(|entry| {
+                    if entry.file_type().ok()?.is_dir() {
+                        self.installed_version(&entry.file_name().into_string().ok()?)
+                    } else {
+                        None
+                    }
+                })
+            })
+            .collect();
+
+        Ok(installed_versions)
+    }
+
+    fn resolve_version(&self, versions: &[String]) -> Result<String, UpError> {
+        let match_version = self.version.clone().unwrap_or_else(|| "latest".to_string());
+        self.resolve_version_from_str(&match_version, versions)
+    }
+
+    fn resolve_version_from_str(
+        &self,
+        match_version: &str,
+        versions: &[String],
+    ) -> Result<String, UpError> {
+        let mut matcher = VersionMatcher::new(match_version);
+        matcher.prerelease(self.prerelease);
+        matcher.build(self.build);
+
+        let version = versions
+            .iter()
+            .filter_map(|version| VersionParser::parse(version))
+            .sorted()
+            .rev()
+            .find(|version| matcher.matches(&version.to_string()))
+            .ok_or_else(|| {
+                UpError::Exec(format!(
+                    "no matching version found for {} {}",
+                    self.crate_name, match_version,
+                ))
+            })?;
+
+        Ok(version.to_string())
+    }
+
+    fn crate_version_path(&self, version: &str) -> PathBuf {
+        cargo_install_bin_path()
+            .join(&self.crate_name)
+            .join(self.version_dir(version))
+    }
+
+    /// Returns an identifier of the options used to build the crate,
+    /// if any is set, so that builds of a same version of the crate
+    /// with different options do not share their install
+    fn build_id(&self) -> Option<String> {
+        let mut features = self.features.clone();
+        features.sort();
+        features.dedup();
+
+        let options = [
+            ("locked", self.locked.then(|| "true".to_string())),
+            (
+                "all_features",
+                self.all_features.then(|| "true".to_string()),
+            ),
+            (
+                "no_default_features",
+                self.no_default_features.then(|| "true".to_string()),
+            ),
+            ("index_url", self.index_url.clone()),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key, value)))
+        .chain(features.into_iter().map(|feature| ("features", feature)))
+        .collect::<Vec<_>>();
+
+        if options.is_empty() {
+            return None;
+        }
+
+        let mut hasher = blake3::Hasher::new();
+        for (key, value) in options {
+            hasher.update(key.as_bytes());
+            hasher.update(b"\0");
+            hasher.update(value.as_bytes());
+            hasher.update(b"\0");
+        }
+
+        Some(base62_encode(hasher.finalize().as_bytes())[..10].to_string())
+    }
+
+    /// Returns the name of the directory in which the version of the
+    /// crate is installed, suffixed by the identifier of the build
+    /// options if any
+    fn version_dir(&self, version: &str) -> String {
+        match self.build_id() {
+            Some(build_id) => format!("{}@{}", version, build_id),
+            None => version.to_string(),
+        }
+    }
+
+    /// Returns the version installed in the given directory name, if
+    /// it was built with the same options as this crate
+    fn installed_version(&self, version_dir: &str) -> Option<String> {
+        match (version_dir.split_once('@'), self.build_id()) {
+            (Some((version, build_id)), Some(expected)) if build_id == expected => {
+                Some(version.to_string())
+            }
+            (None, None) => Some(version_dir.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Returns the name under which the crate is locked, which includes
+    /// the identifier of the build options if any
+    fn lock_name(&self) -> String {
+        match self.build_id() {
+            Some(build_id) => format!("{}@{}", self.crate_name, build_id),
+            None => self.crate_name.clone(),
+        }
+    }
+
+    fn install_command(&self, version: &str, install_path: &std::path::Path) -> TokioCommand {
+        let mut cargo_install = TokioCommand::new("cargo");
+        cargo_install.arg("install");
+        cargo_install.arg(&self.crate_name);
+        cargo_install.arg("--version");
+        cargo_install.arg(format!("={}", version));
+        cargo_install.arg("--root");
+        cargo_install.arg(install_path);
+
+        if let Some(index_url) = &self.index_url {
+            cargo_install.arg("--index");
+            cargo_install.arg(format!("sparse+{}", index_url));
+        }
+
+        if self.locked {
+            cargo_install.arg("--locked");
+        }
+
+        if self.all_features {
+            cargo_install.arg("--all-features");
+        } else if !self.features.is_empty() {
+            cargo_install.arg("--features");
+            cargo_install.arg(self.features.join(","));
+        }
+
+        if self.no_default_features {
+            cargo_install.arg("--no-default-features");
+        }
+
+        cargo_install.stdout(std::process::Stdio::piped());
+        cargo_install.stderr(std::process::Stdio::piped());
+
+        cargo_install
+    }
+
+    fn install_version(
+        &self,
+        options: &UpOptions,
+        version: &str,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<bool, UpError> {
+        let install_path = self.crate_version_path(version);
+
+        if (options.read_cache || options.offline) && install_path.join("bin").is_dir() {
+            progress_handler.progress(
+                format!("installed {} {} (cached)", self.crate_name, version).light_black(),
+            );
+
+            return Ok(false);
+        }
+
+        if options.offline {
+            let err = UpError::Offline(format!("{} {}", self.crate_name, version));
+            progress_handler.error_with_message(err.message());
+            return Err(err);
+        }
+
+        if which::which("cargo").is_err() {
+            let errmsg = "cargo is required to install crates".to_string();
+            progress_handler.error_with_message(errmsg.clone());
+            return Err(UpError::Exec(errmsg));
+        }
+
+        progress_handler.progress(format!(
+            "installing {} {}",
+            self.crate_name.light_yellow(),
+            version.light_yellow()
+        ));
+
+        let mut cargo_install = self.install_command(version, &install_path);
+        if let Err(err) = run_progress(
+            &mut cargo_install,
+            Some(progress_handler),
+            RunConfig::default(),
+        ) {
+            // Force delete the install path if we fail to install
+            // the crate to avoid leaving a partial installation
+            // behind
+            let _ = force_remove_dir_all(&install_path);
+
+            let errmsg = format!("failed to install {}: {}", self.crate_name, err);
+            progress_handler.error_with_message(errmsg.clone());
+            return Err(UpError::Exec(errmsg));
+        }
+
+        progress_handler.progress(format!(
+            "installed {} {}",
+            self.crate_name.light_yellow(),
+            version.light_yellow()
+        ));
+
+        Ok(true)
+    }
+
+    fn handling(&self) -> CargoInstallHandled {
+        match self.was_handled.get() {
+            Some(handled) => handled.clone(),
+            None => CargoInstallHandled::Unhandled,
+        }
+    }
+}
+
+/// Returns the path of the file describing a crate in a sparse
+/// registry index, as documented in
+/// https://doc.rust-lang.org/cargo/reference/registry-index.html#index-files
+fn sparse_index_path(crate_name: &str) -> String {
+    let name = crate_name.to_lowercase();
+    match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod from_config_value {
+        use super::*;
+
+        #[test]
+        fn str() {
+            let config_value =
+                ConfigValue::from_str("cargo-nextest").expect("failed to create config value");
+            let config = UpConfigCargoInstalls::from_config_value(Some(&config_value));
+            assert_eq!(config.crates.len(), 1);
+            assert_eq!(config.crates[0].crate_name, "cargo-nextest");
+            assert_eq!(config.crates[0].version, None);
+            assert!(!config.crates[0].locked);
+        }
+
+        #[test]
+        fn object_single() {
+            let yaml = r#"{"crate": "sqlx-cli", "version": "0.7", "locked": true, "features": ["postgres", "rustls"], "no_default_features": true}"#;
+            let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+            let config = UpConfigCargoInstalls::from_config_value(Some(&config_value));
+            assert_eq!(config.crates.len(), 1);
+            assert_eq!(config.crates[0].crate_name, "sqlx-cli");
+            assert_eq!(config.crates[0].version, Some("0.7".to_string()));
+            assert!(config.crates[0].locked);
+            assert_eq!(config.crates[0].features, vec!["postgres", "rustls"]);
+            assert!(config.crates[0].no_default_features);
+            assert!(!config.crates[0].all_features);
+        }
+
+        #[test]
+        fn object_multi() {
+            let yaml = r#"{"mdbook": "0.4", "cargo-nextest": {"version": "0.9", "locked": true}}"#;
+            let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+            let config = UpConfigCargoInstalls::from_config_value(Some(&config_value));
+            assert_eq!(config.crates.len(), 2);
+
+            assert_eq!(config.crates[0].crate_name, "cargo-nextest");
+            assert_eq!(config.crates[0].version, Some("0.9".to_string()));
+            assert!(config.crates[0].locked);
+
+            assert_eq!(config.crates[1].crate_name, "mdbook");
+            assert_eq!(config.crates[1].version, Some("0.4".to_string()));
+            assert!(!config.crates[1].locked);
+        }
+
+        #[test]
+        fn list_multi() {
+            let yaml = r#"["mdbook", {"crate": "sqlx-cli", "features": "postgres,rustls"}, {"cargo-nextest": "0.9"}]"#;
+            let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+            let config = UpConfigCargoInstalls::from_config_value(Some(&config_value));
+            assert_eq!(config.crates.len(), 3);
+
+            assert_eq!(config.crates[0].crate_name, "mdbook");
+            assert_eq!(config.crates[1].crate_name, "sqlx-cli");
+            assert_eq!(config.crates[1].features, vec!["postgres", "rustls"]);
+            assert_eq!(config.crates[2].crate_name, "cargo-nextest");
+            assert_eq!(config.crates[2].version, Some("0.9".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_sparse_index_path() {
+        assert_eq!(sparse_index_path("a"), "1/a");
+        assert_eq!(sparse_index_path("ab"), "2/ab");
+        assert_eq!(sparse_index_path("abc"), "3/a/abc");
+        assert_eq!(sparse_index_path("cargo-nextest"), "ca/rg/cargo-nextest");
+        assert_eq!(sparse_index_path("Inflector"), "in/fl/inflector");
+    }
+
+    mod up {
+        use super::*;
+
+        use std::os::unix::fs::PermissionsExt;
+
+        use crate::internal::config::up::utils::run_with_env;
+
+        /// Runs the closure in a test environment providing a fake cargo
+        /// binary that records its arguments and creates the binary of
+        /// the crate in the requested root
+        fn run_with_cargo<F>(closure: F)
+        where
+            F: FnOnce(&std::path::Path),
+        {
+            run_with_env(&[], |tempdir| {
+                let bin_path = tempdir.join("bin");
+                std::fs::create_dir_all(&bin_path).expect("failed to create bin dir");
+                let cargo_path = bin_path.join("cargo");
+                std::fs::write(
+                    &cargo_path,
+                    format!(
+                        "#!/usr/bin/env bash\n\
+                         echo \"$@\" > {args}\n\
+                         while [[ $# -gt 0 ]]; do\n\
+                           [[ \"$1\" == \"--root\" ]] && root=\"$2\"\n\
+                           shift\n\
+                         done\n\
+                         mkdir -p \"$root/bin\" && touch \"$root/bin/crate-bin\"\n",
+                        args = tempdir.join("cargo-args").display(),
+                    ),
+                )
+                .expect("failed to write fake cargo");
+                let mut perms = std::fs::metadata(&cargo_path)
+                    .expect("failed to get metadata")
+                    .permissions();
+                perms.set_mode(0o755);
+                std::fs::set_permissions(&cargo_path, perms).expect("failed to set permissions");
+
+                closure(tempdir)
+            });
+        }
+
+        #[test]
+        fn install_matching_version() {
+            run_with_cargo(|tempdir| {
+                let mut mock_server = mockito::Server::new();
+
+                let config = UpConfigCargoInstall {
+                    crate_name: "cargo-nextest".to_string(),
+                    version: Some("0.9".to_string()),
+                    locked: true,
+                    features: vec!["default-no-update".to_string()],
+                    index_url: Some(mock_server.url()),
+                    ..UpConfigCargoInstall::default()
+                };
+
+                let mock_index = mock_server
+                    .mock("GET", "/ca/rg/cargo-nextest")
+                    .with_status(200)
+                    .with_body(
+                        [
+                            r#"{"name":"cargo-nextest","vers":"0.9.66","yanked":false}"#,
+                            r#"{"name":"cargo-nextest","vers":"0.9.67","yanked":false}"#,
+                            r#"{"name":"cargo-nextest","vers":"0.9.68","yanked":true}"#,
+                            r#"{"name":"cargo-nextest","vers":"1.0.0","yanked":false}"#,
+                        ]
+                        .join("\n"),
+                    )
+                    .create();
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = config.up(&options, &progress_handler);
+                assert!(result.is_ok(), "up failed: {:?}", result);
+                mock_index.assert();
+
+                assert_eq!(config.actual_version.get(), Some(&"0.9.67".to_string()));
+
+                let expected_bin = config
+                    .crate_version_path("0.9.67")
+                    .join("bin")
+                    .join("crate-bin");
+                assert!(expected_bin.exists(), "binary not found");
+
+                let args = std::fs::read_to_string(tempdir.join("cargo-args"))
+                    .expect("failed to read cargo args");
+                assert!(args.starts_with("install cargo-nextest --version =0.9.67 --root "));
+                assert!(args.contains(" --locked"));
+                assert!(args.contains(" --features default-no-update"));
+            });
+        }
+
+        #[test]
+        fn build_options_do_not_share_install() {
+            run_with_cargo(|tempdir| {
+                let mut mock_server = mockito::Server::new();
+
+                let index_url = mock_server.url();
+                let config = |features: &[&str]| UpConfigCargoInstall {
+                    crate_name: "sqlx-cli".to_string(),
+                    features: features.iter().map(|f| f.to_string()).collect(),
+                    index_url: Some(index_url.clone()),
+                    ..UpConfigCargoInstall::default()
+                };
+
+                let mock_index = mock_server
+                    .mock("GET", "/sq/lx/sqlx-cli")
+                    .with_status(200)
+                    .with_body(r#"{"name":"sqlx-cli","vers":"0.7.3","yanked":false}"#)
+                    .expect_at_least(1)
+                    .create();
+
+                let options = UpOptions::default();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let postgres = config(&["postgres"]);
+                let result = postgres.up(&options, &progress_handler);
+                assert!(result.is_ok(), "up failed: {:?}", result);
+
+                // The same version built with other features is not
+                // considered installed, and is installed separately
+                let sqlite = config(&["sqlite"]);
+                let result = sqlite.up(&options, &progress_handler);
+                assert!(result.is_ok(), "up failed: {:?}", result);
+                mock_index.assert();
+
+                assert_eq!(sqlite.handling(), CargoInstallHandled::Handled);
+                assert_ne!(
+                    postgres.crate_version_path("0.7.3"),
+                    sqlite.crate_version_path("0.7.3")
+                );
+                assert_ne!(postgres.lock_name(), sqlite.lock_name());
+
+                let args = std::fs::read_to_string(tempdir.join("cargo-args"))
+                    .expect("failed to read cargo args");
+                assert!(args.contains(" --features sqlite"));
+                assert!(sqlite
+                    .crate_version_path("0.7.3")
+                    .join("bin")
+                    .join("crate-bin")
+                    .exists());
+
+                // The order of the features does not matter
+                assert_eq!(
+                    config(&["a", "b"]).version_dir("1.0.0"),
+                    config(&["b", "a"]).version_dir("1.0.0")
+                );
+            });
+        }
+
+        #[test]
+        fn fails_if_no_matching_version() {
+            run_with_cargo(|_tempdir| {
+                let mut mock_server = mockito::Server::new();
+
+                let config = UpConfigCargoInstall {
+                    crate_name: "mdbook".to_string(),
+                    version: Some("2".to_string()),
+                    index_url: Some(mock_server.url()),
+                    ..UpConfigCargoInstall::default()
+                };
+
+                let mock_index = mock_server
+                    .mock("GET", "/md/bo/mdbook")
+                    .with_status(200)
+                    .with_body(r#"{"name":"mdbook","vers":"0.4.36","yanked":false}"#)
+                    .create();
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                assert!(config.up(&options, &progress_handler).is_err());
+                mock_index.assert();
+            });
+        }
+    }
+}
@@ -1,7 +1,17 @@
+use std::path::PathBuf;
+
+use blake3::Hasher;
+use once_cell::sync::OnceCell;
 use serde::Deserialize;
 use serde::Serialize;
 use tokio::process::Command as TokioCommand;
 
+use crate::internal::cache::up_environments::UpEnvironmentsCache;
+use crate::internal::cache::utils::Empty;
+use crate::internal::cache::CacheObject;
+use crate::internal::commands::utils::abs_path_from_path;
+use crate::internal::config::parser::EnvConfig;
+use crate::internal::config::parser::EnvOperationEnum;
 use crate::internal::config::up::utils::run_progress;
 use crate::internal::config::up::utils::ProgressHandler;
 use crate::internal::config::up::utils::RunConfig;
@@ -9,6 +19,12 @@ use crate::internal::config::up::utils::UpProgressHandler;
 use crate::internal::config::up::UpError;
 use crate::internal::config::ConfigValue;
 use crate::internal::user_interface::StringColor;
+use crate::internal::utils::base62_encode;
+use crate::internal::workdir;
+
+/// The environment variable providing the path to the file in which the
+/// `meet` command can write the environment changes of the step
+const OUTPUT_FILE_ENV_VAR: &str = "OMNI_UP_OUTPUT";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpConfigCustom {
@@ -21,6 +37,15 @@ pub struct UpConfigCustom {
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dir: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+    #[serde(default, skip_serializing_if = "Empty::is_empty")]
+    pub env: EnvConfig,
+
+    /// Path to the file storing the outputs of the `meet` command,
+    /// set once the step has been run
+    #[serde(skip)]
+    output_path: OnceCell<PathBuf>,
 }
 
 impl UpConfigCustom {
@@ -30,6 +55,8 @@ impl UpConfigCustom {
         let mut unmeet = None;
         let mut name = None;
         let mut dir = None;
+        let mut paths = vec![];
+        let mut env = EnvConfig::default();
 
         if let Some(config_value) = config_value {
             if let Some(value) = config_value.get_as_str_forced("meet") {
@@ -47,6 +74,17 @@ impl UpConfigCustom {
             if let Some(value) = config_value.get_as_str_forced("dir") {
                 dir = Some(value.to_string());
             }
+            if let Some(value) = config_value.get("paths") {
+                if let Some(array) = value.as_array() {
+                    paths = array
+                        .iter()
+                        .filter_map(|path| path.as_str_forced())
+                        .collect();
+                } else if let Some(path) = value.as_str_forced() {
+                    paths.push(path);
+                }
+            }
+            env = EnvConfig::from_config_value(config_value.get("env"));
         }
 
         if meet.is_none() {
@@ -59,6 +97,9 @@ impl UpConfigCustom {
             unmeet,
             name,
             dir,
+            paths,
+            env,
+            output_path: OnceCell::new(),
         }
     }
 
@@ -80,11 +121,19 @@ impl UpConfigCustom {
         progress_handler.init(format!("{}:", name).light_blue());
 
         if self.met().unwrap_or(false) {
+            if let Err(err) = self.update_cache(progress_handler) {
+                progress_handler.error_with_message(format!("{}", err).light_red());
+                return Err(UpError::StepFailed(name, progress_handler.step()));
+            }
+
             progress_handler.success_with_message("skipping (already met)".light_black());
             return Ok(());
         }
 
-        if let Err(err) = self.meet(progress_handler) {
+        if let Err(err) = self
+            .meet(progress_handler)
+            .and_then(|_| self.update_cache(progress_handler))
+        {
             progress_handler.error_with_message(format!("{}", err).light_red());
             return Err(UpError::StepFailed(name, progress_handler.step()));
         }
@@ -128,6 +177,85 @@ impl UpConfigCustom {
         Ok(())
     }
 
+    pub fn was_upped(&self) -> bool {
+        self.output_path.get().is_some()
+    }
+
+    pub fn data_paths(&self) -> Vec<PathBuf> {
+        self.output_path.get().into_iter().cloned().collect()
+    }
+
+    /// Returns the path of the file in which the outputs of the `meet`
+    /// command are kept, in the data path of the work directory, so that
+    /// they can still be loaded when the step is already met
+    fn output_file(&self) -> Option<PathBuf> {
+        let wd = workdir(".");
+        let data_path = wd.data_path()?;
+
+        let mut hasher = Hasher::new();
+        hasher.update(self.dir.as_deref().unwrap_or("").as_bytes());
+        hasher.update(b"\0");
+        hasher.update(self.meet.as_bytes());
+        let hash_bytes = hasher.finalize();
+        let hash_b62 = base62_encode(hash_bytes.as_bytes())[..20].to_string();
+
+        Some(data_path.join("custom").join(format!("{}.env", hash_b62)))
+    }
+
+    /// Adds the paths and environment variables declared by the step, as
+    /// well as the ones written by the `meet` command to its output file,
+    /// to the environment of the work directory
+    fn update_cache(&self, progress_handler: &dyn ProgressHandler) -> Result<(), UpError> {
+        let wd = workdir(".");
+        let wd_id = match wd.id() {
+            Some(wd_id) => wd_id,
+            None => return Ok(()),
+        };
+
+        let mut paths = self.paths.clone();
+        let mut env_vars = vec![];
+
+        if let Some(output_file) = self.output_file() {
+            if output_file.exists() {
+                let contents = std::fs::read_to_string(&output_file).map_err(|err| {
+                    UpError::Exec(format!("failed to read {}: {}", output_file.display(), err))
+                })?;
+
+                for (key, value) in parse_output(&contents)? {
+                    if key == "PATH" {
+                        paths.push(value);
+                    } else {
+                        env_vars.push((key, value));
+                    }
+                }
+
+                let _ = self.output_path.set(output_file);
+            }
+        }
+
+        if paths.is_empty() && env_vars.is_empty() && self.env.is_empty() {
+            return Ok(());
+        }
+
+        progress_handler.progress("updating cache".to_string());
+
+        UpEnvironmentsCache::exclusive(|up_env| {
+            for path in paths.iter() {
+                up_env.add_path(&wd_id, abs_path_from_path(path.as_str(), None));
+            }
+            up_env.add_env_vars(&wd_id, self.env.operations.clone());
+            for (key, value) in env_vars.iter() {
+                up_env.add_env_var_operation(&wd_id, key, value, EnvOperationEnum::Set);
+            }
+            true
+        })
+        .map_err(|err| UpError::Cache(format!("failed to update cache: {}", err)))?;
+
+        progress_handler.progress("updated cache".to_string());
+
+        Ok(())
+    }
+
     fn met(&self) -> Option<bool> {
         if let Some(met) = &self.met {
             let mut command = std::process::Command::new("bash");
@@ -153,11 +281,52 @@ impl UpConfigCustom {
             command.stdout(std::process::Stdio::piped());
             command.stderr(std::process::Stdio::piped());
 
-            run_progress(
+            // Provide a file in which the command can write its outputs;
+            // the outputs of the previous run are only replaced once the
+            // command succeeds
+            let output_file = self.output_file();
+            let tmp_output_file = match &output_file {
+                Some(output_file) => {
+                    let tmp_output_file = output_file.with_extension("env.tmp");
+                    if let Some(parent) = tmp_output_file.parent() {
+                        std::fs::create_dir_all(parent).map_err(|err| {
+                            UpError::Exec(format!("failed to create {}: {}", parent.display(), err))
+                        })?;
+                    }
+                    std::fs::write(&tmp_output_file, "").map_err(|err| {
+                        UpError::Exec(format!(
+                            "failed to create {}: {}",
+                            tmp_output_file.display(),
+                            err
+                        ))
+                    })?;
+                    command.env(OUTPUT_FILE_ENV_VAR, &tmp_output_file);
+                    Some(tmp_output_file)
+                }
+                None => None,
+            };
+
+            let result = run_progress(
                 &mut command,
                 Some(progress_handler),
                 RunConfig::default().with_askpass(),
-            )?;
+            );
+
+            if let (Some(output_file), Some(tmp_output_file)) = (output_file, tmp_output_file) {
+                if result.is_ok() {
+                    std::fs::rename(&tmp_output_file, &output_file).map_err(|err| {
+                        UpError::Exec(format!(
+                            "failed to write {}: {}",
+                            output_file.display(),
+                            err
+                        ))
+                    })?;
+                } else {
+                    let _ = std::fs::remove_file(&tmp_output_file);
+                }
+            }
+
+            result?;
         }
 
         Ok(())
@@ -179,3 +348,74 @@ impl UpConfigCustom {
         Ok(())
     }
 }
+
+/// Parses the outputs written by a `meet` command, as `KEY=VALUE` lines;
+/// empty lines and lines starting with `#` are ignored, and `PATH` can be
+/// provided multiple times to add multiple paths
+fn parse_output(contents: &str) -> Result<Vec<(String, String)>, UpError> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(idx, line)| match line.split_once('=') {
+            Some((key, value)) if is_valid_env_var_name(key.trim()) => {
+                Ok((key.trim().to_string(), value.to_string()))
+            }
+            _ => Err(UpError::Exec(format!(
+                "invalid output on line {}: expected KEY=VALUE, got '{}'",
+                idx + 1,
+                line
+            ))),
+        })
+        .collect()
+}
+
+fn is_valid_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_output_lines() {
+        let outputs = parse_output(
+            "# comment\nPATH=/opt/tool/bin\n\nTOOL_HOME=/opt/tool\nPATH=bin\nEMPTY=\nEQ=a=b\n",
+        )
+        .expect("failed to parse outputs");
+
+        assert_eq!(
+            outputs,
+            vec![
+                ("PATH".to_string(), "/opt/tool/bin".to_string()),
+                ("TOOL_HOME".to_string(), "/opt/tool".to_string()),
+                ("PATH".to_string(), "bin".to_string()),
+                ("EMPTY".to_string(), "".to_string()),
+                ("EQ".to_string(), "a=b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_output_invalid_lines() {
+        assert!(parse_output("TOOL_HOME=/opt/tool\nnot a variable\n").is_err());
+        assert!(parse_output("1TOOL=value\n").is_err());
+    }
+
+    #[test]
+    fn paths_and_env_from_config() {
+        let config_value = ConfigValue::from_str(
+            "meet: ./install.sh\npaths: [bin, /opt/tool/bin]\nenv:\n  TOOL_HOME: /opt/tool\n",
+        )
+        .expect("failed to create config value");
+        let config = UpConfigCustom::from_config_value(Some(&config_value));
+
+        assert_eq!(config.paths, vec!["bin", "/opt/tool/bin"]);
+        assert_eq!(config.env.len(), 1);
+        assert_eq!(config.env[0].name, "TOOL_HOME");
+        assert_eq!(config.env[0].value, Some("/opt/tool".to_string()));
+    }
+}
@@ -0,0 +1,1352 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use itertools::Itertools;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[cfg(not(test))]
+use once_cell::sync::Lazy;
+
+use crate::internal::cache::download_operation::DownloadInstalled;
+use crate::internal::cache::github_release::GithubReleaseAsset;
+use crate::internal::cache::github_release::GithubReleaseAssetType;
+use crate::internal::cache::up_environments::UpEnvironment;
+use crate::internal::cache::CacheObject;
+use crate::internal::cache::DownloadOperationCache;
+use crate::internal::cache::UpEnvironmentsCache;
+use crate::internal::config::global_config;
+use crate::internal::config::up::github_release::download_file;
+use crate::internal::config::up::github_release::extract_asset;
+use crate::internal::config::up::github_release::install_binaries;
+use crate::internal::config::up::github_release::install_binary;
+use crate::internal::config::up::github_release::make_executable;
+use crate::internal::config::up::github_release::validate_file_checksum;
+use crate::internal::config::up::github_release::GithubReleaseChecksumAlgorithm;
+use crate::internal::config::up::lock::UpLockEntry;
+use crate::internal::config::up::plan::current_version_under;
+use crate::internal::config::up::plan::UpPlanAction;
+use crate::internal::config::up::plan::UpPlanItem;
+use crate::internal::config::up::utils::cleanup_path;
+use crate::internal::config::up::utils::force_remove_dir_all;
+use crate::internal::config::up::utils::ProgressHandler;
+use crate::internal::config::up::utils::UpProgressHandler;
+use crate::internal::config::up::UpError;
+use crate::internal::config::up::UpOptions;
+use crate::internal::config::ConfigValue;
+use crate::internal::env::data_home;
+use crate::internal::self_updater::compatible_release_arch;
+use crate::internal::self_updater::compatible_release_os;
+use crate::internal::user_interface::StringColor;
+use crate::internal::workdir;
+
+cfg_if::cfg_if! {
+    if #[cfg(test)] {
+        fn download_bin_path() -> PathBuf {
+            PathBuf::from(data_home()).join("download")
+        }
+    } else {
+        static DOWNLOAD_BIN_PATH: Lazy<PathBuf> = Lazy::new(|| PathBuf::from(data_home()).join("download"));
+
+        fn download_bin_path() -> PathBuf {
+            DOWNLOAD_BIN_PATH.clone()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct UpConfigDownloads {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    downloads: Vec<UpConfigDownload>,
+}
+
+impl Serialize for UpConfigDownloads {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.downloads.len() {
+            0 => serializer.serialize_none(),
+            1 => serializer.serialize_newtype_struct("UpConfigDownloads", &self.downloads[0]),
+            _ => serializer.collect_seq(self.downloads.iter()),
+        }
+    }
+}
+
+impl UpConfigDownloads {
+    pub fn from_config_value(config_value: Option<&ConfigValue>) -> Self {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return UpConfigDownloads::default(),
+        };
+
+        if let Some(array) = config_value.as_array() {
+            return Self {
+                downloads: array
+                    .iter()
+                    .map(|config_value| UpConfigDownload::from_config_value(Some(config_value)))
+                    .collect(),
+            };
+        }
+
+        Self {
+            downloads: vec![UpConfigDownload::from_config_value(Some(config_value))],
+        }
+    }
+
+    pub fn up(
+        &self,
+        options: &UpOptions,
+        progress_handler: &UpProgressHandler,
+    ) -> Result<(), UpError> {
+        if self.downloads.len() == 1 {
+            return self.downloads[0].up(options, progress_handler);
+        }
+
+        progress_handler.init("download:".light_blue());
+        if self.downloads.is_empty() {
+            progress_handler.error_with_message("no download information".to_string());
+            return Err(UpError::Config(
+                "at least one download required".to_string(),
+            ));
+        }
+
+        progress_handler.progress("install dependencies".to_string());
+
+        let num = self.downloads.len();
+        for (idx, download) in self.downloads.iter().enumerate() {
+            let subhandler = progress_handler.subhandler(
+                &format!(
+                    "[{current:padding$}/{total:padding$}] {download} ",
+                    current = idx + 1,
+                    total = num,
+                    padding = format!("{}", num).len(),
+                    download = download.desc(),
+                )
+                .light_yellow(),
+            );
+            download.up(options, &subhandler).inspect_err(|_err| {
+                progress_handler.error();
+            })?;
+        }
+
+        progress_handler.success_with_message(self.get_up_message());
+
+        Ok(())
+    }
+
+    fn get_up_message(&self) -> String {
+        let count: HashMap<DownloadHandled, usize> = self
+            .downloads
+            .iter()
+            .map(|download| download.handling())
+            .fold(HashMap::new(), |mut map, item| {
+                *map.entry(item).or_insert(0) += 1;
+                map
+            });
+        let handled: Vec<String> = self
+            .downloads
+            .iter()
+            .filter_map(|download| match download.handling() {
+                DownloadHandled::Handled | DownloadHandled::Noop => {
+                    Some(format!("{} {}", download.name(), download.version))
+                }
+                DownloadHandled::Unhandled => None,
+            })
+            .sorted()
+            .collect();
+
+        if handled.is_empty() {
+            return "nothing done".to_string();
+        }
+
+        let mut numbers = vec![];
+
+        if let Some(count) = count.get(&DownloadHandled::Handled) {
+            numbers.push(format!("{} downloaded", count).green());
+        }
+
+        if let Some(count) = count.get(&DownloadHandled::Noop) {
+            numbers.push(format!("{} already downloaded", count).light_black());
+        }
+
+        if numbers.is_empty() {
+            return "nothing done".to_string();
+        }
+
+        format!(
+            "{} {}",
+            numbers.join(", "),
+            format!("({})", handled.join(", ")).light_black().italic(),
+        )
+    }
+
+    pub fn down(&self, progress_handler: &UpProgressHandler) -> Result<(), UpError> {
+        if self.downloads.len() == 1 {
+            return self.downloads[0].down(progress_handler);
+        }
+
+        progress_handler.init("download:".light_blue());
+        progress_handler.progress("updating dependencies".to_string());
+
+        let num = self.downloads.len();
+        for (idx, download) in self.downloads.iter().enumerate() {
+            let subhandler = progress_handler.subhandler(
+                &format!(
+                    "[{current:padding$}/{total:padding$}] ",
+                    current = idx + 1,
+                    total = num,
+                    padding = format!("{}", num).len(),
+                )
+                .light_yellow(),
+            );
+            download.down(&subhandler)?;
+        }
+
+        progress_handler.success_with_message("dependencies cleaned".light_green());
+
+        Ok(())
+    }
+
+    pub fn plan(&self, options: &UpOptions, current: Option<&UpEnvironment>) -> Vec<UpPlanItem> {
+        self.downloads
+            .iter()
+            .map(|download| download.plan(options, current))
+            .collect()
+    }
+
+    /// Removes what the downloads installed during the current run, and
+    /// the record of the work directory requiring them
+    pub fn rollback(&self, progress_handler: &dyn ProgressHandler) {
+        for download in self.downloads.iter() {
+            download.rollback(progress_handler);
+        }
+    }
+
+    /// The names of the downloads handled by the step
+    pub fn names(&self) -> Vec<String> {
+        self.downloads
+            .iter()
+            .map(|download| download.name())
+            .collect()
+    }
+
+    /// The resources, as `(step, name)` pairs, for which the downloads
+    /// handled by the step are locked
+    pub fn lock_keys(&self) -> Vec<(String, String)> {
+        self.downloads
+            .iter()
+            .map(|download| ("download".to_string(), download.name()))
+            .collect()
+    }
+
+    /// The paths that were added to the PATH of the work directory
+    /// by the downloads handled during the current run
+    pub fn env_paths(&self) -> Vec<PathBuf> {
+        self.downloads
+            .iter()
+            .filter(|download| download.handling() != DownloadHandled::Unhandled)
+            .map(|download| download.download_version_path())
+            .collect()
+    }
+
+    pub fn cleanup(progress_handler: &UpProgressHandler) -> Result<Option<String>, UpError> {
+        let wd = workdir(".");
+        let wd_id = match wd.id() {
+            Some(wd_id) => wd_id,
+            None => return Err(UpError::Exec("failed to get workdir id".to_string())),
+        };
+
+        let mut return_value: Result<(bool, usize, Vec<PathBuf>), UpError> =
+            Err(UpError::Exec("cleanup_path not run".to_string()));
+
+        if let Err(err) = DownloadOperationCache::exclusive(|download| {
+            progress_handler.init("download:".light_blue());
+            progress_handler.progress("checking for unused downloads".to_string());
+
+            let mut updated = false;
+
+            let expected_paths = download
+                .installed
+                .iter_mut()
+                .filter_map(|install| {
+                    // Cleanup the references to this repository for
+                    // any download that is not currently listed in
+                    // the up configuration
+                    if install.required_by.contains(&wd_id) && install.stale() {
+                        install.required_by.retain(|id| id != &wd_id);
+                        updated = true;
+                    }
+
+                    // Only return the path if the download is expected,
+                    // as we will clear the bin path from all unexpected
+                    // downloads
+                    if install.removable() {
+                        None
+                    } else {
+                        Some(
+                            download_bin_path()
+                                .join(&install.name)
+                                .join(&install.version),
+                        )
+                    }
+                })
+                .collect::<Vec<PathBuf>>();
+
+            return_value =
+                cleanup_path(download_bin_path(), expected_paths, progress_handler, true);
+
+            return_value.is_ok() && updated
+        }) {
+            progress_handler.progress(format!("failed to update cache: {}", err).light_yellow());
+        }
+
+        let (root_removed, num_removed, removed_paths) = return_value?;
+
+        if root_removed {
+            return Ok(Some("removed all downloads".to_string()));
+        }
+
+        if num_removed == 0 {
+            return Ok(None);
+        }
+
+        // We want to go over the paths that were removed to
+        // return a proper message about the downloads that
+        // were removed
+        let removed_downloads = removed_paths
+            .iter()
+            .filter_map(|path| {
+                // Path should starts with the bin path if it is a download
+                let rest_of_path = match path.strip_prefix(download_bin_path()) {
+                    Ok(rest_of_path) => rest_of_path,
+                    Err(_) => return None,
+                };
+
+                // Path should have two components left after stripping
+                // the bin path: the name (1) and the version (1)
+                let parts = rest_of_path
+                    .components()
+                    .map(|part| part.as_os_str().to_string_lossy().to_string())
+                    .collect::<Vec<String>>();
+                match parts.len() {
+                    1 => Some(format!("{} (all versions)", parts[0].light_yellow())),
+                    2 => Some(format!(
+                        "{} {}",
+                        parts[0].light_yellow(),
+                        parts[1].light_yellow()
+                    )),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if removed_downloads.is_empty() {
+            return Ok(Some(format!(
+                "removed {} download{}",
+                num_removed.light_yellow(),
+                if num_removed > 1 { "s" } else { "" }
+            )));
+        }
+
+        Ok(Some(format!("removed {}", removed_downloads.join(", "))))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+pub enum DownloadHandled {
+    Handled,
+    Noop,
+    Unhandled,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct UpConfigDownload {
+    /// The name of the tool being downloaded, used to identify it
+    /// in the cache and as the name of the binary when the
+    /// downloaded file is not an archive; if not provided, it is
+    /// derived from the file name in the URL.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+
+    /// The URL to download the file from; the `{version}`, `{os}`
+    /// and `{arch}` placeholders are replaced before downloading.
+    pub url: String,
+
+    /// The version of the tool to download
+    pub version: String,
+
+    /// The checksum to validate the downloaded file against
+    #[serde(default)]
+    pub checksum: UpConfigDownloadChecksum,
+
+    /// The path of the binary inside the archive; the placeholders
+    /// of the URL can be used here too. If not provided, all the
+    /// executable files of the archive are installed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// The values to use for the `{os}` placeholder, indexed by
+    /// the operating system omni is running on (e.g. `darwin`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub os: BTreeMap<String, String>,
+
+    /// The values to use for the `{arch}` placeholder, indexed by
+    /// the architecture omni is running on (e.g. `x86_64`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub arch: BTreeMap<String, String>,
+
+    #[serde(default, skip)]
+    was_handled: OnceCell<DownloadHandled>,
+}
+
+impl UpConfigDownload {
+    pub fn from_config_value(config_value: Option<&ConfigValue>) -> Self {
+        let table = match config_value.and_then(|config_value| config_value.as_table()) {
+            Some(table) => table,
+            None => return UpConfigDownload::default(),
+        };
+
+        let name = table
+            .get("name")
+            .and_then(|v| v.as_str_forced())
+            .unwrap_or_default();
+        let url = table
+            .get("url")
+            .and_then(|v| v.as_str_forced())
+            .unwrap_or_default();
+        let version = table
+            .get("version")
+            .and_then(|v| v.as_str_forced())
+            .unwrap_or_default();
+        let checksum = UpConfigDownloadChecksum::from_config_value(table.get("checksum"));
+        let path = table.get("path").and_then(|v| v.as_str_forced());
+
+        let parse_mapping = |key: &str| -> BTreeMap<String, String> {
+            table
+                .get(key)
+                .and_then(|v| v.as_table())
+                .map(|mapping| {
+                    mapping
+                        .iter()
+                        .filter_map(|(k, v)| v.as_str_forced().map(|v| (k.to_string(), v)))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let os = parse_mapping("os");
+        let arch = parse_mapping("arch");
+
+        UpConfigDownload {
+            name,
+            url,
+            version,
+            checksum,
+            path,
+            os,
+            arch,
+            ..UpConfigDownload::default()
+        }
+    }
+
+    /// The name of the download, either as configured or derived
+    /// from the file name of the URL template by keeping what comes
+    /// before the first placeholder or extension.
+    fn name(&self) -> String {
+        if !self.name.is_empty() {
+            return self.name.clone();
+        }
+
+        let file_name = self.url.rsplit('/').next().unwrap_or_default();
+        let file_name = file_name.split(['{', '?', '#']).next().unwrap_or_default();
+        let file_name = file_name.split('.').next().unwrap_or_default();
+        file_name.trim_end_matches(['-', '_', '.']).to_string()
+    }
+
+    fn current_os(&self) -> String {
+        let os = compatible_release_os()
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| std::env::consts::OS.to_string());
+        self.os.get(&os).cloned().unwrap_or(os)
+    }
+
+    fn current_arch(&self) -> String {
+        let arch = compatible_release_arch()
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| std::env::consts::ARCH.to_string());
+        self.arch.get(&arch).cloned().unwrap_or(arch)
+    }
+
+    fn render(&self, template: &str) -> String {
+        template
+            .replace("{version}", &self.version)
+            .replace("{os}", &self.current_os())
+            .replace("{arch}", &self.current_arch())
+    }
+
+    fn update_cache(&self, progress_handler: &dyn ProgressHandler) {
+        let wd = workdir(".");
+        let wd_id = match wd.id() {
+            Some(wd_id) => wd_id,
+            None => return,
+        };
+
+        progress_handler.progress("updating cache".to_string());
+
+        if let Err(err) = DownloadOperationCache::exclusive(|download| {
+            download.add_installed(&wd_id, &self.name(), &self.version)
+        }) {
+            progress_handler.progress(format!("failed to update download cache: {}", err));
+            return;
+        }
+
+        if let Err(err) = UpEnvironmentsCache::exclusive(|up_env| {
+            up_env.add_path(&wd_id, self.download_version_path())
+        }) {
+            progress_handler.progress(format!("failed to update up environment cache: {}", err));
+            return;
+        }
+
+        progress_handler.progress("updated cache".to_string());
+    }
+
+    fn update_lock(&self, options: &UpOptions) {
+        let platform = format!("{}-{}", self.current_os(), self.current_arch());
+        let checksum = self.checksum.value_for(&platform).map(|value| {
+            match self
+                .checksum
+                .algorithm
+                .clone()
+                .or_else(|| GithubReleaseChecksumAlgorithm::from_hash(&value))
+            {
+                Some(algorithm) => format!("{}:{}", algorithm, value),
+                None => value,
+            }
+        });
+
+        options.lock(
+            UpLockEntry::new("download", &self.name(), "", &self.version)
+                .with_asset(&self.render(&self.url), checksum),
+        );
+    }
+
+    fn desc(&self) -> String {
+        let name = self.name();
+        if name.is_empty() {
+            "download:".to_string()
+        } else if self.version.is_empty() {
+            format!("{}:", name)
+        } else {
+            format!("{} ({}):", name, self.version)
+        }
+    }
+
+    pub fn up(
+        &self,
+        options: &UpOptions,
+        progress_handler: &UpProgressHandler,
+    ) -> Result<(), UpError> {
+        progress_handler.init(self.desc().light_blue());
+
+        if let Err(err) = self.validate() {
+            progress_handler.error_with_message(err.message());
+            return Err(err);
+        }
+
+        let downloaded = self.download(options, progress_handler)?;
+
+        if self
+            .was_handled
+            .set(if downloaded {
+                DownloadHandled::Handled
+            } else {
+                DownloadHandled::Noop
+            })
+            .is_err()
+        {
+            unreachable!();
+        }
+
+        self.update_cache(progress_handler);
+        self.update_lock(options);
+
+        let msg = match downloaded {
+            true => format!("{} downloaded", self.version.light_yellow()),
+            false => format!("{} already downloaded", self.version).light_black(),
+        };
+        progress_handler.success_with_message(msg);
+
+        Ok(())
+    }
+
+    pub fn down(&self, progress_handler: &UpProgressHandler) -> Result<(), UpError> {
+        let wd = workdir(".");
+        let wd_id = match wd.id() {
+            Some(wd_id) => wd_id,
+            None => return Ok(()),
+        };
+
+        let name = self.name();
+
+        if let Err(err) = DownloadOperationCache::exclusive(|download| {
+            progress_handler.init(self.desc().light_blue());
+            progress_handler.progress("updating download dependencies".to_string());
+
+            let mut updated = false;
+
+            for install in download
+                .installed
+                .iter_mut()
+                .filter(|install| install.name == name && install.required_by.contains(&wd_id))
+            {
+                install.required_by.retain(|id| id != &wd_id);
+                updated = true;
+            }
+
+            updated
+        }) {
+            progress_handler.progress(format!("failed to update cache: {}", err).light_yellow());
+        }
+
+        progress_handler.success_with_message("download dependencies cleaned".light_green());
+
+        Ok(())
+    }
+
+    fn rollback(&self, progress_handler: &dyn ProgressHandler) {
+        let handling = self.handling();
+        if handling == DownloadHandled::Unhandled {
+            return;
+        }
+
+        let wd = workdir(".");
+        let wd_id = match wd.id() {
+            Some(wd_id) => wd_id,
+            None => return,
+        };
+
+        let name = self.name();
+        progress_handler.progress(format!("rolling back {} {}", name, self.version));
+
+        // The download is only removed if it was installed during this
+        // run and no other work directory requires it
+        let mut remove = handling == DownloadHandled::Handled;
+        if let Err(err) = DownloadOperationCache::exclusive(|download| {
+            let matches = |install: &DownloadInstalled| {
+                install.name == name && install.version == self.version
+            };
+
+            let mut updated = false;
+            for install in download.installed.iter_mut().filter(|i| matches(i)) {
+                updated |= install.required_by.remove(&wd_id);
+                remove &= install.required_by.is_empty();
+            }
+
+            if remove {
+                download.installed.retain(|install| !matches(install));
+            }
+
+            updated
+        }) {
+            progress_handler.progress(format!("failed to update cache: {}", err).light_yellow());
+            return;
+        }
+
+        if remove {
+            if let Err(err) = force_remove_dir_all(self.download_version_path()) {
+                progress_handler.progress(format!("failed to remove download: {}", err));
+            }
+        }
+    }
+
+    pub fn plan(&self, options: &UpOptions, current: Option<&UpEnvironment>) -> UpPlanItem {
+        if let Err(err) = self.validate() {
+            return UpPlanItem::new("download", UpPlanAction::Unknown(err.message()));
+        }
+
+        let install_path = self.download_version_path();
+        let installed = options.read_cache && install_path.is_dir();
+        let current_version =
+            current_version_under(current, &download_bin_path().join(self.name()));
+
+        UpPlanItem::new(
+            &self.name(),
+            UpPlanAction::for_version(installed, current_version, &self.version),
+        )
+        .with_version(&self.version)
+        .with_path(install_path)
+    }
+
+    fn validate(&self) -> Result<(), UpError> {
+        if self.url.is_empty() {
+            return Err(UpError::Config("url is required".to_string()));
+        }
+
+        if self.version.is_empty() {
+            return Err(UpError::Config("version is required".to_string()));
+        }
+
+        let name = self.name();
+        if name.is_empty() || name.contains(['/', '\\']) || name == ".." {
+            return Err(UpError::Config(
+                "name is required when it cannot be derived from the url".to_string(),
+            ));
+        }
+
+        if self.version.contains(['/', '\\']) || self.version == ".." {
+            return Err(UpError::Config(format!(
+                "invalid version: {}",
+                self.version
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn handling(&self) -> DownloadHandled {
+        match self.was_handled.get() {
+            Some(handled) => handled.clone(),
+            None => DownloadHandled::Unhandled,
+        }
+    }
+
+    fn download_version_path(&self) -> PathBuf {
+        download_bin_path().join(self.name()).join(&self.version)
+    }
+
+    fn download(
+        &self,
+        options: &UpOptions,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<bool, UpError> {
+        let install_path = self.download_version_path();
+
+        if (options.read_cache || options.offline) && install_path.exists() && install_path.is_dir()
+        {
+            progress_handler.progress(
+                format!("downloaded {} {} (cached)", self.name(), self.version).light_black(),
+            );
+
+            return Ok(false);
+        }
+
+        if options.offline {
+            let err = UpError::Offline(format!("{} {}", self.name(), self.version));
+            progress_handler.error_with_message(err.message());
+            return Err(err);
+        }
+
+        // Resolve the checksum for the current platform before
+        // downloading anything, as we won't install a file that
+        // we cannot validate
+        let platform = format!("{}-{}", self.current_os(), self.current_arch());
+        let checksum_value = self.checksum.value_for(&platform).ok_or_else(|| {
+            let errmsg = format!("checksum is required (no checksum for {})", platform);
+            progress_handler.error_with_message(errmsg.clone());
+            UpError::Config(errmsg)
+        })?;
+
+        let url = self.render(&self.url);
+        let asset = GithubReleaseAsset {
+            name: url
+                .split(['?', '#'])
+                .next()
+                .and_then(|url| url.rsplit('/').next())
+                .unwrap_or_default()
+                .to_string(),
+            browser_download_url: url.clone(),
+            state: "".to_string(),
+            content_type: "".to_string(),
+            size: 0,
+            checksum_asset: None,
+            signature_asset: None,
+        };
+
+        // Anything that is not a supported archive is considered to be
+        // the binary itself, as the version in the file name would
+        // otherwise prevent it from being recognized as such
+        let asset_type = asset
+            .file_type()
+            .map(|(asset_type, _)| asset_type)
+            .unwrap_or(GithubReleaseAssetType::Binary);
+
+        // Make a temporary directory to download the file
+        let tmp_dir = tempfile::Builder::new()
+            .prefix("omni_download.")
+            .tempdir()
+            .map_err(|err| {
+                progress_handler.error_with_message(format!("failed to create temp dir: {}", err));
+                UpError::Exec(format!("failed to create temp dir: {}", err))
+            })?;
+        let asset_path = tmp_dir.path().join(&asset.name);
+
+        progress_handler.progress(format!("downloading {}", url.light_yellow()));
+        let network = global_config().network;
+        let client = network.client().map_err(|errmsg| {
+            progress_handler.error_with_message(errmsg.clone());
+            UpError::Config(errmsg)
+        })?;
+        let request = client.get(network.rewrite_url(&url));
+        let file = download_file(
+            request,
+            &asset.name,
+            &asset_path,
+            &options.retry,
+            progress_handler,
+        )?;
+
+        validate_file_checksum(
+            &asset.name,
+            &asset_path,
+            &checksum_value,
+            self.checksum.algorithm.as_ref(),
+            progress_handler,
+        )?;
+
+        if asset_type.is_binary() {
+            make_executable(&file, &asset.name, progress_handler)?;
+            install_binary(&asset_path, &self.name(), &install_path, progress_handler)?;
+        } else {
+            let target_dir = tmp_dir.path().join("extracted");
+            extract_asset(
+                &asset_type,
+                &asset.name,
+                &asset_path,
+                &target_dir,
+                progress_handler,
+            )?;
+
+            match &self.path {
+                Some(path) => {
+                    let path = self.render(path);
+
+                    // The path must stay within the extracted archive
+                    if Path::new(&path)
+                        .components()
+                        .any(|component| component == Component::ParentDir)
+                    {
+                        let errmsg = format!("invalid path {}: must not contain ..", path);
+                        progress_handler.error_with_message(errmsg.clone());
+                        return Err(UpError::Config(errmsg));
+                    }
+
+                    let source_path = target_dir.join(path.trim_start_matches('/'));
+                    if !source_path.is_file() {
+                        let errmsg = format!("{} not found in {}", path, asset.name);
+                        progress_handler.error_with_message(errmsg.clone());
+                        return Err(UpError::Exec(errmsg));
+                    }
+
+                    let binary_name = source_path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_else(|| self.name());
+
+                    let source_file = std::fs::File::open(&source_path).map_err(|err| {
+                        let errmsg = format!("failed to open {}: {}", path, err);
+                        progress_handler.error_with_message(errmsg.clone());
+                        UpError::Exec(errmsg)
+                    })?;
+                    make_executable(&source_file, &binary_name, progress_handler)?;
+
+                    install_binary(&source_path, &binary_name, &install_path, progress_handler)?;
+                }
+                None => {
+                    if !install_binaries(&target_dir, &install_path, progress_handler)? {
+                        progress_handler
+                            .error_with_message(format!("no binaries found in {}", asset.name));
+                        return Err(UpError::Exec("no binaries found".to_string()));
+                    }
+                }
+            }
+        }
+
+        progress_handler.progress(format!(
+            "downloaded {} {}",
+            self.name().light_yellow(),
+            self.version.light_yellow()
+        ));
+
+        Ok(true)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct UpConfigDownloadChecksum {
+    /// The checksum algorithm to use; if not set, it is guessed
+    /// from the length of the checksum value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    algorithm: Option<GithubReleaseChecksumAlgorithm>,
+
+    /// The checksum value to use for any platform
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+
+    /// The checksum values for specific platforms, indexed by
+    /// `<os>-<arch>` as rendered in the URL (e.g. `linux-amd64`)
+    #[serde(default, flatten, skip_serializing_if = "BTreeMap::is_empty")]
+    platforms: BTreeMap<String, String>,
+}
+
+impl UpConfigDownloadChecksum {
+    fn from_config_value(config_value: Option<&ConfigValue>) -> Self {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return Self::default(),
+        };
+
+        if let Some(table) = config_value.as_table() {
+            let mut checksum = Self::default();
+            for (key, value) in table.iter() {
+                match key.as_str() {
+                    "algorithm" => {
+                        checksum.algorithm = value
+                            .as_str_forced()
+                            .and_then(|v| GithubReleaseChecksumAlgorithm::from_str(&v));
+                    }
+                    "value" => checksum.value = value.as_str_forced(),
+                    platform => {
+                        if let Some(value) = value.as_str_forced() {
+                            checksum.platforms.insert(platform.to_string(), value);
+                        }
+                    }
+                }
+            }
+            checksum
+        } else {
+            Self {
+                value: config_value.as_str_forced(),
+                ..Self::default()
+            }
+        }
+    }
+
+    fn value_for(&self, platform: &str) -> Option<String> {
+        self.platforms
+            .get(platform)
+            .or(self.value.as_ref())
+            .map(|value| value.trim().to_lowercase())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sha2::Digest;
+
+    mod from_config_value {
+        use super::*;
+
+        #[test]
+        fn single() {
+            let yaml = r#"{"url": "https://example.com/tool-{version}-{os}-{arch}.tar.gz", "version": "1.2.3", "checksum": "abc", "path": "tool-{version}/bin/tool"}"#;
+            let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+            let config = UpConfigDownloads::from_config_value(Some(&config_value));
+            assert_eq!(config.downloads.len(), 1);
+
+            let download = &config.downloads[0];
+            assert_eq!(download.name(), "tool");
+            assert_eq!(download.version, "1.2.3");
+            assert_eq!(download.checksum.value_for("any"), Some("abc".to_string()));
+            assert_eq!(download.path, Some("tool-{version}/bin/tool".to_string()));
+        }
+
+        #[test]
+        fn list() {
+            let yaml = r#"[{"name": "a", "url": "https://example.com/a", "version": "1"}, {"name": "b", "url": "https://example.com/b", "version": "2"}]"#;
+            let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+            let config = UpConfigDownloads::from_config_value(Some(&config_value));
+            assert_eq!(config.downloads.len(), 2);
+            assert_eq!(config.downloads[0].name(), "a");
+            assert_eq!(config.downloads[1].name(), "b");
+        }
+
+        #[test]
+        fn checksum_per_platform() {
+            let yaml = r#"{"url": "https://example.com/tool", "version": "1", "checksum": {"algorithm": "sha256", "linux-amd64": "AAA", "darwin-arm64": "bbb"}}"#;
+            let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+            let config = UpConfigDownload::from_config_value(Some(&config_value));
+            assert!(matches!(
+                config.checksum.algorithm,
+                Some(GithubReleaseChecksumAlgorithm::Sha256)
+            ));
+            assert_eq!(
+                config.checksum.value_for("linux-amd64"),
+                Some("aaa".to_string())
+            );
+            assert_eq!(
+                config.checksum.value_for("darwin-arm64"),
+                Some("bbb".to_string())
+            );
+            assert_eq!(config.checksum.value_for("linux-arm64"), None);
+        }
+
+        #[test]
+        fn render_with_mappings() {
+            let yaml = format!(
+                r#"{{"url": "https://example.com/{{os}}/{{arch}}/tool-{{version}}", "version": "1.0", "os": {{"{os}": "myos"}}, "arch": {{"{arch}": "myarch"}}}}"#,
+                os = compatible_release_os()[0],
+                arch = compatible_release_arch()[0],
+            );
+            let config_value = ConfigValue::from_str(&yaml).expect("failed to create config value");
+            let config = UpConfigDownload::from_config_value(Some(&config_value));
+            assert_eq!(config.name(), "tool");
+            assert_eq!(
+                config.render(&config.url),
+                "https://example.com/myos/myarch/tool-1.0"
+            );
+        }
+    }
+
+    mod up {
+        use super::*;
+
+        use crate::internal::config::up::utils::run_in_workdir;
+        use crate::internal::config::up::utils::run_with_env;
+        use crate::internal::config::up::utils::targz;
+        use crate::internal::config::up::UpConfigTool;
+        use crate::internal::config::up::UpConfigVerify;
+
+        fn sha256(contents: &[u8]) -> String {
+            format!("{:x}", sha2::Sha256::digest(contents))
+        }
+
+        #[test]
+        fn binary() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+                let contents = b"binary contents";
+
+                let config = UpConfigDownload {
+                    url: format!("{}/tool-{{version}}-{{os}}", mock_server.url()),
+                    version: "1.2.3".to_string(),
+                    checksum: UpConfigDownloadChecksum {
+                        value: Some(sha256(contents)),
+                        ..UpConfigDownloadChecksum::default()
+                    },
+                    ..UpConfigDownload::default()
+                };
+
+                let mock_download = mock_server
+                    .mock(
+                        "GET",
+                        format!("/tool-1.2.3-{}", compatible_release_os()[0]).as_str(),
+                    )
+                    .with_status(200)
+                    .with_body(contents)
+                    .create();
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = config.up(&options, &progress_handler);
+                assert!(result.is_ok(), "up failed: {:?}", result);
+                mock_download.assert();
+
+                let expected_bin = download_bin_path().join("tool").join("1.2.3").join("tool");
+                assert!(expected_bin.exists(), "binary file not found");
+            });
+        }
+
+        #[test]
+        fn archive_with_path() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+                let archive = targz(&[("tool-2.0.0/bin/tool", b"#!/bin/sh\n", 0o644)]);
+
+                let config = UpConfigDownload {
+                    name: "mytool".to_string(),
+                    url: format!("{}/tool-{{version}}.tar.gz", mock_server.url()),
+                    version: "2.0.0".to_string(),
+                    checksum: UpConfigDownloadChecksum {
+                        value: Some(sha256(&archive)),
+                        ..UpConfigDownloadChecksum::default()
+                    },
+                    path: Some("tool-{version}/bin/tool".to_string()),
+                    ..UpConfigDownload::default()
+                };
+
+                let mock_download = mock_server
+                    .mock("GET", "/tool-2.0.0.tar.gz")
+                    .with_status(200)
+                    .with_body(archive)
+                    .create();
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = config.up(&options, &progress_handler);
+                assert!(result.is_ok(), "up failed: {:?}", result);
+                mock_download.assert();
+
+                let expected_bin = download_bin_path()
+                    .join("mytool")
+                    .join("2.0.0")
+                    .join("tool");
+                assert!(expected_bin.exists(), "binary file not found");
+            });
+        }
+
+        #[test]
+        fn archive_with_path_outside_archive() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+                let archive = targz(&[("tool-2.0.0/bin/tool", b"#!/bin/sh\n", 0o644)]);
+
+                let config = UpConfigDownload {
+                    name: "mytool".to_string(),
+                    url: format!("{}/tool-{{version}}.tar.gz", mock_server.url()),
+                    version: "2.0.0".to_string(),
+                    checksum: UpConfigDownloadChecksum {
+                        value: Some(sha256(&archive)),
+                        ..UpConfigDownloadChecksum::default()
+                    },
+                    path: Some("tool-{version}/../../tool-{version}.tar.gz".to_string()),
+                    ..UpConfigDownload::default()
+                };
+
+                let mock_download = mock_server
+                    .mock("GET", "/tool-2.0.0.tar.gz")
+                    .with_status(200)
+                    .with_body(archive)
+                    .create();
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = config.up(&options, &progress_handler);
+                assert_eq!(
+                    result,
+                    Err(UpError::Config(
+                        "invalid path tool-2.0.0/../../tool-2.0.0.tar.gz: must not contain .."
+                            .to_string()
+                    ))
+                );
+                mock_download.assert();
+
+                assert!(!download_bin_path().join("mytool").join("2.0.0").exists());
+            });
+        }
+
+        #[test]
+        fn fails_on_checksum_mismatch() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+
+                let config = UpConfigDownload {
+                    url: format!("{}/tool", mock_server.url()),
+                    version: "1.0.0".to_string(),
+                    checksum: UpConfigDownloadChecksum {
+                        value: Some(sha256(b"expected contents")),
+                        ..UpConfigDownloadChecksum::default()
+                    },
+                    ..UpConfigDownload::default()
+                };
+
+                let mock_download = mock_server
+                    .mock("GET", "/tool")
+                    .with_status(200)
+                    .with_body("other contents")
+                    .create();
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = config.up(&options, &progress_handler);
+                assert!(result.is_err(), "up should have failed");
+                mock_download.assert();
+
+                assert!(!download_bin_path().join("tool").exists());
+            });
+        }
+
+        #[test]
+        fn fails_without_checksum() {
+            run_with_env(&[], |_| {
+                let config = UpConfigDownload {
+                    url: "https://example.com/tool".to_string(),
+                    version: "1.0.0".to_string(),
+                    ..UpConfigDownload::default()
+                };
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = config.up(&options, &progress_handler);
+                assert!(result.is_err(), "up should have failed");
+            });
+        }
+
+        #[test]
+        fn through_mirror() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+                let contents = b"binary contents";
+
+                let config_path = PathBuf::from(std::env::var("HOME").unwrap()).join("omni.yaml");
+                std::fs::create_dir_all(config_path.parent().unwrap())
+                    .expect("failed to create home dir");
+                std::fs::write(
+                    &config_path,
+                    format!(
+                        "network:\n  mirrors:\n    \"https://downloads.invalid/\": \"{}/mirror/\"\n",
+                        mock_server.url()
+                    ),
+                )
+                .expect("failed to write config");
+
+                temp_env::with_var("OMNI_CONFIG", Some(&config_path), || {
+                    crate::internal::config::flush_config("/");
+
+                    let config = UpConfigDownload {
+                        url: "https://downloads.invalid/tool-{version}".to_string(),
+                        version: "1.0.0".to_string(),
+                        checksum: UpConfigDownloadChecksum {
+                            value: Some(sha256(contents)),
+                            ..UpConfigDownloadChecksum::default()
+                        },
+                        ..UpConfigDownload::default()
+                    };
+
+                    let mock_download = mock_server
+                        .mock("GET", "/mirror/tool-1.0.0")
+                        .with_status(200)
+                        .with_body(contents)
+                        .create();
+
+                    let options = UpOptions::default().cache_disabled();
+                    let progress_handler = UpProgressHandler::new(None);
+
+                    let result = config.up(&options, &progress_handler);
+                    crate::internal::config::flush_config("/");
+
+                    assert!(result.is_ok(), "up failed: {:?}", result);
+                    mock_download.assert();
+                });
+            });
+        }
+
+        #[test]
+        fn offline() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+                let contents = b"binary contents";
+                let url = format!("{}/tool-{{version}}", mock_server.url());
+
+                let config = |version: &str| UpConfigDownload {
+                    url: url.clone(),
+                    version: version.to_string(),
+                    checksum: UpConfigDownloadChecksum {
+                        value: Some(sha256(contents)),
+                        ..UpConfigDownloadChecksum::default()
+                    },
+                    ..UpConfigDownload::default()
+                };
+
+                let mock_download = mock_server
+                    .mock("GET", "/tool-1.0.0")
+                    .with_status(200)
+                    .with_body(contents)
+                    .expect(1)
+                    .create();
+
+                let progress_handler = UpProgressHandler::new(None);
+
+                let options = UpOptions::default().cache_disabled();
+                let result = config("1.0.0").up(&options, &progress_handler);
+                assert!(result.is_ok(), "up failed: {:?}", result);
+
+                // The installed version is used without any request
+                let options = UpOptions::default().cache_disabled().offline(true);
+                let result = config("1.0.0").up(&options, &progress_handler);
+                assert!(result.is_ok(), "up failed: {:?}", result);
+                mock_download.assert();
+
+                // A version that is not installed cannot be downloaded
+                let result = config("2.0.0").up(&options, &progress_handler);
+                assert_eq!(result, Err(UpError::Offline("tool 2.0.0".to_string())));
+            });
+        }
+
+        #[test]
+        fn failed_verification_rolls_back() {
+            run_with_env(&[], |tempdir| {
+                run_in_workdir(&tempdir.join("wd"), |wd_id| {
+                    let mut mock_server = mockito::Server::new();
+                    let contents = b"binary contents";
+
+                    let mock_download = mock_server
+                        .mock("GET", "/tool-1.0.0")
+                        .with_status(200)
+                        .with_body(contents)
+                        .expect(2)
+                        .create();
+
+                    let tool = |command: &str| {
+                        let download = UpConfigDownload {
+                            url: format!("{}/tool-{{version}}", mock_server.url()),
+                            version: "1.0.0".to_string(),
+                            checksum: UpConfigDownloadChecksum {
+                                value: Some(sha256(contents)),
+                                ..UpConfigDownloadChecksum::default()
+                            },
+                            ..UpConfigDownload::default()
+                        };
+                        let verify = UpConfigVerify {
+                            command: command.to_string(),
+                            exit_code: 0,
+                            output: None,
+                        };
+                        UpConfigTool::Verify(
+                            verify,
+                            Box::new(UpConfigTool::Download(UpConfigDownloads {
+                                downloads: vec![download],
+                            })),
+                        )
+                    };
+
+                    let options = UpOptions::default();
+                    let progress_handler = UpProgressHandler::new(None);
+                    let install_path = download_bin_path().join("tool").join("1.0.0");
+                    let env_paths = || {
+                        UpEnvironmentsCache::get()
+                            .get_env(wd_id)
+                            .map(|env| env.paths.clone())
+                            .unwrap_or_default()
+                    };
+                    let installed = || {
+                        DownloadOperationCache::get()
+                            .installed
+                            .iter()
+                            .any(|install| install.name == "tool" && install.version == "1.0.0")
+                    };
+
+                    let result = tool("exit 1").up(&options, &progress_handler);
+                    assert!(
+                        matches!(&result, Err(UpError::Exec(err)) if err.starts_with("verification failed")),
+                        "unexpected result: {:?}",
+                        result
+                    );
+                    assert!(!install_path.exists(), "download not removed");
+                    assert!(!installed(), "download still in cache");
+                    assert!(!env_paths().contains(&install_path), "path still in env");
+
+                    let result = tool("command -v tool").up(&options, &progress_handler);
+                    assert!(result.is_ok(), "up failed: {:?}", result);
+                    assert!(install_path.join("tool").exists(), "binary file not found");
+                    assert!(installed(), "download not in cache");
+                    assert!(env_paths().contains(&install_path), "path not in env");
+                    mock_download.assert();
+                });
+            });
+        }
+    }
+}
@@ -11,6 +11,8 @@ pub enum UpError {
     Exec(String),
     Timeout(String),
     Cache(String),
+    Offline(String),
+    RateLimited(String),
     HomebrewTapInUse,
     StepFailed(String, Option<(usize, usize)>),
 }
@@ -22,6 +24,8 @@ impl UpError {
             UpError::Exec(message) => message.clone(),
             UpError::Timeout(message) => message.clone(),
             UpError::Cache(message) => message.clone(),
+            UpError::Offline(resource) => format!("{} not available offline", resource),
+            UpError::RateLimited(message) => message.clone(),
             UpError::HomebrewTapInUse => "tap in use".to_string(),
             UpError::StepFailed(message, _) => message.clone(),
         }
@@ -44,6 +48,8 @@ impl Display for UpError {
             UpError::Exec(message) => write!(f, "execution error: {}", message),
             UpError::Timeout(message) => write!(f, "timeout: {}", message),
             UpError::Cache(message) => write!(f, "cache error: {}", message),
+            UpError::Offline(resource) => write!(f, "{} not available offline", resource),
+            UpError::RateLimited(message) => write!(f, "rate limited: {}", message),
             UpError::HomebrewTapInUse => write!(f, "tap in use"),
             UpError::StepFailed(name, progress) => {
                 if let Some((step, total)) = progress {
@@ -11,6 +11,7 @@ use std::process::Command as ProcessCommand;
 use itertools::Itertools;
 use md5::Md5;
 use once_cell::sync::OnceCell;
+use regex::Regex;
 use serde::Deserialize;
 use serde::Serialize;
 use sha1::Sha1;
@@ -18,47 +19,96 @@ use sha2::Digest;
 use sha2::Sha256;
 use sha2::Sha384;
 use sha2::Sha512;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 
 #[cfg(not(test))]
 use once_cell::sync::Lazy;
 
 use crate::internal::cache::github_release::GithubReleaseAsset;
+use crate::internal::cache::github_release::GithubReleaseAssetType;
+use crate::internal::cache::github_release::GithubReleaseInstalled;
 use crate::internal::cache::github_release::GithubReleasesSelector;
+use crate::internal::cache::up_environments::UpEnvironment;
 use crate::internal::cache::utils as cache_utils;
 use crate::internal::cache::CacheObject;
+use crate::internal::cache::GiteaReleaseOperationCache;
 use crate::internal::cache::GithubReleaseOperationCache;
 use crate::internal::cache::GithubReleaseVersion;
 use crate::internal::cache::GithubReleases;
+use crate::internal::cache::GitlabReleaseOperationCache;
 use crate::internal::cache::UpEnvironmentsCache;
 use crate::internal::config;
 use crate::internal::config::global_config;
+use crate::internal::config::parser::EnvOperationEnum;
+use crate::internal::config::parser::ForgeAuthConfig;
 use crate::internal::config::parser::GithubAuthConfig;
+use crate::internal::config::up::lock::UpLockAsset;
+use crate::internal::config::up::lock::UpLockEntry;
+use crate::internal::config::up::plan::current_version_under;
+use crate::internal::config::up::plan::UpPlanAction;
+use crate::internal::config::up::plan::UpPlanItem;
 use crate::internal::config::up::utils::cleanup_path;
 use crate::internal::config::up::utils::force_remove_dir_all;
+use crate::internal::config::up::utils::AttemptError;
 use crate::internal::config::up::utils::ProgressHandler;
+use crate::internal::config::up::utils::RetryPolicy;
+use crate::internal::config::up::utils::SignatureConfig;
 use crate::internal::config::up::utils::UpProgressHandler;
 use crate::internal::config::up::utils::VersionMatcher;
 use crate::internal::config::up::utils::VersionParser;
+use crate::internal::config::up::utils::VoidProgressHandler;
 use crate::internal::config::up::UpError;
 use crate::internal::config::up::UpOptions;
 use crate::internal::config::ConfigValue;
 use crate::internal::env::data_home;
 use crate::internal::user_interface::StringColor;
+use crate::internal::utils::base62_encode;
 use crate::internal::workdir;
 
 const GITHUB_API_URL: &str = "https://api.github.com";
+const GITLAB_API_URL: &str = "https://gitlab.com/api/v4";
+const GITEA_API_URL: &str = "https://gitea.com/api/v1";
+
+/// The maximum number of pages of releases to fetch from the API,
+/// so that repositories with a very long history do not require
+/// an unbounded number of requests
+const MAX_RELEASES_PAGES: usize = 10;
+
+/// The directories, relative to the install path of a release, in
+/// which the zsh completions and man pages are installed
+const COMPLETIONS_DIR: &str = "share/zsh/site-functions";
+const MAN_DIR: &str = "share/man";
 
 cfg_if::cfg_if! {
     if #[cfg(test)] {
         fn github_releases_bin_path() -> PathBuf {
             PathBuf::from(data_home()).join("ghreleases")
         }
+
+        fn gitlab_releases_bin_path() -> PathBuf {
+            PathBuf::from(data_home()).join("glreleases")
+        }
+
+        fn gitea_releases_bin_path() -> PathBuf {
+            PathBuf::from(data_home()).join("gtreleases")
+        }
     } else {
         static GITHUB_RELEASES_BIN_PATH: Lazy<PathBuf> = Lazy::new(|| PathBuf::from(data_home()).join("ghreleases"));
+        static GITLAB_RELEASES_BIN_PATH: Lazy<PathBuf> = Lazy::new(|| PathBuf::from(data_home()).join("glreleases"));
+        static GITEA_RELEASES_BIN_PATH: Lazy<PathBuf> = Lazy::new(|| PathBuf::from(data_home()).join("gtreleases"));
 
         fn github_releases_bin_path() -> PathBuf {
             GITHUB_RELEASES_BIN_PATH.clone()
         }
+
+        fn gitlab_releases_bin_path() -> PathBuf {
+            GITLAB_RELEASES_BIN_PATH.clone()
+        }
+
+        fn gitea_releases_bin_path() -> PathBuf {
+            GITEA_RELEASES_BIN_PATH.clone()
+        }
     }
 }
 
@@ -66,6 +116,9 @@ cfg_if::cfg_if! {
 pub struct UpConfigGithubReleases {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     releases: Vec<UpConfigGithubRelease>,
+
+    #[serde(default, skip)]
+    forge: ReleaseForge,
 }
 
 impl Serialize for UpConfigGithubReleases {
@@ -83,14 +136,30 @@ impl Serialize for UpConfigGithubReleases {
 
 impl UpConfigGithubReleases {
     pub fn from_config_value(config_value: Option<&ConfigValue>) -> Self {
+        Self::from_config_value_with_forge(config_value, ReleaseForge::Github)
+    }
+
+    pub fn from_config_value_with_forge(
+        config_value: Option<&ConfigValue>,
+        forge: ReleaseForge,
+    ) -> Self {
         let config_value = match config_value {
             Some(config_value) => config_value,
-            None => return UpConfigGithubReleases::default(),
+            None => {
+                return UpConfigGithubReleases {
+                    forge,
+                    ..UpConfigGithubReleases::default()
+                }
+            }
         };
 
         if let Some(_repository) = config_value.as_str_forced() {
             return Self {
-                releases: vec![UpConfigGithubRelease::from_config_value(Some(config_value))],
+                releases: vec![UpConfigGithubRelease::from_config_value_with_forge(
+                    Some(config_value),
+                    forge,
+                )],
+                forge,
             };
         }
 
@@ -99,9 +168,13 @@ impl UpConfigGithubReleases {
                 releases: array
                     .iter()
                     .map(|config_value| {
-                        UpConfigGithubRelease::from_config_value(Some(config_value))
+                        UpConfigGithubRelease::from_config_value_with_forge(
+                            Some(config_value),
+                            forge,
+                        )
                     })
                     .collect(),
+                forge,
             };
         }
 
@@ -114,7 +187,11 @@ impl UpConfigGithubReleases {
                 .is_some()
             {
                 return Self {
-                    releases: vec![UpConfigGithubRelease::from_config_value(Some(config_value))],
+                    releases: vec![UpConfigGithubRelease::from_config_value_with_forge(
+                        Some(config_value),
+                        forge,
+                    )],
+                    forge,
                 };
             }
 
@@ -145,13 +222,16 @@ impl UpConfigGithubReleases {
                 };
 
                 repo_config.insert("repository".to_string(), repository);
-                releases.push(UpConfigGithubRelease::from_table(&repo_config));
+                releases.push(UpConfigGithubRelease::from_table(&repo_config, forge));
             }
 
-            return Self { releases };
+            return Self { releases, forge };
         }
 
-        UpConfigGithubReleases::default()
+        UpConfigGithubReleases {
+            forge,
+            ..UpConfigGithubReleases::default()
+        }
     }
 
     pub fn up(
@@ -163,7 +243,7 @@ impl UpConfigGithubReleases {
             return self.releases[0].up(options, progress_handler);
         }
 
-        progress_handler.init("github releases:".light_blue());
+        progress_handler.init(format!("{} releases:", self.forge.name()).light_blue());
         if self.releases.is_empty() {
             progress_handler.error_with_message("no release information".to_string());
             return Err(UpError::Config("at least one release required".to_string()));
@@ -250,7 +330,7 @@ impl UpConfigGithubReleases {
             return self.releases[0].down(progress_handler);
         }
 
-        progress_handler.init("github releases:".light_blue());
+        progress_handler.init(format!("{} releases:", self.forge.name()).light_blue());
         progress_handler.progress("updating dependencies".to_string());
 
         let num = self.releases.len();
@@ -272,7 +352,85 @@ impl UpConfigGithubReleases {
         Ok(())
     }
 
+    pub fn plan(&self, options: &UpOptions, current: Option<&UpEnvironment>) -> Vec<UpPlanItem> {
+        self.releases
+            .iter()
+            .map(|release| release.plan(options, current))
+            .collect()
+    }
+
+    /// Returns an error if the configuration of one of the releases
+    /// cannot be used to install it
+    pub fn validate(&self) -> Result<(), String> {
+        self.releases
+            .iter()
+            .try_for_each(|release| release.validate())
+    }
+
+    /// Removes what the releases installed during the current run, and
+    /// the record of the work directory requiring them
+    pub fn rollback(&self, progress_handler: &dyn ProgressHandler) {
+        for release in self.releases.iter() {
+            release.rollback(progress_handler);
+        }
+    }
+
+    /// The repositories of the releases handled by the step
+    pub fn names(&self) -> Vec<String> {
+        self.releases
+            .iter()
+            .map(|release| release.repository.clone())
+            .collect()
+    }
+
+    /// The resources, as `(step, name)` pairs, for which the releases
+    /// installed by the step are locked
+    pub fn lock_keys(&self) -> Vec<(String, String)> {
+        self.releases
+            .iter()
+            .map(|release| {
+                (
+                    release.forge.step_name().to_string(),
+                    release.install_name(),
+                )
+            })
+            .collect()
+    }
+
+    /// The paths that were added to the PATH of the work directory
+    /// by the releases installed during the current run
+    pub fn env_paths(&self) -> Vec<PathBuf> {
+        self.releases
+            .iter()
+            .filter_map(|release| {
+                release
+                    .actual_version
+                    .get()
+                    .map(|version| release.release_version_path(version))
+            })
+            .collect()
+    }
+
     pub fn cleanup(progress_handler: &UpProgressHandler) -> Result<Option<String>, UpError> {
+        let mut cleanups = vec![];
+
+        for forge in ReleaseForge::ALL {
+            if let Some(cleanup) = Self::cleanup_forge(forge, progress_handler)? {
+                cleanups.push(cleanup);
+            }
+        }
+
+        if cleanups.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(cleanups.join(", ")))
+    }
+
+    fn cleanup_forge(
+        forge: ReleaseForge,
+        progress_handler: &UpProgressHandler,
+    ) -> Result<Option<String>, UpError> {
         let wd = workdir(".");
         let wd_id = match wd.id() {
             Some(wd_id) => wd_id,
@@ -282,14 +440,13 @@ impl UpConfigGithubReleases {
         let mut return_value: Result<(bool, usize, Vec<PathBuf>), UpError> =
             Err(UpError::Exec("cleanup_path not run".to_string()));
 
-        if let Err(err) = GithubReleaseOperationCache::exclusive(|ghrelease| {
-            progress_handler.init("github releases:".light_blue());
-            progress_handler.progress("checking for unused github releases".to_string());
+        if let Err(err) = forge.update_installed(|installed| {
+            progress_handler.init(format!("{} releases:", forge.name()).light_blue());
+            progress_handler.progress(format!("checking for unused {} releases", forge.name()));
 
             let mut updated = false;
 
-            let expected_paths = ghrelease
-                .installed
+            let expected_paths = installed
                 .iter_mut()
                 .filter_map(|install| {
                     // Cleanup the references to this repository for
@@ -303,11 +460,12 @@ impl UpConfigGithubReleases {
                     // Only return the path if the github release is
                     // expected, as we will clear the bin path from
                     // all unexpected github releases
-                    if install.removable() {
+                    if install.removable_after(forge.cleanup_after()) {
                         None
                     } else {
                         Some(
-                            github_releases_bin_path()
+                            forge
+                                .bin_path()
                                 .join(&install.repository)
                                 .join(&install.version),
                         )
@@ -315,12 +473,7 @@ impl UpConfigGithubReleases {
                 })
                 .collect::<Vec<PathBuf>>();
 
-            return_value = cleanup_path(
-                github_releases_bin_path(),
-                expected_paths,
-                progress_handler,
-                true,
-            );
+            return_value = cleanup_path(forge.bin_path(), expected_paths, progress_handler, true);
 
             return_value.is_ok() && updated
         }) {
@@ -330,7 +483,7 @@ impl UpConfigGithubReleases {
         let (root_removed, num_removed, removed_paths) = return_value?;
 
         if root_removed {
-            return Ok(Some("removed all github releases".to_string()));
+            return Ok(Some(format!("removed all {} releases", forge.name())));
         }
 
         if num_removed == 0 {
@@ -344,7 +497,7 @@ impl UpConfigGithubReleases {
             .iter()
             .filter_map(|path| {
                 // Path should starts with the bin path if it is a release
-                let rest_of_path = match path.strip_prefix(github_releases_bin_path()) {
+                let rest_of_path = match path.strip_prefix(forge.bin_path()) {
                     Ok(rest_of_path) => rest_of_path,
                     Err(_) => return None,
                 };
@@ -418,6 +571,182 @@ pub enum GithubReleaseHandled {
     Unhandled,
 }
 
+/// The forge hosting the releases to install; all forges share the
+/// asset selection, checksum validation and extraction logic, but
+/// have their own API, authentication, cache and installation path.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum ReleaseForge {
+    #[default]
+    Github,
+    Gitlab,
+    Gitea,
+}
+
+impl ReleaseForge {
+    const ALL: [ReleaseForge; 3] = [
+        ReleaseForge::Github,
+        ReleaseForge::Gitlab,
+        ReleaseForge::Gitea,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            ReleaseForge::Github => "github",
+            ReleaseForge::Gitlab => "gitlab",
+            ReleaseForge::Gitea => "gitea",
+        }
+    }
+
+    /// The name of the up step installing releases from the forge
+    fn step_name(&self) -> &'static str {
+        match self {
+            ReleaseForge::Github => "github-release",
+            ReleaseForge::Gitlab => "gitlab-release",
+            ReleaseForge::Gitea => "gitea-release",
+        }
+    }
+
+    fn display_name(&self) -> &'static str {
+        match self {
+            ReleaseForge::Github => "GitHub",
+            ReleaseForge::Gitlab => "GitLab",
+            ReleaseForge::Gitea => "Gitea",
+        }
+    }
+
+    fn default_api_url(&self) -> &'static str {
+        match self {
+            ReleaseForge::Github => GITHUB_API_URL,
+            ReleaseForge::Gitlab => GITLAB_API_URL,
+            ReleaseForge::Gitea => GITEA_API_URL,
+        }
+    }
+
+    /// The query to request a page of the releases; Gitea caps the
+    /// page size to 50 by default, while the other forges allow 100
+    fn releases_page_query(&self, page: usize) -> (String, usize) {
+        match self {
+            ReleaseForge::Github | ReleaseForge::Gitlab => {
+                (format!("per_page=100&page={}", page), 100)
+            }
+            ReleaseForge::Gitea => (format!("limit=50&page={}", page), 50),
+        }
+    }
+
+    fn default_hostname(&self) -> &'static str {
+        match self {
+            ReleaseForge::Github => "github.com",
+            ReleaseForge::Gitlab => "gitlab.com",
+            ReleaseForge::Gitea => "gitea.com",
+        }
+    }
+
+    /// The environment variable conventionally holding the token
+    /// to authenticate with the forge, used when no other
+    /// authentication is configured
+    fn token_env_var(&self) -> Option<&'static str> {
+        match self {
+            ReleaseForge::Github => None,
+            ReleaseForge::Gitlab => Some("GITLAB_TOKEN"),
+            ReleaseForge::Gitea => Some("GITEA_TOKEN"),
+        }
+    }
+
+    fn bin_path(&self) -> PathBuf {
+        match self {
+            ReleaseForge::Github => github_releases_bin_path(),
+            ReleaseForge::Gitlab => gitlab_releases_bin_path(),
+            ReleaseForge::Gitea => gitea_releases_bin_path(),
+        }
+    }
+
+    fn versions_expire(&self) -> u64 {
+        let config = global_config();
+        match self {
+            ReleaseForge::Github => config.cache.github_release.versions_expire,
+            ReleaseForge::Gitlab => config.cache.gitlab_release.versions_expire,
+            ReleaseForge::Gitea => config.cache.gitea_release.versions_expire,
+        }
+    }
+
+    fn cleanup_after(&self) -> u64 {
+        let config = global_config();
+        match self {
+            ReleaseForge::Github => config.cache.github_release.cleanup_after,
+            ReleaseForge::Gitlab => config.cache.gitlab_release.cleanup_after,
+            ReleaseForge::Gitea => config.cache.gitea_release.cleanup_after,
+        }
+    }
+
+    fn get_releases(&self, repository: &str) -> Option<GithubReleases> {
+        match self {
+            ReleaseForge::Github => GithubReleaseOperationCache::get()
+                .get_releases(repository)
+                .cloned(),
+            ReleaseForge::Gitlab => GitlabReleaseOperationCache::get()
+                .get_releases(repository)
+                .cloned(),
+            ReleaseForge::Gitea => GiteaReleaseOperationCache::get()
+                .get_releases(repository)
+                .cloned(),
+        }
+    }
+
+    fn add_releases(&self, repository: &str, releases: &GithubReleases) -> io::Result<()> {
+        match self {
+            ReleaseForge::Github => GithubReleaseOperationCache::exclusive(|cache| {
+                cache.add_releases(repository, releases)
+            })
+            .map(|_| ()),
+            ReleaseForge::Gitlab => GitlabReleaseOperationCache::exclusive(|cache| {
+                cache.add_releases(repository, releases)
+            })
+            .map(|_| ()),
+            ReleaseForge::Gitea => GiteaReleaseOperationCache::exclusive(|cache| {
+                cache.add_releases(repository, releases)
+            })
+            .map(|_| ()),
+        }
+    }
+
+    fn add_installed(&self, workdir_id: &str, repository: &str, version: &str) -> io::Result<()> {
+        match self {
+            ReleaseForge::Github => GithubReleaseOperationCache::exclusive(|cache| {
+                cache.add_installed(workdir_id, repository, version)
+            })
+            .map(|_| ()),
+            ReleaseForge::Gitlab => GitlabReleaseOperationCache::exclusive(|cache| {
+                cache.add_installed(workdir_id, repository, version)
+            })
+            .map(|_| ()),
+            ReleaseForge::Gitea => GiteaReleaseOperationCache::exclusive(|cache| {
+                cache.add_installed(workdir_id, repository, version)
+            })
+            .map(|_| ()),
+        }
+    }
+
+    fn update_installed<F>(&self, processing_fn: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut Vec<GithubReleaseInstalled>) -> bool,
+    {
+        match self {
+            ReleaseForge::Github => {
+                GithubReleaseOperationCache::exclusive(|cache| processing_fn(&mut cache.installed))
+                    .map(|_| ())
+            }
+            ReleaseForge::Gitlab => {
+                GitlabReleaseOperationCache::exclusive(|cache| processing_fn(&mut cache.installed))
+                    .map(|_| ())
+            }
+            ReleaseForge::Gitea => {
+                GiteaReleaseOperationCache::exclusive(|cache| processing_fn(&mut cache.installed))
+                    .map(|_| ())
+            }
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct UpConfigGithubRelease {
     /// The repository to install the tool from, should
@@ -460,6 +789,20 @@ struct UpConfigGithubRelease {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub asset_name: Option<String>,
 
+    /// The prefix of the tags of the releases to consider, which
+    /// is stripped from the tags before parsing their version;
+    /// this is useful for repositories releasing multiple tools,
+    /// e.g. with tags like `cli/v1.2.3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_prefix: Option<String>,
+
+    /// A regular expression that the tags of the releases to
+    /// consider must match; if it has a capture group named
+    /// `version`, or any capture group, the version is parsed
+    /// from the first of those instead of the whole tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_regex: Option<String>,
+
     /// Whether to skip the OS matching when downloading the
     /// release. This is useful when the release is not
     /// platform-specific and you want to download it anyway.
@@ -483,6 +826,8 @@ struct UpConfigGithubRelease {
     /// GitHub API URL (https://api.github.com). If you are using
     /// Github Enterprise, you should set this to the URL of your
     /// Github Enterprise instance (e.g. https://github.example.com/api/v3)
+    /// For GitLab and Gitea releases, this defaults respectively to
+    /// https://gitlab.com/api/v4 and https://gitea.com/api/v1.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub api_url: Option<String>,
 
@@ -495,6 +840,41 @@ struct UpConfigGithubRelease {
     )]
     pub checksum: GithubReleaseChecksumConfig,
 
+    /// The signature configuration for the downloaded release
+    /// assets, to verify that they, or their checksum file, were
+    /// signed by a trusted key or identity. When set, an asset
+    /// without a valid signature cannot be installed.
+    #[serde(
+        default,
+        with = "serde_yaml::with::singleton_map",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub signature: Option<SignatureConfig>,
+
+    /// The error raised when parsing the signature configuration,
+    /// reported when validating the release so that it is never
+    /// installed without the signature verification
+    #[serde(default, skip)]
+    signature_error: Option<String>,
+
+    /// The binaries to install from the release assets, identified
+    /// by their path in the extracted assets, which can be a glob
+    /// pattern, and optionally renamed. If not set, all the executable
+    /// files found in the assets are installed under their own names.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub binaries: Vec<GithubReleaseBinaryConfig>,
+
+    /// The glob patterns of the zsh completion files to install from
+    /// the release assets, exposed through `fpath` in the dynamic
+    /// environment.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub completions: Vec<String>,
+
+    /// The glob patterns of the man pages to install from the release
+    /// assets, exposed through `MANPATH` in the dynamic environment.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub man_pages: Vec<String>,
+
     /// The authentication configuration for this specific
     /// github release. This will override the global
     /// authentication configuration, and the default behavior
@@ -505,11 +885,32 @@ struct UpConfigGithubRelease {
     )]
     pub auth: GithubAuthConfig,
 
+    /// The authentication configuration for this specific
+    /// release when not hosted on GitHub. This will override
+    /// the global authentication configuration of the forge.
+    #[serde(
+        default,
+        rename = "auth",
+        with = "serde_yaml::with::singleton_map",
+        skip_serializing_if = "ForgeAuthConfig::is_default",
+        skip_deserializing
+    )]
+    pub forge_auth: ForgeAuthConfig,
+
+    #[serde(default, skip)]
+    pub forge: ReleaseForge,
+
     #[serde(default, skip)]
     pub actual_version: OnceCell<String>,
 
+    #[serde(default, skip)]
+    auth_token: OnceCell<Option<String>>,
+
     #[serde(default, skip)]
     was_handled: OnceCell<GithubReleaseHandled>,
+
+    #[serde(default, skip)]
+    downloaded_assets: OnceCell<Vec<UpLockAsset>>,
 }
 
 impl Default for UpConfigGithubRelease {
@@ -522,37 +923,65 @@ impl Default for UpConfigGithubRelease {
             build: false,
             binary: true,
             asset_name: None,
+            tag_prefix: None,
+            tag_regex: None,
             skip_os_matching: false,
             skip_arch_matching: false,
             api_url: None,
             checksum: GithubReleaseChecksumConfig::default(),
+            signature: None,
+            signature_error: None,
+            binaries: vec![],
+            completions: vec![],
+            man_pages: vec![],
             auth: GithubAuthConfig::default(),
+            forge_auth: ForgeAuthConfig::default(),
+            forge: ReleaseForge::default(),
             actual_version: OnceCell::new(),
+            auth_token: OnceCell::new(),
             was_handled: OnceCell::new(),
+            downloaded_assets: OnceCell::new(),
         }
     }
 }
 
 impl UpConfigGithubRelease {
+    #[cfg(test)]
     pub fn from_config_value(config_value: Option<&ConfigValue>) -> Self {
+        Self::from_config_value_with_forge(config_value, ReleaseForge::Github)
+    }
+
+    pub fn from_config_value_with_forge(
+        config_value: Option<&ConfigValue>,
+        forge: ReleaseForge,
+    ) -> Self {
         let config_value = match config_value {
             Some(config_value) => config_value,
-            None => return UpConfigGithubRelease::default(),
+            None => {
+                return UpConfigGithubRelease {
+                    forge,
+                    ..UpConfigGithubRelease::default()
+                }
+            }
         };
 
         if let Some(table) = config_value.as_table() {
-            Self::from_table(&table)
+            Self::from_table(&table, forge)
         } else if let Some(repository) = config_value.as_str_forced() {
             UpConfigGithubRelease {
                 repository,
+                forge,
                 ..UpConfigGithubRelease::default()
             }
         } else {
-            UpConfigGithubRelease::default()
+            UpConfigGithubRelease {
+                forge,
+                ..UpConfigGithubRelease::default()
+            }
         }
     }
 
-    fn from_table(table: &HashMap<String, ConfigValue>) -> Self {
+    fn from_table(table: &HashMap<String, ConfigValue>, forge: ReleaseForge) -> Self {
         let repository = ["repository", "repo"]
             .iter()
             .find_map(|key| table.get(*key));
@@ -566,6 +995,7 @@ impl UpConfigGithubRelease {
                         return UpConfigGithubRelease {
                             repository: key.clone(),
                             version: Some(version.to_string()),
+                            forge,
                             ..UpConfigGithubRelease::default()
                         };
                     } else if let (Some(table), Ok(repo_config_value)) =
@@ -573,10 +1003,13 @@ impl UpConfigGithubRelease {
                     {
                         let mut repo_config = table.clone();
                         repo_config.insert("repository".to_string(), repo_config_value);
-                        return UpConfigGithubRelease::from_table(&repo_config);
+                        return UpConfigGithubRelease::from_table(&repo_config, forge);
                     }
                 }
-                return UpConfigGithubRelease::default();
+                return UpConfigGithubRelease {
+                    forge,
+                    ..UpConfigGithubRelease::default()
+                };
             }
         };
 
@@ -626,6 +1059,14 @@ impl UpConfigGithubRelease {
             .get("asset_name")
             .map(|v| v.as_str_forced())
             .unwrap_or(None);
+        let tag_prefix = table
+            .get("tag_prefix")
+            .map(|v| v.as_str_forced())
+            .unwrap_or(None);
+        let tag_regex = table
+            .get("tag_regex")
+            .map(|v| v.as_str_forced())
+            .unwrap_or(None);
         let skip_os_matching = table
             .get("skip_os_matching")
             .map(|v| v.as_bool())
@@ -641,7 +1082,24 @@ impl UpConfigGithubRelease {
             .map(|v| v.as_str_forced())
             .unwrap_or(None);
         let checksum = GithubReleaseChecksumConfig::from_config_value(table.get("checksum"));
-        let auth = GithubAuthConfig::from_config_value(table.get("auth").cloned());
+        let (signature, signature_error) =
+            match SignatureConfig::from_config_value(table.get("signature")) {
+                Ok(signature) => (signature, None),
+                Err(err) => (None, Some(err)),
+            };
+        let binaries = GithubReleaseBinaryConfig::from_config_value(table.get("binaries"));
+        let completions = glob_patterns(table.get("completions"));
+        let man_pages = glob_patterns(table.get("man_pages"));
+        let (auth, forge_auth) = match forge {
+            ReleaseForge::Github => (
+                GithubAuthConfig::from_config_value(table.get("auth").cloned()),
+                ForgeAuthConfig::default(),
+            ),
+            ReleaseForge::Gitlab | ReleaseForge::Gitea => (
+                GithubAuthConfig::default(),
+                ForgeAuthConfig::from_config_value(table.get("auth").cloned()),
+            ),
+        };
 
         UpConfigGithubRelease {
             repository,
@@ -651,11 +1109,20 @@ impl UpConfigGithubRelease {
             build,
             binary,
             asset_name,
+            tag_prefix,
+            tag_regex,
             skip_os_matching,
             skip_arch_matching,
             api_url,
             checksum,
+            signature,
+            signature_error,
+            binaries,
+            completions,
+            man_pages,
             auth,
+            forge_auth,
+            forge,
             ..UpConfigGithubRelease::default()
         }
     }
@@ -677,18 +1144,42 @@ impl UpConfigGithubRelease {
 
         progress_handler.progress("updating cache".to_string());
 
-        if let Err(err) = GithubReleaseOperationCache::exclusive(|ghrelease| {
-            ghrelease.add_installed(&wd_id, &self.repository, version)
-        }) {
-            progress_handler.progress(format!("failed to update github release cache: {}", err));
+        if let Err(err) = self
+            .forge
+            .add_installed(&wd_id, &self.install_name(), version)
+        {
+            progress_handler.progress(format!(
+                "failed to update {} release cache: {}",
+                self.forge.name(),
+                err
+            ));
             return;
         }
 
         let release_version_path = self.release_version_path(version);
-
-        if let Err(err) =
-            UpEnvironmentsCache::exclusive(|up_env| up_env.add_path(&wd_id, release_version_path))
-        {
+        let completions_path = release_version_path.join(COMPLETIONS_DIR);
+        let man_path = release_version_path.join(MAN_DIR);
+
+        if let Err(err) = UpEnvironmentsCache::exclusive(|up_env| {
+            up_env.add_path(&wd_id, release_version_path);
+            if completions_path.is_dir() {
+                up_env.add_env_var_operation(
+                    &wd_id,
+                    "FPATH",
+                    &completions_path.to_string_lossy(),
+                    EnvOperationEnum::Prepend,
+                );
+            }
+            if man_path.is_dir() {
+                up_env.add_env_var_operation(
+                    &wd_id,
+                    "MANPATH",
+                    &man_path.to_string_lossy(),
+                    EnvOperationEnum::Prepend,
+                );
+            }
+            true
+        }) {
             progress_handler.progress(format!("failed to update up environment cache: {}", err));
             return;
         }
@@ -698,7 +1189,7 @@ impl UpConfigGithubRelease {
 
     fn desc(&self) -> String {
         if self.repository.is_empty() {
-            "github release:".to_string()
+            format!("{} release:", self.forge.name())
         } else {
             format!(
                 "{} ({}):",
@@ -712,22 +1203,47 @@ impl UpConfigGithubRelease {
         }
     }
 
+    /// Returns an error if the configuration of the release cannot
+    /// be used to install it, e.g. if the tag regex is invalid
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(err) = &self.signature_error {
+            return Err(format!("invalid signature: {}", err));
+        }
+
+        if let Some(tag_regex) = &self.tag_regex {
+            if let Err(err) = Regex::new(tag_regex) {
+                return Err(format!(
+                    "invalid tag_regex pattern '{}': {}",
+                    tag_regex, err
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn up(
         &self,
         options: &UpOptions,
         progress_handler: &UpProgressHandler,
     ) -> Result<(), UpError> {
         progress_handler.init(self.desc().light_blue());
-        progress_handler.progress("install github release".to_string());
+        progress_handler.progress(format!("install {} release", self.forge.name()));
 
         if self.repository.is_empty() {
             progress_handler.error_with_message("repository is required".to_string());
             return Err(UpError::Config("repository is required".to_string()));
         }
 
+        if let Err(err) = self.validate() {
+            progress_handler.error_with_message(err.clone());
+            return Err(UpError::Config(err));
+        }
+
         let installed = self.resolve_and_download_release(options, progress_handler)?;
 
         self.update_cache(progress_handler);
+        self.update_lock(options);
 
         let version = match self.actual_version.get() {
             Some(version) => version.to_string(),
@@ -749,14 +1265,16 @@ impl UpConfigGithubRelease {
             None => return Ok(()),
         };
 
-        if let Err(err) = GithubReleaseOperationCache::exclusive(|ghrelease| {
+        if let Err(err) = self.forge.update_installed(|installed| {
             progress_handler.init(self.desc().light_blue());
-            progress_handler.progress("updating github release dependencies".to_string());
+            progress_handler.progress(format!(
+                "updating {} release dependencies",
+                self.forge.name()
+            ));
 
             let mut updated = false;
 
-            for install in ghrelease
-                .installed
+            for install in installed
                 .iter_mut()
                 .filter(|install| install.required_by.contains(&wd_id))
             {
@@ -769,48 +1287,175 @@ impl UpConfigGithubRelease {
             progress_handler.progress(format!("failed to update cache: {}", err).light_yellow());
         }
 
-        progress_handler.success_with_message("github release dependencies cleaned".light_green());
+        progress_handler.success_with_message(
+            format!("{} release dependencies cleaned", self.forge.name()).light_green(),
+        );
 
         Ok(())
     }
 
-    fn upgrade_release(&self, options: &UpOptions) -> bool {
-        self.upgrade || options.upgrade || config(".").up_command.upgrade
+    fn rollback(&self, progress_handler: &dyn ProgressHandler) {
+        let handling = self.handling();
+        if handling == GithubReleaseHandled::Unhandled {
+            return;
+        }
+
+        let wd = workdir(".");
+        let (wd_id, version) = match (wd.id(), self.actual_version.get()) {
+            (Some(wd_id), Some(version)) => (wd_id, version),
+            _ => return,
+        };
+
+        progress_handler.progress(format!("rolling back {} {}", self.repository, version));
+
+        let install_name = self.install_name();
+        // The release is only removed if it was installed during this
+        // run and no other work directory requires it
+        let mut remove = handling == GithubReleaseHandled::Handled;
+        if let Err(err) = self.forge.update_installed(|installed| {
+            let matches = |install: &GithubReleaseInstalled| {
+                install.repository == install_name && &install.version == version
+            };
+
+            let mut updated = false;
+            for install in installed.iter_mut().filter(|install| matches(install)) {
+                updated |= install.required_by.remove(&wd_id);
+                remove &= install.required_by.is_empty();
+            }
+
+            if remove {
+                installed.retain(|install| !matches(install));
+            }
+
+            updated
+        }) {
+            progress_handler.progress(format!("failed to update cache: {}", err).light_yellow());
+            return;
+        }
+
+        if remove {
+            if let Err(err) = force_remove_dir_all(self.release_version_path(version)) {
+                progress_handler.progress(format!("failed to remove release: {}", err));
+            }
+        }
     }
 
-    fn resolve_and_download_release(
+    /// Resolves the release that would be used by `up` without
+    /// downloading anything nor updating the caches.
+    pub fn plan(&self, options: &UpOptions, current: Option<&UpEnvironment>) -> UpPlanItem {
+        if self.repository.is_empty() {
+            return UpPlanItem::new(
+                self.forge.name(),
+                UpPlanAction::Unknown("repository is required".to_string()),
+            );
+        }
+
+        if let Err(err) = self.validate() {
+            return UpPlanItem::new(&self.repository, UpPlanAction::Unknown(err));
+        }
+
+        let options = UpOptions {
+            write_cache: false,
+            ..options.clone()
+        };
+
+        let version = match self.plan_version(&options, &VoidProgressHandler {}) {
+            Ok(version) => version,
+            Err(err) => {
+                return UpPlanItem::new(&self.repository, UpPlanAction::Unknown(err.message()))
+            }
+        };
+
+        let release_version_path = self.release_version_path(&version);
+        let current_version =
+            current_version_under(current, &self.forge.bin_path().join(self.install_name()));
+
+        UpPlanItem::new(
+            &self.repository,
+            UpPlanAction::for_version(release_version_path.exists(), current_version, &version),
+        )
+        .with_version(&version)
+        .with_path(release_version_path)
+    }
+
+    fn plan_version(
         &self,
         options: &UpOptions,
-        progress_handler: &UpProgressHandler,
-    ) -> Result<bool, UpError> {
-        let mut version = "".to_string();
-        let mut download_release = Err(UpError::Exec("did not even try".to_string()));
-        let mut releases = None;
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<String, UpError> {
+        if let Some(locked) = self.locked(options) {
+            return Ok(locked.version.clone());
+        }
 
-        // If the options do not include upgrade, then we can try using
-        // an already-installed version if any matches the requirements
         if !self.upgrade_release(options) {
             let resolve_str = match self.version.as_ref() {
                 Some(version) if version != "latest" => version.to_string(),
                 _ => {
-                    let list_releases = self.list_releases(options, progress_handler)?;
-                    releases = Some(list_releases.clone());
-                    let latest = self.latest_release_version(&list_releases)?;
-                    progress_handler.progress(
-                        format!("considering installed versions matching {}", latest).light_black(),
-                    );
-                    latest
+                    self.latest_release_version(&self.list_releases(options, progress_handler)?)?
                 }
             };
 
             let installed_versions = self.list_installed_versions(progress_handler)?;
-            match self.resolve_version_from_str(&resolve_str, &installed_versions) {
-                Ok(installed_version) => {
-                    progress_handler.progress(format!(
-                        "found matching installed version {}",
-                        installed_version.light_yellow(),
-                    ));
-
+            if let Ok(version) = self.resolve_version_from_str(&resolve_str, &installed_versions) {
+                return Ok(version);
+            }
+        }
+
+        let releases = self.list_releases(options, progress_handler)?;
+        Ok(self.resolve_release(&releases)?.version())
+    }
+
+    fn upgrade_release(&self, options: &UpOptions) -> bool {
+        self.upgrade || options.upgrade || config(".").up_command.upgrade
+    }
+
+    fn resolve_and_download_release(
+        &self,
+        options: &UpOptions,
+        progress_handler: &UpProgressHandler,
+    ) -> Result<bool, UpError> {
+        let mut version = "".to_string();
+        let mut download_release = Err(UpError::Exec("did not even try".to_string()));
+        let mut releases = None;
+
+        // If the version is locked, we install exactly that version
+        if let Some(locked) = self.locked(options) {
+            progress_handler.progress(format!("using locked version {}", locked.version));
+
+            version = locked.version.clone();
+            download_release = self.download_locked_release(options, locked, progress_handler);
+        }
+
+        // If the options do not include upgrade, then we can try using
+        // an already-installed version if any matches the requirements
+        if version.is_empty() && !self.upgrade_release(options) {
+            let resolve_str = match self.version.as_ref() {
+                Some(version) if version != "latest" => version.to_string(),
+                _ => match self.list_releases(options, progress_handler) {
+                    Ok(list_releases) => {
+                        releases = Some(list_releases.clone());
+                        let latest = self.latest_release_version(&list_releases)?;
+                        progress_handler.progress(
+                            format!("considering installed versions matching {}", latest)
+                                .light_black(),
+                        );
+                        latest
+                    }
+                    // Without a release list while offline, any installed
+                    // version is better than none
+                    Err(UpError::Offline(_)) => "latest".to_string(),
+                    Err(err) => return Err(err),
+                },
+            };
+
+            let installed_versions = self.list_installed_versions(progress_handler)?;
+            match self.resolve_version_from_str(&resolve_str, &installed_versions) {
+                Ok(installed_version) => {
+                    progress_handler.progress(format!(
+                        "found matching installed version {}",
+                        installed_version.light_yellow(),
+                    ));
+
                     version = installed_version;
                     download_release = Ok(false);
                 }
@@ -823,7 +1468,19 @@ impl UpConfigGithubRelease {
         if version.is_empty() {
             let releases = match releases {
                 Some(releases) => releases,
-                None => self.list_releases(options, progress_handler)?,
+                None => match self.list_releases(options, progress_handler) {
+                    Ok(releases) => releases,
+                    Err(UpError::Offline(_)) => {
+                        let err = UpError::Offline(format!(
+                            "{} {}",
+                            self.repository,
+                            self.version.as_deref().unwrap_or("latest")
+                        ));
+                        progress_handler.error_with_message(err.message());
+                        return Err(err);
+                    }
+                    Err(err) => return Err(err),
+                },
             };
             let release = match self.resolve_release(&releases) {
                 Ok(release) => release,
@@ -855,7 +1512,7 @@ impl UpConfigGithubRelease {
             version = release.version();
 
             // Try installing the release found
-            download_release = self.download_release(options, &release, progress_handler);
+            download_release = self.download_release(options, &release, None, progress_handler);
             if download_release.is_err() && !options.fail_on_upgrade {
                 // If we get here and there is an issue downloading the release,
                 // list all installed versions and check if one of those could
@@ -899,17 +1556,94 @@ impl UpConfigGithubRelease {
         download_release
     }
 
+    /// Returns the locked entry for this release, if any, and if the
+    /// locked version still matches the requested version
+    fn locked<'a>(&self, options: &UpOptions<'a>) -> Option<&'a UpLockEntry> {
+        let locked = options.locked(self.forge.step_name(), &self.install_name(), "")?;
+
+        let mut matcher = VersionMatcher::new(self.version.as_deref().unwrap_or("latest"));
+        matcher.prerelease(self.prerelease);
+        matcher.build(self.build);
+        matcher.prefix(true);
+
+        if matcher.matches(&locked.version) {
+            Some(locked)
+        } else {
+            None
+        }
+    }
+
+    fn download_locked_release(
+        &self,
+        options: &UpOptions,
+        locked: &UpLockEntry,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<bool, UpError> {
+        let install_path = self.release_version_path(&locked.version);
+        if (options.read_cache || options.offline) && install_path.is_dir() {
+            return Ok(false);
+        }
+
+        if options.offline {
+            let err = UpError::Offline(format!("{} {}", self.repository, locked.version));
+            progress_handler.error_with_message(err.message());
+            return Err(err);
+        }
+
+        let releases = self.list_releases(options, progress_handler)?;
+        let release = self
+            .resolve_release_from_str(&locked.version, &releases)
+            .ok()
+            .filter(|release| release.version() == locked.version)
+            .ok_or_else(|| {
+                let errmsg = format!(
+                    "locked release {} {} not found",
+                    self.repository, locked.version
+                );
+                progress_handler.error_with_message(errmsg.clone());
+                UpError::Exec(errmsg)
+            })?;
+
+        self.download_release(options, &release, Some(locked), progress_handler)
+    }
+
+    fn update_lock(&self, options: &UpOptions) {
+        let version = match self.actual_version.get() {
+            Some(version) => version,
+            None => return,
+        };
+
+        let mut entry = UpLockEntry::new(self.forge.step_name(), &self.install_name(), "", version);
+        if let Some(assets) = self.downloaded_assets.get() {
+            entry.assets = assets.clone();
+        }
+
+        options.lock(entry);
+    }
+
     fn list_releases(
         &self,
         options: &UpOptions,
-        progress_handler: &UpProgressHandler,
+        progress_handler: &dyn ProgressHandler,
     ) -> Result<GithubReleases, UpError> {
+        // When offline, the cached release list is all we have, no
+        // matter how old it is
+        if options.offline {
+            return match self.forge.get_releases(&self.repository) {
+                Some(releases) => {
+                    progress_handler.progress("using cached release list (offline)".light_black());
+                    Ok(releases)
+                }
+                None => Err(UpError::Offline(format!(
+                    "{} release list",
+                    self.repository
+                ))),
+            };
+        }
+
         let cached_releases = if options.read_cache {
-            let cache = GithubReleaseOperationCache::get();
-            if let Some(releases) = cache.get_releases(&self.repository) {
-                let releases = releases.clone();
-                let config = global_config();
-                let expire = config.cache.github_release.versions_expire;
+            if let Some(releases) = self.forge.get_releases(&self.repository) {
+                let expire = self.forge.versions_expire();
                 if !releases.is_stale(expire) {
                     progress_handler.progress("using cached release list".light_black());
                     return Ok(releases);
@@ -922,20 +1656,35 @@ impl UpConfigGithubRelease {
             None
         };
 
-        progress_handler.progress("refreshing releases list from GitHub".to_string());
-        match self.list_releases_from_api(progress_handler) {
+        progress_handler.progress(format!(
+            "refreshing releases list from {}",
+            self.forge.display_name()
+        ));
+        match self.list_releases_from_api(options, cached_releases.as_ref(), progress_handler) {
             Ok(releases) => {
                 if options.write_cache {
                     progress_handler.progress("updating cache with release list".to_string());
-                    if let Err(err) = GithubReleaseOperationCache::exclusive(|ghrelease| {
-                        ghrelease.add_releases(&self.repository, &releases)
-                    }) {
+                    if let Err(err) = self.forge.add_releases(&self.repository, &releases) {
                         progress_handler.progress(format!("failed to update cache: {}", err));
                     }
                 }
 
                 Ok(releases)
             }
+            Err(UpError::RateLimited(errmsg)) => {
+                if let Some(cached_releases) = cached_releases {
+                    progress_handler.println(format!(
+                        "{} {}; {}",
+                        "warning:".yellow(),
+                        errmsg,
+                        "using stale cached release list".light_black()
+                    ));
+                    Ok(cached_releases)
+                } else {
+                    progress_handler.error_with_message(errmsg.clone());
+                    Err(UpError::RateLimited(errmsg))
+                }
+            }
             Err(err) => {
                 if let Some(cached_releases) = cached_releases {
                     progress_handler.progress(format!(
@@ -951,7 +1700,7 @@ impl UpConfigGithubRelease {
         }
     }
 
-    fn get_api_hostname(&self, progress_handler: &UpProgressHandler) -> String {
+    fn get_api_hostname(&self, progress_handler: &dyn ProgressHandler) -> String {
         if let Some(api_url) = &self.api_url {
             match url::Url::parse(api_url) {
                 Ok(url) => url.host_str().unwrap_or(api_url).to_string(),
@@ -962,11 +1711,11 @@ impl UpConfigGithubRelease {
                 }
             }
         } else {
-            "github.com".to_string()
+            self.forge.default_hostname().to_string()
         }
     }
 
-    fn get_auth_token(&self, progress_handler: &UpProgressHandler) -> Option<String> {
+    fn get_auth_token(&self, progress_handler: &dyn ProgressHandler) -> Option<String> {
         let auth = if !self.auth.is_default() {
             self.auth.clone()
         } else {
@@ -1036,45 +1785,129 @@ impl UpConfigGithubRelease {
         Some(token)
     }
 
+    fn get_forge_auth_token(&self, progress_handler: &dyn ProgressHandler) -> Option<String> {
+        self.auth_token
+            .get_or_init(|| {
+                let auth = if !self.forge_auth.is_default() {
+                    self.forge_auth.clone()
+                } else {
+                    let hostname = self.get_api_hostname(progress_handler);
+                    let config = global_config();
+                    match self.forge {
+                        ReleaseForge::Gitlab => config.gitlab.auth_for(&self.repository, &hostname),
+                        ReleaseForge::Gitea => config.gitea.auth_for(&self.repository, &hostname),
+                        ReleaseForge::Github => unreachable!("github uses its own auth config"),
+                    }
+                };
+
+                match auth {
+                    ForgeAuthConfig::Skip(true) => None,
+                    ForgeAuthConfig::Token(token) => Some(token),
+                    ForgeAuthConfig::TokenEnvVar(env_var) => std::env::var(env_var).ok(),
+                    ForgeAuthConfig::Auto | ForgeAuthConfig::Skip(false) => self
+                        .forge
+                        .token_env_var()
+                        .and_then(|env_var| std::env::var(env_var).ok())
+                        .filter(|token| !token.is_empty()),
+                }
+            })
+            .clone()
+    }
+
+    /// Returns the header to use to authenticate the requests
+    /// to the API of the forge, if any authentication is available
+    fn get_auth_header(
+        &self,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Option<(reqwest::header::HeaderName, reqwest::header::HeaderValue)> {
+        let (header_name, header_value) = match self.forge {
+            ReleaseForge::Github => (
+                reqwest::header::AUTHORIZATION,
+                format!("Bearer {}", self.get_auth_token(progress_handler)?),
+            ),
+            ReleaseForge::Gitlab => (
+                reqwest::header::HeaderName::from_static("private-token"),
+                self.get_forge_auth_token(progress_handler)?,
+            ),
+            ReleaseForge::Gitea => (
+                reqwest::header::AUTHORIZATION,
+                format!("token {}", self.get_forge_auth_token(progress_handler)?),
+            ),
+        };
+
+        match reqwest::header::HeaderValue::from_str(&header_value) {
+            Ok(header_value) => Some((header_name, header_value)),
+            Err(err) => {
+                progress_handler.progress(format!("failed to set auth token: {}", err));
+                None
+            }
+        }
+    }
+
     fn list_releases_from_api(
         &self,
-        progress_handler: &UpProgressHandler,
+        options: &UpOptions,
+        cached: Option<&GithubReleases>,
+        progress_handler: &dyn ProgressHandler,
     ) -> Result<GithubReleases, UpError> {
         // Use https://api.github.com/repos/<owner>/<repo>/releases to
-        // list the available releases
-        let api_url = self.api_url.clone().unwrap_or(GITHUB_API_URL.to_string());
-        let releases_url = format!("{}/repos/{}/releases", api_url, self.repository);
+        // list the available releases; GitLab identifies projects
+        // by their url-encoded path, while Gitea follows GitHub
+        let api_url = self
+            .api_url
+            .clone()
+            .unwrap_or(self.forge.default_api_url().to_string());
+        let releases_url = match self.forge {
+            ReleaseForge::Github | ReleaseForge::Gitea => {
+                format!("{}/repos/{}/releases", api_url, self.repository)
+            }
+            ReleaseForge::Gitlab => format!(
+                "{}/projects/{}/releases",
+                api_url,
+                self.repository.replace('/', "%2F")
+            ),
+        };
 
         let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert(
-            reqwest::header::ACCEPT,
-            reqwest::header::HeaderValue::from_static("application/vnd.github.v3+json"),
-        );
         headers.insert(
             reqwest::header::CONTENT_TYPE,
             reqwest::header::HeaderValue::from_static("application/json"),
         );
-        headers.insert(
-            "X-GitHub-Api-Version",
-            reqwest::header::HeaderValue::from_static("2022-11-28"),
-        );
+        if self.forge == ReleaseForge::Github {
+            headers.insert(
+                reqwest::header::ACCEPT,
+                reqwest::header::HeaderValue::from_static("application/vnd.github.v3+json"),
+            );
+            headers.insert(
+                "X-GitHub-Api-Version",
+                reqwest::header::HeaderValue::from_static("2022-11-28"),
+            );
+        }
 
-        if let Some(token) = self.get_auth_token(progress_handler) {
-            match reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token)) {
-                Ok(header_value) => {
-                    headers.insert(reqwest::header::AUTHORIZATION, header_value);
-                }
-                Err(err) => {
-                    progress_handler.progress(format!("failed to set auth token: {}", err));
-                }
+        // The requests going through a mirror are not authenticated,
+        // as the token is meant for the forge only
+        let network = global_config().network;
+        let mirrored_url = network.rewrite_url(&releases_url);
+        if mirrored_url == releases_url {
+            if let Some((header_name, header_value)) = self.get_auth_header(progress_handler) {
+                headers.insert(header_name, header_value);
             }
         }
 
-        let client = match reqwest::blocking::Client::builder()
-            .user_agent(format!("omni {}", env!("CARGO_PKG_VERSION")))
-            .default_headers(headers)
-            .build()
-        {
+        let mut client_builder = match network.client_builder() {
+            Ok(client_builder) => client_builder,
+            Err(errmsg) => {
+                progress_handler.error_with_message(errmsg.clone());
+                return Err(UpError::Config(errmsg));
+            }
+        }
+        .user_agent(format!("omni {}", env!("CARGO_PKG_VERSION")))
+        .default_headers(headers);
+        if let Some(timeout) = options.retry.timeout() {
+            client_builder = client_builder.timeout(timeout);
+        }
+
+        let client = match client_builder.build() {
             Ok(client) => client,
             Err(err) => {
                 let errmsg = format!("failed to create client: {}", err);
@@ -1083,37 +1916,131 @@ impl UpConfigGithubRelease {
             }
         };
 
-        let response = client.get(&releases_url).send().map_err(|err| {
-            let errmsg = format!("failed to get releases: {}", err);
-            progress_handler.error_with_message(errmsg.clone());
-            UpError::Exec(errmsg)
-        })?;
+        // Fetch the releases page by page, until we get a page that is
+        // not full or we reach the maximum number of pages
+        let mut releases: Option<GithubReleases> = None;
+        for page in 1..=MAX_RELEASES_PAGES {
+            let (query, per_page) = self.forge.releases_page_query(page);
+            let page_url = format!("{}?{}", mirrored_url, query);
+
+            // The releases are listed from the most recent, so any new
+            // release changes the first page; if it did not change, we
+            // can keep using the cached release list
+            let conditional = match cached {
+                Some(cached) if page == 1 && cached.is_conditional() => Some(cached),
+                _ => None,
+            };
 
-        let status = response.status();
-        let contents = response.text().map_err(|err| {
-            let errmsg = format!("failed to read response: {}", err);
-            progress_handler.error_with_message(errmsg.clone());
-            UpError::Exec(errmsg)
-        })?;
+            let response = options
+                .retry
+                .run(Some(progress_handler), || {
+                    let mut request = client.get(&page_url);
+                    if let Some(cached) = conditional {
+                        if let Some(etag) = &cached.etag {
+                            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+                        }
+                        if let Some(last_modified) = &cached.last_modified {
+                            request =
+                                request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+                        }
+                    }
+
+                    let response = request
+                        .send()
+                        .map_err(|err| UpError::Exec(format!("failed to get releases: {}", err)))?;
+
+                    let status = response.status();
+                    if status == reqwest::StatusCode::NOT_MODIFIED {
+                        return Ok(None);
+                    }
+
+                    let rate_limit = ApiRateLimit::from_headers(response.headers());
+                    let header = |name: reqwest::header::HeaderName| {
+                        response
+                            .headers()
+                            .get(name)
+                            .and_then(|value| value.to_str().ok())
+                            .map(|value| value.to_string())
+                    };
+                    let etag = header(reqwest::header::ETAG);
+                    let last_modified = header(reqwest::header::LAST_MODIFIED);
+
+                    let contents = response.text().map_err(|err| {
+                        UpError::Exec(format!("failed to read response: {}", err))
+                    })?;
+
+                    if rate_limit.is_exceeded(status) {
+                        // Trying again before the reset would not help
+                        let errmsg = rate_limit.message(self.forge.display_name());
+                        return Err(AttemptError::Permanent(UpError::RateLimited(errmsg)));
+                    }
+
+                    if !status.is_success() {
+                        // Try parsing the error message from the body, and default to
+                        // the body if we can't parse it
+                        let errmsg = match GithubApiError::from_json(&contents) {
+                            Ok(gherr) => gherr.message,
+                            Err(_) => contents.clone(),
+                        };
+
+                        let errmsg = format!("{}: {} ({})", releases_url, errmsg, status);
+                        return Err(AttemptError::from_status(status, UpError::Exec(errmsg)));
+                    }
+
+                    Ok(Some((contents, etag, last_modified)))
+                })
+                .inspect_err(|err| {
+                    // Rate limits are reported by the caller, which
+                    // might still be able to use the cached releases
+                    if !matches!(err, UpError::RateLimited(_)) {
+                        progress_handler.error_with_message(err.message());
+                    }
+                })?;
 
-        if !status.is_success() {
-            // Try parsing the error message from the body, and default to
-            // the body if we can't parse it
-            let errmsg = match GithubApiError::from_json(&contents) {
-                Ok(gherr) => gherr.message,
-                Err(_) => contents.clone(),
+            let (contents, etag, last_modified) = match (response, conditional) {
+                (Some(response), _) => response,
+                (None, Some(cached)) => {
+                    progress_handler.progress("release list not modified".light_black());
+                    let mut releases = cached.clone();
+                    releases.refresh();
+                    return Ok(releases);
+                }
+                (None, None) => {
+                    let errmsg = format!("{}: unexpected not modified response", releases_url);
+                    progress_handler.error_with_message(errmsg.clone());
+                    return Err(UpError::Exec(errmsg));
+                }
             };
 
-            let errmsg = format!("{}: {} ({})", releases_url, errmsg, status);
-            progress_handler.error_with_message(errmsg.to_string());
-            return Err(UpError::Exec(errmsg));
+            let page_releases = match self.forge {
+                ReleaseForge::Github => GithubReleases::from_json(&contents),
+                ReleaseForge::Gitlab => GithubReleases::from_gitlab_json(&contents),
+                ReleaseForge::Gitea => GithubReleases::from_gitea_json(&contents),
+            }
+            .map_err(|err| {
+                let errmsg = format!("failed to parse releases: {}", err);
+                progress_handler.error_with_message(errmsg.clone());
+                UpError::Exec(errmsg)
+            })?;
+
+            let last_page = page_releases.releases.len() < per_page;
+            match releases {
+                Some(ref mut releases) => releases.releases.extend(page_releases.releases),
+                None => {
+                    releases = Some(GithubReleases {
+                        etag,
+                        last_modified,
+                        ..page_releases
+                    })
+                }
+            }
+
+            if last_page {
+                break;
+            }
         }
 
-        let releases = GithubReleases::from_json(&contents).map_err(|err| {
-            let errmsg = format!("failed to parse releases: {}", err);
-            progress_handler.error_with_message(errmsg.clone());
-            UpError::Exec(errmsg)
-        })?;
+        let releases = releases.expect("at least one page of releases was fetched");
 
         Ok(releases)
     }
@@ -1144,11 +2071,19 @@ impl UpConfigGithubRelease {
                     .build(self.build)
                     .binary(self.binary)
                     .asset_name(self.asset_name.clone())
+                    .tag_prefix(self.tag_prefix.clone())
+                    .tag_regex(self.tag_regex.clone())
                     .skip_os_matching(self.skip_os_matching)
                     .skip_arch_matching(self.skip_arch_matching)
                     .checksum_lookup(self.checksum.is_enabled())
                     .checksum_algorithm(self.checksum.algorithm.clone().map(|a| a.to_string()))
-                    .checksum_asset_name(self.checksum.asset_name.clone()),
+                    .checksum_asset_name(self.checksum.asset_name.clone())
+                    .signature_extensions(
+                        self.signature
+                            .as_ref()
+                            .map(|signature| signature.extensions())
+                            .unwrap_or_default(),
+                    ),
             )
             .ok_or_else(|| {
                 let errmsg = format!(
@@ -1165,7 +2100,7 @@ impl UpConfigGithubRelease {
         &self,
         _progress_handler: &dyn ProgressHandler,
     ) -> Result<Vec<String>, UpError> {
-        let release_path = github_releases_bin_path().join(&self.repository);
+        let release_path = self.forge.bin_path().join(self.install_name());
 
         if !release_path.exists() {
             return Ok(vec![]);
@@ -1221,14 +2156,53 @@ impl UpConfigGithubRelease {
         Ok(version.to_string())
     }
 
+    /// Returns the name under which the release is installed, recorded
+    /// in the cache of installed releases and in the lock file: the
+    /// repository, followed by an identifier of the selection of its
+    /// tags and of the files installed from its assets when those are
+    /// configured, so that the tools released from a same repository
+    /// under different tags, or installed with different binaries, do
+    /// not share their install
+    fn install_name(&self) -> String {
+        let selection = [
+            ("tag_prefix", &self.tag_prefix),
+            ("tag_regex", &self.tag_regex),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.clone().map(|value| (key, value)))
+        .chain(self.binaries.iter().map(|binary| {
+            let name = binary.name.as_deref().unwrap_or_default();
+            ("binaries", format!("{}\0{}", binary.path, name))
+        }))
+        .chain(self.completions.iter().map(|p| ("completions", p.clone())))
+        .chain(self.man_pages.iter().map(|p| ("man_pages", p.clone())))
+        .collect::<Vec<_>>();
+        if selection.is_empty() {
+            return self.repository.clone();
+        }
+
+        let mut hasher = blake3::Hasher::new();
+        for (key, value) in selection {
+            hasher.update(key.as_bytes());
+            hasher.update(b"\0");
+            hasher.update(value.as_bytes());
+            hasher.update(b"\0");
+        }
+        let hash_b62 = base62_encode(hasher.finalize().as_bytes())[..10].to_string();
+
+        format!("{}@{}", self.repository, hash_b62)
+    }
+
     fn release_version_path(&self, version: &str) -> PathBuf {
-        github_releases_bin_path()
-            .join(&self.repository)
+        self.forge
+            .bin_path()
+            .join(self.install_name())
             .join(version)
     }
 
     fn download_asset(
         &self,
+        options: &UpOptions,
         asset_name: &str,
         asset_url: &str,
         asset_path: &Path,
@@ -1236,57 +2210,44 @@ impl UpConfigGithubRelease {
     ) -> Result<std::fs::File, UpError> {
         progress_handler.progress(format!("downloading {}", asset_name.light_yellow()));
 
-        // Download the asset
-        let mut response = reqwest::blocking::get(asset_url).map_err(|err| {
-            let errmsg = format!("failed to download {}: {}", asset_name, err);
+        // Download the asset; for forges other than GitHub, assets can be
+        // uploaded to the forge itself and require authentication when
+        // the project is private, so we authenticate the requests that
+        // target the same host as the API, unless going through a mirror
+        let network = global_config().network;
+        let client = network.client().map_err(|errmsg| {
             progress_handler.error_with_message(errmsg.clone());
-            UpError::Exec(errmsg)
+            UpError::Config(errmsg)
         })?;
-
-        // Check if the download was successful
-        let status = response.status();
-        if !status.is_success() {
-            let contents = response.text().map_err(|err| {
-                let errmsg = format!("failed to read response: {}", err);
-                progress_handler.error_with_message(errmsg.clone());
-                UpError::Exec(errmsg)
-            })?;
-
-            // Try parsing the error message from the body, and default to
-            // the body if we can't parse it
-            let errmsg = match GithubApiError::from_json(&contents) {
-                Ok(gherr) => gherr.message,
-                Err(_) => contents.clone(),
-            };
-
-            let errmsg = format!("failed to download: {} ({})", errmsg, status);
-            progress_handler.error_with_message(errmsg.to_string());
-            return Err(UpError::Exec(errmsg));
+        let mirrored_url = network.rewrite_url(asset_url);
+        let mut request = client.get(&mirrored_url);
+        if self.forge != ReleaseForge::Github && mirrored_url == asset_url {
+            let asset_hostname = url::Url::parse(asset_url)
+                .ok()
+                .and_then(|url| url.host_str().map(|host| host.to_string()));
+            if asset_hostname.is_some_and(|host| host == self.get_api_hostname(progress_handler)) {
+                if let Some(token) = self.get_forge_auth_token(progress_handler) {
+                    request = match self.forge {
+                        ReleaseForge::Gitlab => request.header("PRIVATE-TOKEN", token),
+                        _ => request
+                            .header(reqwest::header::AUTHORIZATION, format!("token {}", token)),
+                    };
+                }
+            }
         }
 
-        // Write the file to disk
-        let mut file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(asset_path)
-            .map_err(|err| {
-                let errmsg = format!("failed to open {}: {}", asset_name, err);
-                progress_handler.error_with_message(errmsg.clone());
-                UpError::Exec(errmsg)
-            })?;
-
-        io::copy(&mut response, &mut file).map_err(|err| {
-            let errmsg = format!("failed to write {}: {}", asset_name, err);
-            progress_handler.error_with_message(errmsg.clone());
-            UpError::Exec(errmsg)
-        })?;
-
-        Ok(file)
+        download_file(
+            request,
+            asset_name,
+            asset_path,
+            &options.retry,
+            progress_handler,
+        )
     }
 
     fn validate_checksum(
         &self,
+        options: &UpOptions,
         asset: &GithubReleaseAsset,
         tmp_dir_path: &Path,
         progress_handler: &dyn ProgressHandler,
@@ -1307,6 +2268,7 @@ impl UpConfigGithubRelease {
             // Download the checksum assets but only if it does not exist
             if !checksum_asset_path.exists() {
                 self.download_asset(
+                    options,
                     &checksum_asset_name,
                     &checksum_asset.browser_download_url,
                     &checksum_asset_path,
@@ -1365,61 +2327,218 @@ impl UpConfigGithubRelease {
             return Ok(());
         };
 
-        // If we have any value to check against, let's validate the checksum
-        let checksum_algorithm = if let Some(checksum_algorithm) = &self.checksum.algorithm {
-            checksum_algorithm.clone()
-        } else if let Some(checksum_algorithm) =
-            GithubReleaseChecksumAlgorithm::from_hash(&checksum_value)
-        {
-            checksum_algorithm
-        } else {
-            let errmsg = format!("checksum algorithm not found for {}", checksum_value);
-            progress_handler.error_with_message(errmsg.clone());
-            return Err(UpError::Exec(errmsg));
-        };
+        validate_file_checksum(
+            &asset_name,
+            &asset_path,
+            &checksum_value,
+            self.checksum.algorithm.as_ref(),
+            progress_handler,
+        )
+    }
 
-        progress_handler.progress(format!(
-            "validating checksum for {}",
-            asset_name.light_yellow()
-        ));
+    /// Installs the binaries matching the configured paths from the
+    /// extracted assets, renaming them if requested
+    fn install_selected_binaries(
+        &self,
+        extracted: &ExtractedAssets,
+        install_path: &Path,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<bool, UpError> {
+        let files = extracted.files();
 
-        let file_checksum = checksum_algorithm
-            .compute_file_hash(&asset_path)
-            .map_err(|err| {
-                let errmsg = format!("failed to compute checksum for {}: {}", asset_name, err);
-                progress_handler.error_with_message(errmsg.clone());
-                UpError::Exec(errmsg)
+        for binary in &self.binaries {
+            let matcher = glob_matcher(&binary.path).map_err(|err| {
+                progress_handler.error_with_message(err.clone());
+                UpError::Config(err)
             })?;
 
-        if file_checksum != checksum_value {
-            let errmsg = format!(
-                "checksum mismatch for {}: expected {} but got {}",
-                asset_name, checksum_value, file_checksum
-            );
-            progress_handler.error_with_message(errmsg.clone());
-            return Err(UpError::Exec(errmsg));
+            let matching = files
+                .iter()
+                .filter(|(_, relative_path)| matcher.is_match(relative_path))
+                .collect::<Vec<_>>();
+
+            let errmsg = match (matching.len(), &binary.name) {
+                (0, _) => Some(format!("no file matching {} found", binary.path)),
+                (1, _) | (_, None) => None,
+                (_, Some(name)) => Some(format!(
+                    "multiple files matching {} found, cannot install them as {}",
+                    binary.path, name
+                )),
+            };
+            if let Some(errmsg) = errmsg {
+                progress_handler.error_with_message(errmsg.clone());
+                let _ = force_remove_dir_all(install_path);
+                return Err(UpError::Exec(errmsg));
+            }
+
+            for (source_path, relative_path) in matching {
+                let binary_name = match &binary.name {
+                    Some(name) => name.clone(),
+                    None => file_name(source_path),
+                };
+
+                let file = std::fs::File::open(source_path).map_err(|err| {
+                    let errmsg = format!("failed to open {}: {}", relative_path, err);
+                    progress_handler.error_with_message(errmsg.clone());
+                    UpError::Exec(errmsg)
+                })?;
+                make_executable(&file, relative_path, progress_handler)?;
+
+                install_binary(source_path, &binary_name, install_path, progress_handler)?;
+            }
         }
 
-        Ok(())
+        Ok(true)
     }
 
-    fn download_release(
+    /// Installs the completions and man pages matching the configured
+    /// patterns from the extracted assets, in the directories exposed
+    /// by the dynamic environment
+    fn install_extra_files(
         &self,
-        options: &UpOptions,
-        release: &GithubReleaseVersion,
+        extracted: &ExtractedAssets,
+        install_path: &Path,
         progress_handler: &dyn ProgressHandler,
-    ) -> Result<bool, UpError> {
-        let version = release.version();
-        let install_path = self.release_version_path(&version);
+    ) -> Result<(), UpError> {
+        if self.completions.is_empty() && self.man_pages.is_empty() {
+            return Ok(());
+        }
 
-        if options.read_cache && install_path.exists() && install_path.is_dir() {
-            progress_handler.progress(
-                format!("downloaded {} {} (cached)", self.repository, version).light_black(),
-            );
+        let files = extracted.files();
+        let extra_files = self
+            .completions
+            .iter()
+            .map(|pattern| (pattern, "completions"))
+            .chain(self.man_pages.iter().map(|pattern| (pattern, "man pages")));
+
+        for (pattern, kind) in extra_files {
+            let matcher = glob_matcher(pattern).map_err(|err| {
+                progress_handler.error_with_message(err.clone());
+                UpError::Config(err)
+            })?;
+
+            let matching = files
+                .iter()
+                .filter(|(_, relative_path)| matcher.is_match(relative_path))
+                .collect::<Vec<_>>();
+
+            // Those files are a nice-to-have, so their absence should
+            // not prevent the binaries from being used
+            if matching.is_empty() {
+                progress_handler
+                    .progress(format!("no {} matching {} found", kind, pattern).light_black());
+                continue;
+            }
+
+            for (source_path, _) in matching {
+                let name = file_name(source_path);
+                let target_dir = if kind == "completions" {
+                    install_path.join(COMPLETIONS_DIR)
+                } else {
+                    install_path.join(MAN_DIR).join(man_section_dir(&name))
+                };
+
+                progress_handler.progress(format!("found {} {}", kind, name.light_yellow()));
+
+                let copy = std::fs::create_dir_all(&target_dir)
+                    .and_then(|_| std::fs::copy(source_path, target_dir.join(&name)));
+                if let Err(err) = copy {
+                    let errmsg = format!("failed to copy {}: {}", name, err);
+                    progress_handler.error_with_message(errmsg.clone());
+                    let _ = force_remove_dir_all(install_path);
+                    return Err(UpError::Exec(errmsg));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_signature(
+        &self,
+        options: &UpOptions,
+        asset: &GithubReleaseAsset,
+        tmp_dir_path: &Path,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<(), UpError> {
+        let signature = match &self.signature {
+            Some(signature) => signature,
+            None => return Ok(()),
+        };
+
+        // The signature of the asset itself is preferred, but a signed
+        // checksum file is as good if the asset checksum was validated
+        // against it
+        let checksum_asset = asset
+            .checksum_asset
+            .as_deref()
+            .filter(|_| self.checksum.is_enabled() && self.checksum.value.is_none());
+        let (signed_asset, signature_asset) = match (&asset.signature_asset, checksum_asset) {
+            (Some(signature_asset), _) => (asset, signature_asset),
+            (None, Some(checksum_asset)) => match &checksum_asset.signature_asset {
+                Some(signature_asset) => (checksum_asset, signature_asset),
+                None => {
+                    let errmsg = format!("could not find signature for {}", asset.name);
+                    progress_handler.error_with_message(errmsg.clone());
+                    return Err(UpError::Exec(errmsg));
+                }
+            },
+            (None, None) => {
+                let errmsg = format!("could not find signature for {}", asset.name);
+                progress_handler.error_with_message(errmsg.clone());
+                return Err(UpError::Exec(errmsg));
+            }
+        };
+
+        let signature_path = tmp_dir_path.join(&signature_asset.name);
+        if !signature_path.exists() {
+            self.download_asset(
+                options,
+                &signature_asset.name,
+                &signature_asset.browser_download_url,
+                &signature_path,
+                progress_handler,
+            )?;
+        }
+
+        progress_handler.progress(format!("verifying signature of {}", signed_asset.name));
+        signature
+            .verify(&tmp_dir_path.join(&signed_asset.name), &signature_path)
+            .map_err(|err| {
+                let errmsg = format!(
+                    "signature verification failed for {}: {}",
+                    signed_asset.name, err
+                );
+                progress_handler.error_with_message(errmsg.clone());
+                UpError::Exec(errmsg)
+            })
+    }
+
+    fn download_release(
+        &self,
+        options: &UpOptions,
+        release: &GithubReleaseVersion,
+        locked: Option<&UpLockEntry>,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<bool, UpError> {
+        let version = release.version();
+        let install_path = self.release_version_path(&version);
+
+        if (options.read_cache || options.offline) && install_path.exists() && install_path.is_dir()
+        {
+            progress_handler.progress(
+                format!("downloaded {} {} (cached)", self.repository, version).light_black(),
+            );
 
             return Ok(false);
         }
 
+        if options.offline {
+            let err = UpError::Offline(format!("{} {}", self.repository, version));
+            progress_handler.error_with_message(err.message());
+            return Err(err);
+        }
+
         // Make a temporary directory to download the release
         let tmp_dir = tempfile::Builder::new()
             .prefix("omni_download.")
@@ -1431,7 +2550,8 @@ impl UpConfigGithubRelease {
 
         // Go over each of the assets that matched the current platform
         // and download them all
-        let mut binary_found = false;
+        let mut downloaded_assets = vec![];
+        let mut extracted = ExtractedAssets::default();
         for asset in &release.assets {
             // Raise an error if the checksum is required but no asset was
             // found to validate the checksum against
@@ -1450,11 +2570,29 @@ impl UpConfigGithubRelease {
             let asset_path = tmp_dir.path().join(&asset_name);
 
             // Download the asset
-            let file =
-                self.download_asset(&asset_name, &asset_url, &asset_path, progress_handler)?;
+            let file = self.download_asset(
+                options,
+                &asset_name,
+                &asset_url,
+                &asset_path,
+                progress_handler,
+            )?;
 
             // Validate the checksum if required
-            self.validate_checksum(asset, tmp_dir.path(), progress_handler)?;
+            self.validate_checksum(options, asset, tmp_dir.path(), progress_handler)?;
+
+            // Validate the signature if configured
+            self.validate_signature(options, asset, tmp_dir.path(), progress_handler)?;
+
+            // Validate the asset against the lock, and record its checksum
+            // so that it can be locked
+            downloaded_assets.push(self.lock_asset(
+                &asset_name,
+                &asset_url,
+                &asset_path,
+                locked,
+                progress_handler,
+            )?);
 
             // Get the parsed asset name
             let (asset_type, target_dir) = asset.file_type().ok_or_else(|| {
@@ -1466,20 +2604,7 @@ impl UpConfigGithubRelease {
 
             if asset_type.is_binary() {
                 // Make the binary executable
-                let mut perms = file
-                    .metadata()
-                    .map_err(|err| {
-                        let errmsg = format!("failed to get metadata for {}: {}", asset_name, err);
-                        progress_handler.error_with_message(errmsg.clone());
-                        UpError::Exec(errmsg)
-                    })?
-                    .permissions();
-                perms.set_mode(0o755);
-                file.set_permissions(perms).map_err(|err| {
-                    let errmsg = format!("failed to set permissions for {}: {}", asset_name, err);
-                    progress_handler.error_with_message(errmsg.clone());
-                    UpError::Exec(errmsg)
-                })?;
+                make_executable(&file, &asset_name, progress_handler)?;
 
                 // Rename the file to get rid of the os, architecture
                 // and version information
@@ -1489,102 +2614,29 @@ impl UpConfigGithubRelease {
                     progress_handler.error_with_message(errmsg.clone());
                     UpError::Exec(errmsg)
                 })?;
+                extracted.binaries.push((new_path, asset_name));
             } else {
-                progress_handler.progress(format!("extracting {}", asset_name.light_yellow()));
-
-                // Open the downloaded file
-                let archive_file = std::fs::File::open(&asset_path).map_err(|err| {
-                    let errmsg = format!("failed to open {}: {}", asset_name, err);
-                    progress_handler.error_with_message(errmsg.clone());
-                    UpError::Exec(errmsg)
-                })?;
-
-                // Perform the extraction
-                if asset_type.is_zip() {
-                    zip_extract::extract(&archive_file, &target_dir, true).map_err(|err| {
-                        let errmsg = format!("failed to extract {}: {}", asset_name, err);
-                        progress_handler.error_with_message(errmsg.clone());
-                        UpError::Exec(errmsg)
-                    })?;
-                } else if asset_type.is_tgz() {
-                    let tar = flate2::read::GzDecoder::new(archive_file);
-                    let mut archive = tar::Archive::new(tar);
-                    archive.unpack(&target_dir).map_err(|err| {
-                        let errmsg = format!("failed to extract {}: {}", asset_name, err);
-                        progress_handler.error_with_message(errmsg.clone());
-                        UpError::Exec(errmsg)
-                    })?;
-                } else {
-                    let errmsg = format!("file extension not supported: {}", asset_name);
-                    progress_handler.error_with_message(errmsg.clone());
-                    return Err(UpError::Exec(errmsg));
-                }
+                extract_asset(
+                    &asset_type,
+                    &asset_name,
+                    &asset_path,
+                    &target_dir,
+                    progress_handler,
+                )?;
+                extracted.dirs.push(target_dir);
             }
         }
 
         // Locate the binary file(s) in the extracted directory, recursively
         // and move them to the workdir data path
-        for entry in walkdir::WalkDir::new(tmp_dir.path())
-            .into_iter()
-            .filter_map(|entry| {
-                let entry = entry.ok()?;
-                let entry_path = entry.path();
-                if entry_path.is_file() {
-                    let metadata = entry.metadata().ok()?;
-                    let is_executable = metadata.permissions().mode() & 0o111 != 0;
-                    if is_executable {
-                        Some(entry)
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            })
-        {
-            let source_path = entry.path();
-            let binary_name = source_path
-                .file_name()
-                .unwrap_or(source_path.as_os_str())
-                .to_string_lossy()
-                .to_string();
-
-            progress_handler.progress(format!("found binary {}", binary_name.light_yellow()));
-
-            let target_path = install_path.join(&binary_name);
-
-            // Make sure the target directory exists
-            if !install_path.exists() {
-                std::fs::create_dir_all(&install_path).map_err(|err| {
-                    let errmsg = format!("failed to create {}: {}", install_path.display(), err);
-                    progress_handler.error_with_message(errmsg.clone());
-                    UpError::Exec(errmsg)
-                })?;
-            }
-
-            // Copy the binary to the install path
-            let copy = std::fs::copy(source_path, &target_path);
-            if copy.is_err() || !target_path.exists() {
-                let err = if let Err(err) = copy {
-                    err
-                } else {
-                    std::io::Error::new(
-                        std::io::ErrorKind::NotFound,
-                        "target file not found after copy".to_string(),
-                    )
-                };
-                let errmsg = format!("failed to copy {}: {}", binary_name, err);
-                progress_handler.error_with_message(errmsg.clone());
-
-                // Force delete the install path if we fail to copy
-                // the binary to avoid leaving a partial installation
-                // behind
-                let _ = force_remove_dir_all(&install_path);
-
-                return Err(UpError::Exec(errmsg));
-            }
+        let binary_found = if self.binaries.is_empty() {
+            install_binaries(tmp_dir.path(), &install_path, progress_handler)?
+        } else {
+            self.install_selected_binaries(&extracted, &install_path, progress_handler)?
+        };
 
-            binary_found = true;
+        if binary_found {
+            self.install_extra_files(&extracted, &install_path, progress_handler)?;
         }
 
         if !binary_found {
@@ -1599,9 +2651,46 @@ impl UpConfigGithubRelease {
             version.light_yellow()
         ));
 
+        if self.downloaded_assets.set(downloaded_assets).is_err() {
+            unreachable!("failed to set downloaded assets");
+        }
+
         Ok(true)
     }
 
+    fn lock_asset(
+        &self,
+        asset_name: &str,
+        asset_url: &str,
+        asset_path: &Path,
+        locked: Option<&UpLockEntry>,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<UpLockAsset, UpError> {
+        let algorithm = GithubReleaseChecksumAlgorithm::Sha256;
+        let hash = algorithm.compute_file_hash(asset_path).map_err(|err| {
+            let errmsg = format!("failed to compute checksum for {}: {}", asset_name, err);
+            progress_handler.error_with_message(errmsg.clone());
+            UpError::Exec(errmsg)
+        })?;
+        let checksum = format!("{}:{}", algorithm, hash);
+
+        if let Some(locked_checksum) = locked.and_then(|locked| locked.asset_checksum(asset_url)) {
+            if locked_checksum != checksum {
+                let errmsg = format!(
+                    "checksum mismatch for {}: locked {}, got {}",
+                    asset_name, locked_checksum, checksum
+                );
+                progress_handler.error_with_message(errmsg.clone());
+                return Err(UpError::Exec(errmsg));
+            }
+        }
+
+        Ok(UpLockAsset {
+            url: asset_url.to_string(),
+            checksum: Some(checksum),
+        })
+    }
+
     fn handling(&self) -> GithubReleaseHandled {
         match self.was_handled.get() {
             Some(handled) => handled.clone(),
@@ -1610,6 +2699,401 @@ impl UpConfigGithubRelease {
     }
 }
 
+/// Download the file targeted by the provided request to the given path,
+/// returning the open file handle once the contents have been written;
+/// the download is retried according to the given policy.
+pub(crate) fn download_file(
+    request: reqwest::blocking::RequestBuilder,
+    asset_name: &str,
+    asset_path: &Path,
+    retry: &RetryPolicy,
+    progress_handler: &dyn ProgressHandler,
+) -> Result<std::fs::File, UpError> {
+    let request = match retry.timeout() {
+        Some(timeout) => request.timeout(timeout),
+        None => request,
+    };
+
+    retry
+        .run(Some(progress_handler), || {
+            let request = request.try_clone().ok_or_else(|| {
+                AttemptError::Permanent(UpError::Exec(format!(
+                    "failed to download {}: request cannot be sent",
+                    asset_name
+                )))
+            })?;
+            download_file_attempt(request, asset_name, asset_path)
+        })
+        .inspect_err(|err| progress_handler.error_with_message(err.message()))
+}
+
+fn download_file_attempt(
+    request: reqwest::blocking::RequestBuilder,
+    asset_name: &str,
+    asset_path: &Path,
+) -> Result<std::fs::File, AttemptError> {
+    let mut response = request
+        .send()
+        .map_err(|err| UpError::Exec(format!("failed to download {}: {}", asset_name, err)))?;
+
+    // Check if the download was successful
+    let status = response.status();
+    if !status.is_success() {
+        let contents = response
+            .text()
+            .map_err(|err| UpError::Exec(format!("failed to read response: {}", err)))?;
+
+        // Try parsing the error message from the body, and default to
+        // the body if we can't parse it
+        let errmsg = match GithubApiError::from_json(&contents) {
+            Ok(gherr) => gherr.message,
+            Err(_) => contents.clone(),
+        };
+
+        let errmsg = format!("failed to download: {} ({})", errmsg, status);
+        return Err(AttemptError::from_status(status, UpError::Exec(errmsg)));
+    }
+
+    // Write the file to disk
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(asset_path)
+        .map_err(|err| {
+            AttemptError::Permanent(UpError::Exec(format!(
+                "failed to open {}: {}",
+                asset_name, err
+            )))
+        })?;
+
+    // Errors while copying are most likely due to the connection
+    // being interrupted, which is worth retrying
+    io::copy(&mut response, &mut file)
+        .map_err(|err| UpError::Exec(format!("failed to write {}: {}", asset_name, err)))?;
+
+    Ok(file)
+}
+
+/// Validate that the checksum of the file at the given path matches the
+/// expected value; if no algorithm is provided, it will be guessed from
+/// the length of the expected checksum.
+pub(crate) fn validate_file_checksum(
+    asset_name: &str,
+    asset_path: &Path,
+    checksum_value: &str,
+    checksum_algorithm: Option<&GithubReleaseChecksumAlgorithm>,
+    progress_handler: &dyn ProgressHandler,
+) -> Result<(), UpError> {
+    // If we have any value to check against, let's validate the checksum
+    let checksum_algorithm = if let Some(checksum_algorithm) = checksum_algorithm {
+        checksum_algorithm.clone()
+    } else if let Some(checksum_algorithm) =
+        GithubReleaseChecksumAlgorithm::from_hash(checksum_value)
+    {
+        checksum_algorithm
+    } else {
+        let errmsg = format!("checksum algorithm not found for {}", checksum_value);
+        progress_handler.error_with_message(errmsg.clone());
+        return Err(UpError::Exec(errmsg));
+    };
+
+    progress_handler.progress(format!(
+        "validating checksum for {}",
+        asset_name.light_yellow()
+    ));
+
+    let file_checksum = checksum_algorithm
+        .compute_file_hash(asset_path)
+        .map_err(|err| {
+            let errmsg = format!("failed to compute checksum for {}: {}", asset_name, err);
+            progress_handler.error_with_message(errmsg.clone());
+            UpError::Exec(errmsg)
+        })?;
+
+    if file_checksum != checksum_value {
+        let errmsg = format!(
+            "checksum mismatch for {}: expected {} but got {}",
+            asset_name, checksum_value, file_checksum
+        );
+        progress_handler.error_with_message(errmsg.clone());
+        return Err(UpError::Exec(errmsg));
+    }
+
+    Ok(())
+}
+
+/// Extract the archive at the given path into the target directory.
+pub(crate) fn extract_asset(
+    asset_type: &GithubReleaseAssetType,
+    asset_name: &str,
+    asset_path: &Path,
+    target_dir: &Path,
+    progress_handler: &dyn ProgressHandler,
+) -> Result<(), UpError> {
+    progress_handler.progress(format!("extracting {}", asset_name.light_yellow()));
+
+    // Open the downloaded file
+    let archive_file = std::fs::File::open(asset_path).map_err(|err| {
+        let errmsg = format!("failed to open {}: {}", asset_name, err);
+        progress_handler.error_with_message(errmsg.clone());
+        UpError::Exec(errmsg)
+    })?;
+
+    // Perform the extraction
+    if asset_type.is_zip() {
+        zip_extract::extract(&archive_file, target_dir, true).map_err(|err| {
+            let errmsg = format!("failed to extract {}: {}", asset_name, err);
+            progress_handler.error_with_message(errmsg.clone());
+            UpError::Exec(errmsg)
+        })?;
+    } else if asset_type.is_tgz() {
+        let tar = flate2::read::GzDecoder::new(archive_file);
+        let mut archive = tar::Archive::new(tar);
+        archive.unpack(target_dir).map_err(|err| {
+            let errmsg = format!("failed to extract {}: {}", asset_name, err);
+            progress_handler.error_with_message(errmsg.clone());
+            UpError::Exec(errmsg)
+        })?;
+    } else {
+        let errmsg = format!("file extension not supported: {}", asset_name);
+        progress_handler.error_with_message(errmsg.clone());
+        return Err(UpError::Exec(errmsg));
+    }
+
+    Ok(())
+}
+
+/// Make the file at the given path executable.
+pub(crate) fn make_executable(
+    file: &std::fs::File,
+    asset_name: &str,
+    progress_handler: &dyn ProgressHandler,
+) -> Result<(), UpError> {
+    let mut perms = file
+        .metadata()
+        .map_err(|err| {
+            let errmsg = format!("failed to get metadata for {}: {}", asset_name, err);
+            progress_handler.error_with_message(errmsg.clone());
+            UpError::Exec(errmsg)
+        })?
+        .permissions();
+    perms.set_mode(0o755);
+    file.set_permissions(perms).map_err(|err| {
+        let errmsg = format!("failed to set permissions for {}: {}", asset_name, err);
+        progress_handler.error_with_message(errmsg.clone());
+        UpError::Exec(errmsg)
+    })
+}
+
+/// Locate the executable files in the source directory, recursively,
+/// and copy them to the install path; returns whether any binary was
+/// found.
+pub(crate) fn install_binaries(
+    source_dir: &Path,
+    install_path: &Path,
+    progress_handler: &dyn ProgressHandler,
+) -> Result<bool, UpError> {
+    let mut binary_found = false;
+
+    for entry in walkdir::WalkDir::new(source_dir)
+        .into_iter()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let entry_path = entry.path();
+            if entry_path.is_file() {
+                let metadata = entry.metadata().ok()?;
+                let is_executable = metadata.permissions().mode() & 0o111 != 0;
+                if is_executable {
+                    Some(entry)
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        })
+    {
+        let source_path = entry.path();
+        let binary_name = source_path
+            .file_name()
+            .unwrap_or(source_path.as_os_str())
+            .to_string_lossy()
+            .to_string();
+
+        install_binary(source_path, &binary_name, install_path, progress_handler)?;
+
+        binary_found = true;
+    }
+
+    Ok(binary_found)
+}
+
+/// Copy a single binary to the install path under the given name; the
+/// install path is removed if the copy fails to avoid leaving a partial
+/// installation behind.
+pub(crate) fn install_binary(
+    source_path: &Path,
+    binary_name: &str,
+    install_path: &Path,
+    progress_handler: &dyn ProgressHandler,
+) -> Result<(), UpError> {
+    progress_handler.progress(format!("found binary {}", binary_name.light_yellow()));
+
+    let target_path = install_path.join(binary_name);
+
+    // Make sure the target directory exists
+    if !install_path.exists() {
+        std::fs::create_dir_all(install_path).map_err(|err| {
+            let errmsg = format!("failed to create {}: {}", install_path.display(), err);
+            progress_handler.error_with_message(errmsg.clone());
+            UpError::Exec(errmsg)
+        })?;
+    }
+
+    // Copy the binary to the install path
+    let copy = std::fs::copy(source_path, &target_path);
+    if copy.is_err() || !target_path.exists() {
+        let err = if let Err(err) = copy {
+            err
+        } else {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "target file not found after copy".to_string(),
+            )
+        };
+        let errmsg = format!("failed to copy {}: {}", binary_name, err);
+        progress_handler.error_with_message(errmsg.clone());
+
+        // Force delete the install path if we fail to copy
+        // the binary to avoid leaving a partial installation
+        // behind
+        let _ = force_remove_dir_all(install_path);
+
+        return Err(UpError::Exec(errmsg));
+    }
+
+    Ok(())
+}
+
+/// The files provided by the downloaded assets of a release, which are
+/// the directories in which the archives were extracted, and the binary
+/// assets along with the name they were published with; the checksum
+/// and signature files are not part of those
+#[derive(Debug, Default)]
+struct ExtractedAssets {
+    dirs: Vec<PathBuf>,
+    binaries: Vec<(PathBuf, String)>,
+}
+
+impl ExtractedAssets {
+    /// Returns the files of the extracted assets, recursively, with
+    /// their path relative to the directory in which their asset was
+    /// extracted, or the name of the asset for binary assets
+    fn files(&self) -> Vec<(PathBuf, String)> {
+        let extracted_files = self.dirs.iter().flat_map(|dir| {
+            walkdir::WalkDir::new(dir)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file())
+                .filter_map(move |entry| {
+                    let relative_path = entry.path().strip_prefix(dir).ok()?;
+                    Some((
+                        entry.path().to_path_buf(),
+                        relative_path.to_string_lossy().to_string(),
+                    ))
+                })
+        });
+
+        extracted_files
+            .chain(self.binaries.iter().cloned())
+            .collect()
+    }
+}
+
+/// Returns a matcher for the given glob pattern, in which `*` does not
+/// match path separators, while `**` does
+fn glob_matcher(pattern: &str) -> Result<globset::GlobMatcher, String> {
+    globset::GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|err| format!("invalid pattern {}: {}", pattern, err))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Returns the directory of the man section of the given man page,
+/// based on its extension, e.g. `man1` for `tool.1` or `tool.1.gz`
+fn man_section_dir(name: &str) -> String {
+    let name = name.strip_suffix(".gz").unwrap_or(name);
+    let section = name
+        .rsplit_once('.')
+        .and_then(|(_, ext)| ext.chars().next())
+        .filter(|section| section.is_ascii_digit())
+        .unwrap_or('1');
+    format!("man{}", section)
+}
+
+/// Returns the list of glob patterns in the configuration value, which
+/// can be a single pattern or a list of patterns
+fn glob_patterns(config_value: Option<&ConfigValue>) -> Vec<String> {
+    match config_value {
+        Some(config_value) => match config_value.as_array() {
+            Some(array) => array.iter().filter_map(|v| v.as_str_forced()).collect(),
+            None => config_value.as_str_forced().into_iter().collect(),
+        },
+        None => vec![],
+    }
+}
+
+/// A binary to install from the release assets
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GithubReleaseBinaryConfig {
+    /// The path of the binary in the extracted assets, which can be
+    /// a glob pattern
+    path: String,
+
+    /// The name to install the binary as, if different from its
+    /// own name; can only be used if the path matches a single file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+impl GithubReleaseBinaryConfig {
+    /// Parses the binaries to install, which can be provided as a single
+    /// path, a list of paths, or a mapping of paths to the names to
+    /// install them as
+    fn from_config_value(config_value: Option<&ConfigValue>) -> Vec<Self> {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return vec![],
+        };
+
+        if let Some(table) = config_value.as_table() {
+            let mut binaries = table
+                .into_iter()
+                .map(|(path, name)| Self {
+                    path,
+                    name: name.as_str_forced().filter(|name| !name.is_empty()),
+                })
+                .collect::<Vec<_>>();
+            binaries.sort_by(|a, b| a.path.cmp(&b.path));
+            return binaries;
+        }
+
+        glob_patterns(Some(config_value))
+            .into_iter()
+            .map(|path| Self { path, name: None })
+            .collect()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 struct GithubReleaseChecksumConfig {
     /// Whether checksum verification is enabled; if set to
@@ -1708,7 +3192,7 @@ impl GithubReleaseChecksumConfig {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-enum GithubReleaseChecksumAlgorithm {
+pub(crate) enum GithubReleaseChecksumAlgorithm {
     #[serde(rename = "md5")]
     Md5,
     #[serde(rename = "sha1")]
@@ -1760,7 +3244,7 @@ impl GithubReleaseChecksumAlgorithm {
         }
     }
 
-    pub fn compute_file_hash(&self, path: &PathBuf) -> io::Result<String> {
+    pub fn compute_file_hash(&self, path: &Path) -> io::Result<String> {
         match self {
             GithubReleaseChecksumAlgorithm::Md5 => {
                 let mut hasher = Md5::new();
@@ -1796,6 +3280,69 @@ impl GithubReleaseChecksumAlgorithm {
     }
 }
 
+/// The rate limit of the API, as reported by the headers of a response;
+/// GitHub and Gitea use the `X-RateLimit-*` headers, while GitLab uses
+/// the `RateLimit-*` headers, and secondary rate limits are reported
+/// with a `Retry-After` header
+#[derive(Debug, Default)]
+struct ApiRateLimit {
+    remaining: Option<u64>,
+    reset: Option<OffsetDateTime>,
+    retry_after: bool,
+}
+
+impl ApiRateLimit {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let header = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                headers
+                    .get(*name)?
+                    .to_str()
+                    .ok()?
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+            })
+        };
+
+        let retry_after = header(&["retry-after"]);
+        let reset = match header(&["x-ratelimit-reset", "ratelimit-reset"]) {
+            Some(reset) => OffsetDateTime::from_unix_timestamp(reset as i64).ok(),
+            None => retry_after
+                .map(|seconds| OffsetDateTime::now_utc() + time::Duration::seconds(seconds as i64)),
+        };
+
+        Self {
+            remaining: header(&["x-ratelimit-remaining", "ratelimit-remaining"]),
+            reset,
+            retry_after: retry_after.is_some(),
+        }
+    }
+
+    /// Returns whether the response with the given status was rejected
+    /// because of the rate limit
+    fn is_exceeded(&self, status: reqwest::StatusCode) -> bool {
+        status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            || (status == reqwest::StatusCode::FORBIDDEN
+                && (self.remaining == Some(0) || self.retry_after))
+    }
+
+    fn message(&self, api_name: &str) -> String {
+        let reset = match self.reset {
+            Some(reset) => reset,
+            None => return format!("{} API rate limit exceeded", api_name),
+        };
+
+        let wait = (reset - OffsetDateTime::now_utc()).max(time::Duration::ZERO);
+        format!(
+            "{} API rate limit exceeded, resets in {} (at {})",
+            api_name,
+            humantime::format_duration(std::time::Duration::from_secs(wait.whole_seconds() as u64)),
+            reset.format(&Rfc3339).unwrap_or_else(|_| reset.to_string()),
+        )
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct GithubApiError {
     message: String,
@@ -1977,50 +3524,115 @@ mod tests {
             assert!(!config.binary);
             assert_eq!(config.api_url, Some("https://gh.example.com".to_string()));
         }
-    }
-
-    mod up {
-        use super::*;
 
-        use crate::internal::self_updater::compatible_release_arch;
-        use crate::internal::self_updater::compatible_release_os;
+        #[test]
+        fn with_tag_filters() {
+            let yaml = r#"{"repository": "owner/monorepo", "tag_prefix": "cli/", "tag_regex": "^v(?P<version>.*)$"}"#;
+            let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+            let config = UpConfigGithubRelease::from_config_value(Some(&config_value));
+            assert_eq!(config.repository, "owner/monorepo");
+            assert_eq!(config.tag_prefix, Some("cli/".to_string()));
+            assert_eq!(config.tag_regex, Some("^v(?P<version>.*)$".to_string()));
+            assert_eq!(config.validate(), Ok(()));
+        }
 
-        fn run_with_env<F>(envs: &[(String, Option<String>)], closure: F)
-        where
-            F: FnOnce(),
-        {
-            let tempdir = tempfile::Builder::new()
-                .prefix("omni_tests.")
-                .tempdir()
-                .expect("failed to create temp dir");
-
-            let run_env: Vec<(String, Option<String>)> = vec![
-                ("XDG_DATA_HOME".into(), None),
-                ("XDG_CONFIG_HOME".into(), None),
-                ("XDG_CACHE_HOME".into(), None),
-                ("XDG_RUNTIME_DIR".into(), None),
-                ("OMNI_DATA_HOME".into(), None),
-                ("OMNI_CACHE_HOME".into(), None),
-                ("OMNI_CMD_FILE".into(), None),
-                ("HOMEBREW_PREFIX".into(), None),
-                (
-                    "HOME".into(),
-                    Some(tempdir.path().join("home").to_string_lossy().to_string()),
-                ),
-                (
-                    "PATH".into(),
-                    Some(format!(
-                        "{}:/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin",
-                        tempdir.path().join("bin").to_string_lossy()
-                    )),
-                ),
-            ]
-            .into_iter()
-            .chain(envs.iter().cloned())
-            .collect();
+        #[test]
+        fn with_invalid_tag_regex() {
+            let yaml = r#"{"repository": "owner/repo", "tag_regex": "^v(?P<version>.*$"}"#;
+            let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+            let config = UpConfigGithubRelease::from_config_value(Some(&config_value));
+            let err = config.validate().expect_err("tag_regex should be invalid");
+            assert!(
+                err.starts_with("invalid tag_regex pattern '^v(?P<version>.*$': "),
+                "unexpected error: {}",
+                err
+            );
+        }
+
+        #[test]
+        fn with_invalid_signature() {
+            // A signature that cannot be verified must not disable the
+            // verification of the assets
+            let yaml =
+                r#"{"repository": "owner/repo", "signature": {"cosign": {"key": "cosign.pub"}}}"#;
+            let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+            let config = UpConfigGithubRelease::from_config_value(Some(&config_value));
+            assert_eq!(
+                config.validate(),
+                Err("invalid signature: unknown signature method 'cosign'".to_string())
+            );
+        }
+
+        #[test]
+        fn with_binaries() {
+            let yaml = r#"{"repository": "owner/repo", "binaries": {"bin/tool-*": "tool", "bin/helper": null}, "completions": "completions/_tool", "man_pages": ["man/*.1", "man/*.5"]}"#;
+            let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+            let config = UpConfigGithubRelease::from_config_value(Some(&config_value));
+            assert_eq!(
+                config.binaries,
+                vec![
+                    GithubReleaseBinaryConfig {
+                        path: "bin/helper".to_string(),
+                        name: None,
+                    },
+                    GithubReleaseBinaryConfig {
+                        path: "bin/tool-*".to_string(),
+                        name: Some("tool".to_string()),
+                    },
+                ]
+            );
+            assert_eq!(config.completions, vec!["completions/_tool".to_string()]);
+            assert_eq!(
+                config.man_pages,
+                vec!["man/*.1".to_string(), "man/*.5".to_string()]
+            );
+
+            let yaml = r#"{"repository": "owner/repo", "binaries": ["**/tool", "**/helper"]}"#;
+            let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+            let config = UpConfigGithubRelease::from_config_value(Some(&config_value));
+            assert_eq!(
+                config.binaries,
+                vec![
+                    GithubReleaseBinaryConfig {
+                        path: "**/tool".to_string(),
+                        name: None,
+                    },
+                    GithubReleaseBinaryConfig {
+                        path: "**/helper".to_string(),
+                        name: None,
+                    },
+                ]
+            );
+        }
 
-            temp_env::with_vars(run_env, closure);
+        #[test]
+        fn with_forge_auth() {
+            let yaml = r#"{"repository": "group/project", "auth": "GITLAB_CI_TOKEN"}"#;
+            let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+            let config = UpConfigGithubRelease::from_config_value_with_forge(
+                Some(&config_value),
+                ReleaseForge::Gitlab,
+            );
+            assert_eq!(config.repository, "group/project");
+            assert_eq!(config.forge, ReleaseForge::Gitlab);
+            assert!(config.auth.is_default());
+            assert_eq!(
+                config.forge_auth,
+                ForgeAuthConfig::TokenEnvVar("GITLAB_CI_TOKEN".to_string())
+            );
         }
+    }
+
+    mod up {
+        use super::*;
+
+        use crate::internal::config::up::lock::UpLock;
+        use crate::internal::config::up::utils::run_in_workdir;
+        use crate::internal::config::up::utils::run_with_env;
+        use crate::internal::config::up::utils::targz;
+
+        use crate::internal::self_updater::compatible_release_arch;
+        use crate::internal::self_updater::compatible_release_os;
 
         #[test]
         fn latest_release_binary() {
@@ -2154,6 +3766,954 @@ mod tests {
             );
         }
 
+        #[test]
+        fn gitlab_release_with_token() {
+            run_with_env(
+                &[("GITLAB_TOKEN".to_string(), Some("glpat-test".to_string()))],
+                |_| {
+                    let mut mock_server = mockito::Server::new();
+                    let api_url = mock_server.url();
+
+                    let config = UpConfigGithubRelease {
+                        repository: "group/project".to_string(),
+                        api_url: Some(api_url.to_string()),
+                        forge: ReleaseForge::Gitlab,
+                        ..UpConfigGithubRelease::default()
+                    };
+
+                    let list_releases_body = format!(
+                        r#"[
+                        {{
+                            "name": "Release 1.1.0",
+                            "tag_name": "v1.1.0",
+                            "upcoming_release": true,
+                            "assets": {{
+                                "links": [
+                                    {{
+                                        "name": "asset1_{arch}_{os}",
+                                        "url": "{url}/download/v1.1.0/asset1"
+                                    }}
+                                ]
+                            }}
+                        }},
+                        {{
+                            "name": "Release 1.0.0",
+                            "tag_name": "v1.0.0",
+                            "upcoming_release": false,
+                            "assets": {{
+                                "links": [
+                                    {{
+                                        "name": "asset1_{arch}_{os}",
+                                        "url": "{url}/link/v1.0.0/asset1",
+                                        "direct_asset_url": "{url}/download/v1.0.0/asset1"
+                                    }}
+                                ]
+                            }}
+                        }}
+                    ]"#,
+                        url = mock_server.url(),
+                        arch = current_arch(),
+                        os = current_os(),
+                    );
+
+                    let mock_list_releases = mock_server
+                        .mock("GET", "/projects/group%2Fproject/releases")
+                        .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+                        .match_header("private-token", "glpat-test")
+                        .with_status(200)
+                        .with_body(list_releases_body)
+                        .create();
+
+                    let mock_download = mock_server
+                        .mock("GET", "/download/v1.0.0/asset1")
+                        .match_header("private-token", "glpat-test")
+                        .with_status(200)
+                        .with_body("asset1 contents")
+                        .create();
+
+                    let options = UpOptions::default().cache_disabled();
+                    let progress_handler = UpProgressHandler::new(None);
+
+                    let result = config.up(&options, &progress_handler);
+                    assert!(result.is_ok(), "up failed: {:?}", result);
+
+                    mock_list_releases.assert();
+                    mock_download.assert();
+
+                    let expected_bin = gitlab_releases_bin_path()
+                        .join("group/project")
+                        .join("v1.0.0")
+                        .join("asset1");
+                    assert!(expected_bin.exists(), "binary file not found");
+                },
+            );
+        }
+
+        #[test]
+        fn gitea_release_with_config_auth() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+                let api_url = mock_server.url();
+
+                let config = UpConfigGithubRelease {
+                    repository: "owner/repo".to_string(),
+                    api_url: Some(api_url.to_string()),
+                    forge: ReleaseForge::Gitea,
+                    forge_auth: ForgeAuthConfig::Token("gitea-token".to_string()),
+                    ..UpConfigGithubRelease::default()
+                };
+
+                let list_releases_body = format!(
+                    r#"[
+                    {{
+                        "name": "Release 2.0.0-rc1",
+                        "tag_name": "v2.0.0-rc1",
+                        "draft": false,
+                        "prerelease": true,
+                        "assets": [
+                            {{
+                                "name": "asset1_{arch}_{os}",
+                                "browser_download_url": "{url}/download/v2.0.0-rc1/asset1"
+                            }}
+                        ]
+                    }},
+                    {{
+                        "name": "Release 1.5.0",
+                        "tag_name": "v1.5.0",
+                        "draft": false,
+                        "prerelease": false,
+                        "assets": [
+                            {{
+                                "name": "asset1_{arch}_{os}",
+                                "browser_download_url": "{url}/download/v1.5.0/asset1"
+                            }}
+                        ]
+                    }}
+                ]"#,
+                    url = mock_server.url(),
+                    arch = current_arch(),
+                    os = current_os(),
+                );
+
+                let mock_list_releases = mock_server
+                    .mock("GET", "/repos/owner/repo/releases")
+                    .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+                    .match_header("authorization", "token gitea-token")
+                    .with_status(200)
+                    .with_body(list_releases_body)
+                    .create();
+
+                let mock_download = mock_server
+                    .mock("GET", "/download/v1.5.0/asset1")
+                    .match_header("authorization", "token gitea-token")
+                    .with_status(200)
+                    .with_body("asset1 contents")
+                    .create();
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = config.up(&options, &progress_handler);
+                assert!(result.is_ok(), "up failed: {:?}", result);
+
+                mock_list_releases.assert();
+                mock_download.assert();
+
+                let expected_bin = gitea_releases_bin_path()
+                    .join("owner/repo")
+                    .join("v1.5.0")
+                    .join("asset1");
+                assert!(expected_bin.exists(), "binary file not found");
+            });
+        }
+
+        fn release_json(url: &str, tag_name: &str) -> String {
+            format!(
+                r#"{{
+                    "name": "Release {tag_name}",
+                    "tag_name": "{tag_name}",
+                    "draft": false,
+                    "prerelease": false,
+                    "assets": [
+                        {{
+                            "name": "asset1_{arch}_{os}",
+                            "browser_download_url": "{url}/download/{tag_name}/asset1"
+                        }}
+                    ]
+                }}"#,
+                url = url,
+                tag_name = tag_name,
+                arch = current_arch(),
+                os = current_os(),
+            )
+        }
+
+        fn test_download_tagged_release(
+            config: UpConfigGithubRelease,
+            tags: &[&str],
+            expected_tag: &str,
+            expected_version: &str,
+        ) {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+
+                let config = UpConfigGithubRelease {
+                    repository: "owner/repo".to_string(),
+                    api_url: Some(mock_server.url()),
+                    ..config
+                };
+
+                let list_releases_body = format!(
+                    "[{}]",
+                    tags.iter()
+                        .map(|tag| release_json(&mock_server.url(), tag))
+                        .join(",")
+                );
+
+                let mock_list_releases = mock_server
+                    .mock("GET", "/repos/owner/repo/releases")
+                    .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+                    .with_status(200)
+                    .with_body(list_releases_body)
+                    .create();
+
+                let mock_download = mock_server
+                    .mock("GET", format!("/download/{}/asset1", expected_tag).as_str())
+                    .with_status(200)
+                    .with_body("asset1 contents")
+                    .create();
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = config.up(&options, &progress_handler);
+                assert!(result.is_ok(), "up failed: {:?}", result);
+
+                mock_list_releases.assert();
+                mock_download.assert();
+
+                let expected_bin = config.release_version_path(expected_version).join("asset1");
+                assert!(expected_bin.exists(), "binary file not found");
+            });
+        }
+
+        #[test]
+        fn tag_prefixes_do_not_share_install() {
+            run_with_env(&[], |tempdir| {
+                run_in_workdir(&tempdir.join("wd"), |_| {
+                    let mut mock_server = mockito::Server::new();
+
+                    let list_releases_body = format!(
+                        "[{}]",
+                        ["cli/v1.2.3", "server/v1.2.3"]
+                            .iter()
+                            .map(|tag| release_json(&mock_server.url(), tag))
+                            .join(",")
+                    );
+                    let mock_list_releases = mock_server
+                        .mock("GET", "/repos/owner/monorepo/releases")
+                        .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+                        .with_status(200)
+                        .with_body(list_releases_body)
+                        .expect(2)
+                        .create();
+
+                    let lock = UpLock::for_workdir(&tempdir.join("wd").to_string_lossy())
+                        .expect("failed to load lock");
+                    let options = UpOptions::default().cache_disabled().version_lock(&lock);
+                    let progress_handler = UpProgressHandler::new(None);
+
+                    let configs = ["cli", "server"].map(|tool| {
+                        let mock_download = mock_server
+                            .mock("GET", format!("/download/{}/v1.2.3/asset1", tool).as_str())
+                            .with_status(200)
+                            .with_body(tool)
+                            .create();
+
+                        let config = UpConfigGithubRelease {
+                            repository: "owner/monorepo".to_string(),
+                            api_url: Some(mock_server.url()),
+                            tag_prefix: Some(format!("{}/", tool)),
+                            ..UpConfigGithubRelease::default()
+                        };
+                        let result = config.up(&options, &progress_handler);
+                        assert!(result.is_ok(), "up failed: {:?}", result);
+                        mock_download.assert();
+
+                        config
+                    });
+                    mock_list_releases.assert();
+
+                    // Each tool keeps its own install, cache entry and
+                    // lock entry, even though they share their version
+                    let [cli, server] = &configs;
+                    assert_ne!(cli.install_name(), server.install_name());
+                    for (config, contents) in [(cli, "cli"), (server, "server")] {
+                        let binary = config.release_version_path("v1.2.3").join("asset1");
+                        assert_eq!(
+                            std::fs::read_to_string(binary).expect("binary not found"),
+                            contents
+                        );
+                        assert!(GithubReleaseOperationCache::get()
+                            .installed
+                            .iter()
+                            .any(|install| install.repository == config.install_name()
+                                && install.version == "v1.2.3"));
+                        assert_eq!(
+                            lock.get("github-release", &config.install_name(), "")
+                                .map(|entry| entry.version.as_str()),
+                            None,
+                            "entries are only locked once saved"
+                        );
+                    }
+
+                    lock.save(false).expect("failed to save lock");
+                    let lock = UpLock::for_workdir(&tempdir.join("wd").to_string_lossy())
+                        .expect("failed to load lock");
+                    for config in &configs {
+                        assert_eq!(
+                            lock.get("github-release", &config.install_name(), "")
+                                .map(|entry| entry.version.as_str()),
+                            Some("v1.2.3")
+                        );
+                    }
+                });
+            });
+        }
+
+        #[test]
+        fn tag_prefix() {
+            test_download_tagged_release(
+                UpConfigGithubRelease {
+                    tag_prefix: Some("cli/".to_string()),
+                    ..UpConfigGithubRelease::default()
+                },
+                &["server/v3.0.0", "cli/v1.2.3", "cli/v1.1.0", "v9.0.0"],
+                "cli/v1.2.3",
+                "v1.2.3",
+            );
+        }
+
+        #[test]
+        fn tag_prefix_with_version() {
+            test_download_tagged_release(
+                UpConfigGithubRelease {
+                    version: Some("1.1".to_string()),
+                    tag_prefix: Some("cli/".to_string()),
+                    ..UpConfigGithubRelease::default()
+                },
+                &["server/v1.1.5", "cli/v1.2.3", "cli/v1.1.0"],
+                "cli/v1.1.0",
+                "v1.1.0",
+            );
+        }
+
+        #[test]
+        fn tag_regex() {
+            test_download_tagged_release(
+                UpConfigGithubRelease {
+                    tag_regex: Some(r"^kubectl-plugin-v(?P<version>\d+\.\d+\.\d+)$".to_string()),
+                    ..UpConfigGithubRelease::default()
+                },
+                &[
+                    "operator-v1.0.0",
+                    "kubectl-plugin-v0.9.1",
+                    "kubectl-plugin-v0.8.0",
+                ],
+                "kubectl-plugin-v0.9.1",
+                "0.9.1",
+            );
+        }
+
+        #[test]
+        fn paginated_releases() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+
+                let config = UpConfigGithubRelease {
+                    repository: "owner/repo".to_string(),
+                    version: Some("1.4".to_string()),
+                    api_url: Some(mock_server.url()),
+                    ..UpConfigGithubRelease::default()
+                };
+
+                // A full first page, with none of the releases matching
+                let first_page_body = format!(
+                    "[{}]",
+                    (0..100)
+                        .map(|patch| release_json(&mock_server.url(), &format!("v2.0.{}", patch)))
+                        .join(",")
+                );
+                let second_page_body = format!("[{}]", release_json(&mock_server.url(), "v1.4.2"));
+
+                let mock_first_page = mock_server
+                    .mock("GET", "/repos/owner/repo/releases")
+                    .match_query(mockito::Matcher::AllOf(vec![
+                        mockito::Matcher::UrlEncoded("per_page".into(), "100".into()),
+                        mockito::Matcher::UrlEncoded("page".into(), "1".into()),
+                    ]))
+                    .with_status(200)
+                    .with_body(first_page_body)
+                    .create();
+
+                let mock_second_page = mock_server
+                    .mock("GET", "/repos/owner/repo/releases")
+                    .match_query(mockito::Matcher::UrlEncoded("page".into(), "2".into()))
+                    .with_status(200)
+                    .with_body(second_page_body)
+                    .create();
+
+                let mock_download = mock_server
+                    .mock("GET", "/download/v1.4.2/asset1")
+                    .with_status(200)
+                    .with_body("asset1 contents")
+                    .create();
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = config.up(&options, &progress_handler);
+                assert!(result.is_ok(), "up failed: {:?}", result);
+
+                mock_first_page.assert();
+                mock_second_page.assert();
+                mock_download.assert();
+            });
+        }
+
+        #[test]
+        fn invalid_signature() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+
+                let config = UpConfigGithubRelease {
+                    repository: "owner/repo".to_string(),
+                    api_url: Some(mock_server.url()),
+                    signature: Some(SignatureConfig::Minisign {
+                        public_keys: vec![
+                            "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3".to_string(),
+                        ],
+                    }),
+                    ..UpConfigGithubRelease::default()
+                };
+
+                let asset_name = format!("asset1_{}_{}", current_arch(), current_os());
+                let list_releases_body = format!(
+                    r#"[{{
+                        "name": "Release v1.2.3",
+                        "tag_name": "v1.2.3",
+                        "draft": false,
+                        "prerelease": false,
+                        "assets": [
+                            {{
+                                "name": "{asset}",
+                                "browser_download_url": "{url}/download/v1.2.3/asset1"
+                            }},
+                            {{
+                                "name": "{asset}.minisig",
+                                "browser_download_url": "{url}/download/v1.2.3/asset1.minisig"
+                            }}
+                        ]
+                    }}]"#,
+                    asset = asset_name,
+                    url = mock_server.url(),
+                );
+
+                let mock_list_releases = mock_server
+                    .mock("GET", "/repos/owner/repo/releases")
+                    .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+                    .with_status(200)
+                    .with_body(list_releases_body)
+                    .create();
+
+                let mock_download = mock_server
+                    .mock("GET", "/download/v1.2.3/asset1")
+                    .with_status(200)
+                    .with_body("asset1 contents")
+                    .create();
+
+                let mock_download_signature = mock_server
+                    .mock("GET", "/download/v1.2.3/asset1.minisig")
+                    .with_status(200)
+                    .with_body("not a signature")
+                    .create();
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = config.up(&options, &progress_handler);
+                assert_eq!(
+                    result,
+                    Err(UpError::Exec(format!(
+                        "signature verification failed for {}: invalid minisign signature",
+                        asset_name
+                    )))
+                );
+
+                mock_list_releases.assert();
+                mock_download.assert();
+                mock_download_signature.assert();
+
+                let release_path = github_releases_bin_path().join("owner/repo/v1.2.3");
+                assert!(!release_path.exists(), "release should not be installed");
+            });
+        }
+
+        fn test_download_archive(
+            config: UpConfigGithubRelease,
+            files: &[(&str, &[u8], u32)],
+        ) -> Result<PathBuf, UpError> {
+            let mut mock_server = mockito::Server::new();
+
+            let config = UpConfigGithubRelease {
+                repository: "owner/repo".to_string(),
+                api_url: Some(mock_server.url()),
+                ..config
+            };
+
+            let list_releases_body = format!(
+                r#"[{{
+                    "name": "Release v1.2.3",
+                    "tag_name": "v1.2.3",
+                    "draft": false,
+                    "prerelease": false,
+                    "assets": [
+                        {{
+                            "name": "tool_{arch}_{os}.tar.gz",
+                            "browser_download_url": "{url}/download/v1.2.3/tool.tar.gz"
+                        }}
+                    ]
+                }}]"#,
+                arch = current_arch(),
+                os = current_os(),
+                url = mock_server.url(),
+            );
+
+            let mock_list_releases = mock_server
+                .mock("GET", "/repos/owner/repo/releases")
+                .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+                .with_status(200)
+                .with_body(list_releases_body)
+                .create();
+
+            let mock_download = mock_server
+                .mock("GET", "/download/v1.2.3/tool.tar.gz")
+                .with_status(200)
+                .with_body(targz(files))
+                .create();
+
+            let options = UpOptions::default().cache_disabled();
+            let progress_handler = UpProgressHandler::new(None);
+
+            let result = config.up(&options, &progress_handler);
+
+            mock_list_releases.assert();
+            mock_download.assert();
+
+            result.map(|_| config.release_version_path("v1.2.3"))
+        }
+
+        const ARCHIVE_FILES: &[(&str, &[u8], u32)] = &[
+            ("tool-1.2.3/bin/tool-linux-amd64", b"tool", 0o755),
+            ("tool-1.2.3/bin/helper", b"helper", 0o755),
+            ("tool-1.2.3/scripts/install.sh", b"install", 0o755),
+            ("tool-1.2.3/completions/_tool", b"#compdef tool", 0o644),
+            ("tool-1.2.3/completions/tool.bash", b"complete", 0o644),
+            ("tool-1.2.3/man/tool.1", b".TH TOOL 1", 0o644),
+            ("tool-1.2.3/man/tool.conf.5.gz", b"gzipped", 0o644),
+        ];
+
+        #[test]
+        fn selected_binaries() {
+            run_with_env(&[], |_| {
+                let config = UpConfigGithubRelease {
+                    binaries: vec![
+                        GithubReleaseBinaryConfig {
+                            path: "*/bin/tool-*".to_string(),
+                            name: Some("tool".to_string()),
+                        },
+                        GithubReleaseBinaryConfig {
+                            path: "**/helper".to_string(),
+                            name: None,
+                        },
+                    ],
+                    completions: vec!["*/completions/_*".to_string()],
+                    man_pages: vec!["*/man/*".to_string()],
+                    ..UpConfigGithubRelease::default()
+                };
+
+                let install_path = test_download_archive(config, ARCHIVE_FILES).expect("up failed");
+
+                let mut installed = walkdir::WalkDir::new(&install_path)
+                    .into_iter()
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.file_type().is_file())
+                    .map(|entry| {
+                        entry
+                            .path()
+                            .strip_prefix(&install_path)
+                            .unwrap()
+                            .to_string_lossy()
+                            .to_string()
+                    })
+                    .collect::<Vec<_>>();
+                installed.sort();
+                assert_eq!(
+                    installed,
+                    vec![
+                        "helper",
+                        "share/man/man1/tool.1",
+                        "share/man/man5/tool.conf.5.gz",
+                        "share/zsh/site-functions/_tool",
+                        "tool",
+                    ]
+                );
+                assert_eq!(
+                    std::fs::read_to_string(install_path.join("tool")).unwrap(),
+                    "tool"
+                );
+            });
+        }
+
+        #[test]
+        fn selected_binaries_rename_multiple() {
+            run_with_env(&[], |_| {
+                let config = UpConfigGithubRelease {
+                    binaries: vec![GithubReleaseBinaryConfig {
+                        path: "*/bin/*".to_string(),
+                        name: Some("tool".to_string()),
+                    }],
+                    ..UpConfigGithubRelease::default()
+                };
+
+                let install_path = UpConfigGithubRelease {
+                    repository: "owner/repo".to_string(),
+                    ..config.clone()
+                }
+                .release_version_path("v1.2.3");
+
+                let result = test_download_archive(config, ARCHIVE_FILES);
+                assert_eq!(
+                    result,
+                    Err(UpError::Exec(
+                        "multiple files matching */bin/* found, cannot install them as tool"
+                            .to_string()
+                    ))
+                );
+                assert!(!install_path.exists());
+            });
+        }
+
+        #[test]
+        fn selected_binaries_do_not_share_install() {
+            run_with_env(&[], |_| {
+                let config = |name: &str| UpConfigGithubRelease {
+                    binaries: vec![GithubReleaseBinaryConfig {
+                        path: "*/bin/tool-*".to_string(),
+                        name: Some(name.to_string()),
+                    }],
+                    ..UpConfigGithubRelease::default()
+                };
+
+                // The same release installed with different binaries
+                // is installed in different directories
+                let tool_path =
+                    test_download_archive(config("tool"), ARCHIVE_FILES).expect("up failed");
+                let mytool_path =
+                    test_download_archive(config("mytool"), ARCHIVE_FILES).expect("up failed");
+                assert_ne!(tool_path, mytool_path);
+
+                for (path, name) in [(tool_path, "tool"), (mytool_path, "mytool")] {
+                    let installed = std::fs::read_dir(&path)
+                        .expect("failed to read install path")
+                        .filter_map(|entry| entry.ok())
+                        .map(|entry| entry.file_name().to_string_lossy().to_string())
+                        .collect::<Vec<_>>();
+                    assert_eq!(installed, vec![name]);
+                }
+            });
+        }
+
+        #[test]
+        fn selected_binaries_not_found() {
+            run_with_env(&[], |_| {
+                let config = UpConfigGithubRelease {
+                    binaries: vec![GithubReleaseBinaryConfig {
+                        path: "tool".to_string(),
+                        name: None,
+                    }],
+                    ..UpConfigGithubRelease::default()
+                };
+
+                let result = test_download_archive(config, ARCHIVE_FILES);
+                assert_eq!(
+                    result,
+                    Err(UpError::Exec("no file matching tool found".to_string()))
+                );
+            });
+        }
+
+        #[test]
+        fn selected_binaries_recursive_pattern() {
+            run_with_env(&[], |_| {
+                let config = UpConfigGithubRelease {
+                    binaries: vec![GithubReleaseBinaryConfig {
+                        path: "**/bin/*".to_string(),
+                        name: None,
+                    }],
+                    ..UpConfigGithubRelease::default()
+                };
+
+                let install_path = test_download_archive(config, ARCHIVE_FILES).expect("up failed");
+
+                let mut installed = std::fs::read_dir(&install_path)
+                    .expect("failed to read install path")
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .collect::<Vec<_>>();
+                installed.sort();
+                assert_eq!(installed, vec!["helper", "tool-linux-amd64"]);
+            });
+        }
+
+        #[test]
+        fn selected_binary_asset() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+
+                // The pattern is matched against the name of the asset
+                // as published, and not against its checksum file
+                let config = UpConfigGithubRelease {
+                    repository: "owner/repo".to_string(),
+                    api_url: Some(mock_server.url()),
+                    binaries: vec![GithubReleaseBinaryConfig {
+                        path: "**/tool_*".to_string(),
+                        name: None,
+                    }],
+                    ..UpConfigGithubRelease::default()
+                };
+
+                let asset_name = format!("tool_{}_{}", current_arch(), current_os());
+                let list_releases_body = format!(
+                    r#"[{{
+                        "name": "Release v1.2.3",
+                        "tag_name": "v1.2.3",
+                        "draft": false,
+                        "prerelease": false,
+                        "assets": [
+                            {{
+                                "name": "{asset}",
+                                "browser_download_url": "{url}/download/v1.2.3/tool"
+                            }},
+                            {{
+                                "name": "{asset}.sha256",
+                                "content_type": "text/plain",
+                                "browser_download_url": "{url}/download/v1.2.3/tool.sha256"
+                            }}
+                        ]
+                    }}]"#,
+                    asset = asset_name,
+                    url = mock_server.url(),
+                );
+
+                let mock_list_releases = mock_server
+                    .mock("GET", "/repos/owner/repo/releases")
+                    .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+                    .with_status(200)
+                    .with_body(list_releases_body)
+                    .create();
+
+                let mock_download = mock_server
+                    .mock("GET", "/download/v1.2.3/tool")
+                    .with_status(200)
+                    .with_body("tool")
+                    .create();
+
+                let digest = openssl::hash::hash(openssl::hash::MessageDigest::sha256(), b"tool")
+                    .expect("failed to hash");
+                let mock_download_checksum = mock_server
+                    .mock("GET", "/download/v1.2.3/tool.sha256")
+                    .with_status(200)
+                    .with_body(format!(
+                        "{}  {}\n",
+                        digest
+                            .iter()
+                            .map(|b| format!("{:02x}", b))
+                            .collect::<String>(),
+                        asset_name
+                    ))
+                    .create();
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = config.up(&options, &progress_handler);
+                assert_eq!(result, Ok(()));
+
+                mock_list_releases.assert();
+                mock_download.assert();
+                mock_download_checksum.assert();
+
+                // The binary is installed under its cleaned name
+                let install_path = config.release_version_path("v1.2.3");
+                let installed = std::fs::read_dir(&install_path)
+                    .expect("failed to read install path")
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .collect::<Vec<_>>();
+                assert_eq!(installed, vec!["tool"]);
+            });
+        }
+
+        fn stale_releases(url: &str, etag: Option<&str>) -> GithubReleases {
+            let mut releases =
+                GithubReleases::from_json(&format!("[{}]", release_json(url, "v1.2.3")))
+                    .expect("failed to parse releases");
+            releases.fetched_at = OffsetDateTime::now_utc() - time::Duration::days(30);
+            releases.etag = etag.map(|etag| etag.to_string());
+            releases
+        }
+
+        #[test]
+        fn not_modified_release_list() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+
+                let config = UpConfigGithubRelease {
+                    repository: "owner/repo".to_string(),
+                    api_url: Some(mock_server.url()),
+                    ..UpConfigGithubRelease::default()
+                };
+
+                let cached = stale_releases(&mock_server.url(), Some("\"abc\""));
+
+                let mock_list_releases = mock_server
+                    .mock("GET", "/repos/owner/repo/releases")
+                    .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+                    .match_header("if-none-match", "\"abc\"")
+                    .with_status(304)
+                    .create();
+
+                let options = UpOptions::default();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let releases = config
+                    .list_releases_from_api(&options, Some(&cached), &progress_handler)
+                    .expect("failed to list releases");
+
+                mock_list_releases.assert();
+                assert_eq!(releases.releases.len(), 1);
+                assert_eq!(releases.releases[0].tag_name, "v1.2.3");
+                assert_eq!(releases.etag, Some("\"abc\"".to_string()));
+                assert!(releases.fetched_at > cached.fetched_at);
+            });
+        }
+
+        #[test]
+        fn release_list_validators() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+
+                let config = UpConfigGithubRelease {
+                    repository: "owner/repo".to_string(),
+                    api_url: Some(mock_server.url()),
+                    ..UpConfigGithubRelease::default()
+                };
+
+                let mock_list_releases = mock_server
+                    .mock("GET", "/repos/owner/repo/releases")
+                    .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+                    .with_status(200)
+                    .with_header("etag", "\"def\"")
+                    .with_header("last-modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+                    .with_body(format!("[{}]", release_json(&mock_server.url(), "v1.2.4")))
+                    .create();
+
+                let options = UpOptions::default();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let releases = config
+                    .list_releases_from_api(&options, None, &progress_handler)
+                    .expect("failed to list releases");
+
+                mock_list_releases.assert();
+                assert_eq!(releases.etag, Some("\"def\"".to_string()));
+                assert_eq!(
+                    releases.last_modified,
+                    Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string())
+                );
+            });
+        }
+
+        #[test]
+        fn rate_limited_release_list() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+
+                let config = UpConfigGithubRelease {
+                    repository: "owner/repo".to_string(),
+                    api_url: Some(mock_server.url()),
+                    ..UpConfigGithubRelease::default()
+                };
+
+                let reset = OffsetDateTime::now_utc() + time::Duration::minutes(10);
+                let mock_list_releases = mock_server
+                    .mock("GET", "/repos/owner/repo/releases")
+                    .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+                    .with_status(403)
+                    .with_header("x-ratelimit-remaining", "0")
+                    .with_header("x-ratelimit-reset", &reset.unix_timestamp().to_string())
+                    .with_body(r#"{"message": "API rate limit exceeded"}"#)
+                    .expect(2)
+                    .create();
+
+                // The rate limit is not retried, even if retries are configured
+                let options = UpOptions::default().retry(RetryPolicy::new(3, None));
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = config.list_releases_from_api(&options, None, &progress_handler);
+                match result {
+                    Err(UpError::RateLimited(errmsg)) => {
+                        assert!(
+                            errmsg.starts_with("GitHub API rate limit exceeded, resets in"),
+                            "unexpected error message: {}",
+                            errmsg
+                        );
+                    }
+                    _ => panic!("expected rate limited error, got {:?}", result),
+                }
+
+                // With a stale cached release list, it is used instead
+                let cached = stale_releases(&mock_server.url(), None);
+                config
+                    .forge
+                    .add_releases(&config.repository, &cached)
+                    .expect("failed to cache releases");
+
+                let releases = config
+                    .list_releases(&options, &progress_handler)
+                    .expect("failed to list releases");
+                assert_eq!(releases.releases.len(), 1);
+                assert_eq!(releases.releases[0].tag_name, "v1.2.3");
+
+                mock_list_releases.assert();
+            });
+        }
+
+        fn current_arch() -> String {
+            compatible_release_arch()
+                .into_iter()
+                .next()
+                .expect("no compatible arch")
+        }
+
+        fn current_os() -> String {
+            compatible_release_os()
+                .into_iter()
+                .next()
+                .expect("no compatible os")
+        }
+
         #[derive(Default)]
         struct TestOptions {
             expected_version: Option<String>,
@@ -2176,7 +4736,7 @@ mod tests {
         }
 
         fn test_download_release(test: TestOptions, config: UpConfigGithubRelease) {
-            run_with_env(&[], || {
+            run_with_env(&[], |_| {
                 let mut mock_server = mockito::Server::new();
                 let api_url = mock_server.url();
 
@@ -2311,6 +4871,7 @@ mod tests {
 
                 let mock_list_releases = mock_server
                     .mock("GET", "/repos/owner/repo/releases")
+                    .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
                     .with_status(200)
                     .with_body(list_releases_body)
                     .create();
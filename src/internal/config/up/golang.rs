@@ -5,26 +5,36 @@ use std::io::BufReader;
 use std::path::Path;
 use std::path::PathBuf;
 
+use lazy_static::lazy_static;
 use normalize_path::NormalizePath;
 use once_cell::sync::OnceCell;
 use serde::Deserialize;
 use serde::Serialize;
+use tokio::process::Command as TokioCommand;
 
 use crate::internal::cache::utils as cache_utils;
 use crate::internal::cache::utils::CacheObject;
 use crate::internal::cache::UpEnvironmentsCache;
 use crate::internal::commands::utils::abs_path;
 use crate::internal::config::up::utils::data_path_dir_hash;
+use crate::internal::config::up::utils::run_progress;
 use crate::internal::config::up::utils::ProgressHandler;
+use crate::internal::config::up::utils::RunConfig;
 use crate::internal::config::up::utils::UpProgressHandler;
 use crate::internal::config::up::AsdfToolUpVersion;
 use crate::internal::config::up::UpConfigAsdfBase;
 use crate::internal::config::up::UpError;
 use crate::internal::config::up::UpOptions;
+use crate::internal::dynenv::update_dynamic_env_for_command;
 use crate::internal::env::current_dir;
 use crate::internal::workdir;
 use crate::internal::ConfigValue;
 
+lazy_static! {
+    static ref GOMOD_TOOL_DIRECTIVE: regex::Regex =
+        regex::Regex::new(r"^tool(?:\s+\S|\s*\()").unwrap();
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct UpConfigGolangSerialized {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -35,6 +45,8 @@ struct UpConfigGolangSerialized {
     upgrade: bool,
     #[serde(skip_serializing_if = "BTreeSet::is_empty")]
     dirs: BTreeSet<String>,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    tools: BTreeSet<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -43,8 +55,11 @@ pub struct UpConfigGolang {
     pub version_file: Option<String>,
     pub upgrade: bool,
     pub dirs: BTreeSet<String>,
+    pub tools: BTreeSet<String>,
     #[serde(skip)]
     pub asdf_base: OnceCell<UpConfigAsdfBase>,
+    #[serde(skip)]
+    config_value: Option<ConfigValue>,
 }
 
 impl Serialize for UpConfigGolang {
@@ -57,6 +72,7 @@ impl Serialize for UpConfigGolang {
             version_file: self.version_file.clone(),
             upgrade: self.upgrade,
             dirs: self.dirs.clone(),
+            tools: self.tools.clone(),
         };
 
         if serialized.version.is_none() && serialized.version_file.is_none() {
@@ -73,6 +89,7 @@ impl UpConfigGolang {
         let mut version_file = None;
         let mut dirs = BTreeSet::new();
         let mut upgrade = false;
+        let mut tools = BTreeSet::new();
 
         if let Some(config_value) = config_value {
             if let Some(value) = config_value.as_str() {
@@ -111,6 +128,8 @@ impl UpConfigGolang {
                 if let Some(value) = config_value.get_as_bool_forced("upgrade") {
                     upgrade = value;
                 }
+
+                tools = parse_tools(Some(config_value));
             }
         }
 
@@ -120,6 +139,8 @@ impl UpConfigGolang {
             version_file,
             upgrade,
             dirs,
+            tools,
+            config_value: config_value.cloned(),
         }
     }
 
@@ -135,6 +156,19 @@ impl UpConfigGolang {
         self.asdf_base()?.down(progress_handler)
     }
 
+    pub fn rollback(&self, progress_handler: &dyn ProgressHandler) {
+        if let Ok(asdf_base) = self.asdf_base() {
+            asdf_base.rollback(progress_handler);
+        }
+    }
+
+    /// The resources, as `(step, name)` pairs, for which the versions
+    /// of go are locked; the asdf configuration is only resolved when
+    /// upping the tool, but always uses the same tool
+    pub fn lock_keys(&self) -> Vec<(String, String)> {
+        vec![("golang".to_string(), "golang".to_string())]
+    }
+
     pub fn was_upped(&self) -> bool {
         self.asdf_base()
             .map_or(false, |asdf_base| asdf_base.was_upped())
@@ -157,8 +191,10 @@ impl UpConfigGolang {
 
             let mut asdf_base =
                 UpConfigAsdfBase::new("golang", version.as_ref(), self.dirs.clone(), self.upgrade);
+            asdf_base.set_config_value(self.config_value.clone());
             asdf_base.add_detect_version_func(detect_version_from_gomod);
             asdf_base.add_post_install_func(setup_individual_gopath);
+            asdf_base.add_post_install_func(install_go_tools);
 
             Ok(asdf_base)
         })
@@ -233,6 +269,7 @@ fn extract_version_from_gomod_file(
 
 fn setup_individual_gopath(
     progress_handler: &dyn ProgressHandler,
+    _options: &UpOptions,
     _config_value: Option<ConfigValue>,
     tool: String,
     tool_real_name: String,
@@ -298,3 +335,185 @@ fn setup_individual_gopath(
 
     Ok(())
 }
+
+fn parse_tools(config_value: Option<&ConfigValue>) -> BTreeSet<String> {
+    let mut tools = BTreeSet::new();
+
+    if let Some(config_value) = config_value {
+        if let Some(value) = config_value.get_as_str_forced("tools") {
+            tools.insert(value);
+        } else if let Some(array) = config_value.get_as_array("tools") {
+            for value in array {
+                if let Some(value) = value.as_str_forced() {
+                    tools.insert(value);
+                }
+            }
+        }
+    }
+
+    tools
+}
+
+/// Returns whether the go.mod file in the given directory declares
+/// tool dependencies through `tool` directives (go 1.24+).
+fn gomod_has_tool_directives(path: &Path) -> bool {
+    let contents = match std::fs::read_to_string(path.join("go.mod")) {
+        Ok(contents) => contents,
+        Err(_) => return false,
+    };
+
+    contents
+        .lines()
+        .any(|line| GOMOD_TOOL_DIRECTIVE.is_match(line.trim()))
+}
+
+fn install_go_tools(
+    progress_handler: &dyn ProgressHandler,
+    options: &UpOptions,
+    config_value: Option<ConfigValue>,
+    _tool: String,
+    _tool_real_name: String,
+    _requested_version: String,
+    versions: Vec<AsdfToolUpVersion>,
+) -> Result<(), UpError> {
+    let tools = parse_tools(config_value.as_ref());
+    let current_dir = current_dir();
+
+    for version in &versions {
+        for dir in &version.dirs {
+            let actual_dir = current_dir.join(dir);
+            let has_tool_directives = gomod_has_tool_directives(&actual_dir);
+
+            if tools.is_empty() && !has_tool_directives {
+                continue;
+            }
+
+            let mut packages = tools.iter().cloned().collect::<Vec<_>>();
+            if has_tool_directives {
+                // The 'tool' meta-pattern installs all the tools from go.mod
+                packages.push("tool".to_string());
+            }
+
+            if options.offline {
+                let err = UpError::Offline(format!("go tools ({})", packages.join(", ")));
+                progress_handler.error_with_message(err.message());
+                return Err(err);
+            }
+
+            // Load the environment for that directory, so that the tools
+            // get installed in the GOBIN of the isolated GOPATH
+            update_dynamic_env_for_command(actual_dir.to_string_lossy());
+
+            for package in packages {
+                progress_handler.progress(format!("installing go tool {}", package));
+
+                let mut go_install = TokioCommand::new("go");
+                go_install.arg("install");
+                go_install.arg(&package);
+                go_install.current_dir(&actual_dir);
+                go_install.stdout(std::process::Stdio::piped());
+                go_install.stderr(std::process::Stdio::piped());
+
+                if let Err(err) = run_progress(
+                    &mut go_install,
+                    Some(progress_handler),
+                    RunConfig::default(),
+                ) {
+                    let msg = format!("failed to install go tool {}: {}", package, err);
+                    progress_handler.error_with_message(msg.clone());
+                    return Err(UpError::Exec(msg));
+                }
+            }
+        }
+    }
+
+    // Load the environment for the current directory
+    update_dynamic_env_for_command(".");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::internal::config::up::utils::VoidProgressHandler;
+
+    #[test]
+    fn test_parse_tools() {
+        let config_value = ConfigValue::from_str(
+            "tools:\n  - golang.org/x/tools/cmd/stringer@v0.20.0\n  - go.uber.org/mock/mockgen@v0.4.0",
+        )
+        .expect("failed to create config value");
+        let config = UpConfigGolang::from_config_value(Some(&config_value));
+
+        assert_eq!(
+            config.tools.into_iter().collect::<Vec<_>>(),
+            vec![
+                "go.uber.org/mock/mockgen@v0.4.0".to_string(),
+                "golang.org/x/tools/cmd/stringer@v0.20.0".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_install_go_tools_offline() {
+        let config_value = ConfigValue::from_str("tools: golang.org/x/tools/cmd/stringer@v0.20.0")
+            .expect("failed to create config value");
+        let versions = vec![AsdfToolUpVersion {
+            version: "1.22.0".to_string(),
+            dirs: BTreeSet::from(["".to_string()]),
+            installed: false,
+        }];
+
+        let result = install_go_tools(
+            &VoidProgressHandler {},
+            &UpOptions::new().offline(true),
+            Some(config_value),
+            "golang".to_string(),
+            "golang".to_string(),
+            "latest".to_string(),
+            versions,
+        );
+        assert_eq!(
+            result,
+            Err(UpError::Offline(
+                "go tools (golang.org/x/tools/cmd/stringer@v0.20.0)".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_gomod_has_tool_directives() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        assert!(!gomod_has_tool_directives(dir.path()));
+
+        std::fs::write(
+            dir.path().join("go.mod"),
+            "module example.com/test\n\ngo 1.23\n\nrequire golang.org/x/tools v0.20.0\n",
+        )
+        .expect("failed to write go.mod");
+        assert!(!gomod_has_tool_directives(dir.path()));
+
+        std::fs::write(
+            dir.path().join("go.mod"),
+            "module example.com/test\n\ngo 1.22\n\ntoolchain go1.22.0\n",
+        )
+        .expect("failed to write go.mod");
+        assert!(!gomod_has_tool_directives(dir.path()));
+
+        std::fs::write(
+            dir.path().join("go.mod"),
+            "module example.com/test\n\ngo 1.24\n\ntool golang.org/x/tools/cmd/stringer\n",
+        )
+        .expect("failed to write go.mod");
+        assert!(gomod_has_tool_directives(dir.path()));
+
+        std::fs::write(
+            dir.path().join("go.mod"),
+            "module example.com/test\n\ngo 1.24\n\ntool (\n\tgolang.org/x/tools/cmd/stringer\n)\n",
+        )
+        .expect("failed to write go.mod");
+        assert!(gomod_has_tool_directives(dir.path()));
+    }
+}
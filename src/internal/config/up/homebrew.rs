@@ -49,6 +49,14 @@ impl UpConfigHomebrew {
         UpConfigHomebrew { install, tap }
     }
 
+    /// The names of the formulae and casks installed by the step
+    pub fn names(&self) -> Vec<String> {
+        self.install
+            .iter()
+            .map(|install| install.name.clone())
+            .collect()
+    }
+
     pub fn up(
         &self,
         options: &UpOptions,
@@ -90,6 +98,27 @@ impl UpConfigHomebrew {
         Ok(())
     }
 
+    /// Records the taps and formulae handled during the current run in
+    /// the operation cache, when it was deferred until the step was
+    /// verified
+    pub fn update_operation_cache(&self, progress_handler: &dyn ProgressHandler) {
+        for tap in self
+            .tap
+            .iter()
+            .filter(|tap| tap.handling() != HomebrewHandled::Unhandled)
+        {
+            tap.update_cache(progress_handler);
+        }
+
+        for install in self
+            .install
+            .iter()
+            .filter(|install| install.handling() != HomebrewHandled::Unhandled)
+        {
+            install.update_operation_cache(progress_handler);
+        }
+    }
+
     fn get_up_message(&self) -> String {
         let count_taps: HashMap<HomebrewHandled, usize> = self
             .tap
@@ -614,7 +643,9 @@ impl HomebrewTap {
                 if self.was_handled.set(was_handled).is_err() {
                     unreachable!("failed to set was_handled (install: {})", self.name);
                 }
-                self.update_cache(&progress_handler);
+                if !options.defer_operation_cache {
+                    self.update_cache(&progress_handler);
+                }
                 progress_handler.success_with_message(message);
                 Ok(())
             }
@@ -767,6 +798,14 @@ impl HomebrewTap {
         progress_handler: &UpProgressHandler,
         is_tapped: bool,
     ) -> Result<bool, UpError> {
+        if options.offline {
+            if is_tapped {
+                progress_handler.progress("already tapped (offline)".light_black());
+                return Ok(false);
+            }
+            return Err(UpError::Offline(format!("{} tap", self.name)));
+        }
+
         let result = if is_tapped {
             self.update_tap(options, progress_handler)
         } else {
@@ -781,7 +820,11 @@ impl HomebrewTap {
             brew_tap.stdout(std::process::Stdio::piped());
             brew_tap.stderr(std::process::Stdio::piped());
 
-            match run_progress(&mut brew_tap, Some(progress_handler), RunConfig::default()) {
+            match run_progress(
+                &mut brew_tap,
+                Some(progress_handler),
+                RunConfig::default().with_retry(&options.retry),
+            ) {
                 Ok(_) => Ok(true),
                 Err(err) => Err(err),
             }
@@ -1006,16 +1049,7 @@ impl HomebrewInstall {
 
         progress_handler.progress("updating cache".to_string());
 
-        if let Err(err) = HomebrewOperationCache::exclusive(|brew_cache| {
-            brew_cache.add_install(
-                &workdir_id,
-                &self.name,
-                self.version.clone(),
-                self.is_cask(),
-                self.was_handled(),
-            )
-        }) {
-            progress_handler.progress(format!("failed to update cache: {}", err));
+        if !options.defer_operation_cache && !self.update_operation_cache(progress_handler) {
             return;
         }
 
@@ -1039,6 +1073,30 @@ impl HomebrewInstall {
         progress_handler.progress("updated cache".to_string());
     }
 
+    /// Records the formula in the operation cache as required by the
+    /// work directory; returns whether the cache was updated
+    fn update_operation_cache(&self, progress_handler: &dyn ProgressHandler) -> bool {
+        let workdir_id = match workdir(".").id() {
+            Some(wd_id) => wd_id,
+            None => return false,
+        };
+
+        if let Err(err) = HomebrewOperationCache::exclusive(|brew_cache| {
+            brew_cache.add_install(
+                &workdir_id,
+                &self.name,
+                self.version.clone(),
+                self.is_cask(),
+                self.was_handled(),
+            )
+        }) {
+            progress_handler.progress(format!("failed to update cache: {}", err));
+            return false;
+        }
+
+        true
+    }
+
     fn up(&self, options: &UpOptions, progress_handler: &UpProgressHandler) -> Result<(), UpError> {
         let progress_handler = progress_handler.subhandler(
             &format!(
@@ -1450,6 +1508,16 @@ impl HomebrewInstall {
         progress_handler: Option<&dyn ProgressHandler>,
         installed: bool,
     ) -> Result<bool, UpError> {
+        if options.offline {
+            if installed {
+                if let Some(progress_handler) = progress_handler {
+                    progress_handler.progress("already installed (offline)".light_black())
+                }
+                return Ok(false);
+            }
+            return Err(UpError::Offline(self.package_id()));
+        }
+
         if !installed {
             self.extract_package(options, progress_handler)?;
         } else if !self.upgrade_install(options) {
@@ -1472,7 +1540,7 @@ impl HomebrewInstall {
             return Ok(false);
         }
 
-        let mut run_config = RunConfig::default();
+        let mut run_config = RunConfig::default().with_retry(&options.retry);
 
         let mut brew_install = TokioCommand::new("brew");
         if installed {
@@ -1639,7 +1707,11 @@ impl HomebrewInstall {
                 brew_update.stdout(std::process::Stdio::piped());
                 brew_update.stderr(std::process::Stdio::piped());
 
-                let result = run_progress(&mut brew_update, progress_handler, RunConfig::default());
+                let result = run_progress(
+                    &mut brew_update,
+                    progress_handler,
+                    RunConfig::default().with_retry(&options.retry),
+                );
                 if result.is_err() {
                     return false;
                 }
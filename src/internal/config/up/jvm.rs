@@ -0,0 +1,279 @@
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::internal::config::up::UpConfigAsdfBase;
+use crate::internal::config::up::UpConfigAsdfBaseParams;
+use crate::internal::config::ConfigValue;
+
+/// The vendor used for java versions that do not specify one
+const JAVA_DEFAULT_VENDOR: &str = "temurin";
+
+lazy_static! {
+    static ref POM_JAVA_VERSION_REGEX: Regex = Regex::new(
+        r"<(?:maven\.compiler\.release|maven\.compiler\.target|maven\.compiler\.source|java\.version|release)>\s*(?P<version>[0-9][0-9.]*)\s*</"
+    )
+    .unwrap();
+    static ref POM_KOTLIN_VERSION_REGEX: Regex =
+        Regex::new(r"<kotlin\.version>\s*(?P<version>[0-9][0-9A-Za-z.\-]*)\s*</").unwrap();
+    static ref GRADLE_JAVA_VERSION_REGEX: Regex = Regex::new(
+        r#"(?:JavaLanguageVersion\.of\(\s*"?|jvmToolchain\(\s*|JavaVersion\.VERSION_)(?P<version>[0-9]+(?:_[0-9]+)?)"#
+    )
+    .unwrap();
+    static ref GRADLE_KOTLIN_VERSION_REGEX: Regex = Regex::new(
+        r#"(?:kotlin\(\s*"[a-z.\-]+"\s*\)|id\s*\(?\s*["']org\.jetbrains\.kotlin\.[a-z.\-]+["']\s*\)?)\s*version\s*["'](?P<version>[0-9][0-9A-Za-z.\-]*)["']"#
+    )
+    .unwrap();
+}
+
+/// Creates the asdf configuration for the java tool; versions of java
+/// are listed by the asdf plugin with a vendor prefix, which defaults
+/// to temurin when the requested version does not specify one.
+pub fn java_from_config_value(config_value: Option<&ConfigValue>) -> UpConfigAsdfBase {
+    let mut asdf_base = UpConfigAsdfBase::from_config_value_with_params(
+        "java",
+        config_value,
+        UpConfigAsdfBaseParams {
+            version_prefix: Some(format!("{}-", JAVA_DEFAULT_VENDOR)),
+            ..UpConfigAsdfBaseParams::default()
+        },
+    );
+    asdf_base.add_detect_version_func(detect_java_version_from_sdkmanrc);
+    asdf_base.add_detect_version_func(detect_java_version_from_pom);
+    asdf_base.add_detect_version_func(detect_java_version_from_gradle);
+    asdf_base
+}
+
+/// Creates the asdf configuration for the kotlin tool.
+pub fn kotlin_from_config_value(config_value: Option<&ConfigValue>) -> UpConfigAsdfBase {
+    let mut asdf_base = UpConfigAsdfBase::from_config_value("kotlin", config_value);
+    asdf_base.add_detect_version_func(detect_kotlin_version_from_sdkmanrc);
+    asdf_base.add_detect_version_func(detect_kotlin_version_from_pom);
+    asdf_base.add_detect_version_func(detect_kotlin_version_from_gradle);
+    asdf_base
+}
+
+fn read_file(path: PathBuf) -> Option<String> {
+    if !path.exists() || path.is_dir() {
+        return None;
+    }
+
+    std::fs::read_to_string(path).ok()
+}
+
+/// Reads the version of a candidate from a `.sdkmanrc` file, which
+/// contains lines in the `candidate=version` format.
+fn sdkmanrc_version(path: PathBuf, candidate: &str) -> Option<String> {
+    let contents = read_file(path.join(".sdkmanrc"))?;
+
+    contents.lines().find_map(|line| {
+        let line = line.trim();
+        if line.starts_with('#') {
+            return None;
+        }
+
+        match line.split_once('=') {
+            Some((key, value)) if key.trim() == candidate && !value.trim().is_empty() => {
+                Some(value.trim().to_string())
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Converts a java version identifier from sdkman (`<version>-<vendor>`)
+/// to the format used by the asdf plugin (`<vendor>-<version>`); if the
+/// vendor is not known, only the version is kept so that the default
+/// vendor gets used.
+fn sdkman_to_asdf_java_version(version: &str) -> String {
+    let (version, vendor) = match version.rsplit_once('-') {
+        Some((version, vendor)) => (version, vendor),
+        None => return version.to_string(),
+    };
+
+    let vendor = match vendor {
+        "tem" => "temurin",
+        "open" => "openjdk",
+        "amzn" => "corretto",
+        "librca" => "liberica",
+        "graalce" => "graalvm-community",
+        "ms" => "microsoft",
+        "sapmchn" => "sapmachine",
+        "oracle" => "oracle",
+        _ => return version.to_string(),
+    };
+
+    format!("{}-{}", vendor, version)
+}
+
+/// Normalizes a java release number, as java versions before 9
+/// were using the `1.x` format (e.g. `1.8` is java 8).
+fn normalize_java_release(version: &str) -> String {
+    let version = version.replace('_', ".");
+    match version.strip_prefix("1.") {
+        Some(release) => release.to_string(),
+        None => version,
+    }
+}
+
+fn detect_java_version_from_sdkmanrc(_tool_name: String, path: PathBuf) -> Option<String> {
+    sdkmanrc_version(path, "java").map(|version| sdkman_to_asdf_java_version(&version))
+}
+
+fn detect_java_version_from_pom(_tool_name: String, path: PathBuf) -> Option<String> {
+    let contents = read_file(path.join("pom.xml"))?;
+    let captures = POM_JAVA_VERSION_REGEX.captures(&contents)?;
+    Some(normalize_java_release(&captures["version"]))
+}
+
+fn detect_java_version_from_gradle(_tool_name: String, path: PathBuf) -> Option<String> {
+    ["build.gradle", "build.gradle.kts"]
+        .iter()
+        .find_map(|file| {
+            let contents = read_file(path.join(file))?;
+            let captures = GRADLE_JAVA_VERSION_REGEX.captures(&contents)?;
+            Some(normalize_java_release(&captures["version"]))
+        })
+}
+
+fn detect_kotlin_version_from_sdkmanrc(_tool_name: String, path: PathBuf) -> Option<String> {
+    sdkmanrc_version(path, "kotlin")
+}
+
+fn detect_kotlin_version_from_pom(_tool_name: String, path: PathBuf) -> Option<String> {
+    let contents = read_file(path.join("pom.xml"))?;
+    let captures = POM_KOTLIN_VERSION_REGEX.captures(&contents)?;
+    Some(captures["version"].to_string())
+}
+
+fn detect_kotlin_version_from_gradle(_tool_name: String, path: PathBuf) -> Option<String> {
+    ["build.gradle", "build.gradle.kts"]
+        .iter()
+        .find_map(|file| {
+            let contents = read_file(path.join(file))?;
+            let captures = GRADLE_KOTLIN_VERSION_REGEX.captures(&contents)?;
+            Some(captures["version"].to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_file(dir: &tempfile::TempDir, name: &str, contents: &str) -> PathBuf {
+        std::fs::write(dir.path().join(name), contents).expect("failed to write file");
+        dir.path().to_path_buf()
+    }
+
+    #[test]
+    fn test_sdkman_to_asdf_java_version() {
+        assert_eq!(sdkman_to_asdf_java_version("21.0.2-tem"), "temurin-21.0.2");
+        assert_eq!(
+            sdkman_to_asdf_java_version("17.0.10-amzn"),
+            "corretto-17.0.10"
+        );
+        assert_eq!(sdkman_to_asdf_java_version("21.0.2-unknown"), "21.0.2");
+        assert_eq!(sdkman_to_asdf_java_version("21"), "21");
+    }
+
+    #[test]
+    fn test_detect_versions_from_sdkmanrc() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = write_file(
+            &dir,
+            ".sdkmanrc",
+            "# Enable auto-env\njava=21.0.2-tem\nkotlin=1.9.22\n",
+        );
+
+        assert_eq!(
+            detect_java_version_from_sdkmanrc("java".to_string(), path.clone()),
+            Some("temurin-21.0.2".to_string())
+        );
+        assert_eq!(
+            detect_kotlin_version_from_sdkmanrc("kotlin".to_string(), path),
+            Some("1.9.22".to_string())
+        );
+    }
+
+    #[test]
+    fn test_detect_versions_from_pom() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = write_file(
+            &dir,
+            "pom.xml",
+            r#"<project>
+              <properties>
+                <maven.compiler.release>17</maven.compiler.release>
+                <kotlin.version>1.9.22</kotlin.version>
+              </properties>
+            </project>"#,
+        );
+
+        assert_eq!(
+            detect_java_version_from_pom("java".to_string(), path.clone()),
+            Some("17".to_string())
+        );
+        assert_eq!(
+            detect_kotlin_version_from_pom("kotlin".to_string(), path),
+            Some("1.9.22".to_string())
+        );
+    }
+
+    #[test]
+    fn test_detect_java_version_from_pom_legacy_format() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = write_file(&dir, "pom.xml", "<java.version>1.8</java.version>");
+
+        assert_eq!(
+            detect_java_version_from_pom("java".to_string(), path),
+            Some("8".to_string())
+        );
+    }
+
+    #[test]
+    fn test_detect_versions_from_gradle() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = write_file(
+            &dir,
+            "build.gradle.kts",
+            r#"plugins {
+                kotlin("jvm") version "1.9.22"
+            }
+
+            java {
+                toolchain {
+                    languageVersion.set(JavaLanguageVersion.of(21))
+                }
+            }"#,
+        );
+
+        assert_eq!(
+            detect_java_version_from_gradle("java".to_string(), path.clone()),
+            Some("21".to_string())
+        );
+        assert_eq!(
+            detect_kotlin_version_from_gradle("kotlin".to_string(), path),
+            Some("1.9.22".to_string())
+        );
+    }
+
+    #[test]
+    fn test_detect_java_version_from_gradle_groovy() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = write_file(
+            &dir,
+            "build.gradle",
+            "id 'org.jetbrains.kotlin.jvm' version '2.0.0'\nsourceCompatibility = JavaVersion.VERSION_1_8\n",
+        );
+
+        assert_eq!(
+            detect_java_version_from_gradle("java".to_string(), path.clone()),
+            Some("8".to_string())
+        );
+        assert_eq!(
+            detect_kotlin_version_from_gradle("kotlin".to_string(), path),
+            Some("2.0.0".to_string())
+        );
+    }
+}
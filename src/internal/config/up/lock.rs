@@ -0,0 +1,389 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::internal::config::up::UpError;
+
+const UP_LOCK_FILE: &str = ".omni/up.lock";
+
+const UP_LOCK_HEADER: &str = concat!(
+    "# This file is generated by `omni up` and records the exact versions\n",
+    "# installed for this work directory; commit it so that everyone gets\n",
+    "# the same versions, and run `omni up --upgrade` to update it.\n",
+);
+
+/// The lock of the versions resolved by `omni up` for a work directory,
+/// stored in the `.omni/up.lock` file of the work directory.
+///
+/// The entries read from the file are used to install exactly the same
+/// versions on later runs, while the entries resolved during the current
+/// run are recorded so that the file can be written once the run succeeds.
+/// The resources declared in the configuration are tracked separately
+/// from the resolved ones, so that the entries of the resources that are
+/// not declared anymore can be removed from the file.
+#[derive(Debug)]
+pub struct UpLock {
+    path: PathBuf,
+    locked: Vec<UpLockEntry>,
+    resolved: Mutex<Vec<UpLockEntry>>,
+    declared: Mutex<Option<Vec<(String, String)>>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct UpLockFile {
+    #[serde(default)]
+    locked: Vec<UpLockEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UpLockEntry {
+    /// The type of step that resolved the version (e.g. `github-release`,
+    /// `cargo-install`, or the name of the tool for asdf-based steps)
+    pub step: String,
+    /// The name of the resource the version was resolved for
+    pub name: String,
+    /// The directory, relative to the work directory root, in which the
+    /// version is used; empty for the root of the work directory
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub dir: String,
+    /// The exact version that was resolved
+    pub version: String,
+    /// The assets that were downloaded to install the version, if any
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assets: Vec<UpLockAsset>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UpLockAsset {
+    pub url: String,
+    /// The checksum of the asset, as `<algorithm>:<value>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+}
+
+impl UpLockEntry {
+    pub fn new(step: &str, name: &str, dir: &str, version: &str) -> Self {
+        Self {
+            step: step.to_string(),
+            name: name.to_string(),
+            dir: dir.to_string(),
+            version: version.to_string(),
+            assets: vec![],
+        }
+    }
+
+    pub fn with_asset(mut self, url: &str, checksum: Option<String>) -> Self {
+        self.assets.push(UpLockAsset {
+            url: url.to_string(),
+            checksum,
+        });
+        self
+    }
+
+    fn same_key(&self, other: &UpLockEntry) -> bool {
+        self.step == other.step && self.name == other.name && self.dir == other.dir
+    }
+
+    /// Returns the checksum recorded for the asset with the given url
+    pub fn asset_checksum(&self, url: &str) -> Option<&str> {
+        self.assets
+            .iter()
+            .find(|asset| asset.url == url)
+            .and_then(|asset| asset.checksum.as_deref())
+    }
+}
+
+impl UpLock {
+    /// Loads the lock of the work directory at the given root; if the
+    /// work directory does not have a lock file yet, the lock is empty.
+    pub fn for_workdir(wd_root: &str) -> Result<Self, UpError> {
+        Self::load(&PathBuf::from(wd_root).join(UP_LOCK_FILE))
+    }
+
+    /// Returns a lock without any locked entry, that is not
+    /// associated with any lock file
+    pub fn empty() -> Self {
+        Self {
+            path: PathBuf::new(),
+            locked: vec![],
+            resolved: Mutex::new(vec![]),
+            declared: Mutex::new(None),
+        }
+    }
+
+    fn load(path: &Path) -> Result<Self, UpError> {
+        let lock_file = if path.exists() {
+            let contents = std::fs::read_to_string(path).map_err(|err| {
+                UpError::Exec(format!("failed to read {}: {}", path.display(), err))
+            })?;
+            serde_yaml::from_str::<UpLockFile>(&contents).map_err(|err| {
+                UpError::Config(format!("failed to parse {}: {}", path.display(), err))
+            })?
+        } else {
+            UpLockFile::default()
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            locked: lock_file.locked,
+            resolved: Mutex::new(vec![]),
+            declared: Mutex::new(None),
+        })
+    }
+
+    /// Returns the locked entry for the given step, resource and directory
+    pub fn get(&self, step: &str, name: &str, dir: &str) -> Option<&UpLockEntry> {
+        self.locked
+            .iter()
+            .find(|entry| entry.step == step && entry.name == name && entry.dir == dir)
+    }
+
+    /// Records an entry resolved during the current run, replacing any
+    /// entry previously recorded for the same step, resource and directory
+    pub fn record(&self, entry: UpLockEntry) {
+        let mut resolved = self
+            .resolved
+            .lock()
+            .expect("failed to lock resolved entries");
+        resolved.retain(|resolved| !resolved.same_key(&entry));
+        resolved.push(entry);
+    }
+
+    /// Declares the resources, as `(step, name)` pairs, of all the steps
+    /// of the configuration, whether they are run or not; this should
+    /// only be done when all the steps are selected, since the locked
+    /// entries of the resources that are not declared are then removed
+    pub fn declare(&self, declared: Vec<(String, String)>) {
+        *self
+            .declared
+            .lock()
+            .expect("failed to lock declared resources") = Some(declared);
+    }
+
+    /// Computes the entries that the lock file should contain after
+    /// the current run.
+    ///
+    /// Locked entries that were not resolved during the run, e.g. for
+    /// steps that were not selected or whose condition was false, are
+    /// kept, unless the resources of the configuration were declared
+    /// and they are not part of them anymore. When upgrading, the entries resolved during the run
+    /// replace the locked ones. Otherwise, locked entries are kept as
+    /// they are unless the version resolved for them changed (e.g.
+    /// because the configuration now requires a version that does not
+    /// match the locked one). Entries for new resources are added.
+    fn entries(&self, upgrade: bool) -> Vec<UpLockEntry> {
+        let resolved = self
+            .resolved
+            .lock()
+            .expect("failed to lock resolved entries");
+
+        let declared = self
+            .declared
+            .lock()
+            .expect("failed to lock declared resources");
+        let is_declared = |entry: &UpLockEntry| match declared.as_ref() {
+            Some(declared) => declared
+                .iter()
+                .any(|(step, name)| step == &entry.step && name == &entry.name),
+            None => true,
+        };
+
+        let mut entries = self
+            .locked
+            .iter()
+            .filter(|locked| {
+                is_declared(locked) || resolved.iter().any(|resolved| resolved.same_key(locked))
+            })
+            .map(
+                |locked| match resolved.iter().find(|resolved| resolved.same_key(locked)) {
+                    Some(resolved)
+                        if upgrade
+                            || resolved.version != locked.version
+                            || (locked.assets.is_empty() && !resolved.assets.is_empty()) =>
+                    {
+                        resolved.clone()
+                    }
+                    _ => locked.clone(),
+                },
+            )
+            .collect::<Vec<_>>();
+
+        entries.extend(
+            resolved
+                .iter()
+                .filter(|resolved| !self.locked.iter().any(|locked| locked.same_key(resolved)))
+                .cloned(),
+        );
+
+        entries.sort_by(|a, b| (&a.step, &a.name, &a.dir).cmp(&(&b.step, &b.name, &b.dir)));
+        entries
+    }
+
+    /// Writes the lock file if its content changed; returns whether
+    /// the file was written.
+    pub fn save(&self, upgrade: bool) -> Result<bool, UpError> {
+        let entries = self.entries(upgrade);
+        if entries == self.locked && (self.path.exists() || entries.is_empty()) {
+            return Ok(false);
+        }
+
+        let contents = serde_yaml::to_string(&UpLockFile { locked: entries })
+            .map_err(|err| UpError::Exec(format!("failed to serialize lock: {}", err)))?;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| {
+                UpError::Exec(format!("failed to create {}: {}", parent.display(), err))
+            })?;
+        }
+
+        std::fs::write(&self.path, format!("{}{}", UP_LOCK_HEADER, contents)).map_err(|err| {
+            UpError::Exec(format!("failed to write {}: {}", self.path.display(), err))
+        })?;
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock_with(entries: Vec<UpLockEntry>) -> (tempfile::TempDir, UpLock) {
+        let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let lock = UpLock::for_workdir(tmp_dir.path().to_str().unwrap()).unwrap();
+        for entry in entries {
+            lock.record(entry);
+        }
+        lock.save(true).unwrap();
+
+        let lock = UpLock::for_workdir(tmp_dir.path().to_str().unwrap()).unwrap();
+        (tmp_dir, lock)
+    }
+
+    #[test]
+    fn roundtrip() {
+        let entry = UpLockEntry::new("github-release", "owner/repo", "", "1.2.3")
+            .with_asset("https://example.com/asset", Some("sha256:abc".to_string()));
+        let (_tmp_dir, lock) = lock_with(vec![
+            entry.clone(),
+            UpLockEntry::new("python", "python", "sub", "3.12.1"),
+        ]);
+
+        assert_eq!(lock.get("github-release", "owner/repo", ""), Some(&entry));
+        assert_eq!(
+            entry.asset_checksum("https://example.com/asset"),
+            Some("sha256:abc")
+        );
+        assert_eq!(
+            lock.get("python", "python", "sub")
+                .map(|e| e.version.as_str()),
+            Some("3.12.1")
+        );
+        assert_eq!(lock.get("python", "python", ""), None);
+    }
+
+    #[test]
+    fn keeps_locked_entries_without_upgrade() {
+        let (_tmp_dir, lock) = lock_with(vec![
+            UpLockEntry::new("cargo-install", "ripgrep", "", "14.0.0"),
+            UpLockEntry::new("cargo-install", "removed", "", "1.0.0"),
+        ]);
+
+        lock.record(UpLockEntry::new("cargo-install", "ripgrep", "", "14.0.0"));
+        lock.record(UpLockEntry::new("cargo-install", "fd-find", "", "9.0.0"));
+        assert!(lock.save(false).unwrap());
+
+        let names = lock
+            .entries(false)
+            .into_iter()
+            .map(|e| format!("{}@{}", e.name, e.version))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["fd-find@9.0.0", "removed@1.0.0", "ripgrep@14.0.0"]
+        );
+    }
+
+    #[test]
+    fn does_not_write_when_unchanged() {
+        let (_tmp_dir, lock) = lock_with(vec![UpLockEntry::new(
+            "cargo-install",
+            "ripgrep",
+            "",
+            "14.0.0",
+        )]);
+
+        lock.record(UpLockEntry::new("cargo-install", "ripgrep", "", "14.0.0"));
+        assert!(!lock.save(false).unwrap());
+    }
+
+    #[test]
+    fn rewrites_on_upgrade() {
+        let (_tmp_dir, lock) = lock_with(vec![
+            UpLockEntry::new("cargo-install", "ripgrep", "", "14.0.0"),
+            UpLockEntry::new("cargo-install", "removed", "", "1.0.0"),
+        ]);
+
+        lock.record(UpLockEntry::new("cargo-install", "ripgrep", "", "14.1.0"));
+
+        let entries = lock.entries(true);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "removed");
+        assert_eq!(entries[1].version, "14.1.0");
+    }
+
+    #[test]
+    fn removes_undeclared_entries() {
+        let (_tmp_dir, lock) = lock_with(vec![
+            UpLockEntry::new("cargo-install", "ripgrep", "", "14.0.0"),
+            UpLockEntry::new("cargo-install", "removed", "", "1.0.0"),
+            UpLockEntry::new("python", "python", "sub", "3.12.1"),
+        ]);
+
+        // The python step is declared but was not run, e.g. because
+        // its condition was false
+        lock.declare(vec![
+            ("cargo-install".to_string(), "ripgrep".to_string()),
+            ("python".to_string(), "python".to_string()),
+        ]);
+        lock.record(UpLockEntry::new("cargo-install", "ripgrep", "", "14.0.0"));
+        assert!(lock.save(false).unwrap());
+
+        let names = lock
+            .entries(false)
+            .into_iter()
+            .map(|e| format!("{}@{}", e.name, e.version))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["ripgrep@14.0.0", "python@3.12.1"]);
+    }
+
+    #[test]
+    fn partial_upgrade_keeps_unresolved_entries() {
+        let entry = UpLockEntry::new("github-release", "owner/repo", "", "1.2.3")
+            .with_asset("https://example.com/asset", Some("sha256:abc".to_string()));
+        let (tmp_dir, lock) = lock_with(vec![
+            UpLockEntry::new("cargo-install", "ripgrep", "", "14.0.0"),
+            UpLockEntry::new("python", "python", "", "3.12.1"),
+            entry.clone(),
+        ]);
+
+        // Only the cargo-install step was run, e.g. with `--only`
+        lock.record(UpLockEntry::new("cargo-install", "ripgrep", "", "14.1.0"));
+        assert!(lock.save(true).unwrap());
+
+        let lock = UpLock::for_workdir(tmp_dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(
+            lock.get("cargo-install", "ripgrep", "")
+                .map(|e| e.version.as_str()),
+            Some("14.1.0")
+        );
+        assert_eq!(
+            lock.get("python", "python", "").map(|e| e.version.as_str()),
+            Some("3.12.1")
+        );
+        assert_eq!(lock.get("github-release", "owner/repo", ""), Some(&entry));
+    }
+}
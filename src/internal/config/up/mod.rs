@@ -1,24 +1,50 @@
 pub(crate) mod base;
 pub(crate) use base::UpConfig;
 
+pub(crate) mod lock;
+pub(crate) use lock::UpLock;
+
 pub(crate) mod options;
 pub(crate) use options::UpOptions;
 
+pub(crate) mod plan;
+pub(crate) use plan::UpPlan;
+
+pub(crate) mod retry;
+pub(crate) use retry::UpConfigRetry;
+
+pub(crate) mod selection;
+pub(crate) use selection::UpStepSelection;
+
 pub(crate) mod tool;
 pub(crate) use tool::UpConfigTool;
 
+pub(crate) mod tool_registry;
+pub(crate) use tool_registry::ToolRegistry;
+
+pub(crate) mod verify;
+pub(crate) use verify::UpConfigVerify;
+
 pub(crate) mod bundler;
 pub(crate) use bundler::UpConfigBundler;
 
+pub(crate) mod cargo_install;
+pub(crate) use cargo_install::UpConfigCargoInstalls;
+
 pub(crate) mod custom;
 pub(crate) use custom::UpConfigCustom;
 
+pub(crate) mod download;
+pub(crate) use download::UpConfigDownloads;
+
 pub(crate) mod github_release;
 pub(crate) use github_release::UpConfigGithubReleases;
 
 pub(crate) mod golang;
 pub(crate) use golang::UpConfigGolang;
 
+pub(crate) mod jvm;
+
 pub(crate) mod nix;
 pub(crate) use nix::UpConfigNix;
 
@@ -28,6 +54,13 @@ pub(crate) use nodejs::UpConfigNodejs;
 pub(crate) mod python;
 pub(crate) use python::UpConfigPython;
 
+pub(crate) mod system_package;
+pub(crate) use system_package::SystemPackageManager;
+pub(crate) use system_package::UpConfigSystemPackages;
+
+pub(crate) mod rust;
+pub(crate) use rust::UpConfigRust;
+
 pub(crate) mod homebrew;
 pub(crate) use homebrew::UpConfigHomebrew;
 
@@ -173,7 +173,7 @@ impl UpConfigNix {
             return Ok(());
         }
 
-        let paths = nix_handler.build(progress_handler)?;
+        let paths = nix_handler.build(options, progress_handler)?;
         if self.data_paths.set(paths).is_err() {
             omni_warning!("failed to save nix profile path".to_string());
         }
@@ -334,7 +334,11 @@ impl NixHandler {
             .exists())
     }
 
-    fn build(&self, progress_handler: &dyn ProgressHandler) -> Result<Vec<PathBuf>, UpError> {
+    fn build(
+        &self,
+        options: &UpOptions,
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<Vec<PathBuf>, UpError> {
         // Generate a temporary file to store the nix profile, we want a directory as
         // a second file will be generated in the same directory as our temp profile,
         // and we want to remove both of them once we're done. Using a temporary
@@ -352,7 +356,7 @@ impl NixHandler {
 
         // Prepare the nix profile
         self.nix_source
-            .print_dev_env(&tmp_profile, progress_handler)?;
+            .print_dev_env(&tmp_profile, options, progress_handler)?;
 
         // Build and return the built paths
         self.nix_source
@@ -566,6 +570,7 @@ impl NixSource {
     fn print_dev_env(
         &self,
         tmp_profile: &PathBuf,
+        options: &UpOptions,
         progress_handler: &dyn ProgressHandler,
     ) -> Result<(), UpError> {
         progress_handler.progress("preparing nix environment".to_string());
@@ -575,6 +580,9 @@ impl NixSource {
         nix_print_dev_env.arg("--print-build-logs");
         nix_print_dev_env.arg("--profile");
         nix_print_dev_env.arg(tmp_profile);
+        if options.offline {
+            nix_print_dev_env.arg("--offline");
+        }
 
         match *self {
             Self::Packages(ref packages) => {
@@ -609,7 +617,7 @@ impl NixSource {
         let result = run_progress(
             &mut nix_print_dev_env,
             Some(progress_handler),
-            RunConfig::default(),
+            RunConfig::default().with_retry(&options.retry),
         );
 
         if let Err(e) = result {
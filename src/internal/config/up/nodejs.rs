@@ -122,6 +122,14 @@ impl UpConfigNodejs {
     pub fn down(&self, progress_handler: &UpProgressHandler) -> Result<(), UpError> {
         self.asdf_base.down(progress_handler)
     }
+
+    pub fn rollback(&self, progress_handler: &dyn ProgressHandler) {
+        self.asdf_base.rollback(progress_handler)
+    }
+
+    pub fn lock_keys(&self) -> Vec<(String, String)> {
+        self.asdf_base.lock_keys()
+    }
 }
 
 fn detect_version_from_package_json(_tool_name: String, path: PathBuf) -> Option<String> {
@@ -181,6 +189,7 @@ fn detect_version_from_nvmrc(_tool_name: String, path: PathBuf) -> Option<String
 
 fn setup_individual_npm_prefix(
     progress_handler: &dyn ProgressHandler,
+    options: &UpOptions,
     config_value: Option<ConfigValue>,
     tool: String,
     tool_real_name: String,
@@ -280,6 +289,16 @@ fn setup_individual_npm_prefix(
                 continue;
             }
 
+            // Installing the engines and packages requires the network
+            if options.offline {
+                let err = UpError::Offline(format!(
+                    "engines and packages of {}",
+                    package_json_path.display()
+                ));
+                progress_handler.error_with_message(err.message());
+                return Err(err);
+            }
+
             let package_json_str = match std::fs::read_to_string(&package_json_path) {
                 Ok(package_json_str) => package_json_str,
                 Err(err) => {
@@ -305,6 +324,15 @@ fn setup_individual_npm_prefix(
             // Load the environment for that directory
             update_dynamic_env_for_command(actual_dir.to_str().unwrap());
 
+            // The package manager pinned in the package.json file, if any
+            let package_manager = pkgfile.package_manager();
+
+            if params.install_packages {
+                if let Some((engine, version)) = &package_manager {
+                    setup_package_manager(progress_handler, &actual_dir, engine, version)?;
+                }
+            }
+
             if params.install_engines {
                 // Install the engines
                 for (engine, version_range) in pkgfile.engines.iter() {
@@ -312,6 +340,14 @@ fn setup_individual_npm_prefix(
                         continue;
                     }
 
+                    // The pinned package manager is already handled
+                    if package_manager
+                        .as_ref()
+                        .is_some_and(|(pm_engine, _)| pm_engine.name() == *engine)
+                    {
+                        continue;
+                    }
+
                     progress_handler
                         .progress(format!("installing {} version {}", engine, version_range));
 
@@ -342,9 +378,13 @@ fn setup_individual_npm_prefix(
 
             if params.install_packages {
                 // Install the packages
-                let engines_slice: Vec<String> = pkgfile.engines.keys().cloned().collect();
-                let install_engines = PackageInstallEngine::all_sorted(&actual_dir, &engines_slice);
-                let install_engine = install_engines.first().unwrap();
+                let install_engine = match &package_manager {
+                    Some((engine, _)) => engine,
+                    None => {
+                        let engines_slice: Vec<String> = pkgfile.engines.keys().cloned().collect();
+                        &PackageInstallEngine::all_sorted(&actual_dir, &engines_slice)[0]
+                    }
+                };
 
                 if which::which(install_engine.name()).is_err() {
                     progress_handler.progress(format!(
@@ -359,7 +399,10 @@ fn setup_individual_npm_prefix(
                     install_engine.name(),
                 ));
 
-                let mut pkg_install = install_engine.install_command();
+                let pinned_version = package_manager
+                    .as_ref()
+                    .map(|(_, version)| version.as_str());
+                let mut pkg_install = install_engine.install_command(&actual_dir, pinned_version);
                 pkg_install.current_dir(&actual_dir);
 
                 let result = run_progress(
@@ -383,13 +426,97 @@ fn setup_individual_npm_prefix(
     Ok(())
 }
 
+/// Sets up the package manager pinned through the `packageManager` field
+/// of the package.json file; corepack is used when available, otherwise
+/// the pinned version of the package manager is installed with npm.
+fn setup_package_manager(
+    progress_handler: &dyn ProgressHandler,
+    dir: &Path,
+    engine: &PackageInstallEngine,
+    version: &str,
+) -> Result<(), UpError> {
+    if *engine == PackageInstallEngine::Npm {
+        // npm comes with node, and corepack does not manage it
+        return Ok(());
+    }
+
+    let npm_prefix = std::env::var("npm_config_prefix").ok();
+
+    let mut setup = if which::which("corepack").is_ok() {
+        progress_handler.progress(format!(
+            "enabling {}@{} with corepack",
+            engine.name(),
+            version
+        ));
+
+        let mut corepack = TokioCommand::new("corepack");
+        corepack.arg("enable");
+        if let Some(npm_prefix) = &npm_prefix {
+            // Make sure the shims are installed in the isolated prefix
+            // instead of the shared installation directory of node
+            corepack.arg("--install-directory");
+            corepack.arg(PathBuf::from(npm_prefix).join("bin"));
+        }
+        corepack.arg(engine.name());
+        corepack
+    } else {
+        progress_handler.progress(format!("installing {}@{}", engine.name(), version));
+
+        let mut npm_install = TokioCommand::new("npm");
+        npm_install.arg("install");
+        npm_install.arg("-g");
+        npm_install.arg(format!("{}@{}", engine.name(), version));
+        npm_install
+    };
+
+    setup.current_dir(dir);
+    setup.env("COREPACK_ENABLE_DOWNLOAD_PROMPT", "0");
+    setup.stdout(std::process::Stdio::piped());
+    setup.stderr(std::process::Stdio::piped());
+
+    if let Err(err) = run_progress(&mut setup, Some(progress_handler), RunConfig::default()) {
+        let msg = format!("failed to setup {}@{}: {}", engine.name(), version, err);
+        progress_handler.error_with_message(msg.clone());
+        return Err(UpError::Exec(msg));
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 struct PackageJson {
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     engines: HashMap<String, String>,
+    #[serde(
+        default,
+        rename = "packageManager",
+        skip_serializing_if = "Option::is_none"
+    )]
+    package_manager: Option<String>,
 }
 
-#[derive(Debug)]
+impl PackageJson {
+    /// Returns the package manager and its version as pinned in the
+    /// `packageManager` field, which follows the `<name>@<version>`
+    /// format with an optional `+<hash>` suffix.
+    fn package_manager(&self) -> Option<(PackageInstallEngine, String)> {
+        let package_manager = self.package_manager.as_ref()?;
+        let (name, version) = package_manager.trim().split_once('@')?;
+        let version = version.split('+').next().unwrap_or(version);
+
+        let engine = PackageInstallEngine::all()
+            .into_iter()
+            .find(|engine| engine.name() == name)?;
+
+        if version.is_empty() {
+            return None;
+        }
+
+        Some((engine, version.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum PackageInstallEngine {
     Pnpm,
     Yarn,
@@ -439,12 +566,106 @@ impl PackageInstallEngine {
         weight
     }
 
-    fn install_command(&self) -> TokioCommand {
+    fn install_command(&self, path: &Path, pinned_version: Option<&str>) -> TokioCommand {
         let mut cmd = TokioCommand::new(self.name());
-        cmd.arg("install");
+        cmd.args(self.install_args(path, pinned_version));
+        cmd.env("COREPACK_ENABLE_DOWNLOAD_PROMPT", "0");
         cmd.stdout(std::process::Stdio::piped());
         cmd.stderr(std::process::Stdio::piped());
 
         cmd
     }
+
+    /// Returns the arguments to install the packages; when a lock file
+    /// exists, the installation is frozen so that the lock file is
+    /// used as-is instead of being updated.
+    fn install_args(&self, path: &Path, pinned_version: Option<&str>) -> Vec<String> {
+        let lock_path = path.join(self.lock_file());
+        if !lock_path.exists() || lock_path.is_dir() {
+            return vec!["install".to_string()];
+        }
+
+        let args: &[&str] = match self {
+            Self::Npm => &["ci"],
+            Self::Pnpm => &["install", "--frozen-lockfile"],
+            Self::Yarn => {
+                // Yarn berry (2+) replaced --frozen-lockfile by --immutable
+                let is_berry = match pinned_version {
+                    Some(version) => !version.starts_with("1."),
+                    None => path.join(".yarnrc.yml").exists(),
+                };
+
+                if is_berry {
+                    &["install", "--immutable"]
+                } else {
+                    &["install", "--frozen-lockfile"]
+                }
+            }
+        };
+
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package_json(contents: &str) -> PackageJson {
+        serde_json::from_str(contents).expect("failed to parse package.json")
+    }
+
+    #[test]
+    fn test_package_manager() {
+        let pkgfile = package_json(r#"{"packageManager": "pnpm@8.15.4+sha256.abcdef"}"#);
+        assert_eq!(
+            pkgfile.package_manager(),
+            Some((PackageInstallEngine::Pnpm, "8.15.4".to_string()))
+        );
+
+        let pkgfile = package_json(r#"{"packageManager": "yarn@4.1.0"}"#);
+        assert_eq!(
+            pkgfile.package_manager(),
+            Some((PackageInstallEngine::Yarn, "4.1.0".to_string()))
+        );
+
+        let pkgfile = package_json(r#"{"packageManager": "bun@1.0.0"}"#);
+        assert_eq!(pkgfile.package_manager(), None);
+
+        let pkgfile = package_json(r#"{"engines": {"node": ">=18"}}"#);
+        assert_eq!(pkgfile.package_manager(), None);
+    }
+
+    #[test]
+    fn test_install_args() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = dir.path();
+
+        assert_eq!(
+            PackageInstallEngine::Npm.install_args(path, None),
+            vec!["install"]
+        );
+
+        std::fs::write(path.join("package-lock.json"), "{}").expect("failed to write file");
+        assert_eq!(
+            PackageInstallEngine::Npm.install_args(path, None),
+            vec!["ci"]
+        );
+
+        std::fs::write(path.join("pnpm-lock.yaml"), "").expect("failed to write file");
+        assert_eq!(
+            PackageInstallEngine::Pnpm.install_args(path, None),
+            vec!["install", "--frozen-lockfile"]
+        );
+
+        std::fs::write(path.join("yarn.lock"), "").expect("failed to write file");
+        assert_eq!(
+            PackageInstallEngine::Yarn.install_args(path, None),
+            vec!["install", "--frozen-lockfile"]
+        );
+        assert_eq!(
+            PackageInstallEngine::Yarn.install_args(path, Some("4.1.0")),
+            vec!["install", "--immutable"]
+        );
+    }
 }
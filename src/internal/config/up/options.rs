@@ -1,14 +1,30 @@
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::internal::config::up::lock::UpLock;
+use crate::internal::config::up::lock::UpLockEntry;
+use crate::internal::config::up::selection::UpStepSelection;
+use crate::internal::config::up::utils::RetryPolicy;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpOptions<'a> {
     pub read_cache: bool,
     pub write_cache: bool,
     pub fail_on_upgrade: bool,
     pub upgrade: bool,
+    pub offline: bool,
+    pub concurrency: usize,
+    #[serde(skip)]
+    pub concurrent: bool,
+    pub retry: RetryPolicy,
+    #[serde(skip)]
+    pub selection: UpStepSelection,
     #[serde(skip)]
     pub lock_file: Option<&'a std::fs::File>,
+    #[serde(skip)]
+    pub version_lock: Option<&'a UpLock>,
+    #[serde(skip)]
+    pub defer_operation_cache: bool,
 }
 
 impl Default for UpOptions<'_> {
@@ -18,7 +34,14 @@ impl Default for UpOptions<'_> {
             write_cache: true,
             fail_on_upgrade: false,
             upgrade: false,
+            offline: false,
+            concurrency: 1,
+            concurrent: false,
+            retry: RetryPolicy::default(),
+            selection: UpStepSelection::default(),
             lock_file: None,
+            version_lock: None,
+            defer_operation_cache: false,
         }
     }
 }
@@ -49,8 +72,72 @@ impl<'a> UpOptions<'a> {
         self
     }
 
+    /// Sets whether the steps should only rely on what is cached or
+    /// already installed, without accessing the network
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Sets the maximum number of steps that can run at the same time
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Marks the steps as running at the same time as other steps, in
+    /// which case they must not touch the environment shared by threads
+    pub fn concurrent(mut self) -> Self {
+        self.concurrent = true;
+        self
+    }
+
+    /// Sets the timeout and retries of the network requests and
+    /// commands run by the steps
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets the steps to run; the other steps are left untouched
+    pub fn selection(mut self, selection: UpStepSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
     pub fn lock_file(mut self, lock_file: &'a std::fs::File) -> Self {
         self.lock_file = Some(lock_file);
         self
     }
+
+    pub fn version_lock(mut self, version_lock: &'a UpLock) -> Self {
+        self.version_lock = Some(version_lock);
+        self
+    }
+
+    /// Returns the locked entry for the given step, resource and directory,
+    /// unless we are upgrading, in which case the lock is being rewritten
+    pub fn locked(&self, step: &str, name: &str, dir: &str) -> Option<&'a UpLockEntry> {
+        if self.upgrade {
+            return None;
+        }
+
+        self.version_lock?.get(step, name, dir)
+    }
+
+    /// Records the version resolved for a resource in the lock, if any
+    pub fn lock(&self, entry: UpLockEntry) {
+        if let Some(version_lock) = self.version_lock {
+            version_lock.record(entry);
+        }
+    }
+
+    /// Declares the resources of all the steps of the configuration in
+    /// the lock, if any, so that the entries of the other resources are
+    /// removed from it
+    pub fn declare_locked(&self, declared: Vec<(String, String)>) {
+        if let Some(version_lock) = self.version_lock {
+            version_lock.declare(declared);
+        }
+    }
 }
@@ -0,0 +1,394 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::internal::cache::up_environments::UpEnvVar;
+use crate::internal::cache::up_environments::UpEnvironment;
+use crate::internal::user_interface::StringColor;
+
+/// The action that would be taken for a resource when running
+/// `omni up` or `omni down`, as computed in dry-run mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpPlanAction {
+    /// The resource would be installed
+    Install,
+    /// The resource would replace the version currently in use,
+    /// which is provided as parameter
+    Upgrade(String),
+    /// The resource is already installed and would be used as-is
+    Keep,
+    /// The step is not available on this system and would be skipped
+    Skip,
+    /// The resource would not be required by the work directory anymore
+    Remove,
+    /// The step cannot be planned without being run
+    Run,
+    /// The plan for the resource could not be computed, with the
+    /// reason provided as parameter
+    Unknown(String),
+}
+
+impl UpPlanAction {
+    /// Computes the action for a resource resolved to the given version,
+    /// depending on whether that version is already installed and on
+    /// the version currently in use by the work directory, if any.
+    pub fn for_version(installed: bool, current_version: Option<String>, version: &str) -> Self {
+        match current_version {
+            Some(current_version) if current_version != version => {
+                UpPlanAction::Upgrade(current_version)
+            }
+            _ if installed => UpPlanAction::Keep,
+            _ => UpPlanAction::Install,
+        }
+    }
+
+    /// Whether the action means that the environment is out of date
+    pub fn is_change(&self) -> bool {
+        matches!(
+            self,
+            UpPlanAction::Install
+                | UpPlanAction::Upgrade(_)
+                | UpPlanAction::Remove
+                | UpPlanAction::Unknown(_)
+        )
+    }
+
+    fn label(&self) -> String {
+        match self {
+            UpPlanAction::Install => "install".green(),
+            UpPlanAction::Upgrade(_) => "upgrade".light_yellow(),
+            UpPlanAction::Keep => "keep".light_black(),
+            UpPlanAction::Skip => "skip".light_black(),
+            UpPlanAction::Remove => "remove".red(),
+            UpPlanAction::Run => "run".light_blue(),
+            UpPlanAction::Unknown(_) => "unknown".red(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UpPlanItem {
+    /// The name of the up step this item belongs to
+    pub step: String,
+    /// The name of the resource handled by the step
+    pub name: String,
+    /// The version of the resource, if known
+    pub version: Option<String>,
+    /// The action that would be taken for the resource
+    pub action: UpPlanAction,
+    /// The paths that would be added to the PATH of the work directory
+    pub paths: Vec<PathBuf>,
+    /// Whether all the changes to the environment of the work directory
+    /// are known for that item
+    pub env_known: bool,
+}
+
+impl UpPlanItem {
+    pub fn new(name: &str, action: UpPlanAction) -> Self {
+        Self {
+            step: "".to_string(),
+            name: name.to_string(),
+            version: None,
+            env_known: action != UpPlanAction::Run,
+            action,
+            paths: vec![],
+        }
+    }
+
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    pub fn with_path(mut self, path: PathBuf) -> Self {
+        self.paths.push(path);
+        self
+    }
+
+    /// Marks the item as making changes to the environment that
+    /// cannot be known without running the step
+    pub fn with_unknown_env(mut self) -> Self {
+        self.env_known = false;
+        self
+    }
+
+    fn describe(&self) -> String {
+        let mut desc = if self.step.is_empty() || self.step == self.name {
+            self.name.light_yellow()
+        } else {
+            format!("{}: {}", self.step, self.name.light_yellow())
+        };
+        match (&self.action, &self.version) {
+            (UpPlanAction::Upgrade(from), Some(version)) => {
+                desc.push_str(&format!(" {} → {}", from, version.light_yellow()));
+            }
+            (_, Some(version)) => desc.push_str(&format!(" {}", version)),
+            _ => {}
+        }
+        if let UpPlanAction::Unknown(reason) = &self.action {
+            desc.push_str(&format!(" {}", format!("({})", reason).light_black()));
+        }
+        desc
+    }
+}
+
+/// Returns the version in use by the work directory for a resource
+/// installed under the given root, in a `<root>/<version>` directory
+/// that is part of the PATH of the work directory.
+pub fn current_version_under(current: Option<&UpEnvironment>, root: &Path) -> Option<String> {
+    current?.paths.iter().find_map(|path| {
+        path.strip_prefix(root)
+            .ok()?
+            .components()
+            .next()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+    })
+}
+
+/// The plan of what `omni up` or `omni down` would do, computed
+/// without touching the filesystem.
+#[derive(Debug, Clone)]
+pub struct UpPlan {
+    items: Vec<UpPlanItem>,
+    env_vars: Vec<UpEnvVar>,
+    current: Option<UpEnvironment>,
+    config_changed: bool,
+}
+
+impl UpPlan {
+    pub fn new(
+        items: Vec<UpPlanItem>,
+        env_vars: Vec<UpEnvVar>,
+        current: Option<UpEnvironment>,
+        config_changed: bool,
+    ) -> Self {
+        Self {
+            items,
+            env_vars,
+            current,
+            config_changed,
+        }
+    }
+
+    /// Whether all the steps could be planned without being run, in
+    /// which case the environment changes are fully known.
+    fn fully_planned(&self) -> bool {
+        self.items.iter().all(|item| item.env_known)
+    }
+
+    /// The paths that would be added to and removed from the PATH
+    /// of the work directory.
+    pub fn path_changes(&self) -> (Vec<PathBuf>, Vec<PathBuf>) {
+        let planned = self
+            .items
+            .iter()
+            .filter(|item| !matches!(item.action, UpPlanAction::Remove | UpPlanAction::Skip))
+            .flat_map(|item| item.paths.clone())
+            .collect::<Vec<_>>();
+        let current = self
+            .current
+            .as_ref()
+            .map(|current| current.paths.clone())
+            .unwrap_or_default();
+
+        let added = planned
+            .iter()
+            .filter(|path| !current.contains(path))
+            .cloned()
+            .collect();
+
+        // Paths added by steps that would be run cannot be known, so
+        // we only consider removals when everything could be planned
+        let removed = if self.fully_planned() {
+            current
+                .iter()
+                .filter(|path| !planned.contains(path))
+                .cloned()
+                .collect()
+        } else {
+            vec![]
+        };
+
+        (added, removed)
+    }
+
+    /// The environment variables that would be set and unset for the
+    /// work directory.
+    pub fn env_changes(&self) -> (Vec<UpEnvVar>, Vec<UpEnvVar>) {
+        let current = self
+            .current
+            .as_ref()
+            .map(|current| current.env_vars.clone())
+            .unwrap_or_default();
+
+        let same = |a: &UpEnvVar, b: &UpEnvVar| {
+            a.name == b.name && a.value == b.value && a.operation == b.operation
+        };
+
+        let added = self
+            .env_vars
+            .iter()
+            .filter(|env_var| !current.iter().any(|current| same(env_var, current)))
+            .cloned()
+            .collect();
+
+        let removed = if self.fully_planned() {
+            current
+                .iter()
+                .filter(|current| !self.env_vars.iter().any(|env_var| same(env_var, current)))
+                .cloned()
+                .collect()
+        } else {
+            vec![]
+        };
+
+        (added, removed)
+    }
+
+    /// Whether the environment is already in the state described
+    /// by the plan.
+    pub fn is_up_to_date(&self) -> bool {
+        let (paths_added, paths_removed) = self.path_changes();
+        let (env_added, env_removed) = self.env_changes();
+
+        !self.config_changed
+            && !self.items.iter().any(|item| item.action.is_change())
+            && paths_added.is_empty()
+            && paths_removed.is_empty()
+            && env_added.is_empty()
+            && env_removed.is_empty()
+    }
+
+    pub fn print(&self) {
+        if self.items.is_empty() {
+            eprintln!("{}", "nothing to do".light_black());
+        }
+
+        for item in self.items.iter() {
+            eprintln!("{:>8} {}", item.action.label(), item.describe());
+        }
+
+        let (paths_added, paths_removed) = self.path_changes();
+        let (env_added, env_removed) = self.env_changes();
+        if !paths_added.is_empty()
+            || !paths_removed.is_empty()
+            || !env_added.is_empty()
+            || !env_removed.is_empty()
+        {
+            eprintln!();
+            eprintln!("{}", "environment changes:".bold());
+            for path in paths_added.iter() {
+                eprintln!("{:>8} PATH {}", "+".green(), path.display());
+            }
+            for path in paths_removed.iter() {
+                eprintln!("{:>8} PATH {}", "-".red(), path.display());
+            }
+            for env_var in env_added.iter() {
+                eprintln!(
+                    "{:>8} {}{}",
+                    "+".green(),
+                    env_var.name,
+                    env_var
+                        .value
+                        .as_ref()
+                        .map(|value| format!("={}", value))
+                        .unwrap_or_default()
+                );
+            }
+            for env_var in env_removed.iter() {
+                eprintln!("{:>8} {}", "-".red(), env_var.name);
+            }
+        }
+
+        if !self.fully_planned() {
+            eprintln!();
+            eprintln!(
+                "{}",
+                "some steps cannot be planned without being run; their changes are not shown"
+                    .light_black()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn action_for_version() {
+        assert_eq!(
+            UpPlanAction::for_version(false, None, "1.0.0"),
+            UpPlanAction::Install
+        );
+        assert_eq!(
+            UpPlanAction::for_version(true, None, "1.0.0"),
+            UpPlanAction::Keep
+        );
+        assert_eq!(
+            UpPlanAction::for_version(true, Some("1.0.0".to_string()), "1.0.0"),
+            UpPlanAction::Keep
+        );
+        assert_eq!(
+            UpPlanAction::for_version(true, Some("0.9.0".to_string()), "1.0.0"),
+            UpPlanAction::Upgrade("0.9.0".to_string())
+        );
+    }
+
+    #[test]
+    fn current_version_from_paths() {
+        let mut current = UpEnvironment::new();
+        current
+            .paths
+            .push(PathBuf::from("/data/ghreleases/owner/repo/v1.2.3"));
+        current
+            .paths
+            .push(PathBuf::from("/data/cargo-install/ripgrep/14.0.0/bin"));
+
+        assert_eq!(
+            current_version_under(Some(&current), Path::new("/data/ghreleases/owner/repo")),
+            Some("v1.2.3".to_string())
+        );
+        assert_eq!(
+            current_version_under(Some(&current), Path::new("/data/cargo-install/ripgrep")),
+            Some("14.0.0".to_string())
+        );
+        assert_eq!(
+            current_version_under(Some(&current), Path::new("/data/download/tool")),
+            None
+        );
+        assert_eq!(current_version_under(None, Path::new("/data")), None);
+    }
+
+    #[test]
+    fn up_to_date() {
+        let mut current = UpEnvironment::new();
+        current
+            .paths
+            .push(PathBuf::from("/data/download/tool/1.0.0"));
+
+        let item = UpPlanItem::new("tool", UpPlanAction::Keep)
+            .with_version("1.0.0")
+            .with_path(PathBuf::from("/data/download/tool/1.0.0"));
+        let plan = UpPlan::new(vec![item.clone()], vec![], Some(current.clone()), false);
+        assert!(plan.is_up_to_date());
+
+        let plan = UpPlan::new(vec![item.clone()], vec![], Some(current.clone()), true);
+        assert!(!plan.is_up_to_date());
+
+        let plan = UpPlan::new(vec![], vec![], Some(current.clone()), false);
+        assert!(!plan.is_up_to_date());
+        assert_eq!(
+            plan.path_changes().1,
+            vec![PathBuf::from("/data/download/tool/1.0.0")]
+        );
+
+        // Removals are not considered when a step cannot be planned
+        let plan = UpPlan::new(
+            vec![UpPlanItem::new("custom", UpPlanAction::Run)],
+            vec![],
+            Some(current),
+            false,
+        );
+        assert!(plan.is_up_to_date());
+    }
+}
@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::path::PathBuf;
 
 use normalize_path::NormalizePath;
@@ -6,6 +7,7 @@ use serde::Deserialize;
 use serde::Serialize;
 use tokio::process::Command as TokioCommand;
 
+use crate::internal::cache::utils as cache_utils;
 use crate::internal::cache::utils::CacheObject;
 use crate::internal::cache::UpEnvironmentsCache;
 use crate::internal::commands::utils::abs_path;
@@ -27,18 +29,42 @@ use crate::internal::ConfigValue;
 const MIN_VERSION_VENV: Version = Version::new(3, 3, 0);
 // const MIN_VERSION_VIRTUALENV: Version = Version::new(2, 6, 0);
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpConfigPythonParams {
     #[serde(default, rename = "pip", skip_serializing_if = "Vec::is_empty")]
     pip_files: Vec<String>,
     #[serde(default, skip)]
     pip_auto: bool,
+    #[serde(
+        default = "cache_utils::set_true",
+        skip_serializing_if = "cache_utils::is_true"
+    )]
+    sync: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    groups: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    extras: Vec<String>,
+}
+
+impl Default for UpConfigPythonParams {
+    fn default() -> Self {
+        Self {
+            pip_files: Vec::new(),
+            pip_auto: false,
+            sync: true,
+            groups: Vec::new(),
+            extras: Vec::new(),
+        }
+    }
 }
 
 impl UpConfigPythonParams {
     pub fn from_config_value(config_value: Option<&ConfigValue>) -> Self {
         let mut pip_files = Vec::new();
         let mut pip_auto = false;
+        let mut sync = true;
+        let mut groups = Vec::new();
+        let mut extras = Vec::new();
 
         if let Some(config_value) = config_value {
             if let Some(config_value) = config_value.get_as_array("pip") {
@@ -54,11 +80,26 @@ impl UpConfigPythonParams {
                     pip_files.push(file_path.to_string());
                 }
             }
+
+            if let Some(value) = config_value.get_as_bool_forced("sync") {
+                sync = value;
+            }
+
+            for (key, values) in [("groups", &mut groups), ("extras", &mut extras)] {
+                if let Some(array) = config_value.get_as_array(key) {
+                    values.extend(array.iter().filter_map(|value| value.as_str_forced()));
+                } else if let Some(value) = config_value.get_as_str_forced(key) {
+                    values.push(value);
+                }
+            }
         }
 
         Self {
             pip_files,
             pip_auto,
+            sync,
+            groups,
+            extras,
         }
     }
 }
@@ -101,8 +142,10 @@ impl Serialize for UpConfigPython {
 impl UpConfigPython {
     pub fn from_config_value(config_value: Option<&ConfigValue>) -> Self {
         let mut asdf_base = UpConfigAsdfBase::from_config_value("python", config_value);
+        asdf_base.add_detect_version_func(detect_version_from_pyproject);
         asdf_base.add_post_install_func(setup_python_venv);
         asdf_base.add_post_install_func(setup_python_pip);
+        asdf_base.add_post_install_func(setup_python_project);
 
         let params = UpConfigPythonParams::from_config_value(config_value);
 
@@ -120,10 +163,19 @@ impl UpConfigPython {
     pub fn down(&self, progress_handler: &UpProgressHandler) -> Result<(), UpError> {
         self.asdf_base.down(progress_handler)
     }
+
+    pub fn rollback(&self, progress_handler: &dyn ProgressHandler) {
+        self.asdf_base.rollback(progress_handler)
+    }
+
+    pub fn lock_keys(&self) -> Vec<(String, String)> {
+        self.asdf_base.lock_keys()
+    }
 }
 
 fn setup_python_venv(
     progress_handler: &dyn ProgressHandler,
+    _options: &UpOptions,
     _config_value: Option<ConfigValue>,
     tool: String,
     tool_real_name: String,
@@ -285,6 +337,7 @@ fn setup_python_venv_per_dir(
 
 fn setup_python_pip(
     progress_handler: &dyn ProgressHandler,
+    options: &UpOptions,
     config_value: Option<ConfigValue>,
     _tool: String,
     _tool_real_name: String,
@@ -329,12 +382,12 @@ fn setup_python_pip(
             // if it exists
             let req_txt = path.join("requirements.txt");
             if req_txt.exists() {
-                setup_python_pip_file(progress_handler, req_txt)?;
+                setup_python_pip_file(progress_handler, options, req_txt)?;
             }
         } else {
             // Otherwise, use the specified files
             for pip_file in &params.pip_files {
-                setup_python_pip_file(progress_handler, PathBuf::from(pip_file))?
+                setup_python_pip_file(progress_handler, options, PathBuf::from(pip_file))?
             }
         }
     }
@@ -344,6 +397,7 @@ fn setup_python_pip(
 
 fn setup_python_pip_file(
     progress_handler: &dyn ProgressHandler,
+    options: &UpOptions,
     pip_file: PathBuf,
 ) -> Result<(), UpError> {
     if !pip_file.exists() {
@@ -353,6 +407,13 @@ fn setup_python_pip_file(
         )));
     }
 
+    if options.offline {
+        return Err(UpError::Offline(format!(
+            "dependencies from {}",
+            pip_file.display()
+        )));
+    }
+
     progress_handler.progress(format!(
         "installing dependencies from {}",
         pip_file.display()
@@ -378,3 +439,352 @@ fn setup_python_pip_file(
 
     Ok(())
 }
+
+/// The project managers that can be used to install the dependencies
+/// of a python project from their lockfile, in order of precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PythonProjectManager {
+    Uv,
+    Poetry,
+    Pipenv,
+}
+
+impl PythonProjectManager {
+    fn detect(path: &Path) -> Option<Self> {
+        [Self::Uv, Self::Poetry, Self::Pipenv]
+            .into_iter()
+            .find(|manager| path.join(manager.lockfile()).exists())
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Uv => "uv",
+            Self::Poetry => "poetry",
+            Self::Pipenv => "pipenv",
+        }
+    }
+
+    fn lockfile(&self) -> &'static str {
+        match self {
+            Self::Uv => "uv.lock",
+            Self::Poetry => "poetry.lock",
+            Self::Pipenv => "Pipfile.lock",
+        }
+    }
+
+    fn sync_command(&self, params: &UpConfigPythonParams, venv_path: &str) -> TokioCommand {
+        let mut command = TokioCommand::new(self.name());
+
+        match self {
+            Self::Uv => {
+                command.arg("sync");
+                command.arg("--frozen");
+                for group in &params.groups {
+                    command.arg("--group");
+                    command.arg(group);
+                }
+                for extra in &params.extras {
+                    command.arg("--extra");
+                    command.arg(extra);
+                }
+                command.env("UV_PROJECT_ENVIRONMENT", venv_path);
+            }
+            Self::Poetry => {
+                command.arg("install");
+                command.arg("--no-interaction");
+                if !params.groups.is_empty() {
+                    command.arg("--with");
+                    command.arg(params.groups.join(","));
+                }
+                if !params.extras.is_empty() {
+                    command.arg("--extras");
+                    command.arg(params.extras.join(" "));
+                }
+            }
+            Self::Pipenv => {
+                command.arg("sync");
+                if !params.groups.is_empty() {
+                    // The development dependencies of pipenv are in
+                    // the `dev-packages` category
+                    let mut categories = vec!["packages".to_string()];
+                    categories.extend(params.groups.iter().map(|group| match group.as_str() {
+                        "dev" => "dev-packages".to_string(),
+                        _ => group.clone(),
+                    }));
+                    command.arg("--categories");
+                    command.arg(categories.join(" "));
+                }
+            }
+        }
+
+        command.env("VIRTUAL_ENV", venv_path);
+        command.stdout(std::process::Stdio::piped());
+        command.stderr(std::process::Stdio::piped());
+
+        command
+    }
+}
+
+fn setup_python_project(
+    progress_handler: &dyn ProgressHandler,
+    options: &UpOptions,
+    config_value: Option<ConfigValue>,
+    tool: String,
+    _tool_real_name: String,
+    _requested_version: String,
+    versions: Vec<AsdfToolUpVersion>,
+) -> Result<(), UpError> {
+    let params = UpConfigPythonParams::from_config_value(config_value.as_ref());
+    if !params.sync {
+        return Ok(());
+    }
+
+    let workdir = workdir(".");
+    let data_path = match workdir.data_path() {
+        Some(data_path) => data_path,
+        None => {
+            return Err(UpError::Exec(format!(
+                "failed to get data path for {}",
+                current_dir().display()
+            )));
+        }
+    };
+
+    for version in &versions {
+        for dir in &version.dirs {
+            let path = PathBuf::from(dir).normalize();
+
+            // Check if path is in current dir
+            let full_path = abs_path(dir);
+            if !full_path.starts_with(current_dir()) {
+                return Err(UpError::Exec(format!(
+                    "directory {} is not in work directory",
+                    path.display(),
+                )));
+            }
+
+            let manager = match PythonProjectManager::detect(&full_path) {
+                Some(manager) => manager,
+                None => continue,
+            };
+
+            // The venv is only created for the versions supporting it
+            let venv_path = data_path
+                .join(&tool)
+                .join(&version.version)
+                .join(data_path_dir_hash(dir));
+            if !venv_path.join("pyvenv.cfg").exists() {
+                continue;
+            }
+
+            if options.offline {
+                return Err(UpError::Offline(format!(
+                    "dependencies from {}",
+                    path.join(manager.lockfile()).display()
+                )));
+            }
+
+            // Load the environment for that directory
+            update_dynamic_env_for_command(full_path.to_string_lossy());
+
+            if which::which(manager.name()).is_err() {
+                return Err(UpError::Exec(format!(
+                    "{} is required to install dependencies from {}",
+                    manager.name(),
+                    path.join(manager.lockfile()).display(),
+                )));
+            }
+
+            progress_handler.progress(format!(
+                "installing dependencies from {}",
+                path.join(manager.lockfile()).display()
+            ));
+
+            let mut sync = manager.sync_command(&params, &venv_path.to_string_lossy());
+            sync.current_dir(&full_path);
+
+            run_progress(&mut sync, Some(progress_handler), RunConfig::default())?;
+
+            progress_handler.progress(format!(
+                "dependencies from {} installed",
+                path.join(manager.lockfile()).display()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn detect_version_from_pyproject(_tool_name: String, path: PathBuf) -> Option<String> {
+    // Version files are more specific than the python requirement
+    // of the project, so let them take precedence if they exist
+    if path.join(".python-version").exists() || path.join(".tool-versions").exists() {
+        return None;
+    }
+
+    let contents = std::fs::read_to_string(path.join("pyproject.toml")).ok()?;
+    let pyproject = contents.parse::<toml::Table>().ok()?;
+
+    let requires_python = pyproject
+        .get("project")
+        .and_then(|project| project.get("requires-python"))
+        .or_else(|| {
+            pyproject
+                .get("tool")
+                .and_then(|tool| tool.get("poetry"))
+                .and_then(|poetry| poetry.get("dependencies"))
+                .and_then(|dependencies| dependencies.get("python"))
+        })
+        .and_then(|requires_python| requires_python.as_str())?;
+
+    python_specifier_to_version_range(requires_python)
+}
+
+/// Converts a python version specifier (PEP 440, or poetry's format)
+/// to a version range that can be used to resolve the python version.
+fn python_specifier_to_version_range(specifier: &str) -> Option<String> {
+    let mut ranges = vec![];
+
+    for clause in specifier.split(',') {
+        let clause = clause.trim().replace(' ', "");
+        if clause.is_empty() || clause.starts_with("!=") {
+            // Exclusions cannot be expressed as a range, but are
+            // generally not required to pick a suitable version
+            continue;
+        }
+
+        let range = if let Some(version) = clause.strip_prefix("~=") {
+            // ~=X.Y means >=X.Y,==X.*, and ~=X.Y.Z means >=X.Y.Z,==X.Y.*
+            if version.matches('.').count() >= 2 {
+                format!("~{}", version)
+            } else {
+                format!("^{}", version)
+            }
+        } else if let Some(version) = clause.strip_prefix("===") {
+            version.to_string()
+        } else if let Some(version) = clause.strip_prefix("==") {
+            version.replace('*', "x")
+        } else {
+            clause.to_string()
+        };
+
+        ranges.push(range);
+    }
+
+    if ranges.is_empty() {
+        None
+    } else {
+        Some(ranges.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_python_specifier_to_version_range() {
+        for (specifier, expected) in [
+            (">=3.10", Some(">=3.10")),
+            (">=3.9, <3.13", Some(">=3.9 <3.13")),
+            ("~=3.10", Some("^3.10")),
+            ("~=3.10.2", Some("~3.10.2")),
+            ("==3.11.*", Some("3.11.x")),
+            ("^3.11", Some("^3.11")),
+            (">=3.8,!=3.9.0", Some(">=3.8")),
+            ("!=3.9.0", None),
+        ] {
+            assert_eq!(
+                python_specifier_to_version_range(specifier),
+                expected.map(|s| s.to_string()),
+                "specifier: {}",
+                specifier
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_version_from_pyproject() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            "[project]\nname = \"test\"\nrequires-python = \">=3.10\"\n",
+        )
+        .expect("failed to write pyproject.toml");
+
+        assert_eq!(
+            detect_version_from_pyproject("python".to_string(), dir.path().to_path_buf()),
+            Some(">=3.10".to_string())
+        );
+
+        // The python version file takes precedence
+        std::fs::write(dir.path().join(".python-version"), "3.11.4\n")
+            .expect("failed to write .python-version");
+        assert_eq!(
+            detect_version_from_pyproject("python".to_string(), dir.path().to_path_buf()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_detect_version_from_poetry_pyproject() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            "[tool.poetry.dependencies]\npython = \"^3.11\"\n",
+        )
+        .expect("failed to write pyproject.toml");
+
+        assert_eq!(
+            detect_version_from_pyproject("python".to_string(), dir.path().to_path_buf()),
+            Some("^3.11".to_string())
+        );
+    }
+
+    #[test]
+    fn test_detect_project_manager() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        assert_eq!(PythonProjectManager::detect(dir.path()), None);
+
+        std::fs::write(dir.path().join("Pipfile.lock"), "{}").expect("failed to write file");
+        assert_eq!(
+            PythonProjectManager::detect(dir.path()),
+            Some(PythonProjectManager::Pipenv)
+        );
+
+        std::fs::write(dir.path().join("uv.lock"), "").expect("failed to write file");
+        assert_eq!(
+            PythonProjectManager::detect(dir.path()),
+            Some(PythonProjectManager::Uv)
+        );
+    }
+
+    #[test]
+    fn test_pipenv_sync_command() {
+        let config_value =
+            ConfigValue::from_str("groups: [dev, docs]").expect("failed to create config value");
+        let params = UpConfigPythonParams::from_config_value(Some(&config_value));
+
+        let command = PythonProjectManager::Pipenv.sync_command(&params, "/venv");
+        let args = command
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            args,
+            vec!["sync", "--categories", "packages dev-packages docs"]
+        );
+    }
+
+    #[test]
+    fn test_params_from_config_value() {
+        let config_value = ConfigValue::from_str("groups: [dev, docs]\nextras: cli\nsync: false")
+            .expect("failed to create config value");
+        let params = UpConfigPythonParams::from_config_value(Some(&config_value));
+
+        assert!(!params.sync);
+        assert_eq!(params.groups, vec!["dev".to_string(), "docs".to_string()]);
+        assert_eq!(params.extras, vec!["cli".to_string()]);
+    }
+}
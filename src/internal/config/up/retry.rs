@@ -0,0 +1,119 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::internal::config::up::utils::RetryPolicy;
+use crate::internal::config::ConfigValue;
+
+/// The timeout and retries set on a step, overriding the ones of the
+/// `up_command` configuration for the network requests and commands
+/// run by that step
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UpConfigRetry {
+    /// The number of retries after the first attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+
+    /// The timeout, in seconds, of each attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+impl UpConfigRetry {
+    /// Parses the values of the `retries` and `timeout` keys of a step,
+    /// returning `None` if none of them is set
+    pub fn from_config_values(
+        retries: Option<&ConfigValue>,
+        timeout: Option<&ConfigValue>,
+    ) -> Result<Option<Self>, String> {
+        let retries = match retries {
+            Some(value) => match value
+                .as_unsigned_integer()
+                .and_then(|retries| u32::try_from(retries).ok())
+            {
+                Some(retries) => Some(retries),
+                None => return Err(format!("invalid retries: {}", value)),
+            },
+            None => None,
+        };
+
+        let timeout = match timeout {
+            Some(value) => match value.as_unsigned_integer().filter(|timeout| *timeout > 0) {
+                Some(timeout) => Some(timeout),
+                None => return Err(format!("invalid timeout: {}", value)),
+            },
+            None => None,
+        };
+
+        if retries.is_none() && timeout.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self { retries, timeout }))
+    }
+
+    /// Returns the given policy with the values set on the step
+    pub fn apply(&self, policy: &RetryPolicy) -> RetryPolicy {
+        RetryPolicy::new(
+            self.retries.unwrap_or(policy.retries),
+            self.timeout.or(policy.timeout),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(
+        retries: Option<&str>,
+        timeout: Option<&str>,
+    ) -> Result<Option<UpConfigRetry>, String> {
+        let retries = retries.map(|yaml| ConfigValue::from_str(yaml).expect("invalid yaml"));
+        let timeout = timeout.map(|yaml| ConfigValue::from_str(yaml).expect("invalid yaml"));
+        UpConfigRetry::from_config_values(retries.as_ref(), timeout.as_ref())
+    }
+
+    #[test]
+    fn parse_values() {
+        assert_eq!(parse(None, None), Ok(None));
+        assert_eq!(
+            parse(Some("3"), None),
+            Ok(Some(UpConfigRetry {
+                retries: Some(3),
+                timeout: None,
+            }))
+        );
+        assert_eq!(
+            parse(Some("0"), Some("60")),
+            Ok(Some(UpConfigRetry {
+                retries: Some(0),
+                timeout: Some(60),
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse(Some("-1"), None).is_err());
+        assert!(parse(Some("many"), None).is_err());
+        assert!(parse(None, Some("0")).is_err());
+        assert!(parse(None, Some("[30]")).is_err());
+    }
+
+    #[test]
+    fn apply() {
+        let policy = RetryPolicy::new(2, Some(30));
+
+        let retry = UpConfigRetry {
+            retries: Some(5),
+            timeout: None,
+        };
+        assert_eq!(retry.apply(&policy), RetryPolicy::new(5, Some(30)));
+
+        let retry = UpConfigRetry {
+            retries: None,
+            timeout: Some(300),
+        };
+        assert_eq!(retry.apply(&policy), RetryPolicy::new(2, Some(300)));
+    }
+}
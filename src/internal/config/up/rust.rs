@@ -0,0 +1,501 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use normalize_path::NormalizePath;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::process::Command as TokioCommand;
+
+use crate::internal::cache::utils as cache_utils;
+use crate::internal::cache::utils::CacheObject;
+use crate::internal::cache::UpEnvironmentsCache;
+use crate::internal::config::up::asdf_tool_path;
+use crate::internal::config::up::utils::data_path_dir_hash;
+use crate::internal::config::up::utils::run_progress;
+use crate::internal::config::up::utils::ProgressHandler;
+use crate::internal::config::up::utils::RunConfig;
+use crate::internal::config::up::utils::UpProgressHandler;
+use crate::internal::config::up::AsdfToolUpVersion;
+use crate::internal::config::up::UpConfigAsdfBase;
+use crate::internal::config::up::UpError;
+use crate::internal::config::up::UpOptions;
+use crate::internal::env::current_dir;
+use crate::internal::user_interface::StringColor;
+use crate::internal::workdir;
+use crate::internal::ConfigValue;
+
+const RUST_TOOLCHAIN_FILES: [&str; 2] = ["rust-toolchain.toml", "rust-toolchain"];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct UpConfigRustSerialized {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    components: BTreeSet<String>,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    targets: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "cache_utils::is_false")]
+    upgrade: bool,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    dirs: BTreeSet<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct UpConfigRust {
+    pub version: Option<String>,
+    pub components: BTreeSet<String>,
+    pub targets: BTreeSet<String>,
+    pub upgrade: bool,
+    pub dirs: BTreeSet<String>,
+    #[serde(skip)]
+    pub asdf_base: OnceCell<UpConfigAsdfBase>,
+    #[serde(skip)]
+    config_value: Option<ConfigValue>,
+}
+
+impl Serialize for UpConfigRust {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::ser::Serializer,
+    {
+        let serialized = UpConfigRustSerialized {
+            version: Some(self.version()),
+            components: self.components.clone(),
+            targets: self.targets.clone(),
+            upgrade: self.upgrade,
+            dirs: self.dirs.clone(),
+        };
+
+        serialized.serialize(serializer)
+    }
+}
+
+impl UpConfigRust {
+    pub fn from_config_value(config_value: Option<&ConfigValue>) -> Self {
+        let mut version = None;
+        let mut components = BTreeSet::new();
+        let mut targets = BTreeSet::new();
+        let mut dirs = BTreeSet::new();
+        let mut upgrade = false;
+
+        if let Some(config_value) = config_value {
+            if let Some(value) = config_value.as_str() {
+                version = Some(value.to_string());
+            } else if let Some(value) = config_value.as_float() {
+                version = Some(value.to_string());
+            } else if let Some(value) = config_value.as_integer() {
+                version = Some(value.to_string());
+            } else {
+                if let Some(value) = config_value.get_as_str_forced("version") {
+                    version = Some(value.to_string());
+                }
+
+                components = parse_str_or_list(config_value.get("components"));
+                targets = parse_str_or_list(config_value.get("targets"));
+
+                dirs = parse_str_or_list(config_value.get("dir"))
+                    .into_iter()
+                    .map(|dir| PathBuf::from(dir).normalize().to_string_lossy().to_string())
+                    .collect();
+
+                if let Some(value) = config_value.get_as_bool_forced("upgrade") {
+                    upgrade = value;
+                }
+            }
+        }
+
+        Self {
+            asdf_base: OnceCell::new(),
+            version,
+            components,
+            targets,
+            upgrade,
+            dirs,
+            config_value: config_value.cloned(),
+        }
+    }
+
+    pub fn up(
+        &self,
+        options: &UpOptions,
+        progress_handler: &UpProgressHandler,
+    ) -> Result<(), UpError> {
+        self.asdf_base()?.up(options, progress_handler)
+    }
+
+    pub fn down(&self, progress_handler: &UpProgressHandler) -> Result<(), UpError> {
+        self.asdf_base()?.down(progress_handler)
+    }
+
+    pub fn rollback(&self, progress_handler: &dyn ProgressHandler) {
+        if let Ok(asdf_base) = self.asdf_base() {
+            asdf_base.rollback(progress_handler);
+        }
+    }
+
+    /// The resources, as `(step, name)` pairs, for which the versions
+    /// of rust are locked; the asdf configuration is only resolved when
+    /// upping the tool, but always uses the same tool
+    pub fn lock_keys(&self) -> Vec<(String, String)> {
+        vec![("rust".to_string(), "rust".to_string())]
+    }
+
+    pub fn was_upped(&self) -> bool {
+        self.asdf_base()
+            .is_ok_and(|asdf_base| asdf_base.was_upped())
+    }
+
+    pub fn data_paths(&self) -> Vec<PathBuf> {
+        self.asdf_base()
+            .map_or(vec![], |asdf_base| asdf_base.data_paths())
+    }
+
+    /// Returns the version to install; when no version is specified,
+    /// the version is detected from the rust-toolchain files if any
+    /// of them exists, or defaults to the latest version otherwise.
+    fn version(&self) -> String {
+        if let Some(version) = &self.version {
+            return version.clone();
+        }
+
+        let current_dir = current_dir();
+        let has_toolchain_file = if self.dirs.is_empty() {
+            find_toolchain_file(&current_dir).is_some()
+        } else {
+            self.dirs
+                .iter()
+                .any(|dir| find_toolchain_file(&current_dir.join(dir)).is_some())
+        };
+
+        if has_toolchain_file {
+            "auto".to_string()
+        } else {
+            "latest".to_string()
+        }
+    }
+
+    pub fn asdf_base(&self) -> Result<&UpConfigAsdfBase, UpError> {
+        self.asdf_base.get_or_try_init(|| {
+            let mut asdf_base =
+                UpConfigAsdfBase::new("rust", &self.version(), self.dirs.clone(), self.upgrade);
+            asdf_base.set_config_value(self.config_value.clone());
+            asdf_base.add_detect_version_func(detect_version_from_rust_toolchain);
+            asdf_base.add_post_install_func(install_components_and_targets);
+            asdf_base.add_post_install_func(setup_individual_cargo_home);
+
+            Ok(asdf_base)
+        })
+    }
+}
+
+fn parse_str_or_list(config_value: Option<ConfigValue>) -> BTreeSet<String> {
+    let mut values = BTreeSet::new();
+
+    if let Some(config_value) = config_value {
+        if let Some(value) = config_value.as_str_forced() {
+            values.insert(value);
+        } else if let Some(array) = config_value.as_array() {
+            for value in array {
+                if let Some(value) = value.as_str_forced() {
+                    values.insert(value);
+                }
+            }
+        }
+    }
+
+    values
+}
+
+fn find_toolchain_file(path: &Path) -> Option<PathBuf> {
+    RUST_TOOLCHAIN_FILES
+        .iter()
+        .map(|file| path.join(file))
+        .find(|file| file.exists() && !file.is_dir())
+}
+
+/// The content of a rust-toolchain file, as described in
+/// https://rust-lang.github.io/rustup/overrides.html#the-toolchain-file
+#[derive(Debug, Default, Clone, PartialEq)]
+struct RustToolchain {
+    channel: Option<String>,
+    components: BTreeSet<String>,
+    targets: BTreeSet<String>,
+}
+
+impl RustToolchain {
+    fn from_path(path: &Path) -> Option<Self> {
+        let file = find_toolchain_file(path)?;
+        let contents = std::fs::read_to_string(file).ok()?;
+        Some(Self::parse(&contents))
+    }
+
+    fn parse(contents: &str) -> Self {
+        let value = match contents.parse::<toml::Table>() {
+            Ok(value) => value,
+            Err(_) => {
+                // The legacy format of the file only contains
+                // the name of the channel
+                let channel = contents.trim();
+                return Self {
+                    channel: (!channel.is_empty()).then(|| channel.to_string()),
+                    ..Self::default()
+                };
+            }
+        };
+
+        let toolchain = match value.get("toolchain").and_then(|t| t.as_table()) {
+            Some(toolchain) => toolchain,
+            None => return Self::default(),
+        };
+
+        let list = |key: &str| -> BTreeSet<String> {
+            toolchain
+                .get(key)
+                .and_then(|values| values.as_array())
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|value| value.as_str().map(|value| value.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        Self {
+            channel: toolchain
+                .get("channel")
+                .and_then(|channel| channel.as_str())
+                .map(|channel| channel.to_string()),
+            components: list("components"),
+            targets: list("targets"),
+        }
+    }
+
+    /// Returns the version to request to asdf for the channel of the
+    /// toolchain; only the stable channel and numbered releases can
+    /// be installed.
+    fn version(&self) -> Option<String> {
+        let channel = self.channel.as_ref()?;
+        if channel == "stable" {
+            Some("latest".to_string())
+        } else if channel.starts_with(|c: char| c.is_ascii_digit()) {
+            Some(channel.clone())
+        } else {
+            None
+        }
+    }
+}
+
+fn detect_version_from_rust_toolchain(_tool_name: String, path: PathBuf) -> Option<String> {
+    RustToolchain::from_path(&path)?.version()
+}
+
+fn install_components_and_targets(
+    progress_handler: &dyn ProgressHandler,
+    options: &UpOptions,
+    config_value: Option<ConfigValue>,
+    tool: String,
+    _tool_real_name: String,
+    _requested_version: String,
+    versions: Vec<AsdfToolUpVersion>,
+) -> Result<(), UpError> {
+    let (config_components, config_targets) = match &config_value {
+        Some(config_value) => (
+            parse_str_or_list(config_value.get("components")),
+            parse_str_or_list(config_value.get("targets")),
+        ),
+        None => (BTreeSet::new(), BTreeSet::new()),
+    };
+
+    let current_dir = current_dir();
+
+    for version in &versions {
+        let mut components = config_components.clone();
+        let mut targets = config_targets.clone();
+
+        for dir in &version.dirs {
+            if let Some(toolchain) = RustToolchain::from_path(&current_dir.join(dir)) {
+                components.extend(toolchain.components);
+                targets.extend(toolchain.targets);
+            }
+        }
+
+        let tool_prefix = asdf_tool_path(&tool, &version.version);
+        for (kind, values) in [("component", components), ("target", targets)] {
+            if values.is_empty() {
+                continue;
+            }
+
+            if options.offline {
+                return Err(UpError::Offline(format!(
+                    "rust {} {}s ({})",
+                    version.version,
+                    kind,
+                    values.iter().cloned().collect::<Vec<_>>().join(", ")
+                )));
+            }
+
+            progress_handler.progress(format!(
+                "installing rust {} {} {}",
+                version.version,
+                kind,
+                values
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                    .light_yellow()
+            ));
+
+            let mut rustup = TokioCommand::new(format!("{}/bin/rustup", tool_prefix));
+            rustup.arg(kind);
+            rustup.arg("add");
+            rustup.args(values);
+            rustup.env("RUSTUP_HOME", &tool_prefix);
+            rustup.env("CARGO_HOME", &tool_prefix);
+            rustup.stdout(std::process::Stdio::piped());
+            rustup.stderr(std::process::Stdio::piped());
+
+            run_progress(&mut rustup, Some(progress_handler), RunConfig::default())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn setup_individual_cargo_home(
+    progress_handler: &dyn ProgressHandler,
+    _options: &UpOptions,
+    _config_value: Option<ConfigValue>,
+    tool: String,
+    tool_real_name: String,
+    _requested_version: String,
+    versions: Vec<AsdfToolUpVersion>,
+) -> Result<(), UpError> {
+    if tool_real_name != "rust" {
+        panic!(
+            "setup_individual_cargo_home called with wrong tool: {}",
+            tool
+        );
+    }
+
+    // Get the data path for the work directory
+    let workdir = workdir(".");
+
+    let workdir_id = match workdir.id() {
+        Some(workdir_id) => workdir_id,
+        None => {
+            return Err(UpError::Exec(format!(
+                "failed to get workdir id for {}",
+                current_dir().display()
+            )));
+        }
+    };
+
+    let data_path = match workdir.data_path() {
+        Some(data_path) => data_path,
+        None => {
+            return Err(UpError::Exec(format!(
+                "failed to get data path for {}",
+                current_dir().display()
+            )));
+        }
+    };
+
+    // Handle each version individually
+    for version in &versions {
+        if let Err(err) = UpEnvironmentsCache::exclusive(|up_env| {
+            let mut any_changed = false;
+            for dir in &version.dirs {
+                let cargo_home = data_path
+                    .join(&tool)
+                    .join(&version.version)
+                    .join(data_path_dir_hash(dir));
+
+                any_changed = up_env.add_version_data_path(
+                    &workdir_id,
+                    &tool,
+                    &version.version,
+                    dir,
+                    &cargo_home.to_string_lossy(),
+                ) || any_changed;
+            }
+            any_changed
+        }) {
+            progress_handler.progress(format!("failed to update tool cache: {}", err));
+            return Err(UpError::Cache(format!(
+                "failed to update tool cache: {}",
+                err
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_toolchain_toml() {
+        let toolchain = RustToolchain::parse(
+            r#"
+            [toolchain]
+            channel = "1.75.0"
+            components = ["rustfmt", "clippy"]
+            targets = ["wasm32-unknown-unknown"]
+            "#,
+        );
+
+        assert_eq!(toolchain.channel, Some("1.75.0".to_string()));
+        assert_eq!(
+            toolchain.components,
+            ["clippy", "rustfmt"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<BTreeSet<_>>()
+        );
+        assert_eq!(
+            toolchain.targets,
+            ["wasm32-unknown-unknown".to_string()].into_iter().collect()
+        );
+        assert_eq!(toolchain.version(), Some("1.75.0".to_string()));
+    }
+
+    #[test]
+    fn test_rust_toolchain_legacy() {
+        let toolchain = RustToolchain::parse("1.70\n");
+        assert_eq!(toolchain.channel, Some("1.70".to_string()));
+        assert!(toolchain.components.is_empty());
+        assert_eq!(toolchain.version(), Some("1.70".to_string()));
+    }
+
+    #[test]
+    fn test_rust_toolchain_channels() {
+        let toolchain = RustToolchain::parse("[toolchain]\nchannel = \"stable\"\n");
+        assert_eq!(toolchain.version(), Some("latest".to_string()));
+
+        let toolchain = RustToolchain::parse("nightly-2024-01-01");
+        assert_eq!(toolchain.version(), None);
+
+        let toolchain = RustToolchain::parse("");
+        assert_eq!(toolchain.version(), None);
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config_value = ConfigValue::from_str(
+            "version: 1.75.0\ncomponents: [rustfmt, clippy]\ntargets: wasm32-unknown-unknown\ndir: some/dir",
+        )
+        .expect("failed to create config value");
+        let config = UpConfigRust::from_config_value(Some(&config_value));
+
+        assert_eq!(config.version, Some("1.75.0".to_string()));
+        assert_eq!(config.components.len(), 2);
+        assert!(config.targets.contains("wasm32-unknown-unknown"));
+        assert!(config.dirs.contains("some/dir"));
+    }
+}
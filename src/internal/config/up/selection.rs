@@ -0,0 +1,172 @@
+use crate::internal::config::up::UpConfigTool;
+
+/// A selector for the steps of the `up` configuration, which can be
+/// the index of the step in the configuration, starting at 1, the type
+/// of the step, or the name of a resource handled by the step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpStepSelector {
+    Index(usize),
+    Name(String),
+}
+
+impl UpStepSelector {
+    pub fn parse(selector: &str) -> Self {
+        let selector = selector.trim();
+        match selector.parse::<usize>() {
+            Ok(index) if index > 0 => UpStepSelector::Index(index),
+            _ => UpStepSelector::Name(selector.to_string()),
+        }
+    }
+
+    /// Returns whether the selector matches the step at the given
+    /// index, starting at 0, of the configuration
+    pub fn matches(&self, index: usize, step: &UpConfigTool) -> bool {
+        match self {
+            UpStepSelector::Index(selected) => *selected == index + 1,
+            UpStepSelector::Name(name) => step.matches_name(name),
+        }
+    }
+}
+
+impl std::fmt::Display for UpStepSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpStepSelector::Index(index) => write!(f, "{}", index),
+            UpStepSelector::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// The selection of the steps to run, from the `--only` and `--skip`
+/// options; when empty, all the steps are selected
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UpStepSelection {
+    only: Vec<UpStepSelector>,
+    skip: Vec<UpStepSelector>,
+}
+
+impl UpStepSelection {
+    pub fn new(only: &[String], skip: &[String]) -> Self {
+        let parse = |selectors: &[String]| {
+            selectors
+                .iter()
+                .flat_map(|selector| selector.split(','))
+                .filter(|selector| !selector.trim().is_empty())
+                .map(UpStepSelector::parse)
+                .collect::<Vec<_>>()
+        };
+
+        Self {
+            only: parse(only),
+            skip: parse(skip),
+        }
+    }
+
+    /// Whether all the steps are selected
+    pub fn is_empty(&self) -> bool {
+        self.only.is_empty() && self.skip.is_empty()
+    }
+
+    /// Returns whether the step at the given index, starting at 0, of
+    /// the configuration is selected
+    pub fn selects(&self, index: usize, step: &UpConfigTool) -> bool {
+        (self.only.is_empty() || self.only.iter().any(|s| s.matches(index, step)))
+            && !self.skip.iter().any(|s| s.matches(index, step))
+    }
+
+    /// Returns the selectors that do not match any of the given steps
+    pub fn unmatched(&self, steps: &[UpConfigTool]) -> Vec<&UpStepSelector> {
+        self.only
+            .iter()
+            .chain(self.skip.iter())
+            .filter(|selector| {
+                !steps
+                    .iter()
+                    .enumerate()
+                    .any(|(index, step)| selector.matches(index, step))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::internal::config::up::UpConfig;
+    use crate::internal::config::ConfigValue;
+
+    fn steps() -> Vec<UpConfigTool> {
+        let config_value = ConfigValue::from_str(concat!(
+            "- github-release: owner/tool\n",
+            "- download:\n",
+            "    name: other\n",
+            "    url: https://example.com/other\n",
+            "- custom:\n",
+            "    name: setup\n",
+            "    meet: echo setup\n",
+            "- github-release:\n",
+            "    owner/another: 1.2.3\n",
+        ))
+        .expect("failed to create config value");
+        UpConfig::from_config_value(Some(config_value))
+            .expect("failed to parse up config")
+            .steps
+    }
+
+    fn selected(selection: &UpStepSelection) -> Vec<usize> {
+        steps()
+            .iter()
+            .enumerate()
+            .filter(|(index, step)| selection.selects(*index, step))
+            .map(|(index, _)| index + 1)
+            .collect()
+    }
+
+    #[test]
+    fn select_all_by_default() {
+        let selection = UpStepSelection::new(&[], &[]);
+        assert!(selection.is_empty());
+        assert_eq!(selected(&selection), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn select_only() {
+        let only = |selectors: &[&str]| {
+            let selectors = selectors.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            selected(&UpStepSelection::new(&selectors, &[]))
+        };
+
+        assert_eq!(only(&["github-release"]), vec![1, 4]);
+        assert_eq!(only(&["owner/another"]), vec![4]);
+        assert_eq!(only(&["other", "3"]), vec![2, 3]);
+        assert_eq!(only(&["setup,1"]), vec![1, 3]);
+        assert_eq!(only(&["unknown"]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn select_skip() {
+        let selection =
+            UpStepSelection::new(&["github-release".to_string()], &["owner/tool".to_string()]);
+        assert_eq!(selected(&selection), vec![4]);
+
+        let selection = UpStepSelection::new(&[], &["download".to_string(), "3".to_string()]);
+        assert_eq!(selected(&selection), vec![1, 4]);
+    }
+
+    #[test]
+    fn unmatched_selectors() {
+        let selection = UpStepSelection::new(
+            &["owner/tool".to_string(), "5".to_string()],
+            &["unknown".to_string()],
+        );
+        assert_eq!(
+            selection
+                .unmatched(&steps())
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>(),
+            vec!["5", "unknown"]
+        );
+    }
+}
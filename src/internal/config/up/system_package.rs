@@ -0,0 +1,804 @@
+use std::collections::HashMap;
+use std::process::Command as StdCommand;
+
+use itertools::Itertools;
+use once_cell::sync::OnceCell;
+use serde::ser::SerializeMap;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::process::Command as TokioCommand;
+
+use crate::internal::cache::CacheObject;
+use crate::internal::cache::SystemPackageOperationCache;
+use crate::internal::config::up::utils::run_progress;
+use crate::internal::config::up::utils::ProgressHandler;
+use crate::internal::config::up::utils::RunConfig;
+use crate::internal::config::up::utils::UpProgressHandler;
+use crate::internal::config::up::UpError;
+use crate::internal::config::up::UpOptions;
+use crate::internal::config::ConfigValue;
+use crate::internal::user_interface::StringColor;
+use crate::internal::workdir;
+
+/// SystemPackageManager represents the system package managers that
+/// omni can use to install packages.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SystemPackageManager {
+    #[default]
+    Apt,
+    Dnf,
+    Pacman,
+}
+
+impl SystemPackageManager {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SystemPackageManager::Apt => "apt",
+            SystemPackageManager::Dnf => "dnf",
+            SystemPackageManager::Pacman => "pacman",
+        }
+    }
+
+    fn binary(&self) -> &'static str {
+        match self {
+            SystemPackageManager::Apt => "apt-get",
+            SystemPackageManager::Dnf => "dnf",
+            SystemPackageManager::Pacman => "pacman",
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        which::which(self.binary()).is_ok()
+    }
+
+    /// Returns the version of the package currently installed on the
+    /// system, or None if the package is not installed.
+    fn installed_version(&self, name: &str) -> Option<String> {
+        let output = match self {
+            SystemPackageManager::Apt => StdCommand::new("dpkg-query")
+                .arg("--show")
+                .arg("--showformat=${Status}\t${Version}")
+                .arg(name)
+                .output(),
+            SystemPackageManager::Dnf => StdCommand::new("rpm")
+                .arg("--query")
+                .arg("--queryformat=%{VERSION}-%{RELEASE}")
+                .arg(name)
+                .output(),
+            SystemPackageManager::Pacman => {
+                StdCommand::new("pacman").arg("--query").arg(name).output()
+            }
+        };
+
+        let output = match output {
+            Ok(output) if output.status.success() => output,
+            _ => return None,
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        match self {
+            SystemPackageManager::Apt => match stdout.split_once('\t') {
+                Some(("install ok installed", version)) => Some(version.to_string()),
+                _ => None,
+            },
+            SystemPackageManager::Dnf => Some(stdout),
+            SystemPackageManager::Pacman => stdout
+                .split_whitespace()
+                .nth(1)
+                .map(|version| version.to_string()),
+        }
+    }
+
+    /// Returns whether the package manager can install a specific
+    /// version of a package; pacman only installs the version of its
+    /// synchronized database.
+    fn supports_versions(&self) -> bool {
+        *self != SystemPackageManager::Pacman
+    }
+
+    /// Returns the identifier to pass to the package manager to
+    /// install the package, including the version if any.
+    fn install_id(&self, package: &SystemPackage) -> String {
+        match (&package.version, self) {
+            (None, _) | (Some(_), SystemPackageManager::Pacman) => package.name.clone(),
+            (Some(version), SystemPackageManager::Dnf) => format!("{}-{}", package.name, version),
+            (Some(version), SystemPackageManager::Apt) => {
+                format!("{}={}", package.name, version)
+            }
+        }
+    }
+
+    /// Prepares a command for the package manager, using sudo if the
+    /// current user is not root and sudo is available.
+    fn command(&self) -> (TokioCommand, RunConfig) {
+        let mut run_config = RunConfig::default();
+
+        let mut command = if whoami::username() != "root" && which::which("sudo").is_ok() {
+            run_config.with_askpass();
+            let mut command = TokioCommand::new("sudo");
+            command.arg("--askpass");
+            command.arg(self.binary());
+            command
+        } else {
+            TokioCommand::new(self.binary())
+        };
+
+        if *self == SystemPackageManager::Apt {
+            command.env("DEBIAN_FRONTEND", "noninteractive");
+        }
+
+        command.stdout(std::process::Stdio::piped());
+        command.stderr(std::process::Stdio::piped());
+
+        (command, run_config)
+    }
+
+    /// Updates the package index of the package manager, if it is
+    /// required for the package manager and if it was not done recently.
+    fn update(&self, options: &UpOptions, progress_handler: &dyn ProgressHandler) {
+        // Only apt requires to update the package index before
+        // installing packages; dnf refreshes its metadata on its own,
+        // and updating pacman's database without upgrading the system
+        // would lead to a partial upgrade
+        if *self != SystemPackageManager::Apt {
+            return;
+        }
+
+        if options.read_cache
+            && !SystemPackageOperationCache::get().should_update_manager(self.name())
+        {
+            return;
+        }
+
+        progress_handler.progress("updating package index".to_string());
+
+        let (mut command, run_config) = self.command();
+        command.arg("update");
+
+        match run_progress(&mut command, Some(progress_handler), run_config) {
+            Ok(_) => {
+                if options.write_cache {
+                    if let Err(err) = SystemPackageOperationCache::exclusive(|cache| {
+                        cache.updated_manager(self.name());
+                        true
+                    }) {
+                        progress_handler.progress(format!("failed to update cache: {}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                progress_handler.progress(format!("failed to update package index: {}", err));
+            }
+        }
+    }
+
+    fn install(
+        &self,
+        packages: &[&SystemPackage],
+        progress_handler: &dyn ProgressHandler,
+    ) -> Result<(), UpError> {
+        let (mut command, run_config) = self.command();
+        match self {
+            SystemPackageManager::Apt => {
+                command.arg("install");
+                command.arg("--yes");
+            }
+            SystemPackageManager::Dnf => {
+                command.arg("install");
+                command.arg("--assumeyes");
+            }
+            SystemPackageManager::Pacman => {
+                command.arg("--sync");
+                command.arg("--needed");
+                command.arg("--noconfirm");
+            }
+        }
+        command.args(packages.iter().map(|package| self.install_id(package)));
+
+        progress_handler.progress(format!(
+            "installing {}",
+            packages
+                .iter()
+                .map(|package| package.name.clone())
+                .join(", ")
+        ));
+
+        run_progress(&mut command, Some(progress_handler), run_config)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum SystemPackageHandled {
+    Handled,
+    Noop,
+}
+
+/// UpConfigSystemPackages represents a list of packages to install
+/// using a system package manager (apt, dnf or pacman).
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct UpConfigSystemPackages {
+    #[serde(skip)]
+    pub manager: SystemPackageManager,
+    #[serde(default = "Vec::new")]
+    pub packages: Vec<SystemPackage>,
+}
+
+impl Serialize for UpConfigSystemPackages {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.packages.serialize(serializer)
+    }
+}
+
+impl UpConfigSystemPackages {
+    pub fn from_config_value(
+        manager: SystemPackageManager,
+        config_value: Option<&ConfigValue>,
+    ) -> Self {
+        let mut packages = Vec::new();
+
+        if let Some(config_value) = config_value {
+            if let Some(array) = config_value.as_array() {
+                for value in array {
+                    packages.extend(SystemPackage::from_config_value(&value));
+                }
+            } else {
+                packages.extend(SystemPackage::from_config_value(config_value));
+            }
+        }
+
+        Self { manager, packages }
+    }
+
+    /// Returns an error if the packages cannot be installed as
+    /// configured with the package manager
+    pub fn validate(&self) -> Result<(), String> {
+        if self.manager.supports_versions() {
+            return Ok(());
+        }
+
+        match self
+            .packages
+            .iter()
+            .find(|package| package.version.is_some())
+        {
+            Some(package) => Err(format!(
+                "{} does not support installing a specific version of a package ({})",
+                self.manager.name(),
+                package.name
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// The names of the packages installed by the step
+    pub fn names(&self) -> Vec<String> {
+        self.packages
+            .iter()
+            .map(|package| package.name.clone())
+            .collect()
+    }
+
+    pub fn up(
+        &self,
+        options: &UpOptions,
+        progress_handler: &UpProgressHandler,
+    ) -> Result<(), UpError> {
+        progress_handler.init(format!("{}:", self.manager.name()).light_blue());
+
+        if !self.is_available() {
+            let msg = format!("{} is not available", self.manager.binary());
+            progress_handler.error_with_message(msg.clone());
+            return Err(UpError::Exec(msg));
+        }
+
+        progress_handler.progress("checking installed packages".to_string());
+
+        let cache = SystemPackageOperationCache::get();
+        let missing = self
+            .packages
+            .iter()
+            .filter(|package| {
+                let known_installed = options.read_cache
+                    && !cache.should_check_install(
+                        self.manager.name(),
+                        &package.name,
+                        package.version.clone(),
+                    );
+
+                !known_installed && !package.is_installed(self.manager)
+            })
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() && options.offline {
+            let err = UpError::Offline(
+                missing
+                    .iter()
+                    .map(|package| self.manager.install_id(package))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+            progress_handler.error_with_message(err.message());
+            return Err(err);
+        }
+
+        if !missing.is_empty() {
+            self.manager.update(options, progress_handler);
+
+            if let Err(err) = self.manager.install(&missing, progress_handler) {
+                progress_handler.error_with_message(err.to_string());
+                return Err(err);
+            }
+        }
+
+        for package in self.packages.iter() {
+            let handled = if missing.iter().any(|m| m.name == package.name) {
+                SystemPackageHandled::Handled
+            } else {
+                SystemPackageHandled::Noop
+            };
+            if package.was_handled.set(handled).is_err() {
+                unreachable!("failed to set was_handled (package: {})", package.name);
+            }
+        }
+
+        if !options.defer_operation_cache {
+            self.update_cache(options, progress_handler);
+        }
+
+        progress_handler.success_with_message(self.get_up_message());
+
+        Ok(())
+    }
+
+    /// Records the packages handled during the current run in the
+    /// operation cache, when it was deferred until the step was verified
+    pub fn update_operation_cache(
+        &self,
+        options: &UpOptions,
+        progress_handler: &dyn ProgressHandler,
+    ) {
+        if self
+            .packages
+            .iter()
+            .any(|package| package.was_handled().is_some())
+        {
+            self.update_cache(options, progress_handler);
+        }
+    }
+
+    fn update_cache(&self, options: &UpOptions, progress_handler: &dyn ProgressHandler) {
+        let workdir = workdir(".");
+        let workdir_id = match workdir.id() {
+            Some(wd_id) => wd_id,
+            None => return,
+        };
+
+        progress_handler.progress("updating cache".to_string());
+
+        if let Err(err) = SystemPackageOperationCache::exclusive(|cache| {
+            let mut updated = false;
+            for package in self.packages.iter() {
+                updated = cache.add_install(
+                    &workdir_id,
+                    self.manager.name(),
+                    &package.name,
+                    package.version.clone(),
+                    package.was_handled() == Some(SystemPackageHandled::Handled),
+                ) || updated;
+
+                if options.write_cache {
+                    cache.checked_install(
+                        self.manager.name(),
+                        &package.name,
+                        package.version.clone(),
+                    );
+                    updated = true;
+                }
+            }
+            updated
+        }) {
+            progress_handler.progress(format!("failed to update cache: {}", err));
+            return;
+        }
+
+        progress_handler.progress("updated cache".to_string());
+    }
+
+    fn get_up_message(&self) -> String {
+        let count: HashMap<SystemPackageHandled, usize> = self
+            .packages
+            .iter()
+            .filter_map(|package| package.was_handled())
+            .fold(HashMap::new(), |mut map, item| {
+                *map.entry(item).or_insert(0) += 1;
+                map
+            });
+
+        let mut numbers = vec![];
+        if let Some(count) = count.get(&SystemPackageHandled::Handled) {
+            numbers.push(format!("+{}", count).green());
+        }
+        if let Some(count) = count.get(&SystemPackageHandled::Noop) {
+            numbers.push(format!("{}", count));
+        }
+
+        if numbers.is_empty() {
+            return "nothing done".to_string();
+        }
+
+        format!(
+            "{} package{} {}",
+            numbers.join(", "),
+            if self.packages.len() > 1 { "s" } else { "" },
+            format!(
+                "({})",
+                self.packages
+                    .iter()
+                    .map(|package| package.name.clone())
+                    .sorted()
+                    .join(", ")
+            )
+            .light_black()
+            .italic(),
+        )
+    }
+
+    pub fn down(&self, progress_handler: &UpProgressHandler) -> Result<(), UpError> {
+        let workdir = workdir(".");
+        let wd_id = match workdir.id() {
+            Some(wd_id) => wd_id,
+            None => return Ok(()),
+        };
+
+        progress_handler.init(format!("{}:", self.manager.name()).light_blue());
+        progress_handler.progress(format!("updating {} dependencies", self.manager.name()));
+
+        if let Err(err) = SystemPackageOperationCache::exclusive(|cache| {
+            let mut updated = false;
+
+            for install in cache.installed.iter_mut().filter(|install| {
+                install.manager == self.manager.name() && install.required_by.contains(&wd_id)
+            }) {
+                install.required_by.retain(|id| id != &wd_id);
+                updated = true;
+            }
+
+            updated
+        }) {
+            progress_handler.progress(format!("failed to update cache: {}", err).light_yellow());
+        }
+
+        // System packages are never uninstalled by omni, as other
+        // software on the system might have started depending on them
+        progress_handler.success_with_message(
+            format!("{} dependencies released", self.manager.name()).light_green(),
+        );
+
+        Ok(())
+    }
+
+    /// Cleanup the references to system packages that are not required
+    /// anymore; this never uninstalls packages from the system.
+    pub fn cleanup(progress_handler: &UpProgressHandler) -> Result<Option<String>, UpError> {
+        let wd = workdir(".");
+        let wd_id = match wd.id() {
+            Some(wd_id) => wd_id,
+            None => return Err(UpError::Exec("failed to get workdir id".to_string())),
+        };
+
+        if let Err(err) = SystemPackageOperationCache::exclusive(|cache| {
+            let mut updated = false;
+
+            // Cleanup the references to this repository for any
+            // package that is not currently listed in the up configuration
+            for install in cache
+                .installed
+                .iter_mut()
+                .filter(|install| install.required_by.contains(&wd_id) && install.stale())
+            {
+                install.required_by.retain(|id| id != &wd_id);
+                updated = true;
+            }
+
+            let removable = cache
+                .installed
+                .iter()
+                .filter(|install| install.removable())
+                .map(|install| {
+                    (
+                        install.manager.clone(),
+                        install.name.clone(),
+                        install.version.clone(),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            for (manager, name, version) in removable {
+                cache.removed_install(&manager, &name, version);
+                updated = true;
+            }
+            cache.installed.retain(|install| !install.removable());
+
+            updated
+        }) {
+            progress_handler.progress(format!("failed to update cache: {}", err).light_yellow());
+        }
+
+        Ok(None)
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.manager.is_available()
+    }
+}
+
+/// SystemPackage represents a single package to install with a
+/// system package manager, optionally pinned to a version.
+#[derive(Debug, Deserialize, Clone)]
+pub struct SystemPackage {
+    pub name: String,
+    pub version: Option<String>,
+    #[serde(skip)]
+    was_handled: OnceCell<SystemPackageHandled>,
+}
+
+impl Serialize for SystemPackage {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match &self.version {
+            None => serializer.serialize_str(&self.name),
+            Some(version) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("package", &self.name)?;
+                map.serialize_entry("version", version)?;
+                map.end()
+            }
+        }
+    }
+}
+
+impl SystemPackage {
+    fn new(name: &str, version: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            version,
+            was_handled: OnceCell::new(),
+        }
+    }
+
+    fn from_config_value(config_value: &ConfigValue) -> Vec<Self> {
+        if let Some(name) = config_value.as_str() {
+            return vec![Self::new(&name, None)];
+        }
+
+        let table = match config_value.as_table() {
+            Some(table) => table,
+            None => return vec![],
+        };
+
+        if let Some(name) = table.get("package").and_then(|name| name.as_str()) {
+            let version = table.get("version").and_then(Self::parse_version);
+            return vec![Self::new(&name, version)];
+        }
+
+        // Otherwise, the table is a map of package names to versions
+        table
+            .iter()
+            .sorted_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(name, version)| Self::new(name, Self::parse_version(version)))
+            .collect()
+    }
+
+    fn parse_version(config_value: &ConfigValue) -> Option<String> {
+        if let Some(version) = config_value.as_str() {
+            Some(version)
+        } else if let Some(version) = config_value.as_integer() {
+            Some(version.to_string())
+        } else {
+            config_value.as_float().map(|version| version.to_string())
+        }
+    }
+
+    fn is_installed(&self, manager: SystemPackageManager) -> bool {
+        match (manager.installed_version(&self.name), &self.version) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(installed), Some(expected)) => {
+                installed == *expected || installed.starts_with(&format!("{}-", expected))
+            }
+        }
+    }
+
+    fn was_handled(&self) -> Option<SystemPackageHandled> {
+        self.was_handled.get().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::os::unix::fs::PermissionsExt;
+
+    use crate::internal::config::up::utils::run_in_workdir;
+    use crate::internal::config::up::utils::run_with_env;
+    use crate::internal::config::up::UpConfigTool;
+    use crate::internal::config::up::UpConfigVerify;
+
+    fn parse(yaml: &str) -> UpConfigSystemPackages {
+        let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+        UpConfigSystemPackages::from_config_value(SystemPackageManager::Apt, Some(&config_value))
+    }
+
+    fn packages(config: &UpConfigSystemPackages) -> Vec<(String, Option<String>)> {
+        config
+            .packages
+            .iter()
+            .map(|package| (package.name.clone(), package.version.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_string() {
+        let config = parse("curl");
+        assert_eq!(packages(&config), vec![("curl".to_string(), None)]);
+    }
+
+    #[test]
+    fn test_parse_list_of_strings() {
+        let config = parse("- curl\n- jq");
+        assert_eq!(
+            packages(&config),
+            vec![("curl".to_string(), None), ("jq".to_string(), None)]
+        );
+    }
+
+    #[test]
+    fn test_parse_package_objects() {
+        let config = parse("- package: curl\n  version: 7.88.1\n- package: jq");
+        assert_eq!(
+            packages(&config),
+            vec![
+                ("curl".to_string(), Some("7.88.1".to_string())),
+                ("jq".to_string(), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_name_version_shorthand() {
+        let config = parse("- curl: 7.88.1\n- jq: 1.6\n- make: 4");
+        assert_eq!(
+            packages(&config),
+            vec![
+                ("curl".to_string(), Some("7.88.1".to_string())),
+                ("jq".to_string(), Some("1.6".to_string())),
+                ("make".to_string(), Some("4".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_none() {
+        let config = UpConfigSystemPackages::from_config_value(SystemPackageManager::Dnf, None);
+        assert!(config.packages.is_empty());
+        assert_eq!(config.manager, SystemPackageManager::Dnf);
+    }
+
+    #[test]
+    fn test_validate() {
+        let config_value = ConfigValue::from_str("- package: curl\n  version: 7.88.1\n- jq")
+            .expect("failed to create config value");
+
+        let config = UpConfigSystemPackages::from_config_value(
+            SystemPackageManager::Pacman,
+            Some(&config_value),
+        );
+        assert_eq!(
+            config.validate(),
+            Err(
+                "pacman does not support installing a specific version of a package (curl)"
+                    .to_string()
+            )
+        );
+
+        let config = UpConfigSystemPackages::from_config_value(
+            SystemPackageManager::Apt,
+            Some(&config_value),
+        );
+        assert_eq!(config.validate(), Ok(()));
+
+        let config = UpConfigSystemPackages::from_config_value(
+            SystemPackageManager::Pacman,
+            Some(&ConfigValue::from_str("- curl\n- jq").unwrap()),
+        );
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_install_id() {
+        let package = SystemPackage::new("curl", Some("7.88.1".to_string()));
+        assert_eq!(
+            SystemPackageManager::Apt.install_id(&package),
+            "curl=7.88.1"
+        );
+        assert_eq!(
+            SystemPackageManager::Dnf.install_id(&package),
+            "curl-7.88.1"
+        );
+        assert_eq!(SystemPackageManager::Pacman.install_id(&package), "curl");
+
+        let package = SystemPackage::new("jq", None);
+        assert_eq!(SystemPackageManager::Apt.install_id(&package), "jq");
+    }
+
+    #[test]
+    fn failed_verification_does_not_record_install() {
+        run_with_env(&[], |tempdir| {
+            // Fake pacman, for which no package is installed and any
+            // package can be installed, and sudo running it directly
+            let bin_path = tempdir.join("bin");
+            std::fs::create_dir_all(&bin_path).expect("failed to create bin dir");
+            for (name, script) in [
+                ("pacman", "[[ \"$1\" == \"--query\" ]] && exit 1\nexit 0\n"),
+                ("sudo", "shift\nexec \"$@\"\n"),
+            ] {
+                let path = bin_path.join(name);
+                std::fs::write(&path, format!("#!/usr/bin/env bash\n{}", script))
+                    .expect("failed to write script");
+                let mut perms = std::fs::metadata(&path)
+                    .expect("failed to get metadata")
+                    .permissions();
+                perms.set_mode(0o755);
+                std::fs::set_permissions(&path, perms).expect("failed to set permissions");
+            }
+
+            run_in_workdir(&tempdir.join("wd"), |wd_id| {
+                let tool = |command: &str| {
+                    let verify = UpConfigVerify {
+                        command: command.to_string(),
+                        exit_code: 0,
+                        output: None,
+                    };
+                    let config_value =
+                        ConfigValue::from_str("jq").expect("failed to create config value");
+                    UpConfigTool::Verify(
+                        verify,
+                        Box::new(UpConfigTool::Pacman(
+                            UpConfigSystemPackages::from_config_value(
+                                SystemPackageManager::Pacman,
+                                Some(&config_value),
+                            ),
+                        )),
+                    )
+                };
+                let required = || {
+                    SystemPackageOperationCache::get()
+                        .installed
+                        .iter()
+                        .any(|install| install.name == "jq" && install.required_by.contains(wd_id))
+                };
+
+                let options = UpOptions::default();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = tool("exit 1").up(&options, &progress_handler);
+                assert!(
+                    matches!(&result, Err(UpError::Exec(err)) if err.starts_with("verification failed")),
+                    "unexpected result: {:?}",
+                    result
+                );
+                assert!(!required(), "package recorded in cache");
+
+                let result = tool("true").up(&options, &progress_handler);
+                assert!(result.is_ok(), "up failed: {:?}", result);
+                assert!(required(), "package not recorded in cache");
+            });
+        });
+    }
+}
@@ -6,23 +6,44 @@ use itertools::Itertools;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::internal::cache::up_environments::UpEnvironment;
+use crate::internal::cache::CacheObject;
+use crate::internal::cache::UpEnvironmentsCache;
 use crate::internal::config::global_config;
+use crate::internal::config::template::config_template_context;
+use crate::internal::config::template::evaluate_template_condition;
+use crate::internal::config::up::github_release::ReleaseForge;
+use crate::internal::config::up::jvm::java_from_config_value;
+use crate::internal::config::up::jvm::kotlin_from_config_value;
+use crate::internal::config::up::plan::UpPlanAction;
+use crate::internal::config::up::plan::UpPlanItem;
+use crate::internal::config::up::utils::ProgressHandler;
 use crate::internal::config::up::utils::UpProgressHandler;
+use crate::internal::config::up::SystemPackageManager;
+use crate::internal::config::up::ToolRegistry;
 use crate::internal::config::up::UpConfig;
 use crate::internal::config::up::UpConfigAsdfBase;
 use crate::internal::config::up::UpConfigAsdfBaseParams;
 use crate::internal::config::up::UpConfigBundler;
+use crate::internal::config::up::UpConfigCargoInstalls;
 use crate::internal::config::up::UpConfigCustom;
+use crate::internal::config::up::UpConfigDownloads;
 use crate::internal::config::up::UpConfigGithubReleases;
 use crate::internal::config::up::UpConfigGolang;
 use crate::internal::config::up::UpConfigHomebrew;
 use crate::internal::config::up::UpConfigNix;
 use crate::internal::config::up::UpConfigNodejs;
 use crate::internal::config::up::UpConfigPython;
+use crate::internal::config::up::UpConfigRetry;
+use crate::internal::config::up::UpConfigRust;
+use crate::internal::config::up::UpConfigSystemPackages;
+use crate::internal::config::up::UpConfigVerify;
 use crate::internal::config::up::UpError;
 use crate::internal::config::up::UpOptions;
 use crate::internal::config::ConfigValue;
 use crate::internal::dynenv::update_dynamic_env_for_command;
+use crate::internal::user_interface::StringColor;
+use crate::internal::workdir;
 
 /// UpConfigTool represents a tool that can be upped or downed.
 /// It can be a single tool or a combination of tools.
@@ -43,30 +64,61 @@ pub enum UpConfigTool {
     /// defined in the other types for special handling.
     Asdf(UpConfigAsdfBase),
 
-    // TODO: Apt(UpConfigApt),
+    /// Apt represents the apt package manager, which can be used to
+    /// install system packages on Debian-based systems.
+    Apt(UpConfigSystemPackages),
+
     /// Bash represents the bash tool.
     Bash(UpConfigAsdfBase),
 
     /// Bundler represents the bundler tool.
     Bundler(UpConfigBundler),
 
+    /// CargoInstall represents a tool that can be installed from
+    /// a crate using `cargo install`.
+    CargoInstall(UpConfigCargoInstalls),
+
     /// Custom represents a custom tool, where the user can define
     /// a custom command to run to up/down the tool.
     Custom(UpConfigCustom),
 
-    // TODO: Dnf(UpConfigDnf),
+    /// Dnf represents the dnf package manager, which can be used to
+    /// install system packages on Fedora-based systems.
+    Dnf(UpConfigSystemPackages),
+
+    /// Download represents a tool that can be downloaded from a
+    /// templated URL and verified with a checksum.
+    Download(UpConfigDownloads),
+
+    /// GiteaRelease represents a tool that can be installed from
+    /// a gitea release.
+    GiteaRelease(UpConfigGithubReleases),
+
     /// GithubRelease represents a tool that can be installed from
     /// a github release.
     GithubRelease(UpConfigGithubReleases),
 
+    /// GitlabRelease represents a tool that can be installed from
+    /// a gitlab release.
+    GitlabRelease(UpConfigGithubReleases),
+
     /// Go represents the golang tool.
     Go(UpConfigGolang),
 
     /// Homebrew represents the homebrew tool.
     Homebrew(UpConfigHomebrew),
 
-    // TODO: Java(UpConfigAsdfBase), // JAVA_HOME
-    // TODO: Kotlin(UpConfigAsdfBase), // KOTLIN_HOME
+    /// If represents a tool that is only upped when its condition,
+    /// provided as a tera expression or template, evaluates to true
+    /// in the context of the work directory.
+    If(String, Box<UpConfigTool>),
+
+    /// Java represents the java tool.
+    Java(UpConfigAsdfBase),
+
+    /// Kotlin represents the kotlin tool.
+    Kotlin(UpConfigAsdfBase),
+
     /// Nix represents the nix tool, which can be used to install
     /// packages from the nix package manager.
     Nix(UpConfigNix),
@@ -79,9 +131,24 @@ pub enum UpConfigTool {
     /// only try the others if the first one fails.
     Or(Vec<UpConfigTool>),
 
-    // TODO: Pacman(UpConfigPacman),
+    /// Pacman represents the pacman package manager, which can be used
+    /// to install system packages on Arch-based systems.
+    Pacman(UpConfigSystemPackages),
+
     /// Python represents the python tool.
     Python(UpConfigPython),
+
+    /// Retry represents a tool with its own timeout and retries for
+    /// the network requests and commands it runs.
+    Retry(UpConfigRetry, Box<UpConfigTool>),
+
+    /// Rust represents the rust tool.
+    Rust(UpConfigRust),
+
+    /// Verify represents a tool that is verified, once upped, by running
+    /// a command in the dynamic environment of the work directory; the
+    /// tool is rolled back if the verification fails.
+    Verify(UpConfigVerify, Box<UpConfigTool>),
 }
 
 // Generic function to create a hashmap with a single key/value pair.
@@ -104,22 +171,73 @@ impl Serialize for UpConfigTool {
             UpConfigTool::Asdf(config) => {
                 create_hashmap(&config.name(), config).serialize(serializer)
             }
+            UpConfigTool::Apt(config) => create_hashmap("apt", config).serialize(serializer),
             UpConfigTool::Bash(config) => create_hashmap("bash", config).serialize(serializer),
             UpConfigTool::Bundler(config) => {
                 create_hashmap("bundler", config).serialize(serializer)
             }
+            UpConfigTool::CargoInstall(config) => {
+                create_hashmap("cargo-install", config).serialize(serializer)
+            }
             UpConfigTool::Custom(config) => create_hashmap("custom", config).serialize(serializer),
+            UpConfigTool::Dnf(config) => create_hashmap("dnf", config).serialize(serializer),
+            UpConfigTool::Download(config) => {
+                create_hashmap("download", config).serialize(serializer)
+            }
+            UpConfigTool::GiteaRelease(config) => {
+                create_hashmap("gitea-release", config).serialize(serializer)
+            }
             UpConfigTool::GithubRelease(config) => {
                 create_hashmap("github-release", config).serialize(serializer)
             }
+            UpConfigTool::GitlabRelease(config) => {
+                create_hashmap("gitlab-release", config).serialize(serializer)
+            }
             UpConfigTool::Go(config) => create_hashmap("go", config).serialize(serializer),
             UpConfigTool::Homebrew(config) => {
                 create_hashmap("homebrew", config).serialize(serializer)
             }
+            UpConfigTool::If(condition, config) => {
+                // A conditional tool is the configuration of the tool
+                // with an additional `if` key
+                let mut value = serde_yaml::to_value(config).map_err(serde::ser::Error::custom)?;
+                if let serde_yaml::Value::Mapping(mapping) = &mut value {
+                    mapping.insert("if".into(), condition.as_str().into());
+                }
+                value.serialize(serializer)
+            }
+            UpConfigTool::Java(config) => create_hashmap("java", config).serialize(serializer),
+            UpConfigTool::Kotlin(config) => create_hashmap("kotlin", config).serialize(serializer),
             UpConfigTool::Nix(config) => create_hashmap("nix", config).serialize(serializer),
             UpConfigTool::Nodejs(config) => create_hashmap("nodejs", config).serialize(serializer),
             UpConfigTool::Or(configs) => create_hashmap("or", configs).serialize(serializer),
+            UpConfigTool::Pacman(config) => create_hashmap("pacman", config).serialize(serializer),
             UpConfigTool::Python(config) => create_hashmap("python", config).serialize(serializer),
+            UpConfigTool::Retry(retry, config) => {
+                // A tool with its own retry policy is the configuration
+                // of the tool with additional `retries` and `timeout` keys
+                let mut value = serde_yaml::to_value(config).map_err(serde::ser::Error::custom)?;
+                if let serde_yaml::Value::Mapping(mapping) = &mut value {
+                    if let Some(retries) = retry.retries {
+                        mapping.insert("retries".into(), retries.into());
+                    }
+                    if let Some(timeout) = retry.timeout {
+                        mapping.insert("timeout".into(), timeout.into());
+                    }
+                }
+                value.serialize(serializer)
+            }
+            UpConfigTool::Rust(config) => create_hashmap("rust", config).serialize(serializer),
+            UpConfigTool::Verify(verify, config) => {
+                // A verified tool is the configuration of the tool
+                // with an additional `verify` key
+                let mut value = serde_yaml::to_value(config).map_err(serde::ser::Error::custom)?;
+                if let serde_yaml::Value::Mapping(mapping) = &mut value {
+                    let verify = serde_yaml::to_value(verify).map_err(serde::ser::Error::custom)?;
+                    mapping.insert("verify".into(), verify);
+                }
+                value.serialize(serializer)
+            }
         }
     }
 }
@@ -144,42 +262,81 @@ impl UpConfigTool {
                     }
                 }
             }
+            "apt" | "apt-get" => Some(UpConfigTool::Apt(
+                UpConfigSystemPackages::from_config_value(SystemPackageManager::Apt, config_value),
+            )),
             "bash" => Some(UpConfigTool::Bash(
                 UpConfigAsdfBase::from_config_value_with_params(
                     "bash",
                     config_value,
                     UpConfigAsdfBaseParams {
                         tool_url: Some("https://github.com/xaf/asdf-bash".into()),
+                        ..UpConfigAsdfBaseParams::default()
                     },
                 ),
             )),
             "bundler" | "bundle" => Some(UpConfigTool::Bundler(
                 UpConfigBundler::from_config_value(config_value),
             )),
+            "cargo-install" | "cargo_install" | "cargoinstall" => Some(UpConfigTool::CargoInstall(
+                UpConfigCargoInstalls::from_config_value(config_value),
+            )),
             "custom" => Some(UpConfigTool::Custom(UpConfigCustom::from_config_value(
                 config_value,
             ))),
+            "dnf" => Some(UpConfigTool::Dnf(
+                UpConfigSystemPackages::from_config_value(SystemPackageManager::Dnf, config_value),
+            )),
+            "download" => Some(UpConfigTool::Download(
+                UpConfigDownloads::from_config_value(config_value),
+            )),
+            "gitea-release" | "gitea_release" | "gitearelease" | "gitea-releases"
+            | "gitea_releases" | "giteareleases" => Some(UpConfigTool::GiteaRelease(
+                UpConfigGithubReleases::from_config_value_with_forge(
+                    config_value,
+                    ReleaseForge::Gitea,
+                ),
+            )),
             "github-release" | "github_release" | "githubrelease" | "ghrelease"
             | "github-releases" | "github_releases" | "githubreleases" | "ghreleases" => {
                 Some(UpConfigTool::GithubRelease(
                     UpConfigGithubReleases::from_config_value(config_value),
                 ))
             }
+            "gitlab-release" | "gitlab_release" | "gitlabrelease" | "glrelease"
+            | "gitlab-releases" | "gitlab_releases" | "gitlabreleases" | "glreleases" => Some(
+                UpConfigTool::GitlabRelease(UpConfigGithubReleases::from_config_value_with_forge(
+                    config_value,
+                    ReleaseForge::Gitlab,
+                )),
+            ),
             "go" | "golang" => Some(UpConfigTool::Go(UpConfigGolang::from_config_value(
                 config_value,
             ))),
             "homebrew" | "brew" => Some(UpConfigTool::Homebrew(
                 UpConfigHomebrew::from_config_value(config_value),
             )),
+            "java" => Some(UpConfigTool::Java(java_from_config_value(config_value))),
+            "kotlin" => Some(UpConfigTool::Kotlin(kotlin_from_config_value(config_value))),
             "nix" => Some(UpConfigTool::Nix(UpConfigNix::from_config_value(
                 config_value,
             ))),
             "nodejs" | "node" => Some(UpConfigTool::Nodejs(UpConfigNodejs::from_config_value(
                 config_value,
             ))),
+            "pacman" => Some(UpConfigTool::Pacman(
+                UpConfigSystemPackages::from_config_value(
+                    SystemPackageManager::Pacman,
+                    config_value,
+                ),
+            )),
             "python" => Some(UpConfigTool::Python(UpConfigPython::from_config_value(
                 config_value,
             ))),
+            "rust" => Some(UpConfigTool::Rust(UpConfigRust::from_config_value(
+                config_value,
+            ))),
+            "tool" | "tools" => ToolRegistry::load().expand(config_value?).ok(),
             _ => Some(UpConfigTool::Asdf(UpConfigAsdfBase::from_config_value(
                 up_name,
                 config_value,
@@ -187,13 +344,41 @@ impl UpConfigTool {
         }
     }
 
+    /// Returns an error if the configuration of the tool, while
+    /// parsed, cannot be used to up the tool
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            UpConfigTool::And(configs) | UpConfigTool::Any(configs) | UpConfigTool::Or(configs) => {
+                configs.iter().try_for_each(|config| config.validate())
+            }
+            UpConfigTool::Apt(config)
+            | UpConfigTool::Dnf(config)
+            | UpConfigTool::Pacman(config) => config.validate(),
+            UpConfigTool::GiteaRelease(config)
+            | UpConfigTool::GithubRelease(config)
+            | UpConfigTool::GitlabRelease(config) => config.validate(),
+            UpConfigTool::If(_, config)
+            | UpConfigTool::Retry(_, config)
+            | UpConfigTool::Verify(_, config) => config.validate(),
+            _ => Ok(()),
+        }
+    }
+
     pub fn up(
         &self,
         options: &UpOptions,
         progress_handler: &UpProgressHandler,
     ) -> Result<(), UpError> {
         match self {
-            UpConfigTool::And(_) | UpConfigTool::Any(_) | UpConfigTool::Or(_) => {}
+            UpConfigTool::And(_)
+            | UpConfigTool::Any(_)
+            | UpConfigTool::If(..)
+            | UpConfigTool::Or(_)
+            | UpConfigTool::Retry(..)
+            | UpConfigTool::Verify(..) => {}
+            // Steps running concurrently do not depend on the environment,
+            // and should not update it since it is shared by all the threads
+            _ if options.concurrent => {}
             _ => {
                 // Update the dynamic environment so that if anything has changed
                 // the command can consider it right away
@@ -220,13 +405,34 @@ impl UpConfigTool {
                 }
                 result
             }
+            UpConfigTool::Apt(config) => config.up(options, progress_handler),
             UpConfigTool::Asdf(config) => config.up(options, progress_handler),
             UpConfigTool::Bash(config) => config.up(options, progress_handler),
-            UpConfigTool::Bundler(config) => config.up(progress_handler),
+            UpConfigTool::Bundler(config) => config.up(options, progress_handler),
+            UpConfigTool::CargoInstall(config) => config.up(options, progress_handler),
             UpConfigTool::Custom(config) => config.up(progress_handler),
+            UpConfigTool::Dnf(config) => config.up(options, progress_handler),
+            UpConfigTool::Download(config) => config.up(options, progress_handler),
+            UpConfigTool::GiteaRelease(config) => config.up(options, progress_handler),
             UpConfigTool::GithubRelease(config) => config.up(options, progress_handler),
+            UpConfigTool::GitlabRelease(config) => config.up(options, progress_handler),
             UpConfigTool::Go(config) => config.up(options, progress_handler),
             UpConfigTool::Homebrew(config) => config.up(options, progress_handler),
+            UpConfigTool::If(condition, config) => match evaluate_condition(condition) {
+                Ok(true) => config.up(options, progress_handler),
+                Ok(false) => {
+                    progress_handler.init(format!("{}:", config.to_name()).light_blue());
+                    progress_handler.success_with_message("skipped: condition false".light_black());
+                    Ok(())
+                }
+                Err(err) => {
+                    progress_handler.init(format!("{}:", config.to_name()).light_blue());
+                    progress_handler.error_with_message(err.message());
+                    Err(err)
+                }
+            },
+            UpConfigTool::Java(config) => config.up(options, progress_handler),
+            UpConfigTool::Kotlin(config) => config.up(options, progress_handler),
             UpConfigTool::Nix(config) => config.up(options, progress_handler),
             UpConfigTool::Nodejs(config) => config.up(options, progress_handler),
             UpConfigTool::Or(configs) => {
@@ -243,7 +449,223 @@ impl UpConfigTool {
                 }
                 result
             }
+            UpConfigTool::Pacman(config) => config.up(options, progress_handler),
             UpConfigTool::Python(config) => config.up(options, progress_handler),
+            UpConfigTool::Retry(retry, config) => {
+                let options = options.clone().retry(retry.apply(&options.retry));
+                config.up(&options, progress_handler)
+            }
+            UpConfigTool::Rust(config) => config.up(options, progress_handler),
+            UpConfigTool::Verify(verify, config) => {
+                Self::up_verified(verify, config, options, progress_handler)
+            }
+        }
+    }
+
+    /// Ups the tool and runs the verification command in the dynamic
+    /// environment resulting from it; if the verification fails, what
+    /// the tool installed during this run and the changes it made to
+    /// the environment are rolled back, and the step fails. For the
+    /// tools that cannot be rolled back, what they installed is only
+    /// recorded in the operation caches once verified.
+    fn up_verified(
+        verify: &UpConfigVerify,
+        config: &UpConfigTool,
+        options: &UpOptions,
+        progress_handler: &UpProgressHandler,
+    ) -> Result<(), UpError> {
+        let wd_id = workdir(".").id();
+        let current_env = |wd_id: &str| {
+            UpEnvironmentsCache::get()
+                .get_env(wd_id)
+                .cloned()
+                .unwrap_or_else(UpEnvironment::new)
+        };
+        let before = wd_id.as_deref().map(current_env);
+
+        // The tool reports its progress without ending the step, since
+        // the step is only successful once verified
+        let tool_handler = progress_handler.deferred();
+        let defer_operation_cache = !config.can_rollback();
+        let tool_options = UpOptions {
+            defer_operation_cache,
+            ..options.clone()
+        };
+        if let Err(err) = config.up(&tool_options, &tool_handler) {
+            progress_handler.error();
+            return Err(err);
+        }
+
+        progress_handler.progress(format!("verifying: {}", verify.command));
+        update_dynamic_env_for_command(".");
+
+        let err = match verify.run() {
+            Ok(()) => {
+                if defer_operation_cache {
+                    config.update_operation_cache(options, progress_handler);
+                }
+
+                let message = match tool_handler.deferred_message() {
+                    Some(message) => format!("{} {}", message, "(verified)".light_black()),
+                    None => "verified".light_green(),
+                };
+                progress_handler.success_with_message(message);
+                return Ok(());
+            }
+            Err(err) => err,
+        };
+
+        config.rollback(progress_handler);
+
+        if let (Some(wd_id), Some(before)) = (&wd_id, &before) {
+            let changes = current_env(wd_id).changes_since(before);
+            if let Err(err) =
+                UpEnvironmentsCache::exclusive(|up_env| up_env.revert_changes(wd_id, &changes))
+            {
+                progress_handler.progress(format!("failed to update cache: {}", err));
+            }
+        }
+        update_dynamic_env_for_command(".");
+
+        let errmsg = format!("verification failed: {}", err);
+        progress_handler.error_with_message(errmsg.clone());
+        Err(UpError::Exec(errmsg))
+    }
+
+    /// Whether what the tool installs can be rolled back; this is only
+    /// the case for the tools installing resources that are managed by
+    /// omni, as opposed to system packages, homebrew or nix packages,
+    /// or the results of custom commands.
+    pub fn can_rollback(&self) -> bool {
+        match self {
+            UpConfigTool::And(configs) | UpConfigTool::Any(configs) | UpConfigTool::Or(configs) => {
+                configs.iter().all(|config| config.can_rollback())
+            }
+            UpConfigTool::Asdf(_)
+            | UpConfigTool::Bash(_)
+            | UpConfigTool::CargoInstall(_)
+            | UpConfigTool::Download(_)
+            | UpConfigTool::GiteaRelease(_)
+            | UpConfigTool::GithubRelease(_)
+            | UpConfigTool::GitlabRelease(_)
+            | UpConfigTool::Go(_)
+            | UpConfigTool::Java(_)
+            | UpConfigTool::Kotlin(_)
+            | UpConfigTool::Nodejs(_)
+            | UpConfigTool::Python(_)
+            | UpConfigTool::Rust(_) => true,
+            UpConfigTool::If(_, config)
+            | UpConfigTool::Retry(_, config)
+            | UpConfigTool::Verify(_, config) => config.can_rollback(),
+            UpConfigTool::Apt(_)
+            | UpConfigTool::Bundler(_)
+            | UpConfigTool::Custom(_)
+            | UpConfigTool::Dnf(_)
+            | UpConfigTool::Homebrew(_)
+            | UpConfigTool::Nix(_)
+            | UpConfigTool::Pacman(_) => false,
+        }
+    }
+
+    /// Records what the tool installed during the current run in the
+    /// operation caches, for the tools that [cannot be rolled
+    /// back](Self::can_rollback) and deferred it until verified
+    pub fn update_operation_cache(
+        &self,
+        options: &UpOptions,
+        progress_handler: &dyn ProgressHandler,
+    ) {
+        match self {
+            UpConfigTool::And(configs) | UpConfigTool::Any(configs) | UpConfigTool::Or(configs) => {
+                for config in configs {
+                    config.update_operation_cache(options, progress_handler);
+                }
+            }
+            UpConfigTool::Apt(config)
+            | UpConfigTool::Dnf(config)
+            | UpConfigTool::Pacman(config) => {
+                config.update_operation_cache(options, progress_handler)
+            }
+            UpConfigTool::Homebrew(config) => config.update_operation_cache(progress_handler),
+            UpConfigTool::If(_, config)
+            | UpConfigTool::Retry(_, config)
+            | UpConfigTool::Verify(_, config) => {
+                config.update_operation_cache(options, progress_handler)
+            }
+            _ => {}
+        }
+    }
+
+    /// Removes what the tool installed during the current run, for the
+    /// tools that [can be rolled back](Self::can_rollback)
+    pub fn rollback(&self, progress_handler: &dyn ProgressHandler) {
+        match self {
+            UpConfigTool::And(configs) | UpConfigTool::Any(configs) | UpConfigTool::Or(configs) => {
+                for config in configs {
+                    config.rollback(progress_handler);
+                }
+            }
+            UpConfigTool::Asdf(config)
+            | UpConfigTool::Bash(config)
+            | UpConfigTool::Java(config)
+            | UpConfigTool::Kotlin(config) => config.rollback(progress_handler),
+            UpConfigTool::CargoInstall(config) => config.rollback(progress_handler),
+            UpConfigTool::Download(config) => config.rollback(progress_handler),
+            UpConfigTool::GiteaRelease(config)
+            | UpConfigTool::GithubRelease(config)
+            | UpConfigTool::GitlabRelease(config) => config.rollback(progress_handler),
+            UpConfigTool::Go(config) => config.rollback(progress_handler),
+            UpConfigTool::If(_, config)
+            | UpConfigTool::Retry(_, config)
+            | UpConfigTool::Verify(_, config) => config.rollback(progress_handler),
+            UpConfigTool::Nodejs(config) => config.rollback(progress_handler),
+            UpConfigTool::Python(config) => config.rollback(progress_handler),
+            UpConfigTool::Rust(config) => config.rollback(progress_handler),
+            UpConfigTool::Apt(_)
+            | UpConfigTool::Bundler(_)
+            | UpConfigTool::Custom(_)
+            | UpConfigTool::Dnf(_)
+            | UpConfigTool::Homebrew(_)
+            | UpConfigTool::Nix(_)
+            | UpConfigTool::Pacman(_) => {}
+        }
+    }
+
+    /// The resources, as `(step, name)` pairs, for which the tool may
+    /// lock versions; this only depends on the configuration, so that
+    /// the resources of the tools that are not run, e.g. because their
+    /// condition is false, are also returned
+    pub fn lock_keys(&self) -> Vec<(String, String)> {
+        match self {
+            UpConfigTool::And(configs) | UpConfigTool::Any(configs) | UpConfigTool::Or(configs) => {
+                configs
+                    .iter()
+                    .flat_map(|config| config.lock_keys())
+                    .collect()
+            }
+            UpConfigTool::Asdf(config)
+            | UpConfigTool::Bash(config)
+            | UpConfigTool::Java(config)
+            | UpConfigTool::Kotlin(config) => config.lock_keys(),
+            UpConfigTool::CargoInstall(config) => config.lock_keys(),
+            UpConfigTool::Download(config) => config.lock_keys(),
+            UpConfigTool::GiteaRelease(config)
+            | UpConfigTool::GithubRelease(config)
+            | UpConfigTool::GitlabRelease(config) => config.lock_keys(),
+            UpConfigTool::Go(config) => config.lock_keys(),
+            UpConfigTool::If(_, config)
+            | UpConfigTool::Retry(_, config)
+            | UpConfigTool::Verify(_, config) => config.lock_keys(),
+            UpConfigTool::Nodejs(config) => config.lock_keys(),
+            UpConfigTool::Python(config) => config.lock_keys(),
+            UpConfigTool::Rust(config) => config.lock_keys(),
+            UpConfigTool::Apt(_)
+            | UpConfigTool::Bundler(_)
+            | UpConfigTool::Custom(_)
+            | UpConfigTool::Dnf(_)
+            | UpConfigTool::Homebrew(_)
+            | UpConfigTool::Nix(_)
+            | UpConfigTool::Pacman(_) => vec![],
         }
     }
 
@@ -255,26 +677,133 @@ impl UpConfigTool {
                 }
                 Ok(())
             }
+            UpConfigTool::Apt(config) => config.down(progress_handler),
             UpConfigTool::Asdf(config) => config.down(progress_handler),
             UpConfigTool::Bash(config) => config.down(progress_handler),
             UpConfigTool::Bundler(config) => config.down(progress_handler),
+            UpConfigTool::CargoInstall(config) => config.down(progress_handler),
             UpConfigTool::Custom(config) => config.down(progress_handler),
+            UpConfigTool::Dnf(config) => config.down(progress_handler),
+            UpConfigTool::Download(config) => config.down(progress_handler),
+            UpConfigTool::GiteaRelease(config) => config.down(progress_handler),
             UpConfigTool::GithubRelease(config) => config.down(progress_handler),
+            UpConfigTool::GitlabRelease(config) => config.down(progress_handler),
             UpConfigTool::Go(config) => config.down(progress_handler),
             UpConfigTool::Homebrew(config) => config.down(progress_handler),
+            // The condition might have changed since the step was upped,
+            // so we always down the tool to make sure nothing is left behind
+            UpConfigTool::If(_, config) => config.down(progress_handler),
+            UpConfigTool::Java(config) => config.down(progress_handler),
+            UpConfigTool::Kotlin(config) => config.down(progress_handler),
             UpConfigTool::Nix(config) => config.down(progress_handler),
             UpConfigTool::Nodejs(config) => config.down(progress_handler),
+            UpConfigTool::Pacman(config) => config.down(progress_handler),
             UpConfigTool::Python(config) => config.down(progress_handler),
+            UpConfigTool::Retry(_, config) => config.down(progress_handler),
+            UpConfigTool::Rust(config) => config.down(progress_handler),
+            UpConfigTool::Verify(_, config) => config.down(progress_handler),
         }
     }
 
+    /// Computes what `up` would do for this step, without touching
+    /// the filesystem; steps that cannot be resolved without being
+    /// run are planned with the `Run` action.
+    pub fn plan(&self, options: &UpOptions, current: Option<&UpEnvironment>) -> Vec<UpPlanItem> {
+        let name = self.to_name();
+
+        if !self.is_available() {
+            return vec![UpPlanItem::new(&name, UpPlanAction::Skip)];
+        }
+
+        let items = match self {
+            UpConfigTool::And(configs) => {
+                return configs
+                    .iter()
+                    .flat_map(|config| config.plan(options, current))
+                    .collect();
+            }
+            UpConfigTool::Any(configs) => {
+                return ordered_configs(configs)
+                    .into_iter()
+                    .find(|config| config.is_available())
+                    .map(|config| config.plan(options, current))
+                    .unwrap_or_default();
+            }
+            UpConfigTool::Or(configs) => {
+                return configs
+                    .iter()
+                    .find(|config| config.is_available())
+                    .map(|config| config.plan(options, current))
+                    .unwrap_or_default();
+            }
+            UpConfigTool::Retry(_, config) | UpConfigTool::Verify(_, config) => {
+                return config.plan(options, current)
+            }
+            UpConfigTool::If(condition, config) => {
+                return match evaluate_condition(condition) {
+                    Ok(true) => config.plan(options, current),
+                    Ok(false) => {
+                        let mut item = UpPlanItem::new(&name, UpPlanAction::Skip);
+                        item.step = name.clone();
+                        vec![item]
+                    }
+                    Err(err) => {
+                        let mut item = UpPlanItem::new(&name, UpPlanAction::Unknown(err.message()));
+                        item.step = name.clone();
+                        vec![item]
+                    }
+                };
+            }
+            UpConfigTool::Asdf(config)
+            | UpConfigTool::Bash(config)
+            | UpConfigTool::Java(config)
+            | UpConfigTool::Kotlin(config) => vec![config.plan(options, current)],
+            UpConfigTool::CargoInstall(config) => config.plan(options, current),
+            UpConfigTool::Download(config) => config.plan(options, current),
+            UpConfigTool::GiteaRelease(config)
+            | UpConfigTool::GithubRelease(config)
+            | UpConfigTool::GitlabRelease(config) => config.plan(options, current),
+            UpConfigTool::Go(config) => match config.asdf_base() {
+                Ok(asdf_base) => vec![asdf_base.plan(options, current)],
+                Err(err) => vec![UpPlanItem::new(&name, UpPlanAction::Unknown(err.message()))],
+            },
+            UpConfigTool::Nodejs(config) => vec![config.asdf_base.plan(options, current)],
+            UpConfigTool::Python(config) => vec![config.asdf_base.plan(options, current)],
+            UpConfigTool::Rust(config) => match config.asdf_base() {
+                Ok(asdf_base) => vec![asdf_base.plan(options, current)],
+                Err(err) => vec![UpPlanItem::new(&name, UpPlanAction::Unknown(err.message()))],
+            },
+            UpConfigTool::Apt(_)
+            | UpConfigTool::Bundler(_)
+            | UpConfigTool::Custom(_)
+            | UpConfigTool::Dnf(_)
+            | UpConfigTool::Homebrew(_)
+            | UpConfigTool::Nix(_)
+            | UpConfigTool::Pacman(_) => vec![UpPlanItem::new(&name, UpPlanAction::Run)],
+        };
+
+        items
+            .into_iter()
+            .map(|mut item| {
+                item.step = name.clone();
+                item
+            })
+            .collect()
+    }
+
     pub fn is_available(&self) -> bool {
         match self {
             UpConfigTool::And(configs) | UpConfigTool::Any(configs) | UpConfigTool::Or(configs) => {
                 any(configs, |config| config.is_available())
             }
+            UpConfigTool::Apt(config)
+            | UpConfigTool::Dnf(config)
+            | UpConfigTool::Pacman(config) => config.is_available(),
             UpConfigTool::Homebrew(config) => config.is_available(),
             UpConfigTool::Nix(config) => config.is_available(),
+            UpConfigTool::If(_, config)
+            | UpConfigTool::Retry(_, config)
+            | UpConfigTool::Verify(_, config) => config.is_available(),
             _ => true,
         }
     }
@@ -282,10 +811,38 @@ impl UpConfigTool {
     pub fn dir(&self) -> Option<String> {
         match self {
             UpConfigTool::Custom(config) => config.dir(),
+            UpConfigTool::If(_, config)
+            | UpConfigTool::Retry(_, config)
+            | UpConfigTool::Verify(_, config) => config.dir(),
             _ => None,
         }
     }
 
+    /// Whether the step can run at the same time as other steps; this is
+    /// only the case for steps that download files to the data directory,
+    /// as they do not depend on the environment set up by previous steps,
+    /// nor change the state of shared tools like asdf or package managers.
+    /// Language tools are thus excluded, as they run commands in the work
+    /// directory and in the environment set up by the previous steps.
+    pub fn can_run_concurrently(&self) -> bool {
+        match self {
+            UpConfigTool::And(configs) | UpConfigTool::Any(configs) | UpConfigTool::Or(configs) => {
+                configs.iter().all(|config| config.can_run_concurrently())
+            }
+            UpConfigTool::Download(_)
+            | UpConfigTool::GiteaRelease(_)
+            | UpConfigTool::GithubRelease(_)
+            | UpConfigTool::GitlabRelease(_) => true,
+            UpConfigTool::If(_, config) | UpConfigTool::Retry(_, config) => {
+                config.can_run_concurrently()
+            }
+            // The verification runs in the dynamic environment, which is
+            // shared by all the threads
+            UpConfigTool::Verify(..) => false,
+            _ => false,
+        }
+    }
+
     pub fn was_upped(&self) -> bool {
         match self {
             UpConfigTool::And(configs) | UpConfigTool::Any(configs) | UpConfigTool::Or(configs) => {
@@ -294,17 +851,46 @@ impl UpConfigTool {
             UpConfigTool::Asdf(config) => config.was_upped(),
             UpConfigTool::Bash(config) => config.was_upped(),
             // UpConfigTool::Bundler(config) => config.was_upped(),
-            // UpConfigTool::Custom(config) => config.was_upped(),
+            UpConfigTool::Custom(config) => config.was_upped(),
             // UpConfigTool::GithubRelease(config) => config.was_upped(),
             UpConfigTool::Go(config) => config.was_upped(),
             // UpConfigTool::Homebrew(config) => config.was_upped(),
+            UpConfigTool::If(_, config)
+            | UpConfigTool::Retry(_, config)
+            | UpConfigTool::Verify(_, config) => config.was_upped(),
+            UpConfigTool::Java(config) => config.was_upped(),
+            UpConfigTool::Kotlin(config) => config.was_upped(),
             UpConfigTool::Nix(config) => config.was_upped(),
             UpConfigTool::Nodejs(config) => config.asdf_base.was_upped(),
             UpConfigTool::Python(config) => config.asdf_base.was_upped(),
+            UpConfigTool::Rust(config) => config.was_upped(),
             _ => false,
         }
     }
 
+    /// The paths added to the PATH of the work directory by the step during
+    /// the current run; this is only provided by the steps that can run
+    /// concurrently, so that the order of the PATH can be kept consistent
+    /// with the order of the steps no matter which one finished first.
+    pub fn env_paths(&self) -> Vec<PathBuf> {
+        match self {
+            UpConfigTool::And(configs) | UpConfigTool::Any(configs) | UpConfigTool::Or(configs) => {
+                configs
+                    .iter()
+                    .flat_map(|config| config.env_paths())
+                    .collect()
+            }
+            UpConfigTool::Download(config) => config.env_paths(),
+            UpConfigTool::GiteaRelease(config)
+            | UpConfigTool::GithubRelease(config)
+            | UpConfigTool::GitlabRelease(config) => config.env_paths(),
+            UpConfigTool::If(_, config)
+            | UpConfigTool::Retry(_, config)
+            | UpConfigTool::Verify(_, config) => config.env_paths(),
+            _ => vec![],
+        }
+    }
+
     pub fn data_paths(&self) -> Vec<PathBuf> {
         match self {
             UpConfigTool::And(configs) => configs
@@ -323,13 +909,19 @@ impl UpConfigTool {
             UpConfigTool::Asdf(config) => config.data_paths(),
             UpConfigTool::Bash(config) => config.data_paths(),
             // UpConfigTool::Bundler(config) => config.data_paths(),
-            // UpConfigTool::Custom(config) => config.data_paths(),
+            UpConfigTool::Custom(config) => config.data_paths(),
             // UpConfigTool::GithubRelease(config) => config.data_paths(),
             UpConfigTool::Go(config) => config.data_paths(),
             // UpConfigTool::Homebrew(config) => config.data_paths(),
+            UpConfigTool::If(_, config)
+            | UpConfigTool::Retry(_, config)
+            | UpConfigTool::Verify(_, config) => config.data_paths(),
+            UpConfigTool::Java(config) => config.data_paths(),
+            UpConfigTool::Kotlin(config) => config.data_paths(),
             UpConfigTool::Nix(config) => config.data_paths(),
             UpConfigTool::Nodejs(config) => config.asdf_base.data_paths(),
             UpConfigTool::Python(config) => config.asdf_base.data_paths(),
+            UpConfigTool::Rust(config) => config.data_paths(),
             _ => vec![],
         }
     }
@@ -338,17 +930,60 @@ impl UpConfigTool {
         match self {
             UpConfigTool::And(_) => "and".into(),
             UpConfigTool::Any(_) => "any".into(),
+            UpConfigTool::Apt(_) => "apt".into(),
             UpConfigTool::Asdf(config) => config.name(),
             UpConfigTool::Or(_) => "or".into(),
             UpConfigTool::Bash(_) => "bash".into(),
             UpConfigTool::Bundler(_) => "bundler".into(),
+            UpConfigTool::CargoInstall(_) => "cargo-install".into(),
             UpConfigTool::Custom(_) => "custom".into(),
+            UpConfigTool::Dnf(_) => "dnf".into(),
+            UpConfigTool::Download(_) => "download".into(),
+            UpConfigTool::GiteaRelease(_) => "gitea-release".into(),
             UpConfigTool::GithubRelease(_) => "github-release".into(),
+            UpConfigTool::GitlabRelease(_) => "gitlab-release".into(),
             UpConfigTool::Go(_) => "go".into(),
             UpConfigTool::Homebrew(_) => "homebrew".into(),
+            UpConfigTool::If(_, config) => config.to_name(),
+            UpConfigTool::Java(_) => "java".into(),
+            UpConfigTool::Kotlin(_) => "kotlin".into(),
             UpConfigTool::Nix(_) => "nix".into(),
             UpConfigTool::Nodejs(_) => "nodejs".into(),
+            UpConfigTool::Pacman(_) => "pacman".into(),
             UpConfigTool::Python(_) => "python".into(),
+            UpConfigTool::Retry(_, config) => config.to_name(),
+            UpConfigTool::Rust(_) => "rust".into(),
+            UpConfigTool::Verify(_, config) => config.to_name(),
+        }
+    }
+
+    /// Returns whether the step matches the given name, which can be the
+    /// type of the step or the name of one of the resources handled by the
+    /// step, like the repository of a release or the name of a package
+    pub fn matches_name(&self, name: &str) -> bool {
+        if self.to_name() == name {
+            return true;
+        }
+
+        match self {
+            UpConfigTool::And(configs) | UpConfigTool::Any(configs) | UpConfigTool::Or(configs) => {
+                configs.iter().any(|config| config.matches_name(name))
+            }
+            UpConfigTool::Apt(config)
+            | UpConfigTool::Dnf(config)
+            | UpConfigTool::Pacman(config) => config.names().iter().any(|n| n == name),
+            UpConfigTool::CargoInstall(config) => config.names().iter().any(|n| n == name),
+            UpConfigTool::Custom(config) => config.name.as_deref() == Some(name),
+            UpConfigTool::Download(config) => config.names().iter().any(|n| n == name),
+            UpConfigTool::GiteaRelease(config)
+            | UpConfigTool::GithubRelease(config)
+            | UpConfigTool::GitlabRelease(config) => config.names().iter().any(|n| n == name),
+            UpConfigTool::Homebrew(config) => config.names().iter().any(|n| n == name),
+            UpConfigTool::If(_, config)
+            | UpConfigTool::Retry(_, config)
+            | UpConfigTool::Verify(_, config) => config.matches_name(name),
+            UpConfigTool::Nix(config) => config.packages.iter().any(|n| n == name),
+            _ => false,
         }
     }
 
@@ -363,6 +998,9 @@ impl UpConfigTool {
                     .min()
                     .unwrap_or(i32::MAX)
             }
+            UpConfigTool::If(_, config)
+            | UpConfigTool::Retry(_, config)
+            | UpConfigTool::Verify(_, config) => config.sort_value(),
             _ => {
                 let config = global_config();
                 let preferred_tools = &config.up_command.preferred_tools;
@@ -384,6 +1022,18 @@ impl UpConfigTool {
     }
 }
 
+/// Evaluates the condition of a conditional tool in the context
+/// of the work directory in the current directory
+fn evaluate_condition(condition: &str) -> Result<bool, UpError> {
+    let context = config_template_context(".");
+    evaluate_template_condition(condition, &context).map_err(|err| {
+        UpError::Config(format!(
+            "failed to evaluate condition '{}': {}",
+            condition, err
+        ))
+    })
+}
+
 fn ordered_configs(configs: &[UpConfigTool]) -> Vec<&UpConfigTool> {
     configs
         .iter()
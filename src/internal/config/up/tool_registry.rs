@@ -0,0 +1,668 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use lazy_static::lazy_static;
+
+use crate::internal::config::config_loader;
+use crate::internal::config::parser::PathConfig;
+use crate::internal::config::parser::ToolRegistryConfig;
+use crate::internal::config::up::UpConfigTool;
+use crate::internal::config::ConfigScope;
+use crate::internal::config::ConfigSource;
+use crate::internal::config::ConfigValue;
+use crate::internal::env::omnipath_env;
+use crate::internal::user_interface::StringColor;
+use crate::internal::workdir;
+use crate::omni_warning;
+
+lazy_static! {
+    static ref BUILTIN_TOOL_REGISTRY: ToolRegistry = {
+        let value = serde_yaml::from_str(include_str!("tool_registry.yaml"))
+            .expect("built-in tool registry should be valid yaml");
+        let config_value =
+            ConfigValue::from_value(ConfigSource::Default, ConfigScope::Default, value);
+        ToolRegistry::from_config_value(&config_value)
+            .expect("built-in tool registry should be valid")
+    };
+}
+
+/// The version of the format of the registry files that is supported
+const TOOL_REGISTRY_VERSION: u64 = 1;
+
+/// The path of the registry file in the repositories of the omnipath
+const TOOL_REGISTRY_FILE: &str = ".omni/tool-registry.yaml";
+
+/// The operations that can be used to install the tools of the registry
+const TOOL_OPERATIONS: [&str; 4] = [
+    "download",
+    "gitea-release",
+    "github-release",
+    "gitlab-release",
+];
+
+/// ToolRegistry maps the short names of tools to the configuration of
+/// the operation used to install them, so that `tool: rg@14` can be
+/// used in place of a fully specified `github-release` operation.
+#[derive(Debug, Clone, Default)]
+pub struct ToolRegistry {
+    tools: HashMap<String, ToolRecipe>,
+}
+
+/// ToolRecipe is the operation installing a tool, and its configuration
+/// before the version and the parameters of the step are applied.
+#[derive(Debug, Clone)]
+struct ToolRecipe {
+    operation: String,
+    config: ConfigValue,
+}
+
+impl ToolRegistry {
+    /// Returns the registry shipped with omni, overridden by the
+    /// registry files of the repositories of the omnipath, themselves
+    /// overridden by the registry files of the configuration
+    pub fn load() -> Self {
+        let mut registry = BUILTIN_TOOL_REGISTRY.clone();
+
+        // The configuration is read from the loader, since the registry
+        // is used while the configuration itself is being parsed
+        let raw_config = config_loader("/").raw_config;
+
+        // The first entries of the omnipath take precedence, so they
+        // are applied last
+        let path_config = PathConfig::from_config_value(raw_config.get("path"));
+        let omnipath = path_config
+            .prepend
+            .iter()
+            .map(|entry| entry.as_string())
+            .chain(omnipath_env())
+            .chain(path_config.append.iter().map(|entry| entry.as_string()))
+            .filter(|path| !path.is_empty())
+            .collect::<Vec<_>>();
+        let mut registry_files = vec![];
+        for path in omnipath.iter().rev() {
+            if let Some(root) = workdir(path).root() {
+                let file = Path::new(root).join(TOOL_REGISTRY_FILE);
+                let file = file.to_string_lossy().to_string();
+                if !registry_files.contains(&file) && Path::new(&file).is_file() {
+                    registry_files.push(file);
+                }
+            }
+        }
+
+        let config = ToolRegistryConfig::from_config_value(raw_config.get("tool_registry"));
+        registry_files.extend(config.files);
+
+        for file in registry_files {
+            match Self::from_file(&file) {
+                Ok(file_registry) => registry.extend(file_registry),
+                Err(err) => {
+                    omni_warning!(format!("ignoring tool registry {}: {}", file, err));
+                }
+            }
+        }
+
+        registry
+    }
+
+    fn from_file(path: &str) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let value = serde_yaml::from_str(&contents).map_err(|err| err.to_string())?;
+        let config_value = ConfigValue::from_value(
+            ConfigSource::File(path.to_string()),
+            ConfigScope::User,
+            value,
+        );
+        Self::from_config_value(&config_value)
+    }
+
+    fn from_config_value(config_value: &ConfigValue) -> Result<Self, String> {
+        if let Some(version) = config_value.get("version") {
+            match version.as_unsigned_integer() {
+                Some(TOOL_REGISTRY_VERSION) => {}
+                Some(version) => return Err(format!("unsupported version {}", version)),
+                None => return Err(format!("invalid version {}", version)),
+            }
+        }
+
+        let mut tools = HashMap::new();
+        for (name, recipe) in config_value.get_as_table("tools").unwrap_or_default() {
+            let (operation, config) = match recipe.as_table() {
+                Some(table) if table.len() == 1 => table.into_iter().next().unwrap(),
+                _ => return Err(format!("invalid recipe for {}", name)),
+            };
+
+            if !TOOL_OPERATIONS.contains(&operation.as_str()) || !config.is_table() {
+                return Err(format!("invalid recipe for {}", name));
+            }
+
+            tools.insert(name, ToolRecipe { operation, config });
+        }
+
+        Ok(Self { tools })
+    }
+
+    fn extend(&mut self, other: Self) {
+        self.tools.extend(other.tools);
+    }
+
+    /// Returns the operation installing the tools of the given step,
+    /// which can be a tool name with an optional version, e.g. `rg@14`,
+    /// a table with the `name` of the tool and parameters overriding
+    /// those of its recipe, e.g. the `version`, or a list of those
+    pub fn expand(&self, config_value: &ConfigValue) -> Result<UpConfigTool, String> {
+        if let Some(array) = config_value.as_array() {
+            let tools = array
+                .iter()
+                .map(|config_value| self.expand(config_value))
+                .collect::<Result<Vec<_>, _>>()?;
+            if tools.is_empty() {
+                return Err("at least one tool is required".to_string());
+            }
+            return Ok(UpConfigTool::And(tools));
+        }
+
+        let mut overrides = match config_value.as_table() {
+            Some(table) => table,
+            None => {
+                // The version is kept as a string, so that e.g. `1.20`
+                // is not read as a number
+                let string_value = |value: &str| {
+                    ConfigValue::from_value(
+                        ConfigSource::Null,
+                        ConfigScope::Null,
+                        serde_yaml::Value::String(value.to_string()),
+                    )
+                };
+
+                let spec = config_value
+                    .as_str_forced()
+                    .ok_or_else(|| format!("invalid tool: {}", config_value))?;
+                let mut overrides = HashMap::new();
+                let (name, version) = match spec.split_once('@') {
+                    Some((name, version)) => (name, Some(version)),
+                    None => (spec.as_str(), None),
+                };
+                overrides.insert("name".to_string(), string_value(name));
+                if let Some(version) = version {
+                    overrides.insert("version".to_string(), string_value(version));
+                }
+                overrides
+            }
+        };
+
+        let name = overrides
+            .remove("name")
+            .and_then(|name| name.as_str_forced())
+            .ok_or_else(|| "tool name is required".to_string())?;
+        let recipe = self
+            .tools
+            .get(&name)
+            .ok_or_else(|| format!("unknown tool '{}' in registry", name))?;
+
+        let mut config = recipe.config.clone();
+        if let Some(table) = config.as_table_mut() {
+            table.extend(overrides);
+        }
+
+        UpConfigTool::from_config_value(&recipe.operation, Some(&config))
+            .ok_or_else(|| format!("invalid recipe for {}", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(registry: &ToolRegistry, yaml: &str) -> Option<serde_yaml::Value> {
+        let config_value = ConfigValue::from_str(yaml).expect("invalid yaml");
+        registry
+            .expand(&config_value)
+            .ok()
+            .map(|tool| serde_yaml::to_value(&tool).expect("failed to serialize tool"))
+    }
+
+    fn yaml(yaml: &str) -> serde_yaml::Value {
+        serde_yaml::from_str(yaml).expect("invalid yaml")
+    }
+
+    #[test]
+    fn builtin_registry() {
+        for name in ["gh", "jq", "kubectl", "rg"] {
+            assert!(
+                BUILTIN_TOOL_REGISTRY.tools.contains_key(name),
+                "{} missing from the built-in registry",
+                name
+            );
+        }
+
+        for name in BUILTIN_TOOL_REGISTRY.tools.keys() {
+            assert!(
+                expand(&BUILTIN_TOOL_REGISTRY, &format!("{}@1.0.0", name)).is_some(),
+                "{} cannot be expanded",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn expand_with_version() {
+        assert_eq!(
+            expand(&BUILTIN_TOOL_REGISTRY, "rg@14"),
+            Some(yaml(
+                r#"
+                github-release:
+                  repository: BurntSushi/ripgrep
+                  version: "14"
+                  asset_name: ripgrep-*.tar.gz
+                  binaries:
+                    - path: "*/rg"
+                  completions:
+                    - "*/complete/_rg"
+                  man_pages:
+                    - "*/doc/rg.1"
+                "#
+            ))
+        );
+
+        // The version is not read as a number
+        let tool = expand(&BUILTIN_TOOL_REGISTRY, "gh@2.40").unwrap();
+        assert_eq!(tool["github-release"]["version"], yaml("'2.40'"));
+
+        let tool = expand(&BUILTIN_TOOL_REGISTRY, "gh").unwrap();
+        assert_eq!(tool["github-release"].get("version"), None);
+    }
+
+    #[test]
+    fn expand_with_overrides() {
+        let tool = expand(
+            &BUILTIN_TOOL_REGISTRY,
+            r#"{"name": "kubectl", "version": "1.31.0", "checksum": {"linux-amd64": "abc"}}"#,
+        )
+        .unwrap();
+        assert_eq!(tool["download"]["name"], yaml("kubectl"));
+        assert_eq!(tool["download"]["version"], yaml("1.31.0"));
+        assert_eq!(tool["download"]["checksum"], yaml("linux-amd64: abc"));
+
+        let tool = expand(
+            &BUILTIN_TOOL_REGISTRY,
+            r#"{"name": "rg", "upgrade": true, "asset_name": "*.zip"}"#,
+        )
+        .unwrap();
+        assert_eq!(tool["github-release"]["upgrade"], yaml("true"));
+        assert_eq!(tool["github-release"]["asset_name"], yaml("'*.zip'"));
+    }
+
+    #[test]
+    fn expand_list() {
+        let config_value = ConfigValue::from_str(r#"["rg@14", "jq"]"#).unwrap();
+        let tool = BUILTIN_TOOL_REGISTRY.expand(&config_value);
+        assert!(matches!(
+            tool,
+            Ok(UpConfigTool::And(tools)) if tools.len() == 2
+        ));
+    }
+
+    #[test]
+    fn expand_unknown() {
+        let expand_err = |yaml: &str| {
+            let config_value = ConfigValue::from_str(yaml).expect("invalid yaml");
+            BUILTIN_TOOL_REGISTRY.expand(&config_value).err()
+        };
+
+        assert_eq!(
+            expand_err("unknown-tool@1"),
+            Some("unknown tool 'unknown-tool' in registry".to_string())
+        );
+        assert_eq!(
+            expand_err(r#"["rg", "unknown-tool"]"#),
+            Some("unknown tool 'unknown-tool' in registry".to_string())
+        );
+        assert_eq!(
+            expand_err(r#"{"version": "14"}"#),
+            Some("tool name is required".to_string())
+        );
+    }
+
+    #[test]
+    fn registry_file_overrides() {
+        let tempdir = tempfile::tempdir().expect("failed to create tempdir");
+        let file = tempdir.path().join("tools.yaml");
+        std::fs::write(
+            &file,
+            r#"
+            version: 1
+            tools:
+              rg:
+                github-release:
+                  repository: owner/ripgrep
+              tool:
+                gitlab-release:
+                  repository: owner/tool
+                  binaries: bin/tool
+            "#,
+        )
+        .expect("failed to write registry");
+
+        let mut registry = BUILTIN_TOOL_REGISTRY.clone();
+        registry.extend(ToolRegistry::from_file(file.to_str().unwrap()).expect("invalid registry"));
+
+        assert_eq!(
+            expand(&registry, "rg@14"),
+            Some(yaml(
+                r#"
+                github-release:
+                  repository: owner/ripgrep
+                  version: "14"
+                "#
+            ))
+        );
+        assert_eq!(
+            expand(&registry, "tool"),
+            Some(yaml(
+                r#"
+                gitlab-release:
+                  repository: owner/tool
+                  binaries:
+                    - path: bin/tool
+                "#
+            ))
+        );
+        assert!(expand(&registry, "jq").is_some());
+    }
+
+    #[test]
+    fn invalid_registry() {
+        let registry = |yaml: &str| {
+            let config_value = ConfigValue::from_str(yaml).expect("invalid yaml");
+            ToolRegistry::from_config_value(&config_value)
+        };
+
+        assert_eq!(
+            registry(r#"{"version": 2, "tools": {}}"#).err(),
+            Some("unsupported version 2".to_string())
+        );
+        assert_eq!(
+            registry(r#"{"tools": {"tool": {"homebrew": {"formula": "tool"}}}}"#).err(),
+            Some("invalid recipe for tool".to_string())
+        );
+        assert_eq!(
+            registry(r#"{"tools": {"tool": {"github-release": "owner/tool"}}}"#).err(),
+            Some("invalid recipe for tool".to_string())
+        );
+        assert!(registry(
+            r#"{"tools": {"tool": {"github-release": {"repository": "owner/tool"}}}}"#
+        )
+        .is_ok());
+    }
+
+    mod up {
+        use super::*;
+
+        use std::path::PathBuf;
+
+        use crate::internal::config::up::utils::run_with_env;
+        use crate::internal::config::up::utils::targz;
+        use crate::internal::config::up::utils::UpProgressHandler;
+        use crate::internal::config::up::UpOptions;
+        use crate::internal::env::data_home;
+        use crate::internal::self_updater::compatible_release_arch;
+
+        /// Returns the files installed in the given directory, with
+        /// their path relative to it
+        fn installed_files(path: &Path) -> Vec<String> {
+            let mut installed = walkdir::WalkDir::new(path)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file())
+                .map(|entry| {
+                    entry
+                        .path()
+                        .strip_prefix(path)
+                        .unwrap()
+                        .to_string_lossy()
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+            installed.sort();
+            installed
+        }
+
+        /// Installs the given tool of the built-in registry from a
+        /// mocked github release publishing the given assets, all of
+        /// which have the given contents, and returns the installed files
+        fn install_github_release(
+            name: &str,
+            version: &str,
+            tag_name: &str,
+            assets: &[String],
+            contents: &[u8],
+        ) -> Vec<String> {
+            let mut mock_server = mockito::Server::new();
+
+            let config_value = ConfigValue::from_str(&format!(
+                r#"{{"name": "{}", "version": "{}", "api_url": "{}"}}"#,
+                name,
+                version,
+                mock_server.url()
+            ))
+            .expect("invalid yaml");
+            let tool = BUILTIN_TOOL_REGISTRY
+                .expand(&config_value)
+                .expect("failed to expand tool");
+            let repository = serde_yaml::to_value(&tool).expect("failed to serialize tool")
+                ["github-release"]["repository"]
+                .as_str()
+                .expect("no repository")
+                .to_string();
+
+            let assets = assets
+                .iter()
+                .map(|asset| {
+                    format!(
+                        r#"{{"name": "{}", "browser_download_url": "{}/download/{}"}}"#,
+                        asset,
+                        mock_server.url(),
+                        asset
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            let list_releases_body = format!(
+                r#"[{{
+                    "name": "{tag}",
+                    "tag_name": "{tag}",
+                    "draft": false,
+                    "prerelease": false,
+                    "assets": [{assets}]
+                }}]"#,
+                tag = tag_name,
+                assets = assets,
+            );
+
+            let mock_list_releases = mock_server
+                .mock("GET", format!("/repos/{}/releases", repository).as_str())
+                .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+                .with_status(200)
+                .with_body(list_releases_body)
+                .create();
+
+            let mock_download = mock_server
+                .mock("GET", mockito::Matcher::Regex("^/download/".to_string()))
+                .with_status(200)
+                .with_body(contents)
+                .create();
+
+            let options = UpOptions::default().cache_disabled();
+            let progress_handler = UpProgressHandler::new(None);
+
+            let result = tool.up(&options, &progress_handler);
+            assert_eq!(result, Ok(()), "failed to install {}", name);
+
+            mock_list_releases.assert();
+            mock_download.assert();
+
+            // The release is installed in a directory named after the
+            // repository, suffixed when its tags are filtered
+            let (owner, name) = repository.split_once('/').expect("invalid repository");
+            let owner_path = PathBuf::from(data_home()).join("ghreleases").join(owner);
+            installed_files(&owner_path)
+                .into_iter()
+                .filter_map(|path| {
+                    let (repository_dir, path) = path.split_once('/')?;
+                    (repository_dir == name || repository_dir.starts_with(&format!("{}@", name)))
+                        .then(|| path.to_string())
+                })
+                .collect()
+        }
+
+        /// Returns the name given by kubernetes to the architecture of
+        /// the current platform
+        fn kubernetes_arch() -> &'static str {
+            if compatible_release_arch().contains(&"arm64".to_string()) {
+                "arm64"
+            } else {
+                "amd64"
+            }
+        }
+
+        #[test]
+        fn gh() {
+            run_with_env(&[], |_| {
+                let archive = targz(&[
+                    ("gh_2.40.0/bin/gh", b"gh", 0o755),
+                    ("gh_2.40.0/share/man/man1/gh.1", b".TH GH 1", 0o644),
+                    ("gh_2.40.0/share/man/man1/gh-pr.1", b".TH GH-PR 1", 0o644),
+                    ("gh_2.40.0/LICENSE", b"license", 0o644),
+                ]);
+                let assets = ["linux_amd64", "linux_arm64", "macOS_amd64", "macOS_arm64"]
+                    .iter()
+                    .map(|platform| format!("gh_2.40.0_{}.tar.gz", platform))
+                    .chain(["gh_2.40.0_checksums.txt".to_string()])
+                    .collect::<Vec<_>>();
+
+                let installed =
+                    install_github_release("gh", "2.40.0", "v2.40.0", &assets, &archive);
+                assert_eq!(
+                    installed,
+                    vec![
+                        "v2.40.0/gh",
+                        "v2.40.0/share/man/man1/gh-pr.1",
+                        "v2.40.0/share/man/man1/gh.1",
+                    ]
+                );
+            });
+        }
+
+        #[test]
+        fn jq() {
+            run_with_env(&[], |_| {
+                let assets = ["linux", "macos"]
+                    .iter()
+                    .flat_map(|os| {
+                        ["amd64", "arm64"]
+                            .iter()
+                            .map(move |arch| format!("jq-{}-{}", os, arch))
+                    })
+                    .chain(["jq-1.7.1.tar.gz".to_string(), "sha256sum.txt".to_string()])
+                    .collect::<Vec<_>>();
+
+                let installed = install_github_release("jq", "1.7.1", "jq-1.7.1", &assets, b"jq");
+                assert_eq!(installed, vec!["1.7.1/jq"]);
+            });
+        }
+
+        #[test]
+        fn rg() {
+            run_with_env(&[], |_| {
+                let archive = targz(&[
+                    ("ripgrep-14.1.0/rg", b"rg", 0o755),
+                    ("ripgrep-14.1.0/complete/_rg", b"#compdef rg", 0o644),
+                    ("ripgrep-14.1.0/complete/rg.bash", b"complete", 0o644),
+                    ("ripgrep-14.1.0/doc/rg.1", b".TH RG 1", 0o644),
+                ]);
+                let assets = [
+                    "x86_64-unknown-linux-musl",
+                    "aarch64-unknown-linux-gnu",
+                    "x86_64-apple-darwin",
+                    "aarch64-apple-darwin",
+                ]
+                .iter()
+                .flat_map(|target| {
+                    [
+                        format!("ripgrep-14.1.0-{}.tar.gz", target),
+                        format!("ripgrep-14.1.0-{}.tar.gz.sha256", target),
+                    ]
+                })
+                .collect::<Vec<_>>();
+
+                let installed = install_github_release("rg", "14.1.0", "14.1.0", &assets, &archive);
+                assert_eq!(
+                    installed,
+                    vec![
+                        "14.1.0/rg",
+                        "14.1.0/share/man/man1/rg.1",
+                        "14.1.0/share/zsh/site-functions/_rg",
+                    ]
+                );
+            });
+        }
+
+        #[test]
+        fn kubectl() {
+            run_with_env(&[], |_| {
+                let mut mock_server = mockito::Server::new();
+                let contents = b"kubectl";
+
+                // The host of the recipe is replaced by the mock server,
+                // but its path and platform mapping are kept
+                let recipe = serde_yaml::to_value(
+                    BUILTIN_TOOL_REGISTRY
+                        .expand(&ConfigValue::from_str("kubectl").unwrap())
+                        .expect("failed to expand tool"),
+                )
+                .expect("failed to serialize tool");
+                let url = recipe["download"]["url"]
+                    .as_str()
+                    .expect("no url")
+                    .replace("https://dl.k8s.io", &mock_server.url());
+
+                let digest = openssl::hash::hash(openssl::hash::MessageDigest::sha256(), contents)
+                    .expect("failed to hash");
+                let config_value = ConfigValue::from_str(&format!(
+                    r#"{{"name": "kubectl", "version": "1.31.0", "url": "{}", "checksum": "{}"}}"#,
+                    url,
+                    digest
+                        .iter()
+                        .map(|b| format!("{:02x}", b))
+                        .collect::<String>()
+                ))
+                .expect("invalid yaml");
+                let tool = BUILTIN_TOOL_REGISTRY
+                    .expand(&config_value)
+                    .expect("failed to expand tool");
+
+                let mock_download = mock_server
+                    .mock(
+                        "GET",
+                        mockito::Matcher::Regex(format!(
+                            r"^/release/v1\.31\.0/bin/[a-z]+/{}/kubectl$",
+                            kubernetes_arch()
+                        )),
+                    )
+                    .with_status(200)
+                    .with_body(contents)
+                    .create();
+
+                let options = UpOptions::default().cache_disabled();
+                let progress_handler = UpProgressHandler::new(None);
+
+                let result = tool.up(&options, &progress_handler);
+                assert_eq!(result, Ok(()));
+                mock_download.assert();
+
+                let install_path = PathBuf::from(data_home()).join("download").join("kubectl");
+                assert_eq!(installed_files(&install_path), vec!["1.31.0/kubectl"]);
+            });
+        }
+    }
+}
@@ -17,13 +17,29 @@ pub(crate) use progress_handler::run_command_with_handler;
 pub(crate) use progress_handler::run_progress;
 pub(crate) use progress_handler::ProgressHandler;
 
+pub(crate) mod retry;
+pub(crate) use retry::AttemptError;
+pub(crate) use retry::RetryPolicy;
+
 pub(crate) mod run_config;
 pub(crate) use run_config::RunConfig;
 
+pub(crate) mod signature;
+pub(crate) use signature::SignatureConfig;
+
 pub(crate) mod shims;
 pub(crate) use shims::handle_shims;
 pub(crate) use shims::reshim;
 
+#[cfg(test)]
+pub(crate) mod test_env;
+#[cfg(test)]
+pub(crate) use test_env::run_in_workdir;
+#[cfg(test)]
+pub(crate) use test_env::run_with_env;
+#[cfg(test)]
+pub(crate) use test_env::targz;
+
 pub(crate) mod spinner_progress_handler;
 pub(crate) use spinner_progress_handler::SpinnerProgressHandler;
 
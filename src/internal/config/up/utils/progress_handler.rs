@@ -11,6 +11,7 @@ use tokio::runtime::Runtime;
 use tokio::time::Duration;
 
 use crate::internal::config::up::utils::AskPassListener;
+use crate::internal::config::up::utils::AttemptError;
 use crate::internal::config::up::utils::RunConfig;
 use crate::internal::config::up::UpError;
 use crate::internal::user_interface::StringColor;
@@ -34,24 +35,27 @@ pub fn run_progress(
     run_config: RunConfig,
 ) -> Result<(), UpError> {
     let rt = Runtime::new().map_err(|err| UpError::Exec(err.to_string()))?;
-    rt.block_on(async_run_progress_readblocks(
-        process_command,
-        |stdout, stderr, hide| {
-            if let Some(progress_handler) = &progress_handler {
-                match hide {
-                    Some(true) => progress_handler.hide(),
-                    Some(false) => progress_handler.show(),
-                    None => {}
-                }
-                if let Some(stdout) = stdout {
-                    progress_handler.progress(stdout);
-                } else if let Some(stderr) = stderr {
-                    progress_handler.progress(stderr);
+    run_config.retry_policy().run(progress_handler, || {
+        rt.block_on(async_run_progress_readblocks(
+            process_command,
+            |stdout, stderr, hide| {
+                if let Some(progress_handler) = &progress_handler {
+                    match hide {
+                        Some(true) => progress_handler.hide(),
+                        Some(false) => progress_handler.show(),
+                        None => {}
+                    }
+                    if let Some(stdout) = stdout {
+                        progress_handler.progress(stdout);
+                    } else if let Some(stderr) = stderr {
+                        progress_handler.progress(stderr);
+                    }
                 }
-            }
-        },
-        run_config,
-    ))
+            },
+            run_config.clone(),
+        ))
+        .map_err(AttemptError::from)
+    })
 }
 
 pub fn run_command_with_handler<F>(
@@ -0,0 +1,193 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::internal::config::up::utils::ProgressHandler;
+use crate::internal::config::up::UpError;
+use crate::internal::user_interface::StringColor;
+
+/// The delay before the first retry, doubled for each following one
+const BACKOFF_INITIAL_SECS: u64 = 1;
+
+/// The maximum delay between two attempts
+const BACKOFF_MAX_SECS: u64 = 30;
+
+/// How many times the network requests and the commands of an up step
+/// are retried when they fail, and how long they can run before being
+/// considered as failed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// The number of retries after the first attempt
+    #[serde(default)]
+    pub retries: u32,
+
+    /// The timeout, in seconds, of each attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+/// The error of a failed attempt, indicating whether it is worth trying again
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttemptError {
+    /// The attempt failed for a reason that might go away, e.g. a
+    /// network error or a server error
+    Transient(UpError),
+
+    /// The attempt failed for a reason that another attempt will not
+    /// fix, e.g. a resource that does not exist
+    Permanent(UpError),
+}
+
+impl AttemptError {
+    /// Returns a transient or permanent error for an HTTP response status
+    pub fn from_status(status: reqwest::StatusCode, error: UpError) -> Self {
+        if is_transient_status(status) {
+            Self::Transient(error)
+        } else {
+            Self::Permanent(error)
+        }
+    }
+}
+
+impl From<UpError> for AttemptError {
+    fn from(error: UpError) -> Self {
+        Self::Transient(error)
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(retries: u32, timeout: Option<u64>) -> Self {
+        Self { retries, timeout }
+    }
+
+    /// Returns the timeout of each attempt, if any
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+
+    /// Returns the delay to wait before the given retry, starting at 1
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u64.saturating_pow(retry.saturating_sub(1));
+        Duration::from_secs(
+            BACKOFF_INITIAL_SECS
+                .saturating_mul(factor)
+                .min(BACKOFF_MAX_SECS),
+        )
+    }
+
+    /// Runs the given attempt until it succeeds, fails permanently, or
+    /// the retries are exhausted, waiting with an exponential backoff
+    /// between attempts and reporting them to the progress handler
+    pub fn run<T, F>(
+        &self,
+        progress_handler: Option<&dyn ProgressHandler>,
+        mut attempt: F,
+    ) -> Result<T, UpError>
+    where
+        F: FnMut() -> Result<T, AttemptError>,
+    {
+        let mut retry = 0;
+        loop {
+            let err = match attempt() {
+                Ok(value) => return Ok(value),
+                Err(AttemptError::Permanent(err)) => return Err(err),
+                Err(AttemptError::Transient(err)) => err,
+            };
+
+            if retry >= self.retries {
+                return Err(err);
+            }
+            retry += 1;
+
+            let backoff = self.backoff(retry);
+            if let Some(progress_handler) = progress_handler {
+                progress_handler.progress(format!(
+                    "{}; {}",
+                    err.message().red(),
+                    format!(
+                        "retrying in {}s (attempt {}/{})",
+                        backoff.as_secs(),
+                        retry + 1,
+                        self.retries + 1,
+                    )
+                    .light_black(),
+                ));
+            }
+            std::thread::sleep(backoff);
+        }
+    }
+}
+
+/// Returns whether a request that failed with the given status could
+/// succeed if tried again
+pub fn is_transient_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error()
+        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+
+    #[test]
+    fn backoff() {
+        let policy = RetryPolicy::new(10, None);
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff(5), Duration::from_secs(16));
+        assert_eq!(policy.backoff(6), Duration::from_secs(30));
+        assert_eq!(policy.backoff(100), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn transient_status() {
+        assert!(is_transient_status(reqwest::StatusCode::BAD_GATEWAY));
+        assert!(is_transient_status(reqwest::StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_transient_status(reqwest::StatusCode::REQUEST_TIMEOUT));
+        assert!(!is_transient_status(reqwest::StatusCode::NOT_FOUND));
+        assert!(!is_transient_status(reqwest::StatusCode::UNAUTHORIZED));
+    }
+
+    #[test]
+    fn run_without_retries() {
+        let attempts = Cell::new(0);
+        let result: Result<(), UpError> = RetryPolicy::default().run(None, || {
+            attempts.set(attempts.get() + 1);
+            Err(UpError::Exec("failed".to_string()).into())
+        });
+        assert_eq!(result, Err(UpError::Exec("failed".to_string())));
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[test]
+    fn run_retries_transient_errors() {
+        let attempts = Cell::new(0);
+        let result = RetryPolicy::new(2, None).run(None, || {
+            attempts.set(attempts.get() + 1);
+            if attempts.get() < 2 {
+                return Err(AttemptError::Transient(UpError::Exec("flaky".to_string())));
+            }
+            Ok(attempts.get())
+        });
+        assert_eq!(result, Ok(2));
+        assert_eq!(attempts.get(), 2);
+    }
+
+    #[test]
+    fn run_does_not_retry_permanent_errors() {
+        let attempts = Cell::new(0);
+        let result: Result<(), UpError> = RetryPolicy::new(3, None).run(None, || {
+            attempts.set(attempts.get() + 1);
+            Err(AttemptError::Permanent(UpError::Exec(
+                "missing".to_string(),
+            )))
+        });
+        assert_eq!(result, Err(UpError::Exec("missing".to_string())));
+        assert_eq!(attempts.get(), 1);
+    }
+}
@@ -1,10 +1,13 @@
 use tokio::time::Duration;
 
+use crate::internal::config::up::utils::RetryPolicy;
+
 #[derive(Debug, Clone)]
 pub struct RunConfig {
     pub timeout: Option<Duration>,
     pub strip_ctrl_chars: bool,
     pub askpass: bool,
+    pub retries: u32,
 }
 
 impl Default for RunConfig {
@@ -13,6 +16,7 @@ impl Default for RunConfig {
             timeout: None,
             strip_ctrl_chars: true,
             askpass: false,
+            retries: 0,
         }
     }
 }
@@ -27,6 +31,15 @@ impl RunConfig {
         self.clone()
     }
 
+    /// Uses the timeout, if any, and the retries of the given policy
+    pub fn with_retry(&mut self, retry: &RetryPolicy) -> Self {
+        if let Some(timeout) = retry.timeout {
+            self.timeout = Some(Duration::from_secs(timeout));
+        }
+        self.retries = retry.retries;
+        self.clone()
+    }
+
     pub fn without_ctrl_chars(&mut self) -> Self {
         self.strip_ctrl_chars = true;
         self.clone()
@@ -44,4 +57,8 @@ impl RunConfig {
     pub fn timeout(&self) -> Option<Duration> {
         self.timeout
     }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::new(self.retries, self.timeout.map(|timeout| timeout.as_secs()))
+    }
 }
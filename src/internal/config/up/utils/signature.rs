@@ -0,0 +1,1411 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::process::Command as ProcessCommand;
+
+use openssl::base64::decode_block;
+use openssl::hash::Hasher;
+use openssl::hash::MessageDigest;
+use openssl::pkey::Id;
+use openssl::pkey::PKey;
+use openssl::sign::Verifier;
+use openssl::stack::Stack;
+use openssl::x509::store::X509StoreBuilder;
+use openssl::x509::verify::X509VerifyParam;
+use openssl::x509::X509StoreContext;
+use openssl::x509::X509;
+use regex::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::internal::config::utils::config_relative_path;
+use crate::internal::config::ConfigValue;
+
+/// The OID of the certificate extension in which Fulcio records the
+/// OIDC issuer as a raw string, deprecated in favor of the v2 one
+const FULCIO_ISSUER_V1_OID: &[u8] = &[
+    0x06, 0x0a, 0x2b, 0x06, 0x01, 0x04, 0x01, 0x83, 0xbf, 0x30, 0x01, 0x01,
+];
+
+/// The OID of the certificate extension in which Fulcio records the
+/// OIDC issuer as a DER-encoded UTF8String
+const FULCIO_ISSUER_V2_OID: &[u8] = &[
+    0x06, 0x0a, 0x2b, 0x06, 0x01, 0x04, 0x01, 0x83, 0xbf, 0x30, 0x01, 0x08,
+];
+
+/// How the signature of a downloaded file is verified; the signature is
+/// expected to be published next to the signed file, with an extension
+/// depending on the signature method.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureConfig {
+    /// Verify minisign signatures against any of the public keys
+    Minisign { public_keys: Vec<String> },
+
+    /// Verify detached GPG signatures against the keys of a keyring
+    Gpg { keyring: String },
+
+    /// Verify sigstore bundles, offline, against a certificate identity
+    Sigstore(SigstoreIdentity),
+}
+
+/// The identity expected to have signed a sigstore bundle, the
+/// certificate authorities trusted to have certified that identity, and
+/// the public key of the transparency log trusted to have timestamped
+/// the signature
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SigstoreIdentity {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_regex: Option<String>,
+    pub issuer: String,
+    pub trusted_root: String,
+    pub rekor_public_key: String,
+}
+
+impl SignatureConfig {
+    /// Parses the signature configuration; a configuration that is
+    /// present but cannot be used to verify signatures is an error, so
+    /// that the files are never installed without the expected checks
+    pub fn from_config_value(config_value: Option<&ConfigValue>) -> Result<Option<Self>, String> {
+        let config_value = match config_value {
+            Some(config_value) => config_value,
+            None => return Ok(None),
+        };
+
+        let table = config_value
+            .as_table()
+            .ok_or_else(|| "expected a table with the signature method".to_string())?;
+        let (method, value) = match table.len() {
+            1 => table.iter().next().unwrap(),
+            0 => return Err("no signature method".to_string()),
+            _ => return Err("only one signature method can be configured".to_string()),
+        };
+
+        match method.as_str() {
+            "minisign" => {
+                let public_keys: Vec<String> = match value.as_array() {
+                    Some(array) => array.iter().filter_map(|key| key.as_str_forced()).collect(),
+                    None => match value.as_table() {
+                        Some(table) => table
+                            .get("public_key")
+                            .and_then(|key| key.as_str_forced())
+                            .into_iter()
+                            .collect(),
+                        None => value.as_str_forced().into_iter().collect(),
+                    },
+                };
+                if public_keys.is_empty() {
+                    return Err("minisign requires a public key".to_string());
+                }
+                Ok(Some(Self::Minisign { public_keys }))
+            }
+            "gpg" => {
+                let keyring = match value.as_table() {
+                    Some(table) => table.get("keyring").and_then(config_relative_path),
+                    None => config_relative_path(value),
+                }
+                .ok_or_else(|| "gpg requires a keyring".to_string())?;
+                Ok(Some(Self::Gpg { keyring }))
+            }
+            "sigstore" => {
+                let table = value
+                    .as_table()
+                    .ok_or_else(|| "sigstore requires a table".to_string())?;
+                SigstoreIdentity::from_table(&table).map(|identity| Some(Self::Sigstore(identity)))
+            }
+            method => Err(format!("unknown signature method '{}'", method)),
+        }
+    }
+
+    /// Returns the extensions that the signature of a file can have,
+    /// appended to the name of the signed file
+    pub fn extensions(&self) -> Vec<String> {
+        let extensions: &[&str] = match self {
+            Self::Minisign { .. } => &[".minisig"],
+            Self::Gpg { .. } => &[".sig", ".asc"],
+            Self::Sigstore(_) => &[".sigstore.json", ".sigstore", ".bundle"],
+        };
+        extensions.iter().map(|ext| ext.to_string()).collect()
+    }
+
+    /// Verifies that the signature at the given path is a valid
+    /// signature of the file at the given path
+    pub fn verify(&self, file_path: &Path, signature_path: &Path) -> Result<(), String> {
+        match self {
+            Self::Minisign { public_keys } => {
+                verify_minisign(public_keys, file_path, signature_path)
+            }
+            Self::Gpg { keyring } => verify_gpg(keyring, file_path, signature_path),
+            Self::Sigstore(identity) => identity.verify(file_path, signature_path),
+        }
+    }
+}
+
+impl SigstoreIdentity {
+    fn from_table(table: &HashMap<String, ConfigValue>) -> Result<Self, String> {
+        let identity = table.get("identity").and_then(|v| v.as_str_forced());
+        let identity_regex = table.get("identity_regex").and_then(|v| v.as_str_forced());
+        if identity.is_none() && identity_regex.is_none() {
+            return Err("sigstore requires an identity or identity_regex".to_string());
+        }
+        if let Some(identity_regex) = &identity_regex {
+            if let Err(err) = Regex::new(identity_regex) {
+                return Err(format!(
+                    "invalid identity_regex pattern '{}': {}",
+                    identity_regex, err
+                ));
+            }
+        }
+
+        let required = |key: &str, value: Option<String>| {
+            value.ok_or_else(|| format!("sigstore requires {}", key))
+        };
+
+        Ok(Self {
+            identity,
+            identity_regex,
+            issuer: required(
+                "issuer",
+                table.get("issuer").and_then(|v| v.as_str_forced()),
+            )?,
+            trusted_root: required(
+                "trusted_root",
+                table.get("trusted_root").and_then(config_relative_path),
+            )?,
+            rekor_public_key: required(
+                "rekor_public_key",
+                table.get("rekor_public_key").and_then(config_relative_path),
+            )?,
+        })
+    }
+
+    fn matches_identity(&self, identity: &str) -> bool {
+        if let Some(expected) = &self.identity {
+            return identity == expected;
+        }
+
+        match &self.identity_regex {
+            Some(regex) => Regex::new(&format!("^(?:{})$", regex))
+                .map(|regex| regex.is_match(identity))
+                .unwrap_or(false),
+            None => false,
+        }
+    }
+
+    /// Verifies a sigstore bundle without contacting the transparency
+    /// log: the entry of the log must be timestamped by the trusted log,
+    /// the certificate must chain to the trusted root at the time the
+    /// entry was integrated in the log, and be issued to the expected
+    /// identity by the expected issuer
+    fn verify(&self, file_path: &Path, bundle_path: &Path) -> Result<(), String> {
+        let bundle = SigstoreBundle::from_file(bundle_path)?;
+        let certificate = bundle
+            .certificates
+            .first()
+            .ok_or_else(|| "no certificate in sigstore bundle".to_string())?;
+
+        // The integration time is only trusted once the signed entry
+        // timestamp of the log has been verified, as it would otherwise
+        // allow to use certificates that expired long ago
+        let rekor_public_key = std::fs::read(&self.rekor_public_key)
+            .map_err(|err| format!("failed to read {}: {}", self.rekor_public_key, err))?;
+        let rekor_public_key = PKey::public_key_from_pem(&rekor_public_key).map_err(|err| {
+            format!(
+                "invalid rekor public key {}: {}",
+                self.rekor_public_key, err
+            )
+        })?;
+        bundle.log_entry.verify_timestamp(&rekor_public_key)?;
+
+        let trusted_root = std::fs::read(&self.trusted_root)
+            .map_err(|err| format!("failed to read {}: {}", self.trusted_root, err))?;
+        let mut store = X509StoreBuilder::new().map_err(|err| err.to_string())?;
+        for root in X509::stack_from_pem(&trusted_root)
+            .map_err(|err| format!("invalid trusted root {}: {}", self.trusted_root, err))?
+        {
+            store.add_cert(root).map_err(|err| err.to_string())?;
+        }
+        let mut param = X509VerifyParam::new().map_err(|err| err.to_string())?;
+        param.set_time(bundle.log_entry.integrated_time as _);
+        store.set_param(&param).map_err(|err| err.to_string())?;
+        let store = store.build();
+
+        let mut chain = Stack::new().map_err(|err| err.to_string())?;
+        for intermediate in bundle.certificates.iter().skip(1) {
+            chain
+                .push(intermediate.clone())
+                .map_err(|err| err.to_string())?;
+        }
+
+        let mut context = X509StoreContext::new().map_err(|err| err.to_string())?;
+        let (verified, result) = context
+            .init(&store, certificate, &chain, |context| {
+                Ok((context.verify_cert()?, context.error()))
+            })
+            .map_err(|err| err.to_string())?;
+        if !verified {
+            return Err(format!(
+                "certificate not trusted: {}",
+                result.error_string()
+            ));
+        }
+
+        let identities = certificate
+            .subject_alt_names()
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| name.uri().or_else(|| name.email()))
+                    .map(|name| name.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if !identities.iter().any(|id| self.matches_identity(id)) {
+            return Err(format!(
+                "certificate identity mismatch: got {}",
+                identities.join(", ")
+            ));
+        }
+
+        let der = certificate.to_der().map_err(|err| err.to_string())?;
+        let issuer = fulcio_issuer(&der).ok_or_else(|| "no issuer in certificate".to_string())?;
+        if issuer != self.issuer {
+            return Err(format!("certificate issuer mismatch: got {}", issuer));
+        }
+
+        let public_key = certificate.public_key().map_err(|err| err.to_string())?;
+        let mut verifier =
+            Verifier::new(MessageDigest::sha256(), &public_key).map_err(|err| err.to_string())?;
+        let mut hasher = Hasher::new(MessageDigest::sha256()).map_err(|err| err.to_string())?;
+        read_chunks(file_path, |chunk| {
+            verifier.update(chunk).map_err(|err| err.to_string())?;
+            hasher.update(chunk).map_err(|err| err.to_string())
+        })?;
+
+        let file_digest = hasher.finish().map_err(|err| err.to_string())?;
+        if let Some(digest) = &bundle.digest {
+            if file_digest.as_ref() != digest.as_slice() {
+                return Err("digest mismatch".to_string());
+            }
+        }
+
+        // The entry of the log must be the one of this signature, or the
+        // timestamp of any other entry could be reused
+        if !bundle
+            .log_entry
+            .matches(&der, &bundle.signature, &file_digest)
+        {
+            return Err("transparency log entry does not match the bundle".to_string());
+        }
+
+        match verifier.verify(&bundle.signature) {
+            Ok(true) => Ok(()),
+            _ => Err("invalid signature".to_string()),
+        }
+    }
+}
+
+/// The parts of a sigstore bundle needed to verify it offline; both the
+/// protobuf-based bundle format and the legacy cosign bundle format are
+/// supported
+struct SigstoreBundle {
+    certificates: Vec<X509>,
+    signature: Vec<u8>,
+    digest: Option<Vec<u8>>,
+    log_entry: RekorLogEntry,
+}
+
+/// The entry of a sigstore bundle in the rekor transparency log, and the
+/// signed entry timestamp promising its inclusion in the log
+struct RekorLogEntry {
+    body: String,
+    integrated_time: i64,
+    log_id: String,
+    log_index: i64,
+    signed_entry_timestamp: Vec<u8>,
+}
+
+impl SigstoreBundle {
+    fn from_file(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+        let bundle: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|err| format!("invalid sigstore bundle: {}", err))?;
+
+        if bundle.get("dsseEnvelope").is_some() {
+            return Err("sigstore bundles with attestations are not supported".to_string());
+        }
+
+        let decode = |pointer: &str| -> Result<Option<Vec<u8>>, String> {
+            match bundle.pointer(pointer).and_then(|value| value.as_str()) {
+                Some(value) => decode_block(value)
+                    .map(Some)
+                    .map_err(|err| format!("invalid base64 at {}: {}", pointer, err)),
+                None => Ok(None),
+            }
+        };
+
+        let certificates = if let Some(der) = decode("/verificationMaterial/certificate/rawBytes")?
+        {
+            vec![der]
+        } else if let Some(chain) = bundle
+            .pointer("/verificationMaterial/x509CertificateChain/certificates")
+            .and_then(|chain| chain.as_array())
+        {
+            (0..chain.len())
+                .filter_map(|idx| {
+                    decode(&format!(
+                        "/verificationMaterial/x509CertificateChain/certificates/{}/rawBytes",
+                        idx
+                    ))
+                    .transpose()
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else if let Some(pem) = decode("/cert")? {
+            X509::stack_from_pem(&pem)
+                .map_err(|err| format!("invalid certificate: {}", err))?
+                .into_iter()
+                .map(|certificate| certificate.to_der().map_err(|err| err.to_string()))
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            vec![]
+        };
+        let certificates = certificates
+            .iter()
+            .map(|der| X509::from_der(der).map_err(|err| format!("invalid certificate: {}", err)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let signature = match decode("/messageSignature/signature")? {
+            Some(signature) => signature,
+            None => decode("/base64Signature")?
+                .ok_or_else(|| "no signature in sigstore bundle".to_string())?,
+        };
+
+        let digest = decode("/messageSignature/messageDigest/digest")?;
+
+        // Integers are encoded as strings in the protobuf format
+        let integer = |pointer: &str| {
+            bundle.pointer(pointer).and_then(|value| {
+                value
+                    .as_i64()
+                    .or_else(|| value.as_str().and_then(|value| value.parse().ok()))
+            })
+        };
+        let string = |pointer: &str| {
+            bundle
+                .pointer(pointer)
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_string())
+        };
+
+        let log_entry = if bundle.pointer("/verificationMaterial").is_some() {
+            let prefix = "/verificationMaterial/tlogEntries/0";
+            (|| {
+                Some(RekorLogEntry {
+                    body: string(&format!("{}/canonicalizedBody", prefix))?,
+                    integrated_time: integer(&format!("{}/integratedTime", prefix))?,
+                    log_id: decode(&format!("{}/logId/keyId", prefix))
+                        .ok()??
+                        .iter()
+                        .map(|b| format!("{:02x}", b))
+                        .collect(),
+                    log_index: integer(&format!("{}/logIndex", prefix))?,
+                    signed_entry_timestamp: decode(&format!(
+                        "{}/inclusionPromise/signedEntryTimestamp",
+                        prefix
+                    ))
+                    .ok()??,
+                })
+            })()
+        } else {
+            (|| {
+                Some(RekorLogEntry {
+                    body: string("/rekorBundle/Payload/body")?,
+                    integrated_time: integer("/rekorBundle/Payload/integratedTime")?,
+                    log_id: string("/rekorBundle/Payload/logID")?,
+                    log_index: integer("/rekorBundle/Payload/logIndex")?,
+                    signed_entry_timestamp: decode("/rekorBundle/SignedEntryTimestamp").ok()??,
+                })
+            })()
+        }
+        .ok_or_else(|| "no transparency log entry in sigstore bundle".to_string())?;
+
+        Ok(Self {
+            certificates,
+            signature,
+            digest,
+            log_entry,
+        })
+    }
+}
+
+impl RekorLogEntry {
+    /// Verifies the signed entry timestamp of the entry, which is the
+    /// signature by the log of the canonical JSON of the entry
+    fn verify_timestamp(&self, public_key: &PKey<openssl::pkey::Public>) -> Result<(), String> {
+        let encode = |value: &str| serde_json::to_string(value).map_err(|err| err.to_string());
+        let payload = format!(
+            r#"{{"body":{},"integratedTime":{},"logID":{},"logIndex":{}}}"#,
+            encode(&self.body)?,
+            self.integrated_time,
+            encode(&self.log_id)?,
+            self.log_index,
+        );
+
+        let mut verifier =
+            Verifier::new(MessageDigest::sha256(), public_key).map_err(|err| err.to_string())?;
+        match verifier.verify_oneshot(&self.signed_entry_timestamp, payload.as_bytes()) {
+            Ok(true) => Ok(()),
+            _ => Err("invalid transparency log entry timestamp".to_string()),
+        }
+    }
+
+    /// Returns whether the entry records the given signature, made with
+    /// the certificate with the given DER encoding over the given digest
+    fn matches(&self, certificate: &[u8], signature: &[u8], digest: &[u8]) -> bool {
+        let body = match decode_block(&self.body)
+            .ok()
+            .and_then(|body| serde_json::from_slice::<serde_json::Value>(&body).ok())
+        {
+            Some(body) => body,
+            None => return false,
+        };
+
+        let decode = |pointer: &str| {
+            body.pointer(pointer)
+                .and_then(|value| value.as_str())
+                .and_then(|value| decode_block(value).ok())
+        };
+
+        let entry_certificate = decode("/spec/signature/publicKey/content")
+            .and_then(|pem| X509::from_pem(&pem).ok())
+            .and_then(|certificate| certificate.to_der().ok());
+        let entry_digest = body
+            .pointer("/spec/data/hash/value")
+            .and_then(|value| value.as_str());
+        let digest = digest
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+
+        body.pointer("/kind").and_then(|kind| kind.as_str()) == Some("hashedrekord")
+            && entry_certificate.as_deref() == Some(certificate)
+            && decode("/spec/signature/content").as_deref() == Some(signature)
+            && entry_digest == Some(digest.as_str())
+    }
+}
+
+/// Returns the OIDC issuer recorded by Fulcio in the certificate with
+/// the given DER encoding
+fn fulcio_issuer(der: &[u8]) -> Option<String> {
+    if let Some(value) = certificate_extension(der, FULCIO_ISSUER_V2_OID) {
+        let (tag, value, _) = der_element(value)?;
+        if tag == 0x0c {
+            return String::from_utf8(value.to_vec()).ok();
+        }
+    }
+
+    let value = certificate_extension(der, FULCIO_ISSUER_V1_OID)?;
+    String::from_utf8(value.to_vec()).ok()
+}
+
+/// Returns the value of the extension with the given encoded OID from
+/// the certificate with the given DER encoding
+fn certificate_extension<'a>(der: &'a [u8], oid: &[u8]) -> Option<&'a [u8]> {
+    let start = der.windows(oid.len()).position(|window| window == oid)? + oid.len();
+
+    // The OID is followed by the optional critical flag, and then by
+    // the value of the extension as an octet string
+    let (mut tag, mut value, rest) = der_element(&der[start..])?;
+    if tag == 0x01 {
+        (tag, value, _) = der_element(rest)?;
+    }
+
+    (tag == 0x04).then_some(value)
+}
+
+/// Parses the DER element at the start of the given data, returning its
+/// tag, its value and the data following it
+fn der_element(data: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let tag = *data.first()?;
+    let length = *data.get(1)? as usize;
+    let (length, header) = if length < 0x80 {
+        (length, 2)
+    } else {
+        let bytes = length & 0x7f;
+        if bytes == 0 || bytes > 4 {
+            return None;
+        }
+        let length = data
+            .get(2..2 + bytes)?
+            .iter()
+            .fold(0usize, |length, byte| (length << 8) | *byte as usize);
+        (length, 2 + bytes)
+    };
+
+    let value = data.get(header..header + length)?;
+    Some((tag, value, &data[header + length..]))
+}
+
+/// A minisign public key, as found in the second line of the public key
+/// file, or on its own
+struct MinisignPublicKey {
+    key_id: Vec<u8>,
+    public_key: Vec<u8>,
+}
+
+impl MinisignPublicKey {
+    fn parse(public_key: &str) -> Option<Self> {
+        let encoded = public_key
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty() && !line.starts_with("untrusted comment:"))?;
+        let decoded = decode_block(encoded).ok()?;
+        if decoded.len() != 42 || &decoded[..2] != b"Ed" {
+            return None;
+        }
+
+        Some(Self {
+            key_id: decoded[2..10].to_vec(),
+            public_key: decoded[10..].to_vec(),
+        })
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> Result<bool, String> {
+        let public_key = PKey::public_key_from_raw_bytes(&self.public_key, Id::ED25519)
+            .map_err(|err| format!("invalid public key: {}", err))?;
+        let mut verifier = Verifier::new_without_digest(&public_key)
+            .map_err(|err| format!("failed to verify signature: {}", err))?;
+        Ok(verifier.verify_oneshot(signature, message).unwrap_or(false))
+    }
+}
+
+fn verify_minisign(
+    public_keys: &[String],
+    file_path: &Path,
+    signature_path: &Path,
+) -> Result<(), String> {
+    let contents = std::fs::read_to_string(signature_path)
+        .map_err(|err| format!("failed to read {}: {}", signature_path.display(), err))?;
+
+    // The signature file contains an untrusted comment, the signature,
+    // a trusted comment and a global signature of the signature and
+    // the trusted comment
+    let lines = contents
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    let (signature, trusted_comment, global_signature) = match lines.as_slice() {
+        [_, signature, trusted_comment, global_signature, ..] => (
+            decode_block(signature).ok(),
+            trusted_comment.strip_prefix("trusted comment: "),
+            decode_block(global_signature).ok(),
+        ),
+        _ => (None, None, None),
+    };
+    let (signature, trusted_comment, global_signature) =
+        match (signature, trusted_comment, global_signature) {
+            (Some(signature), Some(trusted_comment), Some(global_signature))
+                if signature.len() == 74 =>
+            {
+                (signature, trusted_comment, global_signature)
+            }
+            _ => return Err("invalid minisign signature".to_string()),
+        };
+
+    let (algorithm, key_id, signature) = (&signature[..2], &signature[2..10], &signature[10..]);
+    let public_key = public_keys
+        .iter()
+        .filter_map(|public_key| MinisignPublicKey::parse(public_key))
+        .find(|public_key| public_key.key_id == key_id)
+        .ok_or_else(|| {
+            format!(
+                "no public key for key id {}",
+                key_id
+                    .iter()
+                    .rev()
+                    .map(|b| format!("{:02X}", b))
+                    .collect::<String>()
+            )
+        })?;
+
+    // Prehashed signatures sign the BLAKE2b-512 hash of the file, while
+    // legacy signatures sign the file itself
+    let message = match algorithm {
+        b"ED" => {
+            let digest = MessageDigest::from_name("BLAKE2b512")
+                .ok_or_else(|| "BLAKE2b-512 is not available".to_string())?;
+            let mut hasher = Hasher::new(digest).map_err(|err| err.to_string())?;
+            read_chunks(file_path, |chunk| {
+                hasher.update(chunk).map_err(|err| err.to_string())
+            })?;
+            hasher.finish().map_err(|err| err.to_string())?.to_vec()
+        }
+        b"Ed" => std::fs::read(file_path)
+            .map_err(|err| format!("failed to read {}: {}", file_path.display(), err))?,
+        _ => return Err("unsupported minisign signature algorithm".to_string()),
+    };
+
+    if !public_key.verify(&message, signature)? {
+        return Err("invalid signature".to_string());
+    }
+
+    let global_message = [signature, trusted_comment.as_bytes()].concat();
+    if !public_key.verify(&global_message, &global_signature)? {
+        return Err("invalid trusted comment signature".to_string());
+    }
+
+    Ok(())
+}
+
+fn verify_gpg(keyring: &str, file_path: &Path, signature_path: &Path) -> Result<(), String> {
+    if !Path::new(keyring).is_file() {
+        return Err(format!("keyring not found: {}", keyring));
+    }
+
+    let output = ProcessCommand::new("gpgv")
+        .arg("--keyring")
+        .arg(keyring)
+        .arg(signature_path)
+        .arg(file_path)
+        .output()
+        .map_err(|err| format!("failed to run gpgv: {}", err))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "invalid signature: {}",
+            stderr.lines().last().unwrap_or_default().trim()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Reads the file at the given path by chunks, calling the given
+/// function with each of them
+fn read_chunks(path: &Path, mut f: impl FnMut(&[u8]) -> Result<(), String>) -> Result<(), String> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|err| format!("failed to open {}: {}", path.display(), err))?;
+    let mut buffer = [0; 8192];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+        if read == 0 {
+            return Ok(());
+        }
+        f(&buffer[..read])?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use openssl::asn1::Asn1Object;
+    use openssl::asn1::Asn1OctetString;
+    use openssl::asn1::Asn1Time;
+    use openssl::base64::encode_block;
+    use openssl::bn::BigNum;
+    use openssl::ec::EcGroup;
+    use openssl::ec::EcKey;
+    use openssl::nid::Nid;
+    use openssl::pkey::Private;
+    use openssl::sign::Signer;
+    use openssl::x509::extension::BasicConstraints;
+    use openssl::x509::extension::SubjectAlternativeName;
+    use openssl::x509::X509Builder;
+    use openssl::x509::X509Extension;
+    use openssl::x509::X509NameBuilder;
+
+    const ISSUER: &str = "https://token.actions.githubusercontent.com";
+    const IDENTITY: &str =
+        "https://github.com/owner/repo/.github/workflows/release.yml@refs/tags/v1.2.3";
+
+    fn write_file(dir: &Path, name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).expect("failed to write file");
+        path
+    }
+
+    mod minisign {
+        use super::*;
+
+        struct MinisignKey {
+            key: PKey<Private>,
+            key_id: [u8; 8],
+        }
+
+        impl MinisignKey {
+            fn new(key_id: [u8; 8]) -> Self {
+                Self {
+                    key: PKey::generate_ed25519().expect("failed to generate key"),
+                    key_id,
+                }
+            }
+
+            fn public_key(&self) -> String {
+                let public_key = [
+                    b"Ed".as_slice(),
+                    &self.key_id,
+                    &self.key.raw_public_key().expect("failed to get public key"),
+                ]
+                .concat();
+                format!(
+                    "untrusted comment: minisign public key\n{}\n",
+                    encode_block(&public_key)
+                )
+            }
+
+            fn sign(&self, message: &[u8]) -> Vec<u8> {
+                Signer::new_without_digest(&self.key)
+                    .expect("failed to create signer")
+                    .sign_oneshot_to_vec(message)
+                    .expect("failed to sign")
+            }
+
+            fn signature(&self, contents: &[u8], trusted_comment: &str) -> String {
+                let digest = MessageDigest::from_name("BLAKE2b512").expect("no BLAKE2b-512");
+                let prehashed = openssl::hash::hash(digest, contents).expect("failed to hash");
+                let signature = self.sign(&prehashed);
+                let global_signature =
+                    self.sign(&[signature.as_slice(), trusted_comment.as_bytes()].concat());
+
+                format!(
+                    "untrusted comment: signature from minisign secret key\n{}\ntrusted comment: {}\n{}\n",
+                    encode_block(&[b"ED".as_slice(), &self.key_id, &signature].concat()),
+                    trusted_comment,
+                    encode_block(&global_signature),
+                )
+            }
+        }
+
+        fn config(keys: &[&MinisignKey]) -> SignatureConfig {
+            SignatureConfig::Minisign {
+                public_keys: keys.iter().map(|key| key.public_key()).collect(),
+            }
+        }
+
+        #[test]
+        fn valid_signature() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let key = MinisignKey::new(*b"omnikey1");
+            let other_key = MinisignKey::new(*b"omnikey2");
+
+            let file = write_file(tmp_dir.path(), "tool.tar.gz", b"contents");
+            let signature = write_file(
+                tmp_dir.path(),
+                "tool.tar.gz.minisig",
+                key.signature(b"contents", "timestamp:1700000000")
+                    .as_bytes(),
+            );
+
+            let result = config(&[&other_key, &key]).verify(&file, &signature);
+            assert_eq!(result, Ok(()));
+        }
+
+        #[test]
+        fn unknown_key() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let key = MinisignKey::new(*b"omnikey1");
+            let other_key = MinisignKey::new(*b"omnikey2");
+
+            let file = write_file(tmp_dir.path(), "tool.tar.gz", b"contents");
+            let signature = write_file(
+                tmp_dir.path(),
+                "tool.tar.gz.minisig",
+                key.signature(b"contents", "timestamp:1700000000")
+                    .as_bytes(),
+            );
+
+            let result = config(&[&other_key]).verify(&file, &signature);
+            assert!(result.is_err(), "expected an error, got {:?}", result);
+        }
+
+        #[test]
+        fn tampered_file() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let key = MinisignKey::new(*b"omnikey1");
+
+            let file = write_file(tmp_dir.path(), "tool.tar.gz", b"tampered");
+            let signature = write_file(
+                tmp_dir.path(),
+                "tool.tar.gz.minisig",
+                key.signature(b"contents", "timestamp:1700000000")
+                    .as_bytes(),
+            );
+
+            let result = config(&[&key]).verify(&file, &signature);
+            assert_eq!(result, Err("invalid signature".to_string()));
+        }
+
+        #[test]
+        fn tampered_trusted_comment() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let key = MinisignKey::new(*b"omnikey1");
+
+            let file = write_file(tmp_dir.path(), "tool.tar.gz", b"contents");
+            let signature = key
+                .signature(b"contents", "timestamp:1700000000")
+                .replace("timestamp:1700000000", "timestamp:1800000000");
+            let signature = write_file(tmp_dir.path(), "tool.tar.gz.minisig", signature.as_bytes());
+
+            let result = config(&[&key]).verify(&file, &signature);
+            assert_eq!(result, Err("invalid trusted comment signature".to_string()));
+        }
+    }
+
+    mod sigstore {
+        use super::*;
+
+        struct CertificateAuthority {
+            key: PKey<Private>,
+            certificate: X509,
+        }
+
+        fn ec_key() -> PKey<Private> {
+            let group =
+                EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).expect("failed to get curve");
+            let key = EcKey::generate(&group).expect("failed to generate key");
+            PKey::from_ec_key(key).expect("failed to convert key")
+        }
+
+        fn name(common_name: &str) -> openssl::x509::X509Name {
+            let mut name = X509NameBuilder::new().expect("failed to create name");
+            name.append_entry_by_text("CN", common_name)
+                .expect("failed to set name");
+            name.build()
+        }
+
+        impl CertificateAuthority {
+            fn new() -> Self {
+                let key = ec_key();
+                let mut builder = X509Builder::new().expect("failed to create builder");
+                builder.set_version(2).expect("failed to set version");
+                builder
+                    .set_serial_number(&BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap())
+                    .expect("failed to set serial");
+                builder
+                    .set_subject_name(&name("sigstore"))
+                    .expect("failed to set subject");
+                builder
+                    .set_issuer_name(&name("sigstore"))
+                    .expect("failed to set issuer");
+                builder.set_pubkey(&key).expect("failed to set key");
+                builder
+                    .set_not_before(&Asn1Time::from_unix(1_600_000_000).unwrap())
+                    .expect("failed to set not before");
+                builder
+                    .set_not_after(&Asn1Time::from_unix(2_000_000_000).unwrap())
+                    .expect("failed to set not after");
+                builder
+                    .append_extension(BasicConstraints::new().critical().ca().build().unwrap())
+                    .expect("failed to add extension");
+                builder
+                    .sign(&key, MessageDigest::sha256())
+                    .expect("failed to sign");
+
+                Self {
+                    key,
+                    certificate: builder.build(),
+                }
+            }
+
+            fn pem(&self) -> Vec<u8> {
+                self.certificate.to_pem().expect("failed to encode")
+            }
+
+            /// Issues a short-lived certificate, valid for ten minutes
+            /// from the given time, like the ones issued by Fulcio
+            fn issue(&self, identity: &str, issuer: &str, issued_at: i64) -> (PKey<Private>, X509) {
+                let key = ec_key();
+                let mut builder = X509Builder::new().expect("failed to create builder");
+                builder.set_version(2).expect("failed to set version");
+                builder
+                    .set_serial_number(&BigNum::from_u32(2).unwrap().to_asn1_integer().unwrap())
+                    .expect("failed to set serial");
+                builder
+                    .set_issuer_name(self.certificate.subject_name())
+                    .expect("failed to set issuer");
+                builder.set_pubkey(&key).expect("failed to set key");
+                builder
+                    .set_not_before(&Asn1Time::from_unix(issued_at as _).unwrap())
+                    .expect("failed to set not before");
+                builder
+                    .set_not_after(&Asn1Time::from_unix((issued_at + 600) as _).unwrap())
+                    .expect("failed to set not after");
+                let san = SubjectAlternativeName::new()
+                    .critical()
+                    .uri(identity)
+                    .build(&builder.x509v3_context(Some(&self.certificate), None))
+                    .expect("failed to build extension");
+                builder
+                    .append_extension(san)
+                    .expect("failed to add extension");
+                let issuer_extension = X509Extension::new_from_der(
+                    &Asn1Object::from_str("1.3.6.1.4.1.57264.1.1").unwrap(),
+                    false,
+                    &Asn1OctetString::new_from_bytes(issuer.as_bytes()).unwrap(),
+                )
+                .expect("failed to build extension");
+                builder
+                    .append_extension(issuer_extension)
+                    .expect("failed to add extension");
+                builder
+                    .sign(&self.key, MessageDigest::sha256())
+                    .expect("failed to sign");
+
+                (key, builder.build())
+            }
+        }
+
+        /// A transparency log, signing the timestamps of its entries
+        struct TransparencyLog {
+            key: PKey<Private>,
+        }
+
+        impl TransparencyLog {
+            fn new() -> Self {
+                Self { key: ec_key() }
+            }
+
+            fn pem(&self) -> Vec<u8> {
+                self.key.public_key_to_pem().expect("failed to encode")
+            }
+
+            fn sign(&self, payload: &str) -> Vec<u8> {
+                Signer::new(MessageDigest::sha256(), &self.key)
+                    .expect("failed to create signer")
+                    .sign_oneshot_to_vec(payload.as_bytes())
+                    .expect("failed to sign")
+            }
+        }
+
+        fn sign(key: &PKey<Private>, contents: &[u8]) -> Vec<u8> {
+            Signer::new(MessageDigest::sha256(), key)
+                .expect("failed to create signer")
+                .sign_oneshot_to_vec(contents)
+                .expect("failed to sign")
+        }
+
+        /// Returns the base64-encoded body of the hashedrekord entry of
+        /// the given signature in the transparency log
+        fn log_entry_body(certificate: &X509, signature: &[u8], contents: &[u8]) -> String {
+            let digest = openssl::hash::hash(MessageDigest::sha256(), contents).unwrap();
+            let body = serde_json::json!({
+                "apiVersion": "0.0.1",
+                "kind": "hashedrekord",
+                "spec": {
+                    "data": {
+                        "hash": {
+                            "algorithm": "sha256",
+                            "value": digest.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+                        },
+                    },
+                    "signature": {
+                        "content": encode_block(signature),
+                        "publicKey": {
+                            "content": encode_block(&certificate.to_pem().unwrap()),
+                        },
+                    },
+                },
+            });
+            encode_block(body.to_string().as_bytes())
+        }
+
+        fn bundle(
+            log: &TransparencyLog,
+            key: &PKey<Private>,
+            certificate: &X509,
+            contents: &[u8],
+            time: i64,
+        ) -> String {
+            let signature = sign(key, contents);
+            let body = log_entry_body(certificate, &signature, contents);
+            bundle_with_entry(log, certificate, &signature, contents, &body, time)
+        }
+
+        fn bundle_with_entry(
+            log: &TransparencyLog,
+            certificate: &X509,
+            signature: &[u8],
+            contents: &[u8],
+            body: &str,
+            time: i64,
+        ) -> String {
+            let digest = openssl::hash::hash(MessageDigest::sha256(), contents).unwrap();
+            let log_id = [0xc0u8, 0xd2, 0x3d, 0x6a];
+            let timestamp = log.sign(&format!(
+                r#"{{"body":"{}","integratedTime":{},"logID":"c0d23d6a","logIndex":12345}}"#,
+                body, time
+            ));
+
+            serde_json::json!({
+                "mediaType": "application/vnd.dev.sigstore.bundle.v0.3+json",
+                "verificationMaterial": {
+                    "certificate": {
+                        "rawBytes": encode_block(&certificate.to_der().unwrap()),
+                    },
+                    "tlogEntries": [{
+                        "logIndex": "12345",
+                        "logId": {
+                            "keyId": encode_block(&log_id),
+                        },
+                        "integratedTime": time.to_string(),
+                        "inclusionPromise": {
+                            "signedEntryTimestamp": encode_block(&timestamp),
+                        },
+                        "canonicalizedBody": body,
+                    }],
+                },
+                "messageSignature": {
+                    "messageDigest": {
+                        "algorithm": "SHA2_256",
+                        "digest": encode_block(&digest),
+                    },
+                    "signature": encode_block(signature),
+                },
+            })
+            .to_string()
+        }
+
+        fn config(
+            tmp_dir: &Path,
+            authority: &CertificateAuthority,
+            log: &TransparencyLog,
+        ) -> SigstoreIdentity {
+            let trusted_root = write_file(tmp_dir, "trusted_root.pem", &authority.pem());
+            let rekor_public_key = write_file(tmp_dir, "rekor.pub", &log.pem());
+            SigstoreIdentity {
+                identity: Some(IDENTITY.to_string()),
+                identity_regex: None,
+                issuer: ISSUER.to_string(),
+                trusted_root: trusted_root.to_string_lossy().to_string(),
+                rekor_public_key: rekor_public_key.to_string_lossy().to_string(),
+            }
+        }
+
+        fn verify(identity: SigstoreIdentity, contents: &[u8], bundle: &str) -> Result<(), String> {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let file = write_file(tmp_dir.path(), "tool.tar.gz", contents);
+            let bundle = write_file(
+                tmp_dir.path(),
+                "tool.tar.gz.sigstore.json",
+                bundle.as_bytes(),
+            );
+            SignatureConfig::Sigstore(identity).verify(&file, &bundle)
+        }
+
+        #[test]
+        fn valid_bundle() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let authority = CertificateAuthority::new();
+            let log = TransparencyLog::new();
+            let (key, certificate) = authority.issue(IDENTITY, ISSUER, 1_700_000_000);
+            let bundle = bundle(&log, &key, &certificate, b"contents", 1_700_000_100);
+
+            let result = verify(
+                config(tmp_dir.path(), &authority, &log),
+                b"contents",
+                &bundle,
+            );
+            assert_eq!(result, Ok(()));
+
+            // The certificate is long expired, but was valid when
+            // the signature was integrated in the transparency log
+            let identity = SigstoreIdentity {
+                identity: None,
+                identity_regex: Some(
+                    r"https://github\.com/owner/repo/\.github/workflows/release\.yml@refs/tags/v.*"
+                        .to_string(),
+                ),
+                ..config(tmp_dir.path(), &authority, &log)
+            };
+            let result = verify(identity, b"contents", &bundle);
+            assert_eq!(result, Ok(()));
+        }
+
+        #[test]
+        fn untrusted_root() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let authority = CertificateAuthority::new();
+            let log = TransparencyLog::new();
+            let other_authority = CertificateAuthority::new();
+            let (key, certificate) = other_authority.issue(IDENTITY, ISSUER, 1_700_000_000);
+            let bundle = bundle(&log, &key, &certificate, b"contents", 1_700_000_100);
+
+            let result = verify(
+                config(tmp_dir.path(), &authority, &log),
+                b"contents",
+                &bundle,
+            );
+            assert!(
+                matches!(result, Err(ref err) if err.starts_with("certificate not trusted")),
+                "unexpected result: {:?}",
+                result
+            );
+        }
+
+        #[test]
+        fn outside_certificate_validity() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let authority = CertificateAuthority::new();
+            let log = TransparencyLog::new();
+            let (key, certificate) = authority.issue(IDENTITY, ISSUER, 1_700_000_000);
+            let bundle = bundle(&log, &key, &certificate, b"contents", 1_700_001_000);
+
+            let result = verify(
+                config(tmp_dir.path(), &authority, &log),
+                b"contents",
+                &bundle,
+            );
+            assert!(
+                matches!(result, Err(ref err) if err.starts_with("certificate not trusted")),
+                "unexpected result: {:?}",
+                result
+            );
+        }
+
+        #[test]
+        fn identity_mismatch() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let authority = CertificateAuthority::new();
+            let log = TransparencyLog::new();
+            let identity = "https://github.com/attacker/repo/.github/workflows/release.yml";
+            let (key, certificate) = authority.issue(identity, ISSUER, 1_700_000_000);
+            let bundle = bundle(&log, &key, &certificate, b"contents", 1_700_000_100);
+
+            let result = verify(
+                config(tmp_dir.path(), &authority, &log),
+                b"contents",
+                &bundle,
+            );
+            assert_eq!(
+                result,
+                Err(format!("certificate identity mismatch: got {}", identity))
+            );
+        }
+
+        #[test]
+        fn issuer_mismatch() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let authority = CertificateAuthority::new();
+            let log = TransparencyLog::new();
+            let issuer = "https://accounts.example.com";
+            let (key, certificate) = authority.issue(IDENTITY, issuer, 1_700_000_000);
+            let bundle = bundle(&log, &key, &certificate, b"contents", 1_700_000_100);
+
+            let result = verify(
+                config(tmp_dir.path(), &authority, &log),
+                b"contents",
+                &bundle,
+            );
+            assert_eq!(
+                result,
+                Err(format!("certificate issuer mismatch: got {}", issuer))
+            );
+        }
+
+        #[test]
+        fn tampered_file() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let authority = CertificateAuthority::new();
+            let log = TransparencyLog::new();
+            let (key, certificate) = authority.issue(IDENTITY, ISSUER, 1_700_000_000);
+            let bundle = bundle(&log, &key, &certificate, b"contents", 1_700_000_100);
+
+            let result = verify(
+                config(tmp_dir.path(), &authority, &log),
+                b"tampered",
+                &bundle,
+            );
+            assert_eq!(result, Err("digest mismatch".to_string()));
+        }
+
+        #[test]
+        fn untrusted_log() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let authority = CertificateAuthority::new();
+            let log = TransparencyLog::new();
+            let other_log = TransparencyLog::new();
+            let (key, certificate) = authority.issue(IDENTITY, ISSUER, 1_700_000_000);
+            let bundle = bundle(&other_log, &key, &certificate, b"contents", 1_700_000_100);
+
+            let result = verify(
+                config(tmp_dir.path(), &authority, &log),
+                b"contents",
+                &bundle,
+            );
+            assert_eq!(
+                result,
+                Err("invalid transparency log entry timestamp".to_string())
+            );
+        }
+
+        #[test]
+        fn forged_integration_time() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let authority = CertificateAuthority::new();
+            let log = TransparencyLog::new();
+            let (key, certificate) = authority.issue(IDENTITY, ISSUER, 1_700_000_000);
+            let bundle = bundle(&log, &key, &certificate, b"contents", 1_700_001_000).replace(
+                r#""integratedTime":"1700001000""#,
+                r#""integratedTime":"1700000100""#,
+            );
+
+            let result = verify(
+                config(tmp_dir.path(), &authority, &log),
+                b"contents",
+                &bundle,
+            );
+            assert_eq!(
+                result,
+                Err("invalid transparency log entry timestamp".to_string())
+            );
+        }
+
+        #[test]
+        fn mismatched_log_entry() {
+            let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+            let authority = CertificateAuthority::new();
+            let log = TransparencyLog::new();
+            let (key, certificate) = authority.issue(IDENTITY, ISSUER, 1_700_000_000);
+
+            // The entry of another signature made while the certificate
+            // was valid cannot be used to timestamp this one
+            let other_signature = sign(&key, b"other contents");
+            let body = log_entry_body(&certificate, &other_signature, b"other contents");
+            let signature = sign(&key, b"contents");
+            let bundle = bundle_with_entry(
+                &log,
+                &certificate,
+                &signature,
+                b"contents",
+                &body,
+                1_700_000_100,
+            );
+
+            let result = verify(
+                config(tmp_dir.path(), &authority, &log),
+                b"contents",
+                &bundle,
+            );
+            assert_eq!(
+                result,
+                Err("transparency log entry does not match the bundle".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn gpg_missing_keyring() {
+        let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let file = write_file(tmp_dir.path(), "tool.tar.gz", b"contents");
+        let signature = write_file(tmp_dir.path(), "tool.tar.gz.sig", b"signature");
+
+        let config = SignatureConfig::Gpg {
+            keyring: "/nonexistent/keyring.gpg".to_string(),
+        };
+        assert_eq!(
+            config.verify(&file, &signature),
+            Err("keyring not found: /nonexistent/keyring.gpg".to_string())
+        );
+    }
+
+    #[test]
+    fn from_config_value() {
+        let parse = |yaml: &str| {
+            let config_value = ConfigValue::from_str(yaml).expect("invalid yaml");
+            SignatureConfig::from_config_value(Some(&config_value))
+        };
+
+        assert_eq!(
+            parse(r#"{"minisign": "RWQ-key"}"#),
+            Ok(Some(SignatureConfig::Minisign {
+                public_keys: vec!["RWQ-key".to_string()]
+            }))
+        );
+        assert_eq!(
+            parse(r#"{"minisign": ["RWQ-key1", "RWQ-key2"]}"#),
+            Ok(Some(SignatureConfig::Minisign {
+                public_keys: vec!["RWQ-key1".to_string(), "RWQ-key2".to_string()]
+            }))
+        );
+        assert_eq!(
+            parse(r#"{"gpg": {"keyring": "/etc/keys/release.gpg"}}"#),
+            Ok(Some(SignatureConfig::Gpg {
+                keyring: "/etc/keys/release.gpg".to_string()
+            }))
+        );
+        assert_eq!(
+            parse(&format!(
+                r#"{{"sigstore": {{"identity": "{}", "issuer": "{}", "trusted_root": "/etc/keys/fulcio.pem", "rekor_public_key": "/etc/keys/rekor.pub"}}}}"#,
+                IDENTITY, ISSUER
+            )),
+            Ok(Some(SignatureConfig::Sigstore(SigstoreIdentity {
+                identity: Some(IDENTITY.to_string()),
+                identity_regex: None,
+                issuer: ISSUER.to_string(),
+                trusted_root: "/etc/keys/fulcio.pem".to_string(),
+                rekor_public_key: "/etc/keys/rekor.pub".to_string(),
+            })))
+        );
+
+        // The integration time cannot be trusted without the key of the
+        // transparency log
+        assert_eq!(
+            parse(&format!(
+                r#"{{"sigstore": {{"identity": "{}", "issuer": "{}", "trusted_root": "/etc/keys/fulcio.pem"}}}}"#,
+                IDENTITY, ISSUER
+            )),
+            Err("sigstore requires rekor_public_key".to_string())
+        );
+
+        // The identity to verify must be pinned
+        assert_eq!(
+            parse(&format!(
+                r#"{{"sigstore": {{"issuer": "{}", "trusted_root": "/etc/keys/fulcio.pem", "rekor_public_key": "/etc/keys/rekor.pub"}}}}"#,
+                ISSUER
+            )),
+            Err("sigstore requires an identity or identity_regex".to_string())
+        );
+
+        // A configuration that cannot be used to verify signatures is
+        // an error, rather than disabling the verification
+        assert_eq!(SignatureConfig::from_config_value(None), Ok(None));
+        assert_eq!(
+            parse(r#"{"cosign": {"key": "cosign.pub"}}"#),
+            Err("unknown signature method 'cosign'".to_string())
+        );
+        assert_eq!(
+            parse(r#"{"gpg": {}}"#),
+            Err("gpg requires a keyring".to_string())
+        );
+        assert_eq!(
+            parse(r#"{"minisign": []}"#),
+            Err("minisign requires a public key".to_string())
+        );
+        assert_eq!(
+            parse(r#"{"minisign": "RWQ-key", "gpg": "/etc/keys/release.gpg"}"#),
+            Err("only one signature method can be configured".to_string())
+        );
+        assert_eq!(
+            parse(&format!(
+                r#"{{"sigstore": {{"identity": "{}", "trusted_root": "/etc/keys/fulcio.pem", "rekor_public_key": "/etc/keys/rekor.pub"}}}}"#,
+                IDENTITY
+            )),
+            Err("sigstore requires issuer".to_string())
+        );
+        assert_eq!(
+            parse(&format!(
+                r#"{{"sigstore": {{"identity": "{}", "issuer": "{}", "rekor_public_key": "/etc/keys/rekor.pub"}}}}"#,
+                IDENTITY, ISSUER
+            )),
+            Err("sigstore requires trusted_root".to_string())
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use crate::internal::env::workdir_or_init;
+
+/// Runs the closure in an environment where the omni directories and
+/// the home directory are in a temporary directory, which is passed to
+/// the closure; the `bin` directory of the temporary directory comes
+/// first in the `PATH`, and the given variables are set on top
+pub(crate) fn run_with_env<F>(envs: &[(String, Option<String>)], closure: F)
+where
+    F: FnOnce(&Path),
+{
+    let tempdir = tempfile::Builder::new()
+        .prefix("omni_tests.")
+        .tempdir()
+        .expect("failed to create temp dir");
+
+    let run_env: Vec<(String, Option<String>)> = vec![
+        ("XDG_DATA_HOME".into(), None),
+        ("XDG_CONFIG_HOME".into(), None),
+        ("XDG_CACHE_HOME".into(), None),
+        ("XDG_RUNTIME_DIR".into(), None),
+        ("OMNI_DATA_HOME".into(), None),
+        ("OMNI_CACHE_HOME".into(), None),
+        ("OMNI_CMD_FILE".into(), None),
+        ("HOMEBREW_PREFIX".into(), None),
+        (
+            "HOME".into(),
+            Some(tempdir.path().join("home").to_string_lossy().to_string()),
+        ),
+        (
+            "PATH".into(),
+            Some(format!(
+                "{}:/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin",
+                tempdir.path().join("bin").to_string_lossy()
+            )),
+        ),
+    ]
+    .into_iter()
+    .chain(envs.iter().cloned())
+    .collect();
+
+    temp_env::with_vars(run_env, || closure(tempdir.path()));
+}
+
+/// Runs the closure from a new work directory created at the given
+/// path, which is the current directory while the closure runs and is
+/// passed the id of the work directory; this is meant to be called from
+/// [`run_with_env`], which keeps the tests changing the environment
+/// from running at the same time
+pub(crate) fn run_in_workdir<F>(path: &Path, closure: F)
+where
+    F: FnOnce(&str),
+{
+    struct RestoreDir(std::path::PathBuf);
+
+    impl Drop for RestoreDir {
+        fn drop(&mut self) {
+            let _ = std::env::set_current_dir(&self.0);
+        }
+    }
+
+    std::fs::create_dir_all(path).expect("failed to create work directory");
+    let wd = workdir_or_init(path.to_string_lossy()).expect("failed to init work directory");
+    let wd_id = wd.id().expect("failed to get work directory id");
+
+    let _restore = RestoreDir(std::env::current_dir().expect("failed to get current directory"));
+    std::env::set_current_dir(path).expect("failed to change directory");
+
+    closure(&wd_id);
+}
+
+/// Returns a gzipped tarball containing the given files, as tuples of
+/// their path in the archive, their contents and their mode
+pub(crate) fn targz(files: &[(&str, &[u8], u32)]) -> Vec<u8> {
+    let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    for (path, contents, mode) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(*mode);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, path, *contents)
+            .expect("failed to append to archive");
+    }
+
+    builder
+        .into_inner()
+        .expect("failed to build archive")
+        .finish()
+        .expect("failed to compress archive")
+}
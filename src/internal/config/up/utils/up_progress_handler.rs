@@ -1,11 +1,15 @@
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt::Display;
 use std::io::BufRead;
 use std::io::Write;
 use std::process::exit;
+use std::sync::Mutex;
 
 use fs4::fs_std::FileExt;
+use indicatif::MultiProgress;
+use lazy_static::lazy_static;
 use once_cell::sync::OnceCell;
 use serde::Deserialize;
 use serde::Serialize;
@@ -20,6 +24,10 @@ use crate::omni_error;
 use crate::omni_info;
 use crate::omni_warning;
 
+lazy_static! {
+    static ref SYNC_FILE_WRITE_LOCK: Mutex<()> = Mutex::new(());
+}
+
 pub struct UpProgressHandler<'a> {
     handler: OnceCell<Box<dyn ProgressHandler>>,
     handler_id: Option<String>,
@@ -28,7 +36,10 @@ pub struct UpProgressHandler<'a> {
     parent: Option<&'a UpProgressHandler<'a>>,
     allow_ending: bool,
     sync_file: Option<&'a std::fs::File>,
+    multiprogress: Option<MultiProgress>,
     desc: OnceCell<String>,
+    forward_init: bool,
+    deferred_message: OnceCell<String>,
 }
 
 impl<'a> UpProgressHandler<'a> {
@@ -44,7 +55,10 @@ impl<'a> UpProgressHandler<'a> {
             parent: None,
             allow_ending: true,
             sync_file: None,
+            multiprogress: None,
             desc: OnceCell::new(),
+            forward_init: false,
+            deferred_message: OnceCell::new(),
         }
     }
 
@@ -57,7 +71,11 @@ impl<'a> UpProgressHandler<'a> {
     }
 
     pub fn init(&self, desc: String) -> bool {
-        if self.handler.get().is_some() || self.parent.is_some() {
+        if let Some(parent) = self.parent {
+            return self.forward_init && parent.init(desc);
+        }
+
+        if self.handler.get().is_some() {
             return false;
         }
 
@@ -66,11 +84,7 @@ impl<'a> UpProgressHandler<'a> {
         }
 
         #[cfg(not(test))]
-        let boxed_handler: Box<dyn ProgressHandler> = if shell_is_interactive() {
-            Box::new(SpinnerProgressHandler::new(desc, self.step))
-        } else {
-            Box::new(PrintProgressHandler::new(desc, self.step))
-        };
+        let boxed_handler = self.new_handler(desc);
 
         #[cfg(test)]
         let boxed_handler: Box<dyn ProgressHandler> =
@@ -88,18 +102,25 @@ impl<'a> UpProgressHandler<'a> {
         }
 
         self.handler
-            .get_or_init(|| {
-                let desc = "".to_string();
-                let boxed_handler: Box<dyn ProgressHandler> = if shell_is_interactive() {
-                    Box::new(SpinnerProgressHandler::new(desc, self.step))
-                } else {
-                    Box::new(PrintProgressHandler::new(desc, self.step))
-                };
-                boxed_handler
-            })
+            .get_or_init(|| self.new_handler("".to_string()))
             .as_ref()
     }
 
+    fn new_handler(&self, desc: String) -> Box<dyn ProgressHandler> {
+        if !shell_is_interactive() {
+            return Box::new(PrintProgressHandler::new(desc, self.step));
+        }
+
+        match &self.multiprogress {
+            Some(multiprogress) => Box::new(SpinnerProgressHandler::new_with_multi(
+                desc,
+                self.step,
+                multiprogress.clone(),
+            )),
+            None => Box::new(SpinnerProgressHandler::new(desc, self.step)),
+        }
+    }
+
     fn handler_id(&self) -> String {
         if let Some(handler_id) = &self.handler_id {
             return handler_id.clone();
@@ -121,10 +142,28 @@ impl<'a> UpProgressHandler<'a> {
             parent: Some(self),
             allow_ending: false,
             sync_file: None,
+            multiprogress: None,
             desc: OnceCell::new(),
+            forward_init: false,
+            deferred_message: OnceCell::new(),
         }
     }
 
+    /// Returns a handler for the same step that reports its progress
+    /// without ending it, so that more operations can be performed
+    /// before the outcome of the step is reported
+    pub fn deferred(&'a self) -> UpProgressHandler<'a> {
+        let mut handler = self.subhandler(&"");
+        handler.forward_init = true;
+        handler
+    }
+
+    /// The message with which the step would have ended successfully,
+    /// if it was reported through a deferred handler
+    pub fn deferred_message(&self) -> Option<String> {
+        self.deferred_message.get().cloned()
+    }
+
     pub fn step(&self) -> Option<(usize, usize)> {
         if let Some(parent) = self.parent {
             parent.step()
@@ -137,6 +176,12 @@ impl<'a> UpProgressHandler<'a> {
         self.sync_file = Some(sync_file);
     }
 
+    /// Sets the multiprogress to which the spinner of this handler should
+    /// be added, so that it can be shown along other running handlers
+    pub fn set_multiprogress(&mut self, multiprogress: &MultiProgress) {
+        self.multiprogress = Some(multiprogress.clone());
+    }
+
     fn update_sync_file(&self, action: SyncUpdateProgressAction) {
         if let Some(sync_file) = self.sync_file {
             // Overwrite the handler id and description with the current ones
@@ -195,11 +240,17 @@ impl ProgressHandler for UpProgressHandler<'_> {
     }
 
     fn success(&self) {
-        self.update_sync_file(SyncUpdateProgressAction::Success(None));
-        self.handler().success();
+        if self.allow_ending {
+            self.update_sync_file(SyncUpdateProgressAction::Success(None));
+            self.handler().success();
+        }
     }
 
     fn success_with_message(&self, message: String) {
+        if !self.allow_ending {
+            let _ = self.deferred_message.set(message.clone());
+        }
+
         let message = self.format_message(message);
         if self.allow_ending {
             self.update_sync_file(SyncUpdateProgressAction::Success(Some(message.clone())));
@@ -247,8 +298,8 @@ impl ProgressHandler for UpProgressHandler<'_> {
 
 pub struct SyncUpdateListener<'a> {
     expected_init: Option<SyncUpdateInit>,
-    current_handler: Option<UpProgressHandler<'a>>,
-    current_handler_id: Option<String>,
+    handlers: HashMap<String, UpProgressHandler<'a>>,
+    multiprogress: MultiProgress,
     seen_init: bool,
     missing_options: bool,
 }
@@ -257,8 +308,8 @@ impl SyncUpdateListener<'_> {
     pub fn new() -> Self {
         Self {
             expected_init: None,
-            current_handler: None,
-            current_handler_id: None,
+            handlers: HashMap::new(),
+            multiprogress: MultiProgress::new(),
             seen_init: false,
             missing_options: false,
         }
@@ -272,8 +323,7 @@ impl SyncUpdateListener<'_> {
     pub fn follow(&mut self, file: &std::fs::File) -> Result<(), SyncUpdateError> {
         let mut lines = std::io::BufReader::new(file).lines();
 
-        self.current_handler = None;
-        self.current_handler_id = None;
+        self.handlers.clear();
 
         loop {
             for line in (&mut lines).flatten() {
@@ -340,25 +390,19 @@ impl SyncUpdateListener<'_> {
                 omni_info!(info);
             }
             SyncUpdateOperation::Progress(progress) => {
-                let need_new_handler = match self.current_handler_id {
-                    Some(ref current_handler_id) => current_handler_id != progress.handler_id(),
-                    _ => true,
-                };
-
-                if need_new_handler {
-                    // Create a new handler for the new handler id
-                    let new_handler = UpProgressHandler::new(progress.step());
-                    new_handler.init(progress.desc().to_string());
-
-                    self.current_handler = Some(new_handler);
-                    self.current_handler_id = Some(progress.handler_id().to_string());
-                }
-
-                if let Some(ref mut handler) = self.current_handler {
-                    handler.perform_sync_action(progress.action());
-                } else {
-                    return Err(SyncUpdateError::NoProgressHandler);
-                }
+                // Steps can run concurrently, so we keep a handler per
+                // handler id, all shown through the same multiprogress
+                let handler = self
+                    .handlers
+                    .entry(progress.handler_id().to_string())
+                    .or_insert_with(|| {
+                        let mut new_handler = UpProgressHandler::new(progress.step());
+                        new_handler.set_multiprogress(&self.multiprogress);
+                        new_handler.init(progress.desc().to_string());
+                        new_handler
+                    });
+
+                handler.perform_sync_action(progress.action());
             }
         }
 
@@ -392,7 +436,11 @@ impl SyncUpdateOperation {
         // Add a line return at the end of the JSON
         let update_json = format!("{}\n", update_json);
 
-        // Write the JSON to the file
+        // Write the JSON to the file, making sure that updates from steps
+        // running concurrently do not get interleaved
+        let _guard = SYNC_FILE_WRITE_LOCK
+            .lock()
+            .expect("failed to lock sync file");
         file.write_all(update_json.as_bytes())?;
 
         Ok(())
@@ -0,0 +1,188 @@
+use regex::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::internal::config::ConfigValue;
+
+/// A command run once a step has been upped, in the dynamic environment
+/// of the work directory, to check that what the step installed works
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UpConfigVerify {
+    /// The command to run, using bash
+    pub command: String,
+
+    /// The exit code expected from the command
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub exit_code: i32,
+
+    /// A regular expression that the output of the command, both
+    /// stdout and stderr, is expected to match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
+
+impl UpConfigVerify {
+    /// Parses the verification from either a command, or a table with
+    /// the `command`, `exit_code` and `output` keys
+    pub fn from_config_value(config_value: &ConfigValue) -> Result<Self, String> {
+        if let Some(command) = config_value.as_str_forced() {
+            return Self::new(command, 0, None);
+        }
+
+        if !config_value.is_table() {
+            return Err(format!(
+                "expected a command or a table, got {}",
+                config_value
+            ));
+        }
+
+        let command = match config_value.get_as_str_forced("command") {
+            Some(command) => command,
+            None => return Err("command is required".to_string()),
+        };
+
+        let exit_code = match config_value.get("exit_code") {
+            Some(value) => match value.as_integer().and_then(|code| i32::try_from(code).ok()) {
+                Some(exit_code) => exit_code,
+                None => return Err(format!("invalid exit code: {}", value)),
+            },
+            None => 0,
+        };
+
+        Self::new(command, exit_code, config_value.get_as_str_forced("output"))
+    }
+
+    fn new(command: String, exit_code: i32, output: Option<String>) -> Result<Self, String> {
+        if command.trim().is_empty() {
+            return Err("command is required".to_string());
+        }
+
+        if let Some(output) = &output {
+            if let Err(err) = Regex::new(output) {
+                return Err(format!("invalid output pattern '{}': {}", output, err));
+            }
+        }
+
+        Ok(Self {
+            command,
+            exit_code,
+            output,
+        })
+    }
+
+    /// Runs the command in the current environment, returning an error
+    /// describing how its result differs from the expected one
+    pub fn run(&self) -> Result<(), String> {
+        let mut command = std::process::Command::new("bash");
+        command.arg("-c");
+        command.arg(&self.command);
+        command.stdin(std::process::Stdio::null());
+
+        let output = command
+            .output()
+            .map_err(|err| format!("failed to run '{}': {}", self.command, err))?;
+
+        let mut combined = String::from_utf8_lossy(&output.stdout).to_string();
+        combined.push_str(&String::from_utf8_lossy(&output.stderr));
+
+        self.check(output.status.code(), &combined)
+    }
+
+    fn check(&self, exit_code: Option<i32>, output: &str) -> Result<(), String> {
+        match exit_code {
+            Some(exit_code) if exit_code == self.exit_code => {}
+            Some(exit_code) => {
+                return Err(format!(
+                    "'{}' exited with code {} instead of {}",
+                    self.command, exit_code, self.exit_code
+                ))
+            }
+            None => return Err(format!("'{}' was terminated by a signal", self.command)),
+        }
+
+        if let Some(pattern) = &self.output {
+            let regex = Regex::new(pattern)
+                .map_err(|err| format!("invalid output pattern '{}': {}", pattern, err))?;
+            if !regex.is_match(output) {
+                return Err(format!(
+                    "output of '{}' does not match '{}'",
+                    self.command, pattern
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Result<UpConfigVerify, String> {
+        let config_value = ConfigValue::from_str(yaml).expect("failed to create config value");
+        UpConfigVerify::from_config_value(&config_value)
+    }
+
+    #[test]
+    fn parse_command() {
+        let verify = parse("tool --version").expect("failed to parse verify");
+        assert_eq!(verify.command, "tool --version");
+        assert_eq!(verify.exit_code, 0);
+        assert_eq!(verify.output, None);
+    }
+
+    #[test]
+    fn parse_table() {
+        let verify = parse(concat!(
+            "command: tool --version\n",
+            "exit_code: 2\n",
+            "output: '^tool 1\\.'\n",
+        ))
+        .expect("failed to parse verify");
+        assert_eq!(verify.command, "tool --version");
+        assert_eq!(verify.exit_code, 2);
+        assert_eq!(verify.output, Some("^tool 1\\.".to_string()));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse("exit_code: 1").is_err());
+        assert!(parse("command: ''").is_err());
+        assert!(parse("command: tool\nexit_code: nope").is_err());
+        assert!(parse("command: tool\noutput: '[unclosed'").is_err());
+        assert!(parse("- tool").is_err());
+    }
+
+    #[test]
+    fn check_exit_code() {
+        let verify = parse("command: tool\nexit_code: 1").expect("failed to parse verify");
+        assert!(verify.check(Some(1), "").is_ok());
+        assert!(verify.check(Some(0), "").is_err());
+        assert!(verify.check(None, "").is_err());
+    }
+
+    #[test]
+    fn check_output() {
+        let verify = parse("command: tool\noutput: 'version 1\\.\\d+'").expect("failed to parse");
+        assert!(verify.check(Some(0), "tool version 1.42\n").is_ok());
+        assert!(verify.check(Some(0), "tool version 2.0\n").is_err());
+        assert!(verify.check(Some(1), "tool version 1.42\n").is_err());
+    }
+
+    #[test]
+    fn run_command() {
+        let verify = parse("command: echo hello >&2\noutput: ^hello").expect("failed to parse");
+        assert!(verify.run().is_ok());
+
+        let verify = parse("exit 3").expect("failed to parse verify");
+        assert_eq!(
+            verify.run(),
+            Err("'exit 3' exited with code 3 instead of 0".to_string())
+        );
+    }
+}
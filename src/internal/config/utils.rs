@@ -1,9 +1,12 @@
 use std::collections::BTreeMap;
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
 
 use humantime::parse_duration;
 
+use crate::internal::commands::utils::abs_path_from_path;
+use crate::internal::config::ConfigSource;
 use crate::internal::config::ConfigValue;
 
 pub fn sort_serde_yaml(value: &serde_yaml::Value) -> serde_yaml::Value {
@@ -45,3 +48,21 @@ pub fn is_executable(path: &std::path::Path) -> bool {
         .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
         .unwrap_or(false)
 }
+
+/// Resolves the path in the given configuration value, relative to the
+/// configuration file in which it is defined
+pub fn config_relative_path(config_value: &ConfigValue) -> Option<String> {
+    let path = config_value.as_str_forced()?;
+    let path = match config_value.get_source() {
+        ConfigSource::File(file) => {
+            let parent_path = PathBuf::from(file)
+                .parent()
+                .expect("config file path has no parent")
+                .to_string_lossy()
+                .to_string();
+            abs_path_from_path(&path, Some(&parent_path))
+        }
+        _ => abs_path_from_path(&path, None),
+    };
+    Some(path.to_string_lossy().to_string())
+}
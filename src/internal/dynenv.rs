@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::Path;
 
 use blake3::Hasher;
 use itertools::Itertools;
@@ -521,6 +522,12 @@ impl DynamicEnv {
                         envsetter.set_value("RUSTUP_HOME", &tool_prefix);
                         envsetter.set_value("CARGO_HOME", &tool_prefix);
                         envsetter.prepend_to_list("PATH", &format!("{}/bin", tool_prefix));
+
+                        // Handle the isolated CARGO_HOME
+                        if let Some(data_path) = &toolversion.data_path {
+                            envsetter.set_value("CARGO_HOME", data_path);
+                            envsetter.prepend_to_list("PATH", &format!("{}/bin", data_path));
+                        }
                     }
                     "golang" => {
                         if let Some(goroot) = std::env::var_os("GOROOT") {
@@ -557,6 +564,29 @@ impl DynamicEnv {
                             envsetter.prepend_to_list("PATH", &gobin);
                         }
                     }
+                    "java" => {
+                        if let Some(java_home) = std::env::var_os("JAVA_HOME") {
+                            envsetter.remove_from_list(
+                                "PATH",
+                                &format!("{}/bin", java_home.to_str().unwrap()),
+                            );
+                        }
+
+                        // On macOS, the JDK might be packaged as a bundle
+                        let java_home = if Path::new(&tool_prefix).join("Contents/Home").is_dir() {
+                            format!("{}/Contents/Home", tool_prefix)
+                        } else {
+                            tool_prefix
+                        };
+
+                        envsetter.set_value("JAVA_HOME", &java_home);
+                        envsetter.prepend_to_list("PATH", &format!("{}/bin", java_home));
+                    }
+                    "kotlin" => {
+                        let kotlin_home = format!("{}/kotlinc", tool_prefix);
+                        envsetter.set_value("KOTLIN_HOME", &kotlin_home);
+                        envsetter.prepend_to_list("PATH", &format!("{}/bin", kotlin_home));
+                    }
                     "python" => {
                         let tool_prefix = if let Some(data_path) = &toolversion.data_path {
                             envsetter.set_value("VIRTUAL_ENV", data_path);
@@ -963,7 +993,11 @@ impl DynamicEnvData {
             index: 0,
         });
 
-        if cur_val.is_empty() {
+        if cur_val.is_empty() && key == "MANPATH" {
+            // An empty MANPATH means the default search path, which
+            // is only kept when there is an empty entry in the list
+            self.env_set_var(key, &format!("{}:", value));
+        } else if cur_val.is_empty() {
             self.env_set_var(key, value);
         } else {
             self.env_set_var(key, &format!("{}:{}", value, cur_val));
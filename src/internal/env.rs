@@ -564,6 +564,12 @@ pub fn shell_is_interactive() -> bool {
     *INTERACTIVE_SHELL
 }
 
+/// Whether omni should avoid accessing the network, which is the case
+/// when `OMNI_OFFLINE` is set to a non-empty value
+pub fn is_offline() -> bool {
+    std::env::var_os("OMNI_OFFLINE").is_some_and(|offline| !offline.is_empty())
+}
+
 pub fn current_exe() -> PathBuf {
     (*CURRENT_EXE).clone()
 }
@@ -15,8 +15,6 @@ pub enum SyncUpdateError {
     AlreadyInit,
     #[error("invalid format: {0}")]
     InvalidFormat(#[from] serde_json::Error),
-    #[error("progress handler was not initialized")]
-    NoProgressHandler,
 }
 
 #[derive(Error, Debug)]
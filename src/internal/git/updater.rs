@@ -27,6 +27,7 @@ use crate::internal::config::up::utils::ProgressHandler;
 use crate::internal::config::up::utils::RunConfig;
 use crate::internal::config::up::utils::SpinnerProgressHandler;
 use crate::internal::env::current_exe;
+use crate::internal::env::is_offline;
 use crate::internal::env::shell_is_interactive;
 use crate::internal::git::full_git_url_parse;
 use crate::internal::git::path_entry_config;
@@ -372,6 +373,12 @@ impl UpdateOptions {
 }
 
 pub fn update(options: &UpdateOptions) -> (HashSet<PathBuf>, HashSet<PathBuf>) {
+    // Nothing to do if we are not allowed to access the network,
+    // not even when the update is forced
+    if is_offline() {
+        return (HashSet::new(), HashSet::new());
+    }
+
     // Get the configuration
     let config = global_config();
 
@@ -730,6 +737,11 @@ pub fn update_git_repo(
             Box::new(&printer)
         };
 
+    if is_offline() {
+        progress_handler.success_with_message("skipped (offline)".light_black());
+        return Ok(false);
+    }
+
     match ref_type.as_str() {
         "branch" => update_git_branch(repo_id, ref_match, repo_path, Some(*progress_handler)),
         "tag" => update_git_tag(repo_id, ref_match, repo_path, Some(*progress_handler)),
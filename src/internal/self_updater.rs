@@ -6,6 +6,7 @@ use std::io::SeekFrom;
 use std::os::unix::fs::PermissionsExt;
 use std::os::unix::process::CommandExt;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Command as ProcessCommand;
 
 use lazy_static::lazy_static;
@@ -17,10 +18,12 @@ use sha2::Sha256;
 use tokio::process::Command as TokioCommand;
 
 use crate::internal::config::config;
+use crate::internal::config::global_config;
 use crate::internal::config::up::utils::run_progress;
 use crate::internal::config::up::utils::PrintProgressHandler;
 use crate::internal::config::up::utils::ProgressHandler;
 use crate::internal::config::up::utils::RunConfig;
+use crate::internal::config::up::utils::SignatureConfig;
 use crate::internal::config::up::utils::SpinnerProgressHandler;
 use crate::internal::env::current_exe;
 use crate::internal::env::homebrew_prefix;
@@ -192,7 +195,11 @@ impl OmniRelease {
         let json_url =
             "https://raw.githubusercontent.com/XaF/homebrew-omni/main/Formula/resources/omni.json";
 
-        let response = reqwest::blocking::get(json_url);
+        let network = global_config().network;
+        let response = match network.client() {
+            Ok(client) => client.get(network.rewrite_url(json_url)).send(),
+            Err(_err) => return None,
+        };
         if let Err(_err) = response {
             return None;
         }
@@ -280,6 +287,16 @@ impl OmniRelease {
             return;
         }
 
+        // An invalid signature configuration must not lead to updates
+        // being installed without the signature being verified
+        if let Some(err) = &config.path_repo_updates.self_update_signature_error {
+            progress_handler.error_with_message(format!(
+                "failed to update: invalid self_update_signature: {}",
+                err
+            ));
+            return;
+        }
+
         if config.path_repo_updates.self_update.is_ask() {
             progress_handler.hide();
 
@@ -477,7 +494,9 @@ impl OmniRelease {
 
         // Download tar.gz to the temp directory
         progress_handler.progress(format!("downloading: {}", binary.url));
-        let response = reqwest::blocking::get(binary.url.as_str());
+        let network = global_config().network;
+        let client = network.client().map_err(io::Error::other)?;
+        let response = client.get(network.rewrite_url(&binary.url)).send();
         if response.is_err() {
             return Err(io::Error::new(
                 io::ErrorKind::Other,
@@ -510,6 +529,17 @@ impl OmniRelease {
             ));
         }
 
+        // Check the signature, if configured, before anything from the
+        // archive can replace the current binary
+        if let Some(signature) = &global_config().path_repo_updates.self_update_signature {
+            progress_handler.progress("checking archive signature".to_string());
+            let signature_path =
+                download_signature(&client, signature, &binary.url, tmp_dir.path())?;
+            signature
+                .verify(&tarball_path, &signature_path)
+                .map_err(|err| io::Error::other(format!("invalid archive signature: {}", err)))?;
+        }
+
         // Extract the archive in the temp directory
         progress_handler.progress("extracting binary".to_string());
         tarball_file.seek(SeekFrom::Start(0))?;
@@ -527,6 +557,50 @@ impl OmniRelease {
     }
 }
 
+/// Downloads the signature of the file at the given URL, published next
+/// to it with one of the extensions of the signature method, and returns
+/// the path where it was downloaded
+fn download_signature(
+    client: &reqwest::blocking::Client,
+    signature: &SignatureConfig,
+    url: &str,
+    target_dir: &Path,
+) -> io::Result<PathBuf> {
+    let network = global_config().network;
+
+    for ext in signature.extensions() {
+        let signature_url = format!("{}{}", url, ext);
+        let mut response = match client.get(network.rewrite_url(&signature_url)).send() {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) if response.status() == reqwest::StatusCode::NOT_FOUND => continue,
+            Ok(response) => {
+                return Err(io::Error::other(format!(
+                    "failed to download {}: {}",
+                    signature_url,
+                    response.status()
+                )))
+            }
+            Err(err) => {
+                return Err(io::Error::other(format!(
+                    "failed to download {}: {}",
+                    signature_url, err
+                )))
+            }
+        };
+
+        let file_name = Path::new(&signature_url)
+            .file_name()
+            .ok_or_else(|| io::Error::other("failed to get signature name"))?;
+        let signature_path = target_dir.join(file_name);
+        let mut file = std::fs::File::create(&signature_path)?;
+        io::copy(&mut response, &mut file)?;
+
+        return Ok(signature_path);
+    }
+
+    Err(io::Error::other(format!("no signature found for {}", url)))
+}
+
 #[derive(Debug, Deserialize)]
 struct OmniReleaseBinary {
     os: String,
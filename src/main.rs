@@ -246,6 +246,12 @@ fn run_omni_subcommand(parsed: &MainArgs) {
             });
         }
 
+        // The offline mode of `omni up` needs to be known before
+        // trying to update anything, so that nothing is fetched
+        if omni_cmd.name() == ["up"] && argv.iter().any(|arg| arg == "--offline") {
+            env::set_var("OMNI_OFFLINE", "1");
+        }
+
         auto_update_async(omni_cmd);
 
         set_cleanup_handler();